@@ -7,6 +7,8 @@ use crate::commands::bench::agent_generator;
 use crate::commands::configure::handle_configure;
 use crate::commands::info::handle_info;
 use crate::commands::mcp::run_server;
+use crate::commands::memory::{handle_memory_export, handle_memory_import, handle_memory_sync};
+use crate::commands::policy::{handle_policy_explain, handle_policy_validate};
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
 use crate::commands::recipe::{handle_deeplink, handle_list, handle_validate};
 // Import the new handlers from commands::schedule
@@ -15,12 +17,14 @@ use crate::commands::schedule::{
     handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
     handle_schedule_sessions,
 };
-use crate::commands::session::{handle_session_list, handle_session_remove};
+use crate::commands::session::{handle_session_list, handle_session_remove, handle_session_search};
 use crate::logging::setup_logging;
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
 use crate::session;
-use crate::session::{build_session, SessionBuilderConfig, SessionSettings};
+use crate::session::{
+    build_session, OutputFormat, RunStatus, SessionBuilderConfig, SessionSettings,
+};
 use goose_bench::bench_config::BenchRunConfig;
 use goose_bench::runners::bench_runner::BenchRunner;
 use goose_bench::runners::eval_runner::EvalRunner;
@@ -118,6 +122,64 @@ enum SessionCommand {
         )]
         output: Option<PathBuf>,
     },
+    #[command(
+        about = "Fork a session into a new session, keeping the messages before a given index"
+    )]
+    Fork {
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(
+            long,
+            value_name = "MESSAGE_INDEX",
+            help = "Index of the first message to leave out of the fork",
+            long_help = "The fork keeps every message before this index, so forking at the index of a user message lets you retry from that point."
+        )]
+        at: usize,
+
+        #[arg(
+            long = "new-name",
+            value_name = "NAME",
+            help = "Name for the forked session (default: a new generated name)"
+        )]
+        new_name: Option<String>,
+    },
+    #[command(about = "Search the history of all sessions")]
+    Search {
+        #[arg(help = "Text to search for in messages, tool calls and tool output")]
+        query: String,
+
+        #[arg(
+            short = 'w',
+            long = "working-dir",
+            help = "Only search sessions started in this directory or below it"
+        )]
+        working_dir: Option<PathBuf>,
+
+        #[arg(
+            long,
+            help = "Only match messages since a time (e.g., 2025-01-31, 7d, 12h)",
+            long_help = "Only match messages created since this time. Accepts an RFC 3339 timestamp, a YYYY-MM-DD date, or an age such as 30m, 12h, 7d or 2w."
+        )]
+        since: Option<String>,
+
+        #[arg(
+            long,
+            help = "Only search sessions that used a tool whose name contains this text"
+        )]
+        tool: Option<String>,
+
+        #[arg(short, long, help = "Maximum number of results", default_value = "20")]
+        limit: usize,
+
+        #[arg(
+            short,
+            long,
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -137,6 +199,18 @@ enum SchedulerCommand {
             help = "Recipe source (path to file, or base64 encoded recipe string)"
         )]
         recipe_source: String,
+        #[arg(
+            long,
+            value_name = "USD",
+            help = "Spend at which each run pauses, over the recipe's budget"
+        )]
+        soft_budget: Option<f64>,
+        #[arg(
+            long,
+            value_name = "USD",
+            help = "Spend at which each run stops, over the recipe's budget"
+        )]
+        hard_budget: Option<f64>,
     },
     #[command(about = "List all scheduled jobs")]
     List {},
@@ -269,6 +343,147 @@ enum RecipeCommand {
     },
 }
 
+#[derive(Subcommand)]
+enum PolicyCommand {
+    /// Validate a tool policy file
+    #[command(about = "Validate the tool policy file")]
+    Validate {
+        /// Path to the policy file
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Policy file to validate (defaults to the configured policy)"
+        )]
+        path: Option<PathBuf>,
+    },
+
+    /// Explain which policy rule decides a tool call
+    #[command(about = "Show which policy rule decides a tool call")]
+    Explain {
+        /// Tool name, e.g. developer__shell
+        #[arg(value_name = "TOOL", help = "Tool name, e.g. developer__shell")]
+        tool: String,
+
+        /// Tool arguments as a JSON object
+        #[arg(
+            short,
+            long,
+            value_name = "JSON",
+            help = "Tool arguments as a JSON object, e.g. '{\"command\": \"cargo test\"}'"
+        )]
+        args: Option<String>,
+
+        /// Path to the policy file
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Policy file to use (defaults to the configured policy)"
+        )]
+        path: Option<PathBuf>,
+
+        /// Output format (text, json)
+        #[arg(
+            short,
+            long,
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MemoryCommand {
+    /// Export memories to a JSON archive
+    #[command(about = "Export memories to a JSON archive")]
+    Export {
+        /// File to write the archive to
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "File to write the archive to (defaults to stdout)"
+        )]
+        output: Option<PathBuf>,
+
+        /// Which memories to export
+        #[arg(
+            long,
+            value_name = "SCOPE",
+            help = "Which memories to export",
+            default_value = "all",
+            value_parser = ["all", "global", "local"]
+        )]
+        scope: String,
+    },
+
+    /// Import memories from a JSON archive
+    #[command(about = "Import memories from a JSON archive")]
+    Import {
+        /// Archive written by `goose memory export`
+        #[arg(value_name = "FILE", help = "Archive written by 'goose memory export'")]
+        path: PathBuf,
+
+        /// Which memories to import
+        #[arg(
+            long,
+            value_name = "SCOPE",
+            help = "Which memories to import",
+            default_value = "all",
+            value_parser = ["all", "global", "local"]
+        )]
+        scope: String,
+
+        /// What to do when an imported memory conflicts with an existing one
+        #[arg(
+            long = "on-conflict",
+            value_name = "STRATEGY",
+            help = "Keep the newer memory, keep the existing one, or replace it with the imported one",
+            default_value = "newer",
+            value_parser = ["newer", "keep", "replace"]
+        )]
+        on_conflict: String,
+    },
+
+    /// Share memories through a directory such as a git repository
+    #[command(about = "Sync shared memories with a directory or git repository")]
+    Sync {
+        /// Directory holding the shared memories
+        #[arg(
+            value_name = "DIR",
+            help = "Directory holding the shared memories (defaults to GOOSE_MEMORY_SYNC_DIR)"
+        )]
+        dir: Option<PathBuf>,
+
+        /// Also share this machine's memories
+        #[arg(
+            long,
+            help = "Also add this machine's memories to the directory, committing and pushing them if it's a git repository"
+        )]
+        push: bool,
+
+        /// Which memories to sync
+        #[arg(
+            long,
+            value_name = "SCOPE",
+            help = "Which memories to sync; local memories hold project conventions, global ones personal preferences",
+            default_value = "local",
+            value_parser = ["all", "global", "local"]
+        )]
+        scope: String,
+
+        /// What to do when a shared memory conflicts with a local one
+        #[arg(
+            long = "on-conflict",
+            value_name = "STRATEGY",
+            help = "Keep the newer memory, keep the local one, or replace it with the shared one",
+            default_value = "newer",
+            value_parser = ["newer", "keep", "replace"]
+        )]
+        on_conflict: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Configure Goose settings
@@ -568,6 +783,18 @@ enum Command {
         )]
         quiet: bool,
 
+        /// Output format for a headless run
+        #[arg(
+            long = "output-format",
+            value_name = "FORMAT",
+            value_enum,
+            default_value_t = OutputFormat::Text,
+            conflicts_with = "interactive",
+            help = "Output format for the run (text, json, stream-json)",
+            long_help = "How the run is printed to stdout. 'text' renders the conversation for a terminal, 'json' prints a single object with the messages, tool calls, usage and exit status once the run finishes, and 'stream-json' prints one JSON event per line as the run progresses. The exit code is 0 on success, 1 on error and 2 when the maximum number of turns is reached."
+        )]
+        output_format: OutputFormat,
+
         /// Scheduled job ID (used internally for scheduled executions)
         #[arg(
             long = "scheduled-job-id",
@@ -614,6 +841,20 @@ enum Command {
         command: RecipeCommand,
     },
 
+    /// Tool permission policy utilities
+    #[command(about = "Validate and explain the tool permission policy")]
+    Policy {
+        #[command(subcommand)]
+        command: PolicyCommand,
+    },
+
+    /// Export, import and sync the memory extension's memories
+    #[command(about = "Export, import and sync memories")]
+    Memory {
+        #[command(subcommand)]
+        command: MemoryCommand,
+    },
+
     /// Manage scheduled jobs
     #[command(about = "Manage scheduled jobs", visible_alias = "sched")]
     Schedule {
@@ -691,6 +932,7 @@ pub struct RecipeInfo {
     pub sub_recipes: Option<Vec<goose::recipe::SubRecipe>>,
     pub final_output_response: Option<goose::recipe::Response>,
     pub retry_config: Option<goose::agents::types::RetryConfig>,
+    pub compaction_config: Option<goose::context_mgmt::structured::CompactionConfig>,
 }
 
 pub async fn cli() -> Result<()> {
@@ -756,6 +998,40 @@ pub async fn cli() -> Result<()> {
                     crate::commands::session::handle_session_export(session_identifier, output)?;
                     Ok(())
                 }
+                Some(SessionCommand::Fork {
+                    identifier,
+                    at,
+                    new_name,
+                }) => {
+                    let session_identifier = if let Some(id) = identifier {
+                        extract_identifier(id)
+                    } else {
+                        match crate::commands::session::prompt_interactive_session_selection() {
+                            Ok(id) => id,
+                            Err(e) => {
+                                eprintln!("Error: {}", e);
+                                return Ok(());
+                            }
+                        }
+                    };
+                    crate::commands::session::handle_session_fork(
+                        session_identifier,
+                        at,
+                        new_name,
+                    )?;
+                    Ok(())
+                }
+                Some(SessionCommand::Search {
+                    query,
+                    working_dir,
+                    since,
+                    tool,
+                    limit,
+                    format,
+                }) => {
+                    handle_session_search(query, working_dir, since, tool, limit, format)?;
+                    Ok(())
+                }
                 None => {
                     // Run session command by default
                     let mut session: crate::Session = build_session(SessionBuilderConfig {
@@ -780,6 +1056,7 @@ pub async fn cli() -> Result<()> {
                         sub_recipes: None,
                         final_output_response: None,
                         retry_config: None,
+                        compaction_config: None,
                     })
                     .await;
                     setup_logging(
@@ -833,6 +1110,7 @@ pub async fn cli() -> Result<()> {
             render_recipe,
             scheduled_job_id,
             quiet,
+            output_format,
             additional_sub_recipes,
             provider,
             model,
@@ -916,12 +1194,15 @@ pub async fn cli() -> Result<()> {
                 max_turns,
                 scheduled_job_id,
                 interactive, // Use the interactive flag from the Run command
-                quiet,
+                quiet: quiet || output_format != OutputFormat::Text,
                 sub_recipes: recipe_info.as_ref().and_then(|r| r.sub_recipes.clone()),
                 final_output_response: recipe_info
                     .as_ref()
                     .and_then(|r| r.final_output_response.clone()),
                 retry_config: recipe_info.as_ref().and_then(|r| r.retry_config.clone()),
+                compaction_config: recipe_info
+                    .as_ref()
+                    .and_then(|r| r.compaction_config.clone()),
             })
             .await;
 
@@ -937,7 +1218,10 @@ pub async fn cli() -> Result<()> {
             if interactive {
                 let _ = session.interactive(input_config.contents).await;
             } else if let Some(contents) = input_config.contents {
-                let _ = session.headless(contents).await;
+                let status = session.headless_with_format(contents, output_format).await;
+                if status != RunStatus::Success {
+                    std::process::exit(status.exit_code());
+                }
             } else {
                 eprintln!("Error: no text provided for prompt in headless mode");
                 std::process::exit(1);
@@ -951,8 +1235,10 @@ pub async fn cli() -> Result<()> {
                     id,
                     cron,
                     recipe_source,
+                    soft_budget,
+                    hard_budget,
                 } => {
-                    handle_schedule_add(id, cron, recipe_source).await?;
+                    handle_schedule_add(id, cron, recipe_source, soft_budget, hard_budget).await?;
                 }
                 SchedulerCommand::List {} => {
                     handle_schedule_list().await?;
@@ -1022,6 +1308,45 @@ pub async fn cli() -> Result<()> {
             }
             return Ok(());
         }
+        Some(Command::Policy { command }) => {
+            match command {
+                PolicyCommand::Validate { path } => {
+                    handle_policy_validate(path)?;
+                }
+                PolicyCommand::Explain {
+                    tool,
+                    args,
+                    path,
+                    format,
+                } => {
+                    handle_policy_explain(&tool, args, path, &format)?;
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Memory { command }) => {
+            match command {
+                MemoryCommand::Export { output, scope } => {
+                    handle_memory_export(output, &scope)?;
+                }
+                MemoryCommand::Import {
+                    path,
+                    scope,
+                    on_conflict,
+                } => {
+                    handle_memory_import(&path, &scope, &on_conflict)?;
+                }
+                MemoryCommand::Sync {
+                    dir,
+                    push,
+                    scope,
+                    on_conflict,
+                } => {
+                    handle_memory_sync(dir, push, &scope, &on_conflict)?;
+                }
+            }
+            return Ok(());
+        }
         Some(Command::Web { port, host, open }) => {
             crate::commands::web::handle_web(port, host, open).await?;
             return Ok(());
@@ -1054,6 +1379,7 @@ pub async fn cli() -> Result<()> {
                     sub_recipes: None,
                     final_output_response: None,
                     retry_config: None,
+                    compaction_config: None,
                 })
                 .await;
                 setup_logging(
@@ -53,6 +53,7 @@ pub async fn agent_generator(
         sub_recipes: None,
         final_output_response: None,
         retry_config: None,
+        compaction_config: None,
     })
     .await;
 
@@ -112,6 +112,7 @@ pub async fn handle_configure() -> Result<(), Box<dyn Error>> {
                                 display_name: Some(goose::config::DEFAULT_DISPLAY_NAME.to_string()),
                                 timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
                                 bundled: Some(true),
+                                sandbox: None,
                                 description: None,
                             },
                         })?;
@@ -593,6 +594,7 @@ pub fn configure_extensions_dialog() -> Result<(), Box<dyn Error>> {
                     display_name: Some(display_name),
                     timeout: Some(timeout),
                     bundled: Some(true),
+                    sandbox: None,
                     description: None,
                 },
             })?;
@@ -1571,6 +1573,7 @@ pub async fn handle_openrouter_auth() -> Result<(), Box<dyn Error>> {
                                         ),
                                         timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
                                         bundled: Some(true),
+                                        sandbox: None,
                                         description: None,
                                     },
                                 }) {
@@ -1,7 +1,5 @@
 use anyhow::Result;
-use goose_mcp::{
-    ComputerControllerRouter, DeveloperRouter, GoogleDriveRouter, MemoryRouter, TutorialRouter,
-};
+use goose_mcp::{ComputerControllerRouter, DeveloperRouter, GoogleDriveRouter, TutorialRouter};
 use mcp_server::router::RouterService;
 use mcp_server::{BoundedService, ByteTransport, Server};
 use tokio::io::{stdin, stdout};
@@ -29,7 +27,9 @@ pub async fn run_server(name: &str) -> Result<()> {
             let router = GoogleDriveRouter::new().await;
             Some(Box::new(RouterService(router)))
         }
-        "memory" => Some(Box::new(RouterService(MemoryRouter::new()))),
+        "memory" => Some(Box::new(RouterService(
+            crate::commands::memory::memory_router(),
+        ))),
         "tutorial" => Some(Box::new(RouterService(TutorialRouter::new()))),
         _ => None,
     };
@@ -0,0 +1,224 @@
+use anyhow::{anyhow, bail, Context, Result};
+use console::style;
+use goose::config::Config;
+use goose::providers::embedding::BlockingEmbedder;
+use goose_mcp::memory::{ConflictStrategy, FnEmbedder, ImportSummary, MemoryArchive};
+use goose_mcp::MemoryRouter;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+/// The archive kept in a sync directory
+const SYNC_ARCHIVE: &str = "goose-memories.json";
+
+/// Whether a scope argument includes global and local memories
+fn scopes(scope: &str) -> (bool, bool) {
+    match scope {
+        "global" => (true, false),
+        "local" => (false, true),
+        _ => (true, true),
+    }
+}
+
+/// A memory router that embeds memories with the configured embedding model, if there is one
+pub fn memory_router() -> MemoryRouter {
+    let router = MemoryRouter::new();
+    match BlockingEmbedder::from_config() {
+        Ok(Some(embedder)) => {
+            let model = embedder.model().to_string();
+            router.with_embedder(Arc::new(FnEmbedder::new(
+                model,
+                move |texts: &[String]| embedder.embed(texts.to_vec()).map_err(io::Error::other),
+            )))
+        }
+        Ok(None) => {
+            tracing::warn!(
+                "No embedding model is configured, memories are matched by shared words"
+            );
+            router
+        }
+        Err(e) => {
+            tracing::warn!("Failed to set up the embedding model for memories: {}", e);
+            router
+        }
+    }
+}
+
+fn parse_conflict_strategy(on_conflict: &str) -> Result<ConflictStrategy> {
+    on_conflict.parse().map_err(|e: String| anyhow!(e))
+}
+
+fn print_summary(label: &str, summary: ImportSummary) {
+    println!(
+        "{} {}: {} added, {} updated, {} deleted, {} unchanged",
+        style("✓").green().bold(),
+        label,
+        summary.added,
+        summary.updated,
+        summary.removed,
+        summary.unchanged
+    );
+}
+
+/// Writes global and local memories to a JSON archive, or stdout without a path
+pub fn handle_memory_export(output: Option<PathBuf>, scope: &str) -> Result<()> {
+    let (global, local) = scopes(scope);
+    let router = MemoryRouter::new();
+    let archive = MemoryArchive::new(
+        if global { router.export(true)? } else { vec![] },
+        if local { router.export(false)? } else { vec![] },
+    );
+
+    match output {
+        Some(path) => {
+            archive
+                .write(&path)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!(
+                "{} Exported {} global and {} local memories to {}",
+                style("✓").green().bold(),
+                archive
+                    .global
+                    .iter()
+                    .filter(|memory| !memory.is_deleted())
+                    .count(),
+                archive
+                    .local
+                    .iter()
+                    .filter(|memory| !memory.is_deleted())
+                    .count(),
+                path.display()
+            );
+        }
+        None => println!("{}", archive.to_json()?),
+    }
+    Ok(())
+}
+
+/// Merges the memories in an archive into this machine's global and local memories
+pub fn handle_memory_import(path: &Path, scope: &str, on_conflict: &str) -> Result<()> {
+    let on_conflict = parse_conflict_strategy(on_conflict)?;
+    let archive =
+        MemoryArchive::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let (global, local) = scopes(scope);
+    let router = memory_router();
+
+    if global {
+        let summary = router.import(archive.global, true, on_conflict)?;
+        print_summary("Global memories", summary);
+    }
+    if local {
+        let summary = router.import(archive.local, false, on_conflict)?;
+        print_summary("Local memories", summary);
+    }
+    Ok(())
+}
+
+/// Shares memories through a directory, usually a git repository checked out on each machine
+///
+/// The directory's archive keeps global and local memories apart, and each is imported
+/// into the same scope here. With `push`, this machine's memories are added to the archive
+/// too, deletions included, and committed and pushed when the directory is a git
+/// repository with an upstream branch.
+pub fn handle_memory_sync(
+    dir: Option<PathBuf>,
+    push: bool,
+    scope: &str,
+    on_conflict: &str,
+) -> Result<()> {
+    let on_conflict = parse_conflict_strategy(on_conflict)?;
+    let dir = match dir {
+        Some(dir) => dir,
+        None => Config::global()
+            .get_param::<String>("GOOSE_MEMORY_SYNC_DIR")
+            .map(PathBuf::from)
+            .map_err(|_| {
+                anyhow!("No sync directory given, and GOOSE_MEMORY_SYNC_DIR is not set")
+            })?,
+    };
+    if !dir.is_dir() {
+        bail!("Sync directory {} does not exist", dir.display());
+    }
+
+    let tracks_upstream = dir.join(".git").exists()
+        && git(&dir, &["rev-parse", "--abbrev-ref", "@{upstream}"]).is_ok();
+    if tracks_upstream {
+        git(&dir, &["pull", "--ff-only"])?;
+    }
+
+    let archive_path = dir.join(SYNC_ARCHIVE);
+    let mut archive = if archive_path.exists() {
+        MemoryArchive::read(&archive_path)
+            .with_context(|| format!("Failed to read {}", archive_path.display()))?
+    } else {
+        MemoryArchive::new(vec![], vec![])
+    };
+
+    let (global, local) = scopes(scope);
+    let router = memory_router();
+    let mut pushed = ImportSummary::default();
+    for (is_global, label, shared) in [
+        (true, "Shared global memories", &mut archive.global),
+        (false, "Shared local memories", &mut archive.local),
+    ] {
+        if (is_global && !global) || (!is_global && !local) {
+            continue;
+        }
+        let summary = router.import(shared.clone(), is_global, on_conflict)?;
+        print_summary(label, summary);
+
+        if push {
+            let summary =
+                router.merge(shared, router.export(is_global)?, ConflictStrategy::Newer)?;
+            pushed.added += summary.added;
+            pushed.updated += summary.updated;
+            pushed.removed += summary.removed;
+        }
+    }
+
+    if !push {
+        return Ok(());
+    }
+    if !pushed.changed() {
+        println!("Nothing new to share");
+        return Ok(());
+    }
+    MemoryArchive::new(archive.global, archive.local)
+        .write(&archive_path)
+        .with_context(|| format!("Failed to write {}", archive_path.display()))?;
+    println!(
+        "{} Shared {} new, {} updated and {} deleted memories in {}",
+        style("✓").green().bold(),
+        pushed.added,
+        pushed.updated,
+        pushed.removed,
+        archive_path.display()
+    );
+
+    if dir.join(".git").exists() {
+        git(&dir, &["add", SYNC_ARCHIVE])?;
+        git(&dir, &["commit", "-m", "Update shared goose memories"])?;
+        if tracks_upstream {
+            git(&dir, &["push"])?;
+        }
+    }
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
@@ -2,6 +2,8 @@ pub mod bench;
 pub mod configure;
 pub mod info;
 pub mod mcp;
+pub mod memory;
+pub mod policy;
 pub mod project;
 pub mod recipe;
 pub mod schedule;
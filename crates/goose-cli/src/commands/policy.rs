@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use console::style;
+use goose::permission::{PolicyAction, ToolPolicy};
+use std::path::{Path, PathBuf};
+
+fn policy_path(path: Option<PathBuf>) -> Result<PathBuf> {
+    match path {
+        Some(path) => Ok(path),
+        None => ToolPolicy::configured_path(),
+    }
+}
+
+fn load_policy(path: &Path) -> Result<ToolPolicy> {
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "No tool policy found at {}",
+            path.display()
+        ));
+    }
+    ToolPolicy::load(path, &std::env::current_dir()?)
+}
+
+fn styled_action(action: PolicyAction) -> String {
+    match action {
+        PolicyAction::Allow => style("allow").green().bold().to_string(),
+        PolicyAction::Ask => style("ask").yellow().bold().to_string(),
+        PolicyAction::Deny => style("deny").red().bold().to_string(),
+    }
+}
+
+/// Validates a tool policy file
+pub fn handle_policy_validate(path: Option<PathBuf>) -> Result<()> {
+    let path = policy_path(path)?;
+    match load_policy(&path) {
+        Ok(policy) => {
+            println!(
+                "{} {} is valid ({} rules)",
+                style("✓").green().bold(),
+                path.display(),
+                policy.rule_count()
+            );
+            Ok(())
+        }
+        Err(err) => {
+            println!("{} {:#}", style("✗").red().bold(), err);
+            Err(err)
+        }
+    }
+}
+
+/// Reports which policy rule decides a tool call, and why the others don't match
+pub fn handle_policy_explain(
+    tool_name: &str,
+    args: Option<String>,
+    path: Option<PathBuf>,
+    format: &str,
+) -> Result<()> {
+    let arguments = match args {
+        Some(args) => serde_json::from_str(&args).context("--args must be a JSON object")?,
+        None => serde_json::json!({}),
+    };
+    let policy = load_policy(&policy_path(path)?)?;
+    let explanation = policy.explain(tool_name, &arguments);
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&explanation)?);
+        return Ok(());
+    }
+
+    println!("Policy: {}", policy.source().display());
+    for evaluation in &explanation.evaluations {
+        let marker = if evaluation.matched {
+            style("✓").green().bold()
+        } else {
+            style("·").dim()
+        };
+        println!("  {} {}: {}", marker, evaluation.rule, evaluation.detail);
+    }
+
+    match &explanation.decision {
+        Some(decision) => {
+            println!(
+                "\nDecision: {} (rule '{}')",
+                styled_action(decision.action),
+                decision.rule
+            );
+            if let Some(reason) = &decision.reason {
+                println!("Reason: {}", reason);
+            }
+        }
+        None => println!(
+            "\nNo rule matches {}; the tool permissions and goose mode decide",
+            tool_name
+        ),
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use anyhow::{bail, Context, Result};
 use base64::engine::{general_purpose::STANDARD as BASE64_STANDARD, Engine};
+use goose::agents::CostBudget;
 use goose::scheduler::{
     get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, ScheduledJob,
     SchedulerError,
@@ -79,6 +80,8 @@ pub async fn handle_schedule_add(
     id: String,
     cron: String,
     recipe_source_arg: String, // This is expected to be a file path by the Scheduler
+    soft_budget: Option<f64>,
+    hard_budget: Option<f64>,
 ) -> Result<()> {
     println!(
         "[CLI Debug] Scheduling job ID: {}, Cron: {}, Recipe Source Path: {}",
@@ -100,6 +103,10 @@ pub async fn handle_schedule_add(
         current_session_id: None,
         process_start_time: None,
         execution_mode: Some("background".to_string()), // Default to background for CLI
+        budget: (soft_budget.is_some() || hard_budget.is_some()).then_some(CostBudget {
+            soft_limit: soft_budget,
+            hard_limit: hard_budget,
+        }),
     };
 
     let scheduler_storage_path =
@@ -2,7 +2,8 @@ use crate::session::message_to_markdown;
 use anyhow::{Context, Result};
 use cliclack::{confirm, multiselect, select};
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
-use goose::session::{self, Identifier};
+use goose::session::search::parse_since;
+use goose::session::{self, Identifier, SearchQuery};
 use goose::utils::safe_truncate;
 use regex::Regex;
 use std::fs;
@@ -22,8 +23,8 @@ pub fn remove_sessions(sessions: Vec<SessionInfo>) -> Result<()> {
 
     if should_delete {
         for session in sessions {
-            fs::remove_file(session.path.clone())
-                .with_context(|| format!("Failed to remove session file '{}'", session.path))?;
+            session::delete_session(Path::new(&session.path))
+                .with_context(|| format!("Failed to remove session '{}'", session.id))?;
             println!("Session `{}` removed.", session.id);
         }
     } else {
@@ -140,22 +141,42 @@ pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Re
                 return Ok(());
             } else {
                 println!("Available sessions:");
-                for SessionInfo {
-                    id,
-                    path,
-                    metadata,
-                    modified,
-                } in sessions
+                for (
+                    depth,
+                    SessionInfo {
+                        id,
+                        path,
+                        metadata,
+                        modified,
+                    },
+                ) in session::fork_tree(&sessions)
                 {
                     let description = if metadata.description.is_empty() {
                         "(none)"
                     } else {
                         &metadata.description
                     };
-                    let output = format!("{} - {} - {}", id, description, modified);
+                    // Forks are listed under their parent session
+                    let tree_prefix = if depth > 0 {
+                        format!("{}└─ ", "   ".repeat(depth - 1))
+                    } else {
+                        String::new()
+                    };
+                    let fork_point = metadata
+                        .fork_message_index
+                        .map(|index| format!(" (forked at message {})", index))
+                        .unwrap_or_default();
+                    let cost = metadata
+                        .accumulated_cost
+                        .map(|cost| format!(" - ${:.2}", cost))
+                        .unwrap_or_default();
+                    let output = format!(
+                        "{}{} - {} - {}{}{}",
+                        tree_prefix, id, description, modified, cost, fork_point
+                    );
                     if verbose {
                         println!("  {}", output);
-                        println!("    Path: {}", path);
+                        println!("    {}Path: {}", "   ".repeat(depth), path);
                     } else {
                         println!("{}", output);
                     }
@@ -166,6 +187,81 @@ pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Re
     Ok(())
 }
 
+pub fn handle_session_fork(
+    identifier: Identifier,
+    at: usize,
+    new_name: Option<String>,
+) -> Result<()> {
+    let parent_path = session::get_path(identifier)
+        .map_err(|e| anyhow::anyhow!("Invalid session identifier: {}", e))?;
+    let fork_name = new_name.unwrap_or_else(session::generate_fork_id);
+    let fork_path = session::get_path(Identifier::Name(fork_name.clone()))
+        .map_err(|e| anyhow::anyhow!("Invalid name for forked session: {}", e))?;
+
+    let metadata = session::fork_session(&parent_path, at, &fork_path)
+        .map_err(|e| anyhow::anyhow!("Failed to fork session: {}", e))?;
+
+    println!(
+        "Forked session `{}` at message {} into `{}`.",
+        metadata.parent_session_id.unwrap_or_default(),
+        at,
+        fork_name
+    );
+    println!(
+        "Resume it with: goose session --resume --name {}",
+        fork_name
+    );
+    Ok(())
+}
+
+pub fn handle_session_search(
+    query: String,
+    working_dir: Option<PathBuf>,
+    since: Option<String>,
+    tool: Option<String>,
+    limit: usize,
+    format: String,
+) -> Result<()> {
+    let mut search_query = SearchQuery::new(query);
+    search_query.working_dir = working_dir.map(|dir| dir.canonicalize().unwrap_or(dir));
+    search_query.since = since.as_deref().map(parse_since).transpose()?;
+    search_query.tool = tool;
+    search_query.limit = limit;
+
+    let results = match session::search_sessions(&search_query) {
+        Ok(results) => results,
+        Err(e) => {
+            tracing::error!("Failed to search sessions: {:?}", e);
+            return Err(anyhow::anyhow!("Failed to search sessions"));
+        }
+    };
+
+    match format.as_str() {
+        "json" => {
+            println!("{}", serde_json::to_string(&results)?);
+        }
+        _ => {
+            if results.is_empty() {
+                println!("No matching sessions found");
+                return Ok(());
+            }
+            for result in results {
+                let description = if result.description.is_empty() {
+                    "(none)"
+                } else {
+                    &result.description
+                };
+                println!(
+                    "{} #{} - {}",
+                    result.session_id, result.message_index, description
+                );
+                println!("    {}", result.snippet.replace('\n', " "));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Export a session to Markdown without creating a full Session object
 ///
 /// This function directly reads messages from the session file and converts them to Markdown
@@ -179,7 +275,7 @@ pub fn handle_session_export(identifier: Identifier, output_path: Option<PathBuf
         }
     };
 
-    if !session_file_path.exists() {
+    if !session::session_exists(&session_file_path) {
         return Err(anyhow::anyhow!(
             "Session file not found (expected path: {})",
             session_file_path.display()
@@ -586,6 +586,19 @@ async fn process_message_streaming(
                                         }
                                     ).await;
                                 }
+                                MessageContent::ElicitationRequest(elicitation) => {
+                                    // The web interface has no form UI yet, so decline
+                                    // rather than leave the extension waiting
+                                    let _ = agent
+                                        .handle_elicitation_response(
+                                            elicitation.id.clone(),
+                                            mcp_core::protocol::ElicitResult {
+                                                action: mcp_core::protocol::ElicitAction::Decline,
+                                                content: None,
+                                            },
+                                        )
+                                        .await;
+                                }
                                 MessageContent::Thinking(thinking) => {
                                     // Send thinking indicator
                                     let mut sender = sender.lock().await;
@@ -617,7 +630,7 @@ async fn process_message_streaming(
                                     // For now, auto-summarize in web mode
                                     // TODO: Implement proper UI for context handling
                                     let (summarized_messages, _) =
-                                        agent.summarize_context(&messages).await?;
+                                        agent.compact_context(&messages).await?;
                                     {
                                         let mut session_msgs = session_messages.lock().await;
                                         *session_msgs = summarized_messages;
@@ -658,10 +671,16 @@ async fn process_message_streaming(
                         // For now, we'll just log them
                         tracing::info!("Received MCP notification in web interface");
                     }
+                    Ok(AgentEvent::ToolProgress(_progress)) => {
+                        tracing::debug!("Received tool progress in web interface");
+                    }
                     Ok(AgentEvent::ModelChange { model, mode }) => {
                         // Log model change
                         tracing::info!("Model changed to {} in {} mode", model, mode);
                     }
+                    Ok(AgentEvent::MaxTurnsReached | AgentEvent::Error(_)) => {
+                        // The assistant message before this event already told the user
+                    }
 
                     Err(e) => {
                         error!("Error in message stream: {}", e);
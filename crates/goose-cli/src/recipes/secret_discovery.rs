@@ -160,6 +160,7 @@ mod tests {
                     description: None,
                     timeout: None,
                     bundled: None,
+                    sandbox: None,
                 },
             ]),
             context: None,
@@ -170,6 +171,7 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            compaction: None,
         }
     }
 
@@ -214,6 +216,7 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            compaction: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -257,6 +260,7 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            compaction: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -309,6 +313,7 @@ mod tests {
             parameters: None,
             response: None,
             retry: None,
+            compaction: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
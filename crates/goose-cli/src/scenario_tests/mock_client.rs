@@ -74,6 +74,18 @@ impl McpClientTrait for MockClient {
         ))
     }
 
+    async fn subscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+        Err(Error::UnexpectedResponse(
+            "Resources not supported by mock client".to_string(),
+        ))
+    }
+
+    async fn unsubscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+        Err(Error::UnexpectedResponse(
+            "Resources not supported by mock client".to_string(),
+        ))
+    }
+
     async fn list_tools(&self, _: Option<String>) -> Result<ListToolsResult, Error> {
         let rmcp_tools: Vec<rmcp::model::Tool> = self
             .tools
@@ -129,6 +141,10 @@ impl McpClientTrait for MockClient {
     async fn subscribe(&self) -> Receiver<ServerNotification> {
         mpsc::channel(1).1
     }
+
+    async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 pub const WEATHER_TYPE: &str = "cloudy";
@@ -1,8 +1,9 @@
 use console::style;
 use goose::agents::extension::ExtensionError;
 use goose::agents::types::RetryConfig;
-use goose::agents::Agent;
+use goose::agents::{Agent, CostBudget};
 use goose::config::{Config, ExtensionConfig, ExtensionConfigManager};
+use goose::context_mgmt::structured::CompactionConfig;
 use goose::providers::create;
 use goose::recipe::{Response, SubRecipe};
 use goose::session;
@@ -63,6 +64,8 @@ pub struct SessionBuilderConfig {
     pub final_output_response: Option<Response>,
     /// Retry configuration for automated validation and recovery
     pub retry_config: Option<RetryConfig>,
+    /// How auto-compaction condenses the session
+    pub compaction_config: Option<CompactionConfig>,
 }
 
 /// Offers to help debug an extension failure by creating a minimal debugging session
@@ -172,6 +175,7 @@ pub struct SessionSettings {
     pub goose_model: Option<String>,
     pub goose_provider: Option<String>,
     pub temperature: Option<f32>,
+    pub budget: Option<CostBudget>,
 }
 
 pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
@@ -220,6 +224,14 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         agent.add_final_output_tool(final_output_response).await;
     }
 
+    if let Some(compaction_config) = session_config.compaction_config {
+        agent.set_compaction_config(compaction_config).await;
+    }
+
+    if let Some(budget) = session_config.settings.as_ref().and_then(|s| s.budget) {
+        agent.set_cost_budget(budget).await;
+    }
+
     let new_provider = match create(&provider_name, model_config) {
         Ok(provider) => provider,
         Err(e) => {
@@ -273,7 +285,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
                 }
                 Ok(path) => path,
             };
-            if !session_file.exists() {
+            if !session::session_exists(&session_file) {
                 output::render_error(&format!(
                     "Cannot resume session {} - no such session exists",
                     style(session_file.display()).cyan()
@@ -613,6 +625,7 @@ mod tests {
             sub_recipes: None,
             final_output_response: None,
             retry_config: None,
+            compaction_config: None,
         };
 
         assert_eq!(config.extensions.len(), 1);
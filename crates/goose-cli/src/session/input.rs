@@ -21,6 +21,7 @@ pub enum InputResult {
     Clear,
     Recipe(Option<String>),
     Summarize,
+    Rewind(Option<String>),
 }
 
 #[derive(Debug)]
@@ -96,6 +97,7 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
     const CMD_SUMMARIZE: &str = "/summarize";
+    const CMD_REWIND: &str = "/rewind";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -156,6 +158,10 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_SUMMARIZE => Some(InputResult::Summarize),
+        s if s == CMD_REWIND => Some(InputResult::Rewind(None)),
+        s if s.starts_with("/rewind ") => Some(InputResult::Rewind(Some(
+            s[CMD_REWIND.len()..].trim().to_string(),
+        ))),
         _ => None,
     }
 }
@@ -266,6 +272,8 @@ fn print_help() {
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
 /summarize - Summarize the current conversation to reduce context length while preserving key information.
+/rewind [checkpoint] - Roll back the files goose edited since the start of an earlier turn.
+                       Without a checkpoint, choose one from the list of turns that edited files.
 /? or /help - Display this help message
 /clear - Clears the current chat history
 
@@ -511,4 +519,18 @@ mod tests {
         let result = handle_slash_command("  /summarize  ");
         assert!(matches!(result, Some(InputResult::Summarize)));
     }
+
+    #[test]
+    fn test_rewind_command() {
+        let result = handle_slash_command("/rewind");
+        assert!(matches!(result, Some(InputResult::Rewind(None))));
+
+        if let Some(InputResult::Rewind(checkpoint)) = handle_slash_command("/rewind 1718000000") {
+            assert_eq!(checkpoint.as_deref(), Some("1718000000"));
+        } else {
+            panic!("Expected Rewind with a checkpoint");
+        }
+
+        assert!(handle_slash_command("/rewinding").is_none());
+    }
 }
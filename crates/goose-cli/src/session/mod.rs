@@ -4,6 +4,7 @@ mod export;
 mod input;
 mod output;
 mod prompt;
+mod run_output;
 mod task_execution_display;
 mod thinking;
 
@@ -23,6 +24,8 @@ use goose::permission::PermissionConfirmation;
 use goose::providers::base::Provider;
 pub use goose::session::Identifier;
 use goose::utils::safe_truncate;
+use goose_mcp::CheckpointJournal;
+pub use run_output::{OutputFormat, RunStatus};
 
 use anyhow::{Context, Result};
 use completion::GooseCompleter;
@@ -31,13 +34,15 @@ use goose::agents::extension::{Envs, ExtensionConfig};
 use goose::agents::types::RetryConfig;
 use goose::agents::{Agent, SessionConfig};
 use goose::config::Config;
-use goose::message::{Message, MessageContent};
+use goose::message::{ConfirmationKind, Message, MessageContent};
 use goose::providers::pricing::initialize_pricing_cache;
 use goose::session;
 use input::InputResult;
 use mcp_core::handler::ToolError;
+use mcp_core::protocol::{ElicitAction, ElicitResult};
 use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
+use run_output::{RunOutput, RunUsage};
 
 use rand::{distributions::Alphanumeric, Rng};
 use rustyline::EditMode;
@@ -66,6 +71,8 @@ pub struct Session {
     max_turns: Option<u32>,
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
+    // Collects the events of a headless run while one is in progress
+    run_output: Option<RunOutput>,
 }
 
 // Cache structure for completion data
@@ -152,6 +159,7 @@ impl Session {
             max_turns,
             edit_mode,
             retry_config,
+            run_output: None,
         }
     }
 
@@ -160,11 +168,14 @@ impl Session {
         messages: &mut Vec<Message>,
         agent: &Agent,
         message_suffix: &str,
+        render: bool,
     ) -> Result<()> {
         // Summarize messages to fit within context length
-        let (summarized_messages, _) = agent.summarize_context(messages).await?;
-        let msg = format!("Context maxed out\n{}\n{}", "-".repeat(50), message_suffix);
-        output::render_text(&msg, Some(Color::Yellow), true);
+        let (summarized_messages, _) = agent.compact_context(messages).await?;
+        if render {
+            let msg = format!("Context maxed out\n{}\n{}", "-".repeat(50), message_suffix);
+            output::render_text(&msg, Some(Color::Yellow), true);
+        }
         *messages = summarized_messages;
 
         Ok(())
@@ -303,6 +314,7 @@ impl Session {
                 // TODO: should set a timeout
                 timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
                 bundled: None,
+                sandbox: None,
                 description: None,
             };
             self.agent
@@ -645,9 +657,12 @@ impl Session {
                         &Message::assistant().with_text("Chat context cleared."),
                         self.debug,
                     );
-                    if let Some(file) = self.session_file.as_ref().filter(|f| f.exists()) {
-                        std::fs::remove_file(file)?;
-                        std::fs::File::create(file)?;
+                    if let Some(file) = self
+                        .session_file
+                        .as_ref()
+                        .filter(|f| session::session_exists(f))
+                    {
+                        session::clear_session(file)?;
                     }
                     continue;
                 }
@@ -711,9 +726,9 @@ impl Session {
                         // Get the provider for summarization
                         let provider = self.agent.provider().await?;
 
-                        // Call the summarize_context method which uses the summarize_messages function
+                        // Compact in the session's compaction mode, so a structured ledger is kept too
                         let (summarized_messages, _) =
-                            self.agent.summarize_context(&self.messages).await?;
+                            self.agent.compact_context(&self.messages).await?;
 
                         // Update the session messages with the summarized ones
                         self.messages = summarized_messages;
@@ -749,6 +764,14 @@ impl Session {
 
                     continue;
                 }
+                InputResult::Rewind(checkpoint) => {
+                    save_history(&mut editor);
+
+                    if let Err(e) = self.rewind(checkpoint) {
+                        output::render_error(&format!("Rewind failed: {:#}", e));
+                    }
+                    continue;
+                }
             }
         }
 
@@ -845,7 +868,50 @@ impl Session {
         self.process_message(message).await
     }
 
+    /// Process a single message and exit, printing the run in the given format
+    ///
+    /// Returns how the run ended, so the caller can set the exit code.
+    pub async fn headless_with_format(
+        &mut self,
+        prompt: String,
+        format: OutputFormat,
+    ) -> RunStatus {
+        let metadata_before = self.get_metadata().ok();
+        let message = Message::user().with_text(&prompt);
+
+        let mut run_output = RunOutput::new(format);
+        run_output.message(&message);
+        self.run_output = Some(run_output);
+        let result = self.process_message(message).await;
+        let mut run_output = self
+            .run_output
+            .take()
+            .unwrap_or_else(|| RunOutput::new(format));
+
+        if let Err(e) = result {
+            if run_output.is_text() {
+                output::render_error(&e.to_string());
+            }
+            run_output.fail(e.to_string());
+        }
+
+        let usage = self
+            .get_metadata()
+            .ok()
+            .map(|after| RunUsage::between(metadata_before.as_ref(), &after));
+        let session_id = self
+            .session_file
+            .as_ref()
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string());
+        run_output.finish(session_id, usage)
+    }
+
     async fn process_agent_response(&mut self, interactive: bool) -> Result<()> {
+        // Structured output replaces the rendered output of a headless run
+        let render = self.run_output.as_ref().is_none_or(RunOutput::is_text);
+
         // Messages will be auto-compacted in agent.reply() if needed
         let cancel_token = CancellationToken::new();
         let cancel_token_clone = cancel_token.clone();
@@ -878,8 +944,21 @@ impl Session {
                             if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
                                 output::hide_thinking();
 
+                                // Sampling requests have no tool call rendered above them
+                                let is_sampling = confirmation.kind == ConfirmationKind::Sampling;
+                                if is_sampling {
+                                    output::render_sampling_request(&confirmation.tool_name, &confirmation.arguments, self.debug);
+                                }
+                                if let Some(reason) = confirmation.reason() {
+                                    output::render_text(reason, Some(Color::Yellow), false);
+                                }
+
                                 // Format the confirmation prompt
-                                let prompt = "Goose would like to call the above tool, do you allow?".to_string();
+                                let prompt = if is_sampling {
+                                    "The extension would like to use the model with the above messages, do you allow?".to_string()
+                                } else {
+                                    "Goose would like to call the above tool, do you allow?".to_string()
+                                };
 
                                 // Get confirmation from user
                                 let permission_result = cliclack::select(prompt)
@@ -904,12 +983,15 @@ impl Session {
                                 if permission == Permission::Cancel {
                                     output::render_text("Tool call cancelled. Returning to chat...", Some(Color::Yellow), true);
 
-                                    let mut response_message = Message::user();
-                                    response_message.content.push(MessageContent::tool_response(
-                                        confirmation.id.clone(),
-                                        Err(ToolError::ExecutionError("Tool call cancelled by user".to_string()))
-                                    ));
-                                    push_message(&mut self.messages, response_message);
+                                    // A sampling request has no tool request to answer
+                                    if !is_sampling {
+                                        let mut response_message = Message::user();
+                                        response_message.content.push(MessageContent::tool_response(
+                                            confirmation.id.clone(),
+                                            Err(ToolError::ExecutionError("Tool call cancelled by user".to_string()))
+                                        ));
+                                        push_message(&mut self.messages, response_message);
+                                    }
                                     if let Some(session_file) = &self.session_file {
                                         let working_dir = std::env::current_dir().ok();
                                         session::persist_messages_with_schedule_id(
@@ -930,6 +1012,29 @@ impl Session {
                                         permission,
                                     },).await;
                                 }
+                            } else if let Some(MessageContent::ElicitationRequest(elicitation)) = message.content.first() {
+                                output::hide_thinking();
+
+                                let mut result = if interactive {
+                                    output::prompt_elicitation(elicitation)?
+                                } else {
+                                    if render {
+                                        output::render_text(
+                                            &format!("The {} extension asked for input, declining in headless mode", elicitation.extension_name),
+                                            Some(Color::Yellow),
+                                            true,
+                                        );
+                                    }
+                                    ElicitResult {
+                                        action: ElicitAction::Decline,
+                                        content: None,
+                                    }
+                                };
+                                // Declining always succeeds, so only values typed in can be rejected
+                                while let Err(e) = self.agent.handle_elicitation_response(elicitation.id.clone(), result).await {
+                                    output::render_error(&e);
+                                    result = output::prompt_elicitation(elicitation)?;
+                                }
                             } else if let Some(MessageContent::ContextLengthExceeded(_)) = message.content.first() {
                                 output::hide_thinking();
 
@@ -966,7 +1071,9 @@ impl Session {
                                         } else {
                                             format!("Session cleared.\n{}", "-".repeat(50))
                                         };
-                                        output::render_text(&msg, Some(Color::Yellow), true);
+                                        if render {
+                                            output::render_text(&msg, Some(Color::Yellow), true);
+                                        }
                                         break;  // exit the loop to hand back control to the user
                                     }
                                     "truncate" => {
@@ -977,8 +1084,10 @@ impl Session {
                                         } else {
                                             format!("Context maxed out\n{}\nGoose tried its best to truncate messages for you.", "-".repeat(50))
                                         };
-                                        output::render_text("", Some(Color::Yellow), true);
-                                        output::render_text(&msg, Some(Color::Yellow), true);
+                                        if render {
+                                            output::render_text("", Some(Color::Yellow), true);
+                                            output::render_text(&msg, Some(Color::Yellow), true);
+                                        }
                                         self.messages = truncated_messages;
                                     }
                                     "summarize" => {
@@ -990,7 +1099,7 @@ impl Session {
                                         } else {
                                             "Goose automatically summarized messages to continue processing."
                                         };
-                                        Self::summarize_context_messages(&mut self.messages, &self.agent, message_suffix).await?;
+                                        Self::summarize_context_messages(&mut self.messages, &self.agent, message_suffix, render).await?;
                                     }
                                     _ => {
                                         unreachable!()
@@ -1024,12 +1133,23 @@ impl Session {
                                     .await?;
                                 }
 
-                                if interactive {output::hide_thinking()};
-                                let _ = progress_bars.hide();
-                                output::render_message(&message, self.debug);
+                                if let Some(run_output) = &mut self.run_output {
+                                    run_output.message(&message);
+                                }
+                                if render {
+                                    if interactive {output::hide_thinking()};
+                                    let _ = progress_bars.hide();
+                                    output::render_message(&message, self.debug);
+                                }
                             }
                         }
-                        Some(Ok(AgentEvent::McpNotification((_id, message)))) => {
+                        Some(Ok(AgentEvent::McpNotification((id, message)))) => {
+                            if let Some(run_output) = &mut self.run_output {
+                                run_output.notification(&id, &message);
+                            }
+                            if !render {
+                                continue;
+                            }
                             match &message {
                                 ServerNotification::LoggingMessageNotification(notification) => {
                                     let data = &notification.params.data;
@@ -1126,21 +1246,14 @@ impl Session {
                                         }
                                     }
                                 },
-                                ServerNotification::ProgressNotification(notification) => {
-                                    let progress = notification.params.progress;
-                                    let text = notification.params.message.as_deref();
-                                    let total = notification.params.total;
-                                    let token = &notification.params.progress_token;
-                                    progress_bars.update(
-                                        &token.0.to_string(),
-                                        progress,
-                                        total,
-                                        text,
-                                    );
-                                },
                                 _ => (),
                             }
                         }
+                        Some(Ok(AgentEvent::ToolProgress(progress))) => {
+                            if render {
+                                progress_bars.update(&progress);
+                            }
+                        }
                         Some(Ok(AgentEvent::HistoryReplaced(new_messages))) => {
                             // Replace the session's message history with the compacted messages
                             self.messages = new_messages;
@@ -1166,26 +1279,46 @@ impl Session {
                                 eprintln!("Model changed to {} in {} mode", model, mode);
                             }
                         }
+                        // Both are shown to the user in an assistant message, so they only
+                        // change how a headless run ends
+                        Some(Ok(AgentEvent::MaxTurnsReached)) => {
+                            if let Some(run_output) = &mut self.run_output {
+                                run_output.max_turns();
+                            }
+                        }
+                        Some(Ok(AgentEvent::Error(e))) => {
+                            if let Some(run_output) = &mut self.run_output {
+                                run_output.fail(e);
+                            }
+                        }
 
                         Some(Err(e)) => {
                             eprintln!("Error: {}", e);
+                            if let Some(run_output) = &mut self.run_output {
+                                run_output.fail(e.to_string());
+                            }
                             cancel_token_clone.cancel();
                             drop(stream);
                             if let Err(e) = self.handle_interrupted_messages(false).await {
                                 eprintln!("Error handling interruption: {}", e);
                             }
-                            output::render_error(
-                                "The error above was an exception we were not able to handle.\n\
-                                These errors are often related to connection or authentication\n\
-                                We've removed the conversation up to the most recent user message\n\
-                                - depending on the error you may be able to continue",
-                            );
+                            if render {
+                                output::render_error(
+                                    "The error above was an exception we were not able to handle.\n\
+                                    These errors are often related to connection or authentication\n\
+                                    We've removed the conversation up to the most recent user message\n\
+                                    - depending on the error you may be able to continue",
+                                );
+                            }
                             break;
                         }
                         None => break,
                     }
                 }
                 _ = tokio::signal::ctrl_c() => {
+                    if let Some(run_output) = &mut self.run_output {
+                        run_output.fail("Interrupted by the user");
+                    }
                     cancel_token_clone.cancel();
                     drop(stream);
                     if let Err(e) = self.handle_interrupted_messages(true).await {
@@ -1195,7 +1328,9 @@ impl Session {
                 }
             }
         }
-        println!();
+        if render {
+            println!();
+        }
 
         Ok(())
     }
@@ -1394,7 +1529,11 @@ impl Session {
     }
 
     pub fn get_metadata(&self) -> Result<session::SessionMetadata> {
-        if !self.session_file.as_ref().is_some_and(|f| f.exists()) {
+        if !self
+            .session_file
+            .as_ref()
+            .is_some_and(|f| session::session_exists(f))
+        {
             return Err(anyhow::anyhow!("Session file does not exist"));
         }
 
@@ -1560,6 +1699,67 @@ impl Session {
         Ok(path)
     }
 
+    /// Roll back the files edited since a checkpoint, choosing one interactively if not given
+    fn rewind(&self, checkpoint: Option<String>) -> Result<()> {
+        let session_file = self
+            .session_file
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Checkpoints are only kept for saved sessions"))?;
+        let journal = CheckpointJournal::new(session::checkpoint_dir(session_file)?);
+
+        let checkpoints = journal.list()?;
+        if checkpoints.is_empty() {
+            println!(
+                "{}",
+                console::style("No file edits to rewind in this session.").yellow()
+            );
+            return Ok(());
+        }
+
+        let name = match checkpoint {
+            Some(name) => name,
+            None => {
+                let mut select = cliclack::select("Rewind files to before which turn?");
+                for checkpoint in checkpoints.iter().rev() {
+                    let time = chrono::DateTime::from_timestamp(checkpoint.created, 0)
+                        .map(|t| {
+                            t.with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M:%S")
+                                .to_string()
+                        })
+                        .unwrap_or_default();
+                    let label = checkpoint.label.as_deref().unwrap_or(&checkpoint.name);
+                    select = select.item(
+                        checkpoint.name.clone(),
+                        safe_truncate(label, 60),
+                        format!("{} · {} files", time, checkpoint.files.len()),
+                    );
+                }
+                match select.interact() {
+                    Ok(name) => name,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => return Ok(()),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        };
+
+        let restored = journal.restore(&name)?;
+        for file in &restored {
+            let action = if file.removed { "removed" } else { "restored" };
+            println!("  {} {}", console::style(action).dim(), file.path.display());
+        }
+        println!(
+            "{}",
+            console::style(format!(
+                "Rewound {} files to checkpoint {}.",
+                restored.len(),
+                name
+            ))
+            .green()
+        );
+        Ok(())
+    }
+
     fn push_message(&mut self, message: Message) {
         push_message(&mut self.messages, message);
     }
@@ -1,12 +1,13 @@
 use bat::WrappingMode;
 use console::{style, Color};
+use goose::agents::ToolProgress;
 use goose::config::Config;
-use goose::message::{Message, MessageContent, ToolRequest, ToolResponse};
-use goose::providers::pricing::get_model_pricing;
-use goose::providers::pricing::parse_model_id;
+use goose::message::{ElicitationRequest, Message, MessageContent, ToolRequest, ToolResponse};
+use goose::providers::base::Usage;
+use goose::providers::pricing::estimate_cost;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use mcp_core::protocol::{ElicitAction, ElicitResult};
 use mcp_core::tool::ToolCall;
-use regex::Regex;
 use rmcp::model::PromptArgument;
 use serde_json::Value;
 use std::cell::RefCell;
@@ -432,6 +433,136 @@ fn render_dynamic_task_request(call: &ToolCall, debug: bool) {
     println!();
 }
 
+/// Show what an extension wants to send to the model before the user approves it
+pub fn render_sampling_request(name: &str, arguments: &Value, debug: bool) {
+    render_default_request(&ToolCall::new(name, arguments.clone()), debug);
+}
+
+/// Ask the user to fill in the form an extension requested, one field at a time
+///
+/// Ctrl+C dismisses the form and Ctrl+D declines to answer. Optional fields can be left
+/// empty, and invalid values are asked for again.
+pub fn prompt_elicitation(request: &ElicitationRequest) -> anyhow::Result<ElicitResult> {
+    println!(
+        "\n{} {}",
+        style(format!("─── {} asks", request.extension_name))
+            .magenta()
+            .dim(),
+        style("(Ctrl+C to cancel, Ctrl+D to decline)").dim()
+    );
+    print_markdown(&request.message, get_theme());
+
+    let properties = request
+        .requested_schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let required: Vec<&str> = request
+        .requested_schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut editor = rustyline::DefaultEditor::new()?;
+    let mut content = serde_json::Map::new();
+
+    for (name, schema) in &properties {
+        let is_required = required.contains(&name.as_str());
+        let label = schema.get("title").and_then(Value::as_str).unwrap_or(name);
+        let mut hints = Vec::new();
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            hints.push(description.to_string());
+        }
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            let options: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            hints.push(format!("one of {}", options.join(", ")));
+        } else if schema.get("type").and_then(Value::as_str) == Some("boolean") {
+            hints.push("y/n".to_string());
+        }
+        if !hints.is_empty() {
+            println!("{}", style(hints.join(" · ")).dim());
+        }
+        let prompt = format!("{}{}: ", label, if is_required { "*" } else { "" });
+
+        loop {
+            let line = match editor.readline(&prompt) {
+                Ok(line) => line,
+                Err(rustyline::error::ReadlineError::Interrupted) => {
+                    return Ok(ElicitResult {
+                        action: ElicitAction::Cancel,
+                        content: None,
+                    })
+                }
+                Err(rustyline::error::ReadlineError::Eof) => {
+                    return Ok(ElicitResult {
+                        action: ElicitAction::Decline,
+                        content: None,
+                    })
+                }
+                Err(e) => return Err(e.into()),
+            };
+            match parse_elicitation_field(schema, &line, is_required) {
+                Ok(Some(value)) => {
+                    content.insert(name.clone(), value);
+                    break;
+                }
+                Ok(None) => break,
+                Err(message) => println!("{}", style(message).red()),
+            }
+        }
+    }
+
+    Ok(ElicitResult {
+        action: ElicitAction::Accept,
+        content: Some(Value::Object(content)),
+    })
+}
+
+/// Convert what the user typed into the value a form field's schema asks for
+fn parse_elicitation_field(
+    schema: &Value,
+    input: &str,
+    required: bool,
+) -> Result<Option<Value>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return if required {
+            Err("This field is required".to_string())
+        } else {
+            Ok(None)
+        };
+    }
+
+    let value = match schema.get("type").and_then(Value::as_str) {
+        Some("boolean") => match input.to_lowercase().as_str() {
+            "y" | "yes" | "true" => Value::Bool(true),
+            "n" | "no" | "false" => Value::Bool(false),
+            _ => return Err("Please answer y or n".to_string()),
+        },
+        Some("integer") => input
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| "Please enter a whole number".to_string())?,
+        Some("number") => input
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| "Please enter a number".to_string())?,
+        _ => Value::String(input.to_string()),
+    };
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(&value) {
+            let options: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            return Err(format!("Please enter one of {}", options.join(", ")));
+        }
+    }
+    Ok(Some(value))
+}
+
 fn render_default_request(call: &ToolCall, debug: bool) {
     print_tool_header(call);
     print_params(&call.arguments, 0, debug);
@@ -711,61 +842,6 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     );
 }
 
-fn normalize_model_name(model: &str) -> String {
-    let mut result = model.to_string();
-
-    // Remove "-latest" suffix
-    if result.ends_with("-latest") {
-        result = result.strip_suffix("-latest").unwrap().to_string();
-    }
-
-    // Remove date-like suffixes: -YYYYMMDD
-    let re_date = Regex::new(r"-\d{8}$").unwrap();
-    if re_date.is_match(&result) {
-        result = re_date.replace(&result, "").to_string();
-    }
-
-    // Convert version numbers like -3-5- to -3.5- (e.g., claude-3-5-haiku -> claude-3.5-haiku)
-    let re_version = Regex::new(r"-(\d+)-(\d+)-").unwrap();
-    if re_version.is_match(&result) {
-        result = re_version.replace(&result, "-$1.$2-").to_string();
-    }
-
-    result
-}
-
-async fn estimate_cost_usd(
-    provider: &str,
-    model: &str,
-    input_tokens: usize,
-    output_tokens: usize,
-) -> Option<f64> {
-    // For OpenRouter, parse the model name to extract real provider/model
-    let openrouter_data = if provider == "openrouter" {
-        parse_model_id(model)
-    } else {
-        None
-    };
-
-    let (provider_to_use, model_to_use) = match &openrouter_data {
-        Some((real_provider, real_model)) => (real_provider.as_str(), real_model.as_str()),
-        None => (provider, model),
-    };
-
-    // Use the pricing module's get_model_pricing which handles model name mapping internally
-    let cleaned_model = normalize_model_name(model_to_use);
-    let pricing_info = get_model_pricing(provider_to_use, &cleaned_model).await;
-
-    match pricing_info {
-        Some(pricing) => {
-            let input_cost = pricing.input_cost * input_tokens as f64;
-            let output_cost = pricing.output_cost * output_tokens as f64;
-            Some(input_cost + output_cost)
-        }
-        None => None,
-    }
-}
-
 /// Display cost information, if price data is available.
 pub async fn display_cost_usage(
     provider: &str,
@@ -773,7 +849,8 @@ pub async fn display_cost_usage(
     input_tokens: usize,
     output_tokens: usize,
 ) {
-    if let Some(cost) = estimate_cost_usd(provider, model, input_tokens, output_tokens).await {
+    let usage = Usage::new(Some(input_tokens as i32), Some(output_tokens as i32), None);
+    if let Some(cost) = estimate_cost(provider, model, &usage).await {
         use console::style;
         println!(
             "Cost: {} USD ({} tokens: in {}, out {})",
@@ -819,22 +896,42 @@ impl McpSpinners {
         spinner.set_message(message.to_string());
     }
 
-    pub fn update(&mut self, token: &str, value: u32, total: Option<u32>, message: Option<&str>) {
-        let bar = self.bars.entry(token.to_string()).or_insert_with(|| {
-            if let Some(total) = total {
-                self.multi_bar.add(
-                    ProgressBar::new((total * 100) as u64).with_style(
-                        ProgressStyle::with_template("[{elapsed}] {bar:40} {pos:>3}/{len:3} {msg}")
+    /// Show the progress of a tool call, as a bar when the extension knows the total
+    pub fn update(&mut self, progress: &ToolProgress) {
+        let percentage = progress.percentage();
+        let bar = self
+            .bars
+            .entry(progress.request_id.clone())
+            .or_insert_with(|| {
+                let bar = if percentage.is_some() {
+                    ProgressBar::new(100).with_style(
+                        ProgressStyle::with_template("[{elapsed}] {bar:40} {pos:>3}% {msg}")
                             .unwrap(),
-                    ),
-                )
-            } else {
-                self.multi_bar.add(ProgressBar::new_spinner())
+                    )
+                } else {
+                    ProgressBar::new_spinner().with_style(
+                        ProgressStyle::with_template("{spinner:.green} [{elapsed}] {pos} {msg}")
+                            .unwrap()
+                            .tick_chars("⠋⠙⠚⠛⠓⠒⠊⠉"),
+                    )
+                };
+                self.multi_bar.add(bar)
+            });
+
+        if let Some(msg) = &progress.message {
+            bar.set_message(msg.clone());
+        }
+        match percentage {
+            Some(percentage) => {
+                bar.set_position(percentage.round() as u64);
+                if percentage >= 100.0 {
+                    bar.finish();
+                }
+            }
+            None => {
+                bar.set_position(progress.progress as u64);
+                bar.tick();
             }
-        });
-        bar.set_position((value * 100) as u64);
-        if let Some(msg) = message {
-            bar.set_message(msg.to_string());
         }
     }
 
@@ -906,4 +1003,29 @@ mod tests {
             "/v/l/p/w/m/components/file.txt"
         );
     }
+
+    #[test]
+    fn test_parse_elicitation_field() {
+        let schema = serde_json::json!({"type": "integer"});
+        assert_eq!(
+            parse_elicitation_field(&schema, " 42 ", true),
+            Ok(Some(Value::from(42)))
+        );
+        assert!(parse_elicitation_field(&schema, "4.2", true).is_err());
+        assert!(parse_elicitation_field(&schema, "", true).is_err());
+        assert_eq!(parse_elicitation_field(&schema, "", false), Ok(None));
+
+        let schema = serde_json::json!({"type": "boolean"});
+        assert_eq!(
+            parse_elicitation_field(&schema, "Yes", true),
+            Ok(Some(Value::Bool(true)))
+        );
+
+        let schema = serde_json::json!({"type": "string", "enum": ["small", "large"]});
+        assert_eq!(
+            parse_elicitation_field(&schema, "large", true),
+            Ok(Some(Value::from("large")))
+        );
+        assert!(parse_elicitation_field(&schema, "medium", true).is_err());
+    }
 }
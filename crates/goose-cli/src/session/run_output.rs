@@ -0,0 +1,359 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use goose::message::{push_message, Message, MessageContent, ToolRequest, ToolResponse};
+use goose::session::SessionMetadata;
+use rmcp::model::{Role, ServerNotification};
+use serde::Serialize;
+use serde_json::Value;
+
+/// How `goose run` prints a headless run
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Rendered markdown for reading in a terminal
+    #[default]
+    Text,
+    /// A single JSON object once the run has finished
+    Json,
+    /// Newline-delimited JSON events as the run progresses
+    StreamJson,
+}
+
+/// How a headless run ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Success,
+    MaxTurns,
+    Error,
+}
+
+impl RunStatus {
+    /// The process exit code for a run that ended this way
+    pub fn exit_code(self) -> i32 {
+        match self {
+            RunStatus::Success => 0,
+            RunStatus::Error => 1,
+            RunStatus::MaxTurns => 2,
+        }
+    }
+}
+
+/// Tokens used by a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RunUsage {
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub total_tokens: i32,
+}
+
+impl RunUsage {
+    /// Tokens used between two reads of the session metadata, so a resumed session
+    /// only counts this run
+    pub fn between(before: Option<&SessionMetadata>, after: &SessionMetadata) -> Self {
+        let used = |field: fn(&SessionMetadata) -> Option<i32>| {
+            field(after).unwrap_or(0) - before.and_then(field).unwrap_or(0)
+        };
+        Self {
+            input_tokens: used(|m| m.accumulated_input_tokens),
+            output_tokens: used(|m| m.accumulated_output_tokens),
+            total_tokens: used(|m| m.accumulated_total_tokens),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStatus {
+    Pending,
+    Success,
+    Error,
+}
+
+/// A tool the agent called during the run, and how the call went
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolCallRecord {
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+    pub status: ToolCallStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Everything a headless run produced, printed as the `json` output and as the last
+/// `stream-json` event
+#[derive(Debug, Clone, Serialize)]
+pub struct RunResult {
+    pub status: RunStatus,
+    pub exit_code: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Text of the last assistant message
+    pub response: String,
+    /// The messages of the run, left out of the `stream-json` result since they were
+    /// already streamed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<Message>,
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Tokens used, when the run has a session to count them in
+    pub usage: Option<RunUsage>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent<'a> {
+    /// Part of a message as it is produced; parts with the same id belong to one message
+    Message {
+        message: &'a Message,
+    },
+    ToolRequest(&'a ToolRequest),
+    ToolResponse(&'a ToolResponse),
+    Notification {
+        extension: &'a str,
+        notification: &'a ServerNotification,
+    },
+    Result(&'a RunResult),
+}
+
+/// Collects the events of a headless run and prints them in the requested format
+///
+/// With the text format nothing is printed here, since the session renders the run
+/// itself, but the outcome is still tracked for the exit code.
+pub struct RunOutput {
+    format: OutputFormat,
+    status: RunStatus,
+    error: Option<String>,
+    messages: Vec<Message>,
+    tool_calls: Vec<ToolCallRecord>,
+}
+
+impl RunOutput {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            status: RunStatus::Success,
+            error: None,
+            messages: Vec::new(),
+            tool_calls: Vec::new(),
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.format == OutputFormat::Text
+    }
+
+    /// Record a message, or part of one, from the user or the agent
+    pub fn message(&mut self, message: &Message) {
+        push_message(&mut self.messages, message.clone());
+
+        let mut delta = Message::new(message.role.clone(), message.created, Vec::new());
+        delta.id = message.id.clone();
+        for content in &message.content {
+            match content {
+                MessageContent::ToolRequest(request) => {
+                    self.record_request(request);
+                    self.emit(&StreamEvent::ToolRequest(request));
+                }
+                MessageContent::ToolResponse(response) => {
+                    self.record_response(response);
+                    self.emit(&StreamEvent::ToolResponse(response));
+                }
+                other => delta.content.push(other.clone()),
+            }
+        }
+        if !delta.content.is_empty() {
+            self.emit(&StreamEvent::Message { message: &delta });
+        }
+    }
+
+    pub fn notification(&mut self, extension: &str, notification: &ServerNotification) {
+        self.emit(&StreamEvent::Notification {
+            extension,
+            notification,
+        });
+    }
+
+    /// Mark the run as stopped by the turn limit, unless it has already failed
+    pub fn max_turns(&mut self) {
+        if self.status != RunStatus::Error {
+            self.status = RunStatus::MaxTurns;
+        }
+    }
+
+    /// Mark the run as failed; the first error is the one reported
+    pub fn fail(&mut self, error: impl Into<String>) {
+        self.status = RunStatus::Error;
+        self.error.get_or_insert_with(|| error.into());
+    }
+
+    /// Print the result of the run and return how it ended
+    pub fn finish(self, session_id: Option<String>, usage: Option<RunUsage>) -> RunStatus {
+        let format = self.format;
+        let mut result = self.into_result(session_id, usage);
+        match format {
+            OutputFormat::Text => {}
+            OutputFormat::Json => print_json(&result, true),
+            OutputFormat::StreamJson => {
+                result.messages.clear();
+                print_json(&StreamEvent::Result(&result), false);
+            }
+        }
+        result.status
+    }
+
+    fn into_result(self, session_id: Option<String>, usage: Option<RunUsage>) -> RunResult {
+        let response = self
+            .messages
+            .iter()
+            .rev()
+            .find(|message| message.role == Role::Assistant)
+            .map(|message| message.as_concat_text())
+            .unwrap_or_default();
+        RunResult {
+            status: self.status,
+            exit_code: self.status.exit_code(),
+            error: self.error,
+            session_id,
+            response,
+            messages: self.messages,
+            tool_calls: self.tool_calls,
+            usage,
+        }
+    }
+
+    fn record_request(&mut self, request: &ToolRequest) {
+        if let Ok(call) = &request.tool_call {
+            self.tool_calls.push(ToolCallRecord {
+                id: request.id.clone(),
+                name: call.name.clone(),
+                arguments: call.arguments.clone(),
+                status: ToolCallStatus::Pending,
+                error: None,
+            });
+        }
+    }
+
+    fn record_response(&mut self, response: &ToolResponse) {
+        if let Some(record) = self
+            .tool_calls
+            .iter_mut()
+            .find(|record| record.id == response.id)
+        {
+            match &response.tool_result {
+                Ok(_) => record.status = ToolCallStatus::Success,
+                Err(error) => {
+                    record.status = ToolCallStatus::Error;
+                    record.error = Some(error.to_string());
+                }
+            }
+        }
+    }
+
+    fn emit(&self, event: &StreamEvent) {
+        if self.format == OutputFormat::StreamJson {
+            print_json(event, false);
+        }
+    }
+}
+
+fn print_json(value: &impl Serialize, pretty: bool) {
+    let json = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    match json {
+        Ok(json) => {
+            let mut stdout = std::io::stdout().lock();
+            let _ = writeln!(stdout, "{}", json);
+            let _ = stdout.flush();
+        }
+        Err(e) => tracing::error!("Failed to serialize run output: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::handler::ToolError;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    #[test]
+    fn test_run_output_tracks_tool_calls_and_response() {
+        let mut output = RunOutput::new(OutputFormat::Json);
+        output.message(&Message::user().with_text("List the files"));
+        output.message(&Message::assistant().with_tool_request(
+            "1",
+            Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+        ));
+        output.message(&Message::assistant().with_tool_request(
+            "2",
+            Ok(ToolCall::new(
+                "developer__shell",
+                json!({"command": "cat x"}),
+            )),
+        ));
+        output.message(&Message::user().with_tool_response("1", Ok(vec![Content::text("x")])));
+        output.message(&Message::user().with_tool_response(
+            "2",
+            Err(ToolError::ExecutionError("no such file".to_string())),
+        ));
+        output.message(&Message::assistant().with_text("There is one file."));
+
+        let result = output.into_result(Some("20250101_1".to_string()), None);
+
+        assert_eq!(result.status, RunStatus::Success);
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(result.response, "There is one file.");
+        assert_eq!(result.tool_calls.len(), 2);
+        assert_eq!(result.tool_calls[0].status, ToolCallStatus::Success);
+        assert_eq!(result.tool_calls[1].status, ToolCallStatus::Error);
+        assert!(result.tool_calls[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("no such file"));
+    }
+
+    #[test]
+    fn test_run_output_status() {
+        let mut output = RunOutput::new(OutputFormat::Text);
+        output.max_turns();
+        assert_eq!(output.status, RunStatus::MaxTurns);
+
+        output.fail("connection reset");
+        output.fail("interrupted");
+        let result = output.into_result(None, None);
+        assert_eq!(result.status, RunStatus::Error);
+        assert_eq!(result.exit_code, 1);
+        assert_eq!(result.error.as_deref(), Some("connection reset"));
+        assert_eq!(RunStatus::MaxTurns.exit_code(), 2);
+
+        let mut output = RunOutput::new(OutputFormat::Text);
+        output.fail("overloaded");
+        output.max_turns();
+        assert_eq!(output.status, RunStatus::Error);
+    }
+
+    #[test]
+    fn test_stream_events_serialize_with_type() {
+        let request = ToolRequest {
+            id: "1".to_string(),
+            tool_call: Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+        };
+        let event = serde_json::to_value(StreamEvent::ToolRequest(&request)).unwrap();
+        assert_eq!(event["type"], "tool_request");
+        assert_eq!(event["id"], "1");
+        assert_eq!(event["toolCall"]["value"]["name"], "developer__shell");
+
+        let message = Message::assistant().with_text("Hello");
+        let event = serde_json::to_value(StreamEvent::Message { message: &message }).unwrap();
+        assert_eq!(event["type"], "message");
+        assert_eq!(event["message"]["content"][0]["text"], "Hello");
+    }
+}
@@ -0,0 +1,321 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub use mcp_core::checkpoint::CHECKPOINT_ARG;
+
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// Where to journal edits and which checkpoint they belong to, as passed by goose
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointContext {
+    pub dir: PathBuf,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// A file's content before one edit, or `None` if the edit created it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalEntry {
+    checkpoint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    path: PathBuf,
+    before: Option<String>,
+    created: i64,
+}
+
+/// The edits grouped under one checkpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointSummary {
+    pub name: String,
+    pub label: Option<String>,
+    pub created: i64,
+    pub files: Vec<PathBuf>,
+}
+
+/// A file put back by a restore, and whether it was removed because the agent created it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestoredFile {
+    pub path: PathBuf,
+    pub removed: bool,
+}
+
+/// Edit journal for one session, kept on disk so undo survives extension restarts
+///
+/// Every entry records the content of a file before an edit. Entries are appended in
+/// order, so rolling back a checkpoint restores the earliest content of each file
+/// touched since that checkpoint began.
+pub struct CheckpointJournal {
+    dir: PathBuf,
+}
+
+impl CheckpointJournal {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        self.dir.join(JOURNAL_FILE)
+    }
+
+    fn read_entries(&self) -> Result<Vec<JournalEntry>> {
+        let path = self.journal_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read checkpoint journal {}", path.display()))?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).context("Corrupt checkpoint journal entry"))
+            .collect()
+    }
+
+    fn write_entries(&self, entries: &[JournalEntry]) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        let tmp = self.dir.join(format!("{}.tmp", JOURNAL_FILE));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, self.journal_path())?;
+        Ok(())
+    }
+
+    /// Journal the current content of `path` before it is edited under `context`
+    pub fn record(&self, context: &CheckpointContext, path: &Path) -> Result<()> {
+        let before = if path.exists() {
+            Some(
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?,
+            )
+        } else {
+            None
+        };
+        let entry = JournalEntry {
+            checkpoint: context.name.clone(),
+            label: context.label.clone(),
+            path: path.to_path_buf(),
+            before,
+            created: chrono::Utc::now().timestamp(),
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.journal_path())?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    /// Revert the most recent journaled edit to `path`, returning false if there is none
+    pub fn undo(&self, path: &Path) -> Result<bool> {
+        let mut entries = self.read_entries()?;
+        let Some(index) = entries.iter().rposition(|entry| entry.path == path) else {
+            return Ok(false);
+        };
+        let entry = entries.remove(index);
+        match &entry.before {
+            Some(content) => fs::write(path, content)?,
+            None => remove_if_exists(path)?,
+        }
+        self.write_entries(&entries)?;
+        Ok(true)
+    }
+
+    /// Checkpoints in the order they were created
+    pub fn list(&self) -> Result<Vec<CheckpointSummary>> {
+        let mut summaries: Vec<CheckpointSummary> = Vec::new();
+        for entry in self.read_entries()? {
+            match summaries.iter_mut().find(|s| s.name == entry.checkpoint) {
+                Some(summary) => {
+                    if !summary.files.contains(&entry.path) {
+                        summary.files.push(entry.path);
+                    }
+                }
+                None => summaries.push(CheckpointSummary {
+                    name: entry.checkpoint,
+                    label: entry.label,
+                    created: entry.created,
+                    files: vec![entry.path],
+                }),
+            }
+        }
+        Ok(summaries)
+    }
+
+    /// Roll back every file touched since `checkpoint` began
+    ///
+    /// The restored contents are staged next to their targets before any file is replaced,
+    /// so a failure while staging leaves the working tree untouched. Files created since the
+    /// checkpoint are removed. The journal is truncated to before the checkpoint.
+    pub fn restore(&self, checkpoint: &str) -> Result<Vec<RestoredFile>> {
+        let entries = self.read_entries()?;
+        let start = entries
+            .iter()
+            .position(|entry| entry.checkpoint == checkpoint)
+            .ok_or_else(|| {
+                let mut names: Vec<&str> = Vec::new();
+                for entry in &entries {
+                    if !names.contains(&entry.checkpoint.as_str()) {
+                        names.push(&entry.checkpoint);
+                    }
+                }
+                anyhow::anyhow!(
+                    "Unknown checkpoint '{}'. Available checkpoints: {}",
+                    checkpoint,
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                )
+            })?;
+
+        // The first entry for each file holds its content from before the checkpoint
+        let mut seen = HashSet::new();
+        let originals: Vec<&JournalEntry> = entries[start..]
+            .iter()
+            .filter(|entry| seen.insert(entry.path.clone()))
+            .collect();
+
+        let mut staged = Vec::new();
+        for entry in &originals {
+            let Some(content) = &entry.before else {
+                continue;
+            };
+            match stage(&entry.path, content) {
+                Ok(tmp) => staged.push((tmp, &entry.path)),
+                Err(e) => {
+                    for (tmp, _) in &staged {
+                        let _ = fs::remove_file(tmp);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        for (tmp, path) in &staged {
+            fs::rename(tmp, path)
+                .with_context(|| format!("Failed to restore {}", path.display()))?;
+        }
+        for entry in originals.iter().filter(|entry| entry.before.is_none()) {
+            remove_if_exists(&entry.path)?;
+        }
+
+        self.write_entries(&entries[..start])?;
+
+        Ok(originals
+            .into_iter()
+            .map(|entry| RestoredFile {
+                path: entry.path.clone(),
+                removed: entry.before.is_none(),
+            })
+            .collect())
+    }
+}
+
+/// Write `content` to a hidden file beside `path`, ready to be renamed over it
+fn stage(path: &Path, content: &str) -> Result<PathBuf> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path {}", path.display()))?;
+    fs::create_dir_all(parent)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path {}", path.display()))?;
+    let tmp = parent.join(format!(".{}.goose-restore", file_name.to_string_lossy()));
+    fs::write(&tmp, content).with_context(|| format!("Failed to stage {}", path.display()))?;
+    Ok(tmp)
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn context(dir: &Path, name: &str) -> CheckpointContext {
+        CheckpointContext {
+            dir: dir.join("checkpoints"),
+            name: name.to_string(),
+            label: None,
+        }
+    }
+
+    fn edit(journal: &CheckpointJournal, context: &CheckpointContext, path: &Path, text: &str) {
+        journal.record(context, path).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_restore_rolls_back_all_files_since_checkpoint() {
+        let dir = tempdir().unwrap();
+        let first = context(dir.path(), "1");
+        let second = context(dir.path(), "2");
+        let journal = CheckpointJournal::new(&first.dir);
+
+        let existing = dir.path().join("existing.txt");
+        let created = dir.path().join("created.txt");
+        fs::write(&existing, "original").unwrap();
+
+        edit(&journal, &first, &existing, "turn one");
+        edit(&journal, &second, &existing, "turn two");
+        edit(&journal, &second, &created, "new file");
+        edit(&journal, &second, &existing, "turn two again");
+
+        let summaries = journal.list().unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[1].files, vec![existing.clone(), created.clone()]);
+
+        let restored = journal.restore("2").unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "turn one");
+        assert!(!created.exists());
+        assert_eq!(journal.list().unwrap().len(), 1);
+
+        journal.restore("1").unwrap();
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "original");
+        assert!(journal.list().unwrap().is_empty());
+
+        let err = journal.restore("2").unwrap_err();
+        assert!(err.to_string().contains("Unknown checkpoint '2'"));
+    }
+
+    #[test]
+    fn test_undo_survives_new_journal_instance() {
+        let dir = tempdir().unwrap();
+        let context = context(dir.path(), "1");
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "v1").unwrap();
+
+        let journal = CheckpointJournal::new(&context.dir);
+        edit(&journal, &context, &file, "v2");
+        edit(&journal, &context, &file, "v3");
+
+        // A restarted extension reads the same journal from disk
+        let journal = CheckpointJournal::new(&context.dir);
+        assert!(journal.undo(&file).unwrap());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v2");
+        assert!(journal.undo(&file).unwrap());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v1");
+        assert!(!journal.undo(&file).unwrap());
+    }
+}
@@ -1,6 +1,10 @@
+pub mod checkpoint;
 mod editor_models;
 mod lang;
+mod patch;
+mod sandbox;
 mod shell;
+mod symbols;
 
 use anyhow::Result;
 use base64::Engine;
@@ -10,17 +14,22 @@ use serde_json::Value;
 use std::{
     collections::HashMap,
     future::Future,
-    io::Cursor,
+    io::{Cursor, Write},
     path::{Path, PathBuf},
     pin::Pin,
 };
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
-    process::Command,
+    process::{Child, Command},
     sync::mpsc,
 };
 use url::Url;
 
+#[cfg(unix)]
+use nix::sys::signal::{kill, Signal};
+#[cfg(unix)]
+use nix::unistd::Pid;
+
 use include_dir::{include_dir, Dir};
 use mcp_core::{
     handler::{PromptError, ResourceError, ToolError},
@@ -36,15 +45,22 @@ use rmcp::model::{
 };
 use rmcp::object;
 
+use self::checkpoint::{CheckpointContext, CheckpointJournal, CHECKPOINT_ARG};
 use self::editor_models::{create_editor_model, EditorModel};
-use self::shell::{expand_path, get_shell_config, is_absolute_path, normalize_line_endings};
+use self::shell::{
+    expand_path, get_shell_config, is_absolute_path, normalize_line_endings, resolve_symlinks,
+};
 use indoc::indoc;
+use mcp_core::sandbox::SandboxConfig;
 use std::process::Stdio;
 use std::sync::{Arc, Mutex};
 use xcap::{Monitor, Window};
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
+/// Source files with the symbols parsed from each, or the reason it couldn't be parsed
+type ParsedFiles = Vec<(PathBuf, Result<Vec<symbols::Symbol>>)>;
+
 // Embeds the prompts directory to the build
 static PROMPTS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/developer/prompts");
 
@@ -95,9 +111,20 @@ pub struct DeveloperRouter {
     tools: Vec<Tool>,
     prompts: Arc<HashMap<String, Prompt>>,
     instructions: String,
-    file_history: Arc<Mutex<HashMap<PathBuf, Vec<String>>>>,
+    file_history: Arc<Mutex<HashMap<PathBuf, Vec<Option<String>>>>>,
     ignore_patterns: Arc<Gitignore>,
     editor_model: Option<EditorModel>,
+    sandbox: Option<SandboxConfig>,
+}
+
+/// Kill a shell command along with the processes it started
+async fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // The shell leads its own process group, so the group id is its pid
+        let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGKILL);
+    }
+    let _ = child.kill().await;
 }
 
 impl Default for DeveloperRouter {
@@ -266,6 +293,8 @@ impl DeveloperRouter {
                 - `edit_file`: Edit the file with the new content.
                 - `insert`: Insert text at a specific line location in the file.
                 - `undo_edit`: Undo the last edit made to a file.
+                - `apply_patch`: Apply a unified diff to one or more files.
+                - `restore_checkpoint`: Roll back every file edited since the start of an earlier turn.
 
                 To use the write command, you must specify `file_text` which will become the new content of the file. Be careful with
                 existing files! This is a full overwrite, so you must include everything - not just sections you are modifying.
@@ -274,6 +303,14 @@ impl DeveloperRouter {
 
                 To use the insert command, you must specify both `insert_line` (the line number after which to insert, 0 for beginning) 
                 and `new_str` (the text to insert).
+
+                To use the apply_patch command, specify the unified diff in `patch`. It can span several files and hunks, and
+                can create or delete files using /dev/null. Relative paths in the diff are resolved against `path` if given,
+                otherwise the current directory, and every file in the diff must be inside that directory. Prefer apply_patch over many str_replace calls for larger changes. If any hunk
+                doesn't match, nothing is written unless `allow_partial` is true.
+
+                To use the restore_checkpoint command, specify the `checkpoint` to roll back to; `path` is not needed. Files created
+                since that checkpoint are removed.
             "#, editor.get_str_replace_description()},
                 "edit_file",
             )
@@ -287,6 +324,8 @@ impl DeveloperRouter {
                 - `str_replace`: Replace a string in a file with a new string.
                 - `insert`: Insert text at a specific line location in the file.
                 - `undo_edit`: Undo the last edit made to a file.
+                - `apply_patch`: Apply a unified diff to one or more files.
+                - `restore_checkpoint`: Roll back every file edited since the start of an earlier turn.
 
                 To use the write command, you must specify `file_text` which will become the new content of the file. Be careful with
                 existing files! This is a full overwrite, so you must include everything - not just sections you are modifying.
@@ -297,6 +336,14 @@ impl DeveloperRouter {
 
                 To use the insert command, you must specify both `insert_line` (the line number after which to insert, 0 for beginning) 
                 and `new_str` (the text to insert).
+
+                To use the apply_patch command, specify the unified diff in `patch`. It can span several files and hunks, and
+                can create or delete files using /dev/null. Relative paths in the diff are resolved against `path` if given,
+                otherwise the current directory, and every file in the diff must be inside that directory. Prefer apply_patch over many str_replace calls for larger changes. If any hunk
+                doesn't match, nothing is written unless `allow_partial` is true.
+
+                To use the restore_checkpoint command, specify the `checkpoint` to roll back to; `path` is not needed. Files created
+                since that checkpoint are removed.
             "#}.to_string(), "str_replace")
         };
 
@@ -305,16 +352,16 @@ impl DeveloperRouter {
             text_editor_desc.to_string(),
             object!({
                 "type": "object",
-                "required": ["command", "path"],
+                "required": ["command"],
                 "properties": {
                     "path": {
-                        "description": "Absolute path to file or directory, e.g. `/repo/file.py` or `/repo`.",
+                        "description": "Absolute path to file or directory, e.g. `/repo/file.py` or `/repo`. For apply_patch, the directory relative paths in the patch are resolved against.",
                         "type": "string"
                     },
                     "command": {
                         "type": "string",
-                        "enum": ["view", "write", str_replace_command, "insert", "undo_edit"],
-                        "description": format!("Allowed options are: `view`, `write`, `{}`, `insert`, `undo_edit`.", str_replace_command)
+                        "enum": ["view", "write", str_replace_command, "insert", "undo_edit", "apply_patch", "restore_checkpoint"],
+                        "description": format!("Allowed options are: `view`, `write`, `{}`, `insert`, `undo_edit`, `apply_patch`, `restore_checkpoint`.", str_replace_command)
                     },
                     "view_range": {
                         "type": "array",
@@ -329,11 +376,97 @@ impl DeveloperRouter {
                     },
                     "old_str": {"type": "string"},
                     "new_str": {"type": "string"},
-                    "file_text": {"type": "string"}
+                    "file_text": {"type": "string"},
+                    "patch": {
+                        "type": "string",
+                        "description": "A unified diff with --- and +++ file headers and @@ hunks. This parameter is required when using the apply_patch command."
+                    },
+                    "allow_partial": {
+                        "type": "boolean",
+                        "description": "For apply_patch, write the hunks that matched even if others were rejected. Defaults to false."
+                    },
+                    "checkpoint": {
+                        "type": "string",
+                        "description": "The checkpoint to roll back to. This parameter is required when using the restore_checkpoint command."
+                    }
                 }
             }),
         );
 
+        let list_symbols_tool = Tool::new(
+            "list_symbols".to_string(),
+            indoc! {r#"
+                List the functions, types, impls and other named items defined in a source file or directory.
+
+                Each symbol is shown with its line range and kind, with methods nested under their class or impl.
+                Use this to get an outline of code before viewing it, rather than reading whole files.
+                Supports Rust, Python, JavaScript, TypeScript, Go, Java, C, C++, Ruby, PHP, Swift and Kotlin.
+                Directories are searched recursively and respect .gitignore and .gooseignore patterns.
+            "#}.to_string(),
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {"type": "string", "description": "Absolute path to a source file or directory"}
+                }
+            })
+        ).annotate(ToolAnnotations {
+            title: Some("List code symbols".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        let find_definition_tool = Tool::new(
+            "find_definition".to_string(),
+            indoc! {r#"
+                Find where a function, type or other named item is defined.
+
+                The symbol can be a bare name like `parse` or qualified by its class or impl like `Parser::parse`
+                or `Parser.parse`. Returns the file, line and signature of each definition. Use `view_symbol`
+                to read one of them.
+            "#}.to_string(),
+            object!({
+                "type": "object",
+                "required": ["symbol"],
+                "properties": {
+                    "symbol": {"type": "string", "description": "The name of the symbol to find"},
+                    "path": {"type": "string", "description": "Absolute path to the directory to search (defaults to current directory)"}
+                }
+            })
+        ).annotate(ToolAnnotations {
+            title: Some("Find a definition".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        let view_symbol_tool = Tool::new(
+            "view_symbol".to_string(),
+            indoc! {r#"
+                View the source of a single function, type or other named item in a file, with line numbers.
+
+                Includes the doc comments, attributes and decorators attached to the item. Prefer this over
+                viewing the whole file or guessing line ranges when you know which item you need.
+            "#}.to_string(),
+            object!({
+                "type": "object",
+                "required": ["path", "symbol"],
+                "properties": {
+                    "path": {"type": "string", "description": "Absolute path to the source file"},
+                    "symbol": {"type": "string", "description": "The name of the item, optionally qualified like `Parser::parse`"}
+                }
+            })
+        ).annotate(ToolAnnotations {
+            title: Some("View a code symbol".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
         let list_windows_tool = Tool::new(
             "list_windows",
             indoc! {r#"
@@ -514,6 +647,15 @@ impl DeveloperRouter {
             hints.push_str(&local_hints_contents.join("\n"));
         }
 
+        let sandbox = sandbox::from_env();
+        let base_instructions = match sandbox.as_ref().filter(|sandbox| sandbox.enabled) {
+            Some(sandbox) => format!(
+                "{base_instructions}{}\n",
+                sandbox::instructions(sandbox, &cwd)
+            ),
+            None => base_instructions,
+        };
+
         // Return base instructions directly when no hints are found
         let instructions = if hints.is_empty() {
             base_instructions
@@ -577,6 +719,9 @@ impl DeveloperRouter {
                 glob_tool,
                 grep_tool,
                 text_editor_tool,
+                list_symbols_tool,
+                find_definition_tool,
+                view_symbol_tool,
                 list_windows_tool,
                 screen_capture_tool,
                 image_processor_tool,
@@ -586,6 +731,7 @@ impl DeveloperRouter {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model,
+            sandbox,
         }
     }
 
@@ -650,14 +796,32 @@ impl DeveloperRouter {
         // Get platform-specific shell configuration
         let shell_config = get_shell_config();
 
-        // Execute the command using platform-specific shell
-        let mut child = Command::new(&shell_config.executable)
+        // Execute the command using platform-specific shell, inside the sandbox if configured
+        let mut shell_command = match self.sandbox.as_ref().filter(|sandbox| sandbox.enabled) {
+            Some(sandbox) => {
+                let cwd = std::env::current_dir()
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                let (program, args) = sandbox::wrap(sandbox, &shell_config, command, &cwd)
+                    .map_err(ToolError::ExecutionError)?;
+                let mut shell_command = Command::new(program);
+                shell_command.args(args);
+                shell_command
+            }
+            None => {
+                let mut shell_command = Command::new(&shell_config.executable);
+                shell_command.args(&shell_config.args).arg(command);
+                shell_command
+            }
+        };
+        // Run the command in its own process group so a timeout also stops anything it
+        // started in the background
+        #[cfg(unix)]
+        shell_command.process_group(0);
+        let mut child = shell_command
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .stdin(Stdio::null())
             .kill_on_drop(true)
-            .args(&shell_config.args)
-            .arg(command)
             .spawn()
             .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
 
@@ -743,10 +907,29 @@ impl DeveloperRouter {
         });
 
         // Wait for the command to complete and get output
-        child
-            .wait()
-            .await
-            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        match self.sandbox.as_ref().and_then(SandboxConfig::timeout) {
+            Some(timeout) => match tokio::time::timeout(timeout, child.wait()).await {
+                Ok(status) => {
+                    status.map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                }
+                Err(_) => {
+                    kill_process_group(&mut child).await;
+                    // Anything that escaped the group could still hold the output pipes open
+                    output_task.abort();
+                    return Err(ToolError::ExecutionError(format!(
+                        "The command '{}' was stopped after the time limit of {} seconds",
+                        command,
+                        timeout.as_secs()
+                    )));
+                }
+            },
+            None => {
+                child
+                    .wait()
+                    .await
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            }
+        }
 
         let output_str = match output_task.await {
             Ok(result) => result.map_err(|e| ToolError::ExecutionError(e.to_string()))?,
@@ -844,6 +1027,21 @@ impl DeveloperRouter {
                 ToolError::InvalidParameters("Missing 'command' parameter".to_string())
             })?;
 
+        // goose passes the session's checkpoint journal and the current turn with each call
+        let checkpoint = params
+            .get(CHECKPOINT_ARG)
+            .cloned()
+            .and_then(|v| serde_json::from_value::<CheckpointContext>(v).ok());
+
+        if command == "restore_checkpoint" {
+            return self.text_editor_restore_checkpoint(&params, checkpoint.as_ref());
+        }
+        if command == "apply_patch" {
+            return self
+                .text_editor_apply_patch(&params, checkpoint.as_ref())
+                .await;
+        }
+
         let path_str = params
             .get("path")
             .and_then(|v| v.as_str())
@@ -883,7 +1081,8 @@ impl DeveloperRouter {
                         ToolError::InvalidParameters("Missing 'file_text' parameter".into())
                     })?;
 
-                self.text_editor_write(&path, file_text).await
+                self.text_editor_write(&path, file_text, checkpoint.as_ref())
+                    .await
             }
             "str_replace" | "edit_file" => {
                 let old_str = params
@@ -899,7 +1098,8 @@ impl DeveloperRouter {
                         ToolError::InvalidParameters("Missing 'new_str' parameter".into())
                     })?;
 
-                self.text_editor_replace(&path, old_str, new_str).await
+                self.text_editor_replace(&path, old_str, new_str, checkpoint.as_ref())
+                    .await
             }
             "insert" => {
                 let insert_line = params
@@ -915,9 +1115,10 @@ impl DeveloperRouter {
                         ToolError::InvalidParameters("Missing 'new_str' parameter".into())
                     })?;
 
-                self.text_editor_insert(&path, insert_line, new_str).await
+                self.text_editor_insert(&path, insert_line, new_str, checkpoint.as_ref())
+                    .await
             }
-            "undo_edit" => self.text_editor_undo(&path).await,
+            "undo_edit" => self.text_editor_undo(&path, checkpoint.as_ref()).await,
             _ => Err(ToolError::InvalidParameters(format!(
                 "Unknown command '{}'",
                 command
@@ -1058,6 +1259,7 @@ impl DeveloperRouter {
         &self,
         path: &PathBuf,
         file_text: &str,
+        checkpoint: Option<&CheckpointContext>,
     ) -> Result<Vec<Content>, ToolError> {
         // Normalize line endings based on platform
         let mut normalized_text = normalize_line_endings(file_text); // Make mutable
@@ -1067,6 +1269,9 @@ impl DeveloperRouter {
             normalized_text.push('\n');
         }
 
+        // Save history for undo, including whether the file existed
+        self.save_file_history(path, checkpoint)?;
+
         // Write to the file
         std::fs::write(path, &normalized_text) // Write the potentially modified text
             .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
@@ -1100,6 +1305,7 @@ impl DeveloperRouter {
         path: &PathBuf,
         old_str: &str,
         new_str: &str,
+        checkpoint: Option<&CheckpointContext>,
     ) -> Result<Vec<Content>, ToolError> {
         // Check if file exists and is active
         if !path.exists() {
@@ -1116,7 +1322,7 @@ impl DeveloperRouter {
         // Check if Editor API is configured and use it as the primary path
         if let Some(ref editor) = self.editor_model {
             // Editor API path - save history then call API directly
-            self.save_file_history(path)?;
+            self.save_file_history(path, checkpoint)?;
 
             match editor.edit_code(&content, old_str, new_str).await {
                 Ok(updated_content) => {
@@ -1160,7 +1366,7 @@ impl DeveloperRouter {
         }
 
         // Save history for undo (original behavior - after validation)
-        self.save_file_history(path)?;
+        self.save_file_history(path, checkpoint)?;
 
         let new_content = content.replace(old_str, new_str);
         let normalized_content = normalize_line_endings(&new_content);
@@ -1226,6 +1432,7 @@ impl DeveloperRouter {
         path: &PathBuf,
         insert_line: usize,
         new_str: &str,
+        checkpoint: Option<&CheckpointContext>,
     ) -> Result<Vec<Content>, ToolError> {
         // Check if file exists
         if !path.exists() {
@@ -1240,7 +1447,7 @@ impl DeveloperRouter {
             .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
 
         // Save history for undo
-        self.save_file_history(path)?;
+        self.save_file_history(path, checkpoint)?;
 
         let lines: Vec<&str> = content.lines().collect();
         let total_lines = lines.len();
@@ -1330,39 +1537,553 @@ impl DeveloperRouter {
         ])
     }
 
-    async fn text_editor_undo(&self, path: &PathBuf) -> Result<Vec<Content>, ToolError> {
-        let mut history = self.file_history.lock().unwrap();
-        if let Some(contents) = history.get_mut(path) {
-            if let Some(previous_content) = contents.pop() {
-                // Write previous content back to file
-                std::fs::write(path, previous_content).map_err(|e| {
-                    ToolError::ExecutionError(format!("Failed to write file: {}", e))
-                })?;
+    async fn text_editor_undo(
+        &self,
+        path: &PathBuf,
+        checkpoint: Option<&CheckpointContext>,
+    ) -> Result<Vec<Content>, ToolError> {
+        // With a checkpoint context the journal is the only undo history, since /rewind
+        // restores files through the journal without going through this extension
+        if let Some(checkpoint) = checkpoint {
+            let undone = CheckpointJournal::new(&checkpoint.dir)
+                .undo(path)
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to undo edit: {}", e)))?;
+            return if undone {
                 Ok(vec![Content::text("Undid the last edit")])
             } else {
                 Err(ToolError::InvalidParameters(
                     "No edit history available to undo".into(),
                 ))
+            };
+        }
+
+        let previous = self
+            .file_history
+            .lock()
+            .unwrap()
+            .get_mut(path)
+            .and_then(|contents| contents.pop());
+
+        match previous {
+            Some(Some(previous_content)) => {
+                // Write previous content back to file
+                std::fs::write(path, previous_content).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to write file: {}", e))
+                })?;
+                Ok(vec![Content::text("Undid the last edit")])
             }
-        } else {
-            Err(ToolError::InvalidParameters(
+            Some(None) => {
+                // The file didn't exist before the edit
+                std::fs::remove_file(path).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to remove file: {}", e))
+                })?;
+                Ok(vec![Content::text("Undid the last edit")])
+            }
+            None => Err(ToolError::InvalidParameters(
                 "No edit history available to undo".into(),
-            ))
+            )),
         }
     }
 
-    fn save_file_history(&self, path: &PathBuf) -> Result<(), ToolError> {
+    fn save_file_history(
+        &self,
+        path: &PathBuf,
+        checkpoint: Option<&CheckpointContext>,
+    ) -> Result<(), ToolError> {
+        if let Some(checkpoint) = checkpoint {
+            CheckpointJournal::new(&checkpoint.dir)
+                .record(checkpoint, path)
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to journal edit: {}", e)))?;
+            return Ok(());
+        }
+
         let mut history = self.file_history.lock().unwrap();
-        let content = if path.exists() {
-            std::fs::read_to_string(path)
-                .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?
-        } else {
-            String::new()
-        };
+        let content =
+            if path.exists() {
+                Some(std::fs::read_to_string(path).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to read file: {}", e))
+                })?)
+            } else {
+                None
+            };
         history.entry(path.clone()).or_default().push(content);
         Ok(())
     }
 
+    async fn text_editor_apply_patch(
+        &self,
+        params: &Value,
+        checkpoint: Option<&CheckpointContext>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let patch_text = params
+            .get("patch")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'patch' parameter".into()))?;
+        let allow_partial = params
+            .get("allow_partial")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let base_dir = match params.get("path").and_then(|v| v.as_str()) {
+            Some(path_str) => self.resolve_path(path_str)?,
+            None => std::env::current_dir().expect("should have a current working dir"),
+        };
+
+        let file_patches = patch::parse_patch(patch_text)
+            .map_err(|e| ToolError::InvalidParameters(format!("Invalid patch: {}", e)))?;
+
+        // Work out every file's new content before writing anything
+        let mut changes: Vec<(PathBuf, Option<String>)> = Vec::new();
+        let mut patched = 0;
+        let mut report = Vec::new();
+        let mut clean = true;
+        let base_dir_resolved = resolve_symlinks(&base_dir);
+        for file_patch in &file_patches {
+            // Paths come from the patch rather than the tool arguments, so they must stay
+            // under the base directory the caller, and any policy on `path`, agreed to
+            let resolve = |patch_path: &str| {
+                let expanded = expand_path(patch_path);
+                let path = if is_absolute_path(&expanded) {
+                    PathBuf::from(expanded)
+                } else {
+                    base_dir.join(patch_path)
+                };
+                let climbs = path
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir));
+                if climbs || !resolve_symlinks(&path).starts_with(&base_dir_resolved) {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "The patch path {} is outside {}, set `path` to a directory that contains every file in the patch",
+                        patch_path,
+                        base_dir.display()
+                    )));
+                }
+                Ok(path)
+            };
+            let old_path = file_patch.old_path.as_deref().map(resolve).transpose()?;
+            let new_path = file_patch.new_path.as_deref().map(resolve).transpose()?;
+            let renamed_from = match (&old_path, &new_path) {
+                (Some(old_path), Some(new_path)) if old_path != new_path => Some(old_path),
+                _ => None,
+            };
+            let path = new_path
+                .clone()
+                .or_else(|| old_path.clone())
+                .expect("a file patch has an old or new path");
+
+            if let Some(ignored) = [&old_path, &new_path]
+                .into_iter()
+                .flatten()
+                .find(|p| self.is_ignored(p))
+            {
+                report.push(format!(
+                    "{}: rejected, access is restricted by .gooseignore",
+                    ignored.display()
+                ));
+                clean = false;
+                continue;
+            }
+
+            let original = match &old_path {
+                Some(old_path) => match std::fs::read_to_string(old_path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        report.push(format!(
+                            "{}: rejected, failed to read: {}",
+                            old_path.display(),
+                            e
+                        ));
+                        clean = false;
+                        continue;
+                    }
+                },
+                None => String::new(),
+            };
+            if (old_path.is_none() || renamed_from.is_some()) && path.exists() {
+                report.push(format!(
+                    "{}: rejected, the patch creates this file but it already exists",
+                    path.display()
+                ));
+                clean = false;
+                continue;
+            }
+
+            let (content, outcomes) = patch::apply_hunks(&original, &file_patch.hunks);
+            let applied = outcomes.iter().filter(|o| o.is_applied()).count();
+            if new_path.is_none() {
+                report.push(format!("{}: deleted", path.display()));
+            } else if let Some(old_path) = renamed_from {
+                report.push(format!(
+                    "{}: renamed from {}, {} of {} hunks applied",
+                    path.display(),
+                    old_path.display(),
+                    applied,
+                    outcomes.len()
+                ));
+            } else {
+                report.push(format!(
+                    "{}: {} of {} hunks applied",
+                    path.display(),
+                    applied,
+                    outcomes.len()
+                ));
+            }
+            for (hunk, outcome) in file_patch.hunks.iter().zip(&outcomes) {
+                report.push(format!("  {} {}", hunk.header, outcome));
+            }
+
+            if applied < outcomes.len() {
+                clean = false;
+                // A file is only deleted once everything the patch expects to remove matched
+                if applied == 0 || new_path.is_none() {
+                    continue;
+                }
+            }
+            let content = new_path.as_ref().map(|_| content);
+            changes.push((path, content));
+            // A rename writes the new file, then deletes the old one
+            if let Some(old_path) = renamed_from {
+                changes.push((old_path.clone(), None));
+            }
+            patched += 1;
+        }
+
+        let report = report.join("\n");
+        if !clean && !allow_partial {
+            return Err(ToolError::ExecutionError(formatdoc! {r#"
+                {report}
+
+                The patch did not apply cleanly, so no files were changed. Fix the rejected hunks and try again,
+                or set `allow_partial` to write the hunks that matched.
+                "#,
+                report=report,
+            }));
+        }
+
+        // New contents go to temporary files next to their targets first, so a failed write
+        // leaves every file as it was. They're dropped, and removed, on error.
+        let mut staged = Vec::new();
+        for (path, content) in &changes {
+            let Some(content) = content else {
+                continue;
+            };
+            let parent = path.parent().unwrap_or(&base_dir);
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to create directory: {}", e))
+            })?;
+            let mut file = tempfile::NamedTempFile::new_in(parent).map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to create temporary file: {}", e))
+            })?;
+            file.write_all(normalize_line_endings(content).as_bytes())
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to write file: {}", e)))?;
+            staged.push(file);
+        }
+        for (path, _) in &changes {
+            self.save_file_history(path, checkpoint)?;
+        }
+
+        let mut staged = staged.into_iter();
+        for (path, content) in &changes {
+            match content {
+                Some(_) => {
+                    let file = staged.next().expect("every written file was staged");
+                    file.persist(path).map_err(|e| {
+                        ToolError::ExecutionError(format!("Failed to write file: {}", e))
+                    })?;
+                }
+                None => std::fs::remove_file(path).map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to delete file: {}", e))
+                })?,
+            }
+        }
+
+        let summary = format!(
+            "Patched {} of {} files\n{}",
+            patched,
+            file_patches.len(),
+            report
+        );
+        Ok(vec![
+            Content::text(summary.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(summary)
+                .with_audience(vec![Role::User])
+                .with_priority(0.2),
+        ])
+    }
+
+    fn text_editor_restore_checkpoint(
+        &self,
+        params: &Value,
+        checkpoint: Option<&CheckpointContext>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let Some(context) = checkpoint else {
+            return Err(ToolError::ExecutionError(
+                "Checkpoints are not available outside a goose session".into(),
+            ));
+        };
+        let journal = CheckpointJournal::new(&context.dir);
+
+        let name = match params.get("checkpoint").and_then(|v| v.as_str()) {
+            Some(name) => name,
+            None => {
+                let names: Vec<String> = journal
+                    .list()
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+                    .into_iter()
+                    .map(|summary| summary.name)
+                    .collect();
+                return Err(ToolError::InvalidParameters(format!(
+                    "Missing 'checkpoint' parameter. Available checkpoints: {}",
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                )));
+            }
+        };
+
+        let restored = journal
+            .restore(name)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        // Undo history from before the restore no longer applies to these files
+        let mut history = self.file_history.lock().unwrap();
+        for file in &restored {
+            history.remove(&file.path);
+        }
+
+        let mut summary = format!("Restored checkpoint {} ({} files)", name, restored.len());
+        for file in &restored {
+            let action = if file.removed { "removed" } else { "restored" };
+            summary.push_str(&format!("\n- {} {}", action, file.path.display()));
+        }
+        Ok(vec![Content::text(summary)])
+    }
+
+    /// Parse the files with symbols under `path`, or `path` itself when it is a file
+    ///
+    /// Walking and parsing up to `MAX_SYMBOL_FILES` files is CPU bound, so it runs on the
+    /// blocking pool rather than stalling the runtime.
+    async fn parse_symbol_files(&self, path: &Path) -> Result<(ParsedFiles, bool), ToolError> {
+        const MAX_SYMBOL_FILES: usize = 500;
+
+        if self.is_ignored(path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+        if !path.exists() {
+            return Err(ToolError::InvalidParameters(format!(
+                "The path '{}' does not exist",
+                path.display()
+            )));
+        }
+
+        let path = path.to_path_buf();
+        let ignore_patterns = Arc::clone(&self.ignore_patterns);
+        tokio::task::spawn_blocking(move || {
+            let (files, truncated) = if path.is_dir() {
+                symbols::source_files(
+                    &path,
+                    |p| ignore_patterns.matched(p, false).is_ignore(),
+                    MAX_SYMBOL_FILES,
+                )
+            } else {
+                (vec![path], false)
+            };
+            let parsed = files
+                .into_iter()
+                .map(|file| {
+                    let symbols = symbols::parse_file(&file).map(|(_, symbols)| symbols);
+                    (file, symbols)
+                })
+                .collect();
+            (parsed, truncated)
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to parse symbols: {}", e)))
+    }
+
+    async fn list_symbols(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path_str = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        let path = self.resolve_path(path_str)?;
+        let (files, truncated) = self.parse_symbol_files(&path).await?;
+
+        let mut output = Vec::new();
+        for (file, symbols) in &files {
+            let symbols = match symbols {
+                Ok(symbols) => symbols,
+                // A single file that can't be parsed is an error, other files in a directory are skipped
+                Err(e) if path.is_file() => return Err(ToolError::ExecutionError(e.to_string())),
+                Err(_) => continue,
+            };
+            if symbols.is_empty() {
+                continue;
+            }
+            output.push(file.display().to_string());
+            for symbol in symbols {
+                let indent = if symbol.parent.is_some() {
+                    "    "
+                } else {
+                    "  "
+                };
+                output.push(format!(
+                    "{}{}-{} {} {}",
+                    indent, symbol.start_line, symbol.end_line, symbol.kind, symbol.name
+                ));
+            }
+        }
+
+        if output.is_empty() {
+            output.push(format!("No symbols found in {}", path.display()));
+        }
+        if truncated {
+            output.push(format!(
+                "Only the first {} source files were listed, narrow the path to see more",
+                files.len()
+            ));
+        }
+
+        let result = output.join("\n");
+        Ok(vec![
+            Content::text(result.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(result)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn find_definition(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let symbol = params
+            .get("symbol")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'symbol' parameter".into()))?;
+        let path = match params.get("path").and_then(|v| v.as_str()) {
+            Some(path_str) => self.resolve_path(path_str)?,
+            None => std::env::current_dir().expect("should have a current working dir"),
+        };
+        let (files, truncated) = self.parse_symbol_files(&path).await?;
+
+        let mut output = Vec::new();
+        for (file, symbols) in &files {
+            let Ok(symbols) = symbols else {
+                continue;
+            };
+            for found in symbols.iter().filter(|s| s.matches(symbol)) {
+                output.push(format!(
+                    "{}:{} {} {}\n    {}",
+                    file.display(),
+                    found.start_line,
+                    found.kind,
+                    found.qualified_name(),
+                    found.signature
+                ));
+            }
+        }
+
+        if output.is_empty() {
+            output.push(format!(
+                "No definition of '{}' found in {} source files under {}",
+                symbol,
+                files.len(),
+                path.display()
+            ));
+        }
+        if truncated {
+            output.push(format!(
+                "Only the first {} source files were searched, narrow the path to search the rest",
+                files.len()
+            ));
+        }
+
+        let result = output.join("\n");
+        Ok(vec![
+            Content::text(result.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(result)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    async fn view_symbol(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path_str = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+        let symbol = params
+            .get("symbol")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'symbol' parameter".into()))?;
+        let path = self.resolve_path(path_str)?;
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
+        if !path.is_file() {
+            return Err(ToolError::InvalidParameters(format!(
+                "The path '{}' is not a file",
+                path.display()
+            )));
+        }
+
+        let parse_path = path.clone();
+        let (source, symbols) =
+            tokio::task::spawn_blocking(move || symbols::parse_file(&parse_path))
+                .await
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to parse symbols: {}", e)))?
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        let matches: Vec<_> = symbols.iter().filter(|s| s.matches(symbol)).collect();
+        if matches.is_empty() {
+            let mut names: Vec<String> = symbols.iter().map(|s| s.qualified_name()).collect();
+            names.dedup();
+            return Err(ToolError::InvalidParameters(format!(
+                "No symbol named '{}' in {}. Defined symbols: {}",
+                symbol,
+                path.display(),
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            )));
+        }
+
+        let language = lang::get_language_identifier(&path);
+        let sections: Vec<String> = matches
+            .iter()
+            .map(|found| {
+                formatdoc! {r#"
+                    ### {path}:{start}-{end} ({kind} {name})
+                    ```{language}
+                    {content}
+                    ```
+                    "#,
+                    path=path.display(),
+                    start=found.doc_start_line,
+                    end=found.end_line,
+                    kind=found.kind,
+                    name=found.qualified_name(),
+                    language=language,
+                    content=symbols::numbered_lines(&source, found.doc_start_line, found.end_line),
+                }
+            })
+            .collect();
+
+        let result = sections.join("\n");
+        Ok(vec![
+            Content::text(result.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(result)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
     async fn list_windows(&self, _params: Value) -> Result<Vec<Content>, ToolError> {
         let windows = Window::all()
             .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
@@ -1605,6 +2326,9 @@ impl Router for DeveloperRouter {
                 "glob" => this.glob(arguments).await,
                 "grep" => this.bash(arguments, notifier).await,
                 "text_editor" => this.text_editor(arguments).await,
+                "list_symbols" => this.list_symbols(arguments).await,
+                "find_definition" => this.find_definition(arguments).await,
+                "view_symbol" => this.view_symbol(arguments).await,
                 "list_windows" => this.list_windows(arguments).await,
                 "screen_capture" => this.screen_capture(arguments).await,
                 "image_processor" => this.image_processor(arguments).await,
@@ -1666,6 +2390,7 @@ impl Clone for DeveloperRouter {
             file_history: Arc::clone(&self.file_history),
             ignore_patterns: Arc::clone(&self.ignore_patterns),
             editor_model: create_editor_model(), // Recreate the editor model since it's not Clone
+            sandbox: self.sandbox.clone(),
         }
     }
 }
@@ -2111,6 +2836,321 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_restore_checkpoint() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let existing = temp_dir.path().join("existing.txt");
+        let created = temp_dir.path().join("created.txt");
+        fs::write(&existing, "original\n").unwrap();
+
+        let checkpoint = |name: &str| {
+            json!({
+                "dir": temp_dir.path().join("checkpoints"),
+                "name": name
+            })
+        };
+
+        for (path, text, name) in [
+            (&existing, "first turn", "1"),
+            (&existing, "second turn", "2"),
+            (&created, "created in second turn", "2"),
+        ] {
+            router
+                .call_tool(
+                    "text_editor",
+                    json!({
+                        "command": "write",
+                        "path": path.to_str().unwrap(),
+                        "file_text": text,
+                        CHECKPOINT_ARG: checkpoint(name)
+                    }),
+                    dummy_sender(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "restore_checkpoint",
+                    "checkpoint": "2",
+                    CHECKPOINT_ARG: checkpoint("3")
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let text = result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("Restored checkpoint 2 (2 files)"));
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "first turn\n");
+        assert!(!created.exists());
+
+        // An unknown checkpoint lists the ones that can still be restored
+        let err = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "restore_checkpoint",
+                    "checkpoint": "2",
+                    CHECKPOINT_ARG: checkpoint("3")
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Available checkpoints: 1"));
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_undo_after_rewind() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let file_path = temp_dir.path().join("file.txt");
+        fs::write(&file_path, "original\n").unwrap();
+        let checkpoint_dir = temp_dir.path().join("checkpoints");
+
+        for (text, name) in [("first turn", "1"), ("second turn", "2")] {
+            router
+                .call_tool(
+                    "text_editor",
+                    json!({
+                        "command": "write",
+                        "path": file_path.to_str().unwrap(),
+                        "file_text": text,
+                        CHECKPOINT_ARG: {"dir": checkpoint_dir, "name": name}
+                    }),
+                    dummy_sender(),
+                )
+                .await
+                .unwrap();
+        }
+
+        // /rewind restores through the journal without going through the extension
+        CheckpointJournal::new(&checkpoint_dir)
+            .restore("1")
+            .unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "original\n");
+
+        // Undo must not bring back edits from before the rewind
+        let err = router
+            .call_tool(
+                "text_editor",
+                json!({
+                    "command": "undo_edit",
+                    "path": file_path.to_str().unwrap(),
+                    CHECKPOINT_ARG: {"dir": checkpoint_dir, "name": "3"}
+                }),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No edit history available to undo"));
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "original\n");
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_symbol_tools() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let file_path = temp_dir.path().join("shapes.rs");
+        fs::write(
+            &file_path,
+            "struct Circle {\n    radius: f64,\n}\n\nimpl Circle {\n    /// The area\n    fn area(&self) -> f64 {\n        3.14 * self.radius * self.radius\n    }\n}\n",
+        )
+        .unwrap();
+
+        let result = router
+            .call_tool(
+                "list_symbols",
+                json!({"path": temp_dir.path().to_str().unwrap()}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let text = result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("  1-3 struct Circle"));
+        assert!(text.text.contains("    7-9 method area"));
+
+        let result = router
+            .call_tool(
+                "find_definition",
+                json!({"symbol": "Circle::area"}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let text = result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("shapes.rs:7 method Circle::area"));
+
+        let result = router
+            .call_tool(
+                "view_symbol",
+                json!({"path": file_path.to_str().unwrap(), "symbol": "area"}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let text = result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("6:     /// The area"));
+        assert!(text.text.contains("9:     }"));
+        assert!(!text.text.contains("radius: f64"));
+
+        let err = router
+            .call_tool(
+                "view_symbol",
+                json!({"path": file_path.to_str().unwrap(), "symbol": "perimeter"}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Defined symbols: Circle, Circle::area"));
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_apply_patch() {
+        let router = get_router().await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+        let base = temp_dir.path().to_str().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+
+        let patch = "--- a/a.txt\n+++ b/a.txt\n@@ -1,3 +1,3 @@\n one\n-two\n+TWO\n three\n--- /dev/null\n+++ b/sub/new.txt\n@@ -0,0 +1 @@\n+created\n";
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({"command": "apply_patch", "path": base, "patch": patch}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let text = result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("Patched 2 of 2 files"));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("a.txt")).unwrap(),
+            "one\nTWO\nthree\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("sub/new.txt")).unwrap(),
+            "created\n"
+        );
+
+        // A rejected hunk leaves every file untouched unless partial application is allowed
+        let patch =
+            "--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-one\n+ONE\n@@ -3 +3 @@\n-missing\n+gone\n";
+        let err = router
+            .call_tool(
+                "text_editor",
+                json!({"command": "apply_patch", "path": base, "patch": patch}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("1 of 2 hunks applied"));
+        assert!(err.to_string().contains("no files were changed"));
+        assert!(fs::read_to_string(temp_dir.path().join("a.txt"))
+            .unwrap()
+            .starts_with("one\n"));
+
+        router
+            .call_tool(
+                "text_editor",
+                json!({"command": "apply_patch", "path": base, "patch": patch, "allow_partial": true}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("a.txt")).unwrap(),
+            "ONE\nTWO\nthree\n"
+        );
+
+        // Each patched file can be undone
+        let a_path = temp_dir.path().join("a.txt");
+        router
+            .call_tool(
+                "text_editor",
+                json!({"command": "undo_edit", "path": a_path.to_str().unwrap()}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&a_path).unwrap(), "one\nTWO\nthree\n");
+
+        // A rename reads the old path and writes the new one
+        let patch = "--- a/a.txt\n+++ b/b.txt\n@@ -1 +1 @@\n-one\n+uno\n";
+        let result = router
+            .call_tool(
+                "text_editor",
+                json!({"command": "apply_patch", "path": base, "patch": patch}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        let text = result.first().unwrap().as_text().unwrap();
+        assert!(text.text.contains("renamed from"));
+        assert!(!a_path.exists());
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("b.txt")).unwrap(),
+            "uno\nTWO\nthree\n"
+        );
+
+        // Relative patch paths can't climb out of the base directory
+        let patch = "--- a/b.txt\n+++ b/../b.txt\n@@ -1 +1 @@\n-uno\n+one\n";
+        let err = router
+            .call_tool(
+                "text_editor",
+                json!({"command": "apply_patch", "path": base, "patch": patch}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is outside"));
+
+        // Absolute patch paths must be under the base directory too
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("target.txt");
+        let patch = format!(
+            "--- /dev/null\n+++ {}\n@@ -0,0 +1 @@\n+escaped\n",
+            target.display()
+        );
+        let err = router
+            .call_tool(
+                "text_editor",
+                json!({"command": "apply_patch", "path": base, "patch": patch}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is outside"));
+        assert!(!target.exists());
+
+        temp_dir.close().unwrap();
+    }
+
     // Test GooseIgnore pattern matching
     #[tokio::test]
     #[serial]
@@ -2131,6 +3171,7 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            sandbox: None,
         };
 
         // Test basic file matching
@@ -2164,6 +3205,49 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    #[serial]
+    async fn test_shell_timeout_applies_without_sandbox() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let router = DeveloperRouter {
+            tools: vec![],
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(GitignoreBuilder::new(temp_dir.path()).build().unwrap()),
+            editor_model: None,
+            sandbox: Some(SandboxConfig {
+                enabled: false,
+                timeout_seconds: Some(1),
+                ..SandboxConfig::default()
+            }),
+        };
+
+        let error = router
+            .call_tool("shell", json!({"command": "sleep 5"}), dummy_sender())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("time limit of 1 seconds"));
+
+        // Background processes holding the output open are stopped with the shell
+        let started = std::time::Instant::now();
+        let error = router
+            .call_tool(
+                "shell",
+                json!({"command": "sleep 30 & sleep 30 | cat"}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("time limit of 1 seconds"));
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_text_editor_respects_ignore_patterns() {
@@ -2182,6 +3266,7 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            sandbox: None,
         };
 
         // Try to write to an ignored file
@@ -2242,6 +3327,7 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            sandbox: None,
         };
 
         // Create an ignored file
@@ -0,0 +1,488 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+
+/// How many context lines may be dropped from each end of a hunk that doesn't match exactly
+const MAX_FUZZ: usize = 2;
+
+/// The changes a unified diff makes to one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePatch {
+    /// Path before the change, `None` when the patch creates the file
+    pub old_path: Option<String>,
+    /// Path after the change, `None` when the patch deletes the file
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    pub header: String,
+    /// Line the hunk claims to start at in the original file, 1-indexed
+    old_start: usize,
+    /// Lines the hunk claims to cover in the original file; when 0, `old_start` is the
+    /// line the hunk inserts after
+    old_count: usize,
+    lines: Vec<HunkLine>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// What happened to a hunk when the patch was applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum HunkOutcome {
+    Applied {
+        /// Line the hunk was applied at in the original file, 1-indexed
+        line: usize,
+        offset: isize,
+        /// How the hunk had to be loosened to match, if it didn't match exactly
+        fuzz: Option<String>,
+    },
+    Rejected(String),
+}
+
+impl HunkOutcome {
+    pub fn is_applied(&self) -> bool {
+        matches!(self, HunkOutcome::Applied { .. })
+    }
+}
+
+impl fmt::Display for HunkOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HunkOutcome::Applied { line, offset, fuzz } => {
+                write!(f, "applied at line {}", line)?;
+                if *offset != 0 {
+                    write!(f, " (offset {:+})", offset)?;
+                }
+                if let Some(fuzz) = fuzz {
+                    write!(f, " with {}", fuzz)?;
+                }
+                Ok(())
+            }
+            HunkOutcome::Rejected(reason) => write!(f, "rejected, {}", reason),
+        }
+    }
+}
+
+/// Parse a unified diff, possibly covering several files
+///
+/// Line counts in hunk headers are only used to tell insertions apart, since models often
+/// get them wrong; a hunk runs until the next hunk or file header. Blank lines missing their leading space are treated
+/// as blank context lines.
+pub fn parse_patch(text: &str) -> Result<Vec<FilePatch>> {
+    let lines: Vec<&str> = text.lines().collect();
+    let is_file_header = |i: usize| {
+        lines[i].starts_with("--- ") && lines.get(i + 1).is_some_and(|l| l.starts_with("+++ "))
+    };
+
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if is_file_header(i) {
+            let (old_path, new_path) =
+                strip_prefixes(parse_path(&lines[i][4..]), parse_path(&lines[i + 1][4..]));
+            if old_path.is_none() && new_path.is_none() {
+                return Err(anyhow!(
+                    "Both sides of the file header at line {} are /dev/null",
+                    i + 1
+                ));
+            }
+            patches.push(FilePatch {
+                old_path,
+                new_path,
+                hunks: Vec::new(),
+            });
+            i += 2;
+            continue;
+        }
+
+        if !lines[i].starts_with("@@") {
+            // diff --git, index, mode and other extended header lines
+            i += 1;
+            continue;
+        }
+
+        let patch = patches.last_mut().ok_or_else(|| {
+            anyhow!(
+                "Hunk at line {} comes before any --- / +++ file header",
+                i + 1
+            )
+        })?;
+        let header = lines[i].to_string();
+        let (old_start, old_count) = parse_old_range(&header);
+        i += 1;
+
+        let mut hunk_lines = Vec::new();
+        let mut trailing_blank = 0;
+        while i < lines.len()
+            && !lines[i].starts_with("@@")
+            && !lines[i].starts_with("diff ")
+            && !is_file_header(i)
+        {
+            let line = lines[i];
+            let parsed = match line.chars().next() {
+                None => Some(HunkLine::Context(String::new())),
+                Some(' ') => Some(HunkLine::Context(line[1..].to_string())),
+                Some('-') => Some(HunkLine::Remove(line[1..].to_string())),
+                Some('+') => Some(HunkLine::Add(line[1..].to_string())),
+                // "\ No newline at end of file"
+                Some('\\') => None,
+                Some(_) => break,
+            };
+            trailing_blank = if line.is_empty() {
+                trailing_blank + 1
+            } else {
+                0
+            };
+            hunk_lines.extend(parsed);
+            i += 1;
+        }
+        // Bare blank lines at the end of a hunk usually separate it from what follows
+        hunk_lines.truncate(hunk_lines.len() - trailing_blank);
+
+        if hunk_lines
+            .iter()
+            .any(|l| matches!(l, HunkLine::Add(_) | HunkLine::Remove(_)))
+        {
+            patch.hunks.push(Hunk {
+                header,
+                old_start,
+                old_count,
+                lines: hunk_lines,
+            });
+        }
+    }
+
+    patches.retain(|patch| !patch.hunks.is_empty() || patch.new_path.is_none());
+    if patches.is_empty() {
+        return Err(anyhow!("No file changes found in the patch"));
+    }
+    Ok(patches)
+}
+
+/// Path from a --- or +++ line, without a trailing timestamp; `None` for /dev/null
+fn parse_path(raw: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or_default().trim();
+    (path != "/dev/null" && !path.is_empty()).then(|| path.to_string())
+}
+
+/// Drop git's a/ and b/ prefixes, but only when the header actually uses them
+fn strip_prefixes(old: Option<String>, new: Option<String>) -> (Option<String>, Option<String>) {
+    let prefixed = old.as_deref().is_none_or(|p| p.starts_with("a/"))
+        && new.as_deref().is_none_or(|p| p.starts_with("b/"));
+    if !prefixed {
+        return (old, new);
+    }
+    let strip = |p: Option<String>| p.map(|p| p[2..].to_string());
+    (strip(old), strip(new))
+}
+
+/// Start and line count of the original side of a hunk header, `-N` meaning `-N,1`
+fn parse_old_range(header: &str) -> (usize, usize) {
+    let range = header
+        .strip_prefix("@@ -")
+        .and_then(|rest| rest.split(' ').next())
+        .unwrap_or_default();
+    let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+    (start.parse().unwrap_or(1), count.parse().unwrap_or(1))
+}
+
+impl Hunk {
+    /// Where the hunk expects to start in the original file, 0-indexed
+    fn expected_pos(&self) -> usize {
+        if self.old_count == 0 {
+            self.old_start
+        } else {
+            self.old_start.saturating_sub(1)
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Matching {
+    Exact,
+    TrailingWhitespace,
+    Whitespace,
+}
+
+impl Matching {
+    fn eq(self, a: &str, b: &str) -> bool {
+        match self {
+            Matching::Exact => a == b,
+            Matching::TrailingWhitespace => a.trim_end() == b.trim_end(),
+            Matching::Whitespace => a.trim() == b.trim(),
+        }
+    }
+
+    fn describe(self) -> Option<&'static str> {
+        match self {
+            Matching::Exact => None,
+            Matching::TrailingWhitespace => Some("trailing whitespace ignored"),
+            Matching::Whitespace => Some("whitespace ignored"),
+        }
+    }
+}
+
+/// Find where `old` occurs in `lines` at or after `from`, preferring positions close to `hint`
+fn find_lines(
+    lines: &[String],
+    old: &[&str],
+    hint: usize,
+    from: usize,
+    matching: Matching,
+) -> Option<usize> {
+    if old.len() > lines.len() {
+        return None;
+    }
+    let last = lines.len() - old.len();
+    let mut candidates: Vec<usize> = (from.min(last + 1)..=last).collect();
+    candidates.sort_by_key(|&pos| pos.abs_diff(hint));
+    candidates.into_iter().find(|&pos| {
+        old.iter()
+            .zip(&lines[pos..])
+            .all(|(expected, actual)| matching.eq(expected, actual))
+    })
+}
+
+/// Apply hunks in order to `original`, returning the new content and each hunk's outcome
+///
+/// Rejected hunks leave the content unchanged; the caller decides whether a partial result
+/// should be written.
+pub fn apply_hunks(original: &str, hunks: &[Hunk]) -> (String, Vec<HunkOutcome>) {
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let mut outcomes = Vec::with_capacity(hunks.len());
+    // Lines added minus lines removed by the hunks applied so far
+    let mut delta: isize = 0;
+    // Hunks apply in order and can't overlap the previous one
+    let mut from = 0;
+
+    for hunk in hunks {
+        let hint = (hunk.expected_pos() as isize + delta).max(0) as usize;
+        match locate(&lines, hunk, hint, from) {
+            Some((pos, lead, trail, fuzz)) => {
+                let hunk_lines = &hunk.lines[lead..hunk.lines.len() - trail];
+                let mut replacement = Vec::new();
+                let mut matched = 0;
+                for line in hunk_lines {
+                    match line {
+                        // Keep the file's own context lines when matching ignored whitespace
+                        HunkLine::Context(_) => {
+                            replacement.push(lines[pos + matched].clone());
+                            matched += 1;
+                        }
+                        HunkLine::Remove(_) => matched += 1,
+                        HunkLine::Add(text) => replacement.push(text.clone()),
+                    }
+                }
+                let added = replacement.len();
+                lines.splice(pos..pos + matched, replacement);
+
+                // Report where the whole hunk starts, including context dropped by fuzz
+                let original_line = (pos.saturating_sub(lead) as isize - delta).max(0) as usize + 1;
+                outcomes.push(HunkOutcome::Applied {
+                    line: original_line,
+                    offset: original_line as isize - 1 - hunk.expected_pos() as isize,
+                    fuzz,
+                });
+                delta += added as isize - matched as isize;
+                from = pos + added;
+            }
+            None => outcomes.push(HunkOutcome::Rejected(rejection_reason(hunk))),
+        }
+    }
+
+    let mut content = lines.join("\n");
+    if !content.is_empty() && (original.is_empty() || original.ends_with('\n')) {
+        content.push('\n');
+    }
+    (content, outcomes)
+}
+
+/// Find the position of a hunk, dropping up to `MAX_FUZZ` context lines from each end if needed
+///
+/// Returns the position along with how many hunk lines were dropped from the start and end.
+fn locate(
+    lines: &[String],
+    hunk: &Hunk,
+    hint: usize,
+    from: usize,
+) -> Option<(usize, usize, usize, Option<String>)> {
+    let leading = hunk
+        .lines
+        .iter()
+        .take_while(|l| matches!(l, HunkLine::Context(_)))
+        .count();
+    let trailing = hunk
+        .lines
+        .iter()
+        .rev()
+        .take_while(|l| matches!(l, HunkLine::Context(_)))
+        .count();
+
+    for fuzz in 0..=MAX_FUZZ {
+        let lead = fuzz.min(leading);
+        let trail = fuzz.min(trailing);
+        if fuzz > 0 && lead == 0 && trail == 0 {
+            break;
+        }
+        let old: Vec<&str> = hunk.lines[lead..hunk.lines.len() - trail]
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect();
+
+        if old.is_empty() {
+            if fuzz > 0 {
+                // Dropping every context line leaves nothing to check the position against
+                return None;
+            }
+            // Pure additions go where the header says, as long as that's after the last hunk
+            return Some((
+                hint.clamp(from.min(lines.len()), lines.len()),
+                lead,
+                trail,
+                None,
+            ));
+        }
+
+        for matching in [
+            Matching::Exact,
+            Matching::TrailingWhitespace,
+            Matching::Whitespace,
+        ] {
+            if let Some(pos) = find_lines(lines, &old, hint + lead, from, matching) {
+                let mut loosened: Vec<String> =
+                    matching.describe().map(String::from).into_iter().collect();
+                if fuzz > 0 {
+                    loosened.push(format!("fuzz {}", fuzz));
+                }
+                let fuzz = (!loosened.is_empty()).then(|| loosened.join(" and "));
+                return Some((pos, lead, trail, fuzz));
+            }
+        }
+    }
+    None
+}
+
+fn rejection_reason(hunk: &Hunk) -> String {
+    let expected = hunk.lines.iter().find_map(|line| match line {
+        HunkLine::Remove(text) => Some(text),
+        _ => None,
+    });
+    match expected {
+        Some(text) => format!(
+            "the lines it changes were not found, starting with {:?}",
+            text
+        ),
+        None => "its context lines were not found".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n\nfn other() {\n    todo!()\n}\n";
+
+    #[test]
+    fn test_parse_multi_file_patch() {
+        let patch = "diff --git a/src/main.rs b/src/main.rs\nindex 123..456 100644\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    let a = 1;\n+    let a = 10;\n@@ -7,2 +7,2 @@\n fn other() {\n-    todo!()\n+    unimplemented!()\n--- /dev/null\n+++ b/NEW.md\n@@ -0,0 +1,1 @@\n+# New\n";
+        let patches = parse_patch(patch).unwrap();
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].new_path.as_deref(), Some("src/main.rs"));
+        assert_eq!(patches[0].hunks.len(), 2);
+        assert_eq!(patches[0].hunks[1].old_start, 7);
+        assert_eq!(patches[1].old_path, None);
+        assert_eq!(patches[1].new_path.as_deref(), Some("NEW.md"));
+
+        assert!(parse_patch("just some text").is_err());
+        assert!(parse_patch("@@ -1 +1 @@\n-a\n+b\n").is_err());
+    }
+
+    #[test]
+    fn test_apply_with_offset_and_fuzz() {
+        let patch = "--- main.rs\n+++ main.rs\n@@ -4,3 +4,3 @@\n     let b = 2;\n-    println!(\"{}\", a + b);\n+    println!(\"{}\", a * b);\n }\n@@ -20,3 +20,3 @@\n fn other() {\n-  todo!()\n+    unimplemented!()\n }\n";
+        let patches = parse_patch(patch).unwrap();
+        let (content, outcomes) = apply_hunks(ORIGINAL, &patches[0].hunks);
+
+        assert_eq!(
+            outcomes[0],
+            HunkOutcome::Applied {
+                line: 3,
+                offset: -1,
+                fuzz: None
+            }
+        );
+        assert_eq!(
+            outcomes[1].to_string(),
+            "applied at line 7 (offset -13) with whitespace ignored"
+        );
+        assert!(content.contains("a * b"));
+        assert!(content.contains("    unimplemented!()\n}\n"));
+        assert!(!content.contains("todo!"));
+    }
+
+    #[test]
+    fn test_rejected_hunk_leaves_content() {
+        let patch = "--- main.rs\n+++ main.rs\n@@ -2,1 +2,1 @@\n-    let a = 1;\n+    let a = 5;\n@@ -3,1 +3,1 @@\n-    let c = 3;\n+    let c = 4;\n";
+        let patches = parse_patch(patch).unwrap();
+        let (content, outcomes) = apply_hunks(ORIGINAL, &patches[0].hunks);
+
+        assert!(outcomes[0].is_applied());
+        assert_eq!(
+            outcomes[1],
+            HunkOutcome::Rejected(
+                "the lines it changes were not found, starting with \"    let c = 3;\"".to_string()
+            )
+        );
+        assert!(content.contains("let a = 5;"));
+        assert!(content.contains("let b = 2;"));
+    }
+
+    #[test]
+    fn test_fuzz_drops_stale_context() {
+        let patch = "--- main.rs\n+++ main.rs\n@@ -1,4 +1,4 @@\n fn main() {\n-    let a = 1;\n+    let a = 100;\n     let stale = 0;\n";
+        let patches = parse_patch(patch).unwrap();
+        let (content, outcomes) = apply_hunks(ORIGINAL, &patches[0].hunks);
+        assert_eq!(outcomes[0].to_string(), "applied at line 1 with fuzz 1");
+        assert!(content.starts_with("fn main() {\n    let a = 100;\n    let b = 2;\n"));
+    }
+
+    #[test]
+    fn test_fuzz_never_drops_all_context() {
+        let patches =
+            parse_patch("--- a.txt\n+++ a.txt\n@@ -1,2 +1,3 @@\n foo\n+bar\n baz\n").unwrap();
+        let (content, outcomes) = apply_hunks("FOO\nBAZ\n", &patches[0].hunks);
+        assert_eq!(
+            outcomes[0],
+            HunkOutcome::Rejected("its context lines were not found".to_string())
+        );
+        assert_eq!(content, "FOO\nBAZ\n");
+    }
+
+    #[test]
+    fn test_insert_after_line() {
+        let patches = parse_patch("--- a.txt\n+++ a.txt\n@@ -2,0 +3,1 @@\n+new\n").unwrap();
+        assert_eq!(patches[0].hunks[0].old_count, 0);
+        let (content, outcomes) = apply_hunks("one\ntwo\nthree\n", &patches[0].hunks);
+        assert_eq!(content, "one\ntwo\nnew\nthree\n");
+        assert_eq!(outcomes[0].to_string(), "applied at line 3");
+    }
+
+    #[test]
+    fn test_create_file() {
+        let patches =
+            parse_patch("--- /dev/null\n+++ b/hello.txt\n@@ -0,0 +1,2 @@\n+hello\n+world\n")
+                .unwrap();
+        let (content, outcomes) = apply_hunks("", &patches[0].hunks);
+        assert!(outcomes[0].is_applied());
+        assert_eq!(content, "hello\nworld\n");
+    }
+}
@@ -0,0 +1,184 @@
+use std::path::{Path, PathBuf};
+
+use mcp_core::sandbox::{SandboxConfig, SANDBOX_ENV};
+
+use super::shell::{expand_path, ShellConfig};
+
+/// Read the sandbox settings passed by goose, if any
+///
+/// Settings that can't be parsed fall back to the strictest sandbox rather than none.
+/// Disabled settings are still returned, since their time limit applies without the sandbox.
+pub fn from_env() -> Option<SandboxConfig> {
+    let value = std::env::var(SANDBOX_ENV).ok()?;
+    Some(
+        serde_json::from_str::<SandboxConfig>(&value).unwrap_or_else(|e| {
+            tracing::warn!("Invalid {}, using the default sandbox: {}", SANDBOX_ENV, e);
+            SandboxConfig::default()
+        }),
+    )
+}
+
+/// Describe the sandbox to the model so it knows why writes or network access fail
+pub fn instructions(config: &SandboxConfig, cwd: &Path) -> String {
+    let mut writable = vec![cwd.display().to_string()];
+    writable.extend(config.writable_paths.iter().map(|p| expand_path(p)));
+    format!(
+        "Shell commands run in a sandbox. Only {} and /tmp are writable, and network access is {}.",
+        writable.join(", "),
+        if config.network {
+            "allowed"
+        } else {
+            "disabled"
+        }
+    )
+}
+
+/// The program and arguments that run `command` inside the sandbox
+pub fn wrap(
+    config: &SandboxConfig,
+    shell: &ShellConfig,
+    command: &str,
+    cwd: &Path,
+) -> Result<(PathBuf, Vec<String>), String> {
+    if !cfg!(target_os = "linux") {
+        return Err("The shell sandbox is only supported on Linux".to_string());
+    }
+    let bwrap = which::which("bwrap").map_err(|_| {
+        "The shell sandbox is enabled but bubblewrap (bwrap) is not installed".to_string()
+    })?;
+    Ok((bwrap, bwrap_args(config, shell, command, cwd)))
+}
+
+fn bwrap_args(
+    config: &SandboxConfig,
+    shell: &ShellConfig,
+    command: &str,
+    cwd: &Path,
+) -> Vec<String> {
+    let cwd = cwd.display().to_string();
+    let mut args: Vec<String> = [
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+        "--bind",
+        cwd.as_str(),
+        cwd.as_str(),
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+
+    for path in &config.writable_paths {
+        let path = expand_path(path);
+        args.extend(["--bind-try".to_string(), path.clone(), path]);
+    }
+
+    args.extend(
+        [
+            "--unshare-user",
+            "--unshare-pid",
+            "--unshare-ipc",
+            "--unshare-uts",
+            "--unshare-cgroup-try",
+        ]
+        .iter()
+        .map(|s| s.to_string()),
+    );
+    if !config.network {
+        args.push("--unshare-net".to_string());
+    }
+    args.extend(
+        [
+            "--die-with-parent",
+            "--new-session",
+            "--chdir",
+            cwd.as_str(),
+            "--",
+        ]
+        .iter()
+        .map(|s| s.to_string()),
+    );
+
+    args.push(shell.executable.clone());
+    args.extend(shell.args.iter().cloned());
+    args.push(format!("{}{}", limits_prefix(config), command));
+    args
+}
+
+/// `ulimit` calls that apply the CPU and memory limits to the command and its children
+fn limits_prefix(config: &SandboxConfig) -> String {
+    let mut prefix = String::new();
+    if let Some(seconds) = config.cpu_seconds {
+        prefix.push_str(&format!("ulimit -t {}; ", seconds));
+    }
+    if let Some(mb) = config.memory_mb {
+        prefix.push_str(&format!("ulimit -v {}; ", mb * 1024));
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn bash() -> ShellConfig {
+        ShellConfig {
+            executable: "bash".to_string(),
+            args: vec!["-c".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_defaults_to_strict() {
+        let config: SandboxConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.enabled);
+        assert!(!config.network);
+        assert!(config.writable_paths.is_empty());
+        assert_eq!(config.timeout(), None);
+
+        let config: SandboxConfig =
+            serde_json::from_str(r#"{"network": true, "timeout_seconds": 30}"#).unwrap();
+        assert!(config.network);
+        assert_eq!(config.timeout(), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_bwrap_args() {
+        let config = SandboxConfig {
+            writable_paths: vec!["/var/cache/build".to_string()],
+            cpu_seconds: Some(60),
+            memory_mb: Some(512),
+            ..SandboxConfig::default()
+        };
+        let args = bwrap_args(&config, &bash(), "cargo test", Path::new("/work/project"));
+
+        let joined = args.join(" ");
+        assert!(joined.starts_with("--ro-bind / / "));
+        assert!(joined.contains("--bind /work/project /work/project"));
+        assert!(joined.contains("--bind-try /var/cache/build /var/cache/build"));
+        assert!(joined.contains("--unshare-net"));
+        assert!(joined.contains("--chdir /work/project --"));
+
+        let script = args.last().unwrap();
+        assert_eq!(script, "ulimit -t 60; ulimit -v 524288; cargo test");
+        assert_eq!(args[args.len() - 3..args.len() - 1], ["bash", "-c"]);
+    }
+
+    #[test]
+    fn test_network_can_be_enabled() {
+        let config = SandboxConfig {
+            network: true,
+            ..SandboxConfig::default()
+        };
+        let args = bwrap_args(&config, &bash(), "curl example.com", Path::new("/work"));
+        assert!(!args.contains(&"--unshare-net".to_string()));
+        assert_eq!(args.last().unwrap(), "curl example.com");
+    }
+}
@@ -1,4 +1,5 @@
 use std::env;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct ShellConfig {
@@ -96,6 +97,26 @@ pub fn is_absolute_path(path_str: &str) -> bool {
     }
 }
 
+/// `path` with symlinks resolved in the part of it that already exists
+pub fn resolve_symlinks(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .unwrap_or_else(|_| existing.to_path_buf());
+    resolved.extend(missing.iter().rev());
+    resolved
+}
+
 pub fn normalize_line_endings(text: &str) -> String {
     if cfg!(windows) {
         // Ensure CRLF line endings on Windows
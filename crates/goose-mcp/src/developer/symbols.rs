@@ -0,0 +1,573 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Language, Node, Parser};
+
+use super::lang;
+
+/// Files larger than this are skipped rather than parsed
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// Signatures are cut to this many characters so long one-liners don't flood the listing
+const MAX_SIGNATURE_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Trait,
+    Interface,
+    Impl,
+    Module,
+    Type,
+    Constant,
+    Macro,
+}
+
+impl SymbolKind {
+    /// Whether functions nested inside this kind of symbol are methods
+    fn has_methods(self) -> bool {
+        matches!(
+            self,
+            SymbolKind::Class
+                | SymbolKind::Struct
+                | SymbolKind::Trait
+                | SymbolKind::Interface
+                | SymbolKind::Impl
+        )
+    }
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            SymbolKind::Function => "fn",
+            SymbolKind::Method => "method",
+            SymbolKind::Class => "class",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Impl => "impl",
+            SymbolKind::Module => "module",
+            SymbolKind::Type => "type",
+            SymbolKind::Constant => "const",
+            SymbolKind::Macro => "macro",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// A named item found in a source file
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Name of the enclosing symbol, such as the class or impl of a method
+    pub parent: Option<String>,
+    /// First line of the item, 1-indexed
+    pub start_line: usize,
+    /// Last line of the item, 1-indexed
+    pub end_line: usize,
+    /// First line of the doc comments, attributes or decorators attached to the item
+    pub doc_start_line: usize,
+    pub signature: String,
+}
+
+impl Symbol {
+    /// Whether `query` names this symbol, either bare or qualified by its parent
+    pub fn matches(&self, query: &str) -> bool {
+        if self.name == query {
+            return true;
+        }
+        match &self.parent {
+            Some(parent) => [
+                format!("{}::{}", parent, self.name),
+                format!("{}.{}", parent, self.name),
+            ]
+            .contains(&query.to_string()),
+            None => false,
+        }
+    }
+
+    pub fn qualified_name(&self) -> String {
+        match &self.parent {
+            Some(parent) => format!("{}::{}", parent, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+struct LanguageSpec {
+    language: fn() -> Language,
+    symbols: &'static [(&'static str, SymbolKind)],
+}
+
+const C_SYMBOLS: &[(&str, SymbolKind)] = &[
+    ("function_definition", SymbolKind::Function),
+    ("struct_specifier", SymbolKind::Struct),
+    ("union_specifier", SymbolKind::Struct),
+    ("enum_specifier", SymbolKind::Enum),
+    ("type_definition", SymbolKind::Type),
+];
+
+const CPP_SYMBOLS: &[(&str, SymbolKind)] = &[
+    ("function_definition", SymbolKind::Function),
+    ("class_specifier", SymbolKind::Class),
+    ("struct_specifier", SymbolKind::Struct),
+    ("union_specifier", SymbolKind::Struct),
+    ("enum_specifier", SymbolKind::Enum),
+    ("type_definition", SymbolKind::Type),
+    ("namespace_definition", SymbolKind::Module),
+];
+
+const JAVASCRIPT_SYMBOLS: &[(&str, SymbolKind)] = &[
+    ("function_declaration", SymbolKind::Function),
+    ("generator_function_declaration", SymbolKind::Function),
+    ("class_declaration", SymbolKind::Class),
+    ("method_definition", SymbolKind::Method),
+];
+
+const TYPESCRIPT_SYMBOLS: &[(&str, SymbolKind)] = &[
+    ("function_declaration", SymbolKind::Function),
+    ("generator_function_declaration", SymbolKind::Function),
+    ("class_declaration", SymbolKind::Class),
+    ("abstract_class_declaration", SymbolKind::Class),
+    ("method_definition", SymbolKind::Method),
+    ("interface_declaration", SymbolKind::Interface),
+    ("type_alias_declaration", SymbolKind::Type),
+    ("enum_declaration", SymbolKind::Enum),
+    ("internal_module", SymbolKind::Module),
+];
+
+/// The tree-sitter grammar and symbol node kinds for a file, if its language is supported
+fn language_spec(path: &Path) -> Option<LanguageSpec> {
+    let spec = match lang::get_language_identifier(path) {
+        "rust" => LanguageSpec {
+            language: || tree_sitter_rust::LANGUAGE.into(),
+            symbols: &[
+                ("function_item", SymbolKind::Function),
+                ("function_signature_item", SymbolKind::Function),
+                ("struct_item", SymbolKind::Struct),
+                ("union_item", SymbolKind::Struct),
+                ("enum_item", SymbolKind::Enum),
+                ("trait_item", SymbolKind::Trait),
+                ("impl_item", SymbolKind::Impl),
+                ("mod_item", SymbolKind::Module),
+                ("type_item", SymbolKind::Type),
+                ("const_item", SymbolKind::Constant),
+                ("static_item", SymbolKind::Constant),
+                ("macro_definition", SymbolKind::Macro),
+            ],
+        },
+        "python" => LanguageSpec {
+            language: || tree_sitter_python::LANGUAGE.into(),
+            symbols: &[
+                ("function_definition", SymbolKind::Function),
+                ("class_definition", SymbolKind::Class),
+            ],
+        },
+        "javascript" => LanguageSpec {
+            language: || tree_sitter_javascript::LANGUAGE.into(),
+            symbols: JAVASCRIPT_SYMBOLS,
+        },
+        "typescript" => LanguageSpec {
+            language: || tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            symbols: TYPESCRIPT_SYMBOLS,
+        },
+        "go" => LanguageSpec {
+            language: || tree_sitter_go::LANGUAGE.into(),
+            symbols: &[
+                ("function_declaration", SymbolKind::Function),
+                ("method_declaration", SymbolKind::Method),
+                ("type_spec", SymbolKind::Type),
+                ("const_spec", SymbolKind::Constant),
+            ],
+        },
+        "java" => LanguageSpec {
+            language: || tree_sitter_java::LANGUAGE.into(),
+            symbols: &[
+                ("class_declaration", SymbolKind::Class),
+                ("record_declaration", SymbolKind::Class),
+                ("interface_declaration", SymbolKind::Interface),
+                ("enum_declaration", SymbolKind::Enum),
+                ("method_declaration", SymbolKind::Method),
+                ("constructor_declaration", SymbolKind::Method),
+            ],
+        },
+        "c" => LanguageSpec {
+            language: || tree_sitter_c::LANGUAGE.into(),
+            symbols: C_SYMBOLS,
+        },
+        "cpp" => LanguageSpec {
+            language: || tree_sitter_cpp::LANGUAGE.into(),
+            symbols: CPP_SYMBOLS,
+        },
+        "ruby" => LanguageSpec {
+            language: || tree_sitter_ruby::LANGUAGE.into(),
+            symbols: &[
+                ("method", SymbolKind::Function),
+                ("singleton_method", SymbolKind::Method),
+                ("class", SymbolKind::Class),
+                ("module", SymbolKind::Module),
+            ],
+        },
+        "php" => LanguageSpec {
+            language: || tree_sitter_php::LANGUAGE_PHP.into(),
+            symbols: &[
+                ("function_definition", SymbolKind::Function),
+                ("method_declaration", SymbolKind::Method),
+                ("class_declaration", SymbolKind::Class),
+                ("interface_declaration", SymbolKind::Interface),
+                ("trait_declaration", SymbolKind::Trait),
+                ("enum_declaration", SymbolKind::Enum),
+                ("namespace_definition", SymbolKind::Module),
+            ],
+        },
+        "swift" => LanguageSpec {
+            language: || tree_sitter_swift::LANGUAGE.into(),
+            symbols: &[
+                ("function_declaration", SymbolKind::Function),
+                ("protocol_function_declaration", SymbolKind::Method),
+                ("init_declaration", SymbolKind::Method),
+                ("class_declaration", SymbolKind::Class),
+                ("protocol_declaration", SymbolKind::Interface),
+                ("typealias_declaration", SymbolKind::Type),
+            ],
+        },
+        "kotlin" => LanguageSpec {
+            language: || tree_sitter_kotlin_ng::LANGUAGE.into(),
+            symbols: &[
+                ("function_declaration", SymbolKind::Function),
+                ("class_declaration", SymbolKind::Class),
+                ("object_declaration", SymbolKind::Class),
+                ("companion_object", SymbolKind::Class),
+                ("type_alias", SymbolKind::Type),
+            ],
+        },
+        _ => return None,
+    };
+    Some(spec)
+}
+
+/// Whether symbols can be extracted from this file
+pub fn is_supported(path: &Path) -> bool {
+    language_spec(path).is_some()
+}
+
+/// Parse a file and list the symbols it defines, in source order
+pub fn parse_file(path: &Path) -> Result<(String, Vec<Symbol>)> {
+    let spec = language_spec(path).ok_or_else(|| {
+        anyhow!(
+            "Symbols are not supported for {}. Supported languages: Rust, Python, JavaScript, TypeScript, Go, Java, C, C++, Ruby, PHP, Swift and Kotlin",
+            path.display()
+        )
+    })?;
+    let size = std::fs::metadata(path)?.len();
+    if size > MAX_FILE_SIZE {
+        return Err(anyhow!(
+            "{} is too large to parse ({} bytes)",
+            path.display(),
+            size
+        ));
+    }
+    let source = std::fs::read_to_string(path)?;
+    let symbols = parse_source(&spec, &source)?;
+    Ok((source, symbols))
+}
+
+fn parse_source(spec: &LanguageSpec, source: &str) -> Result<Vec<Symbol>> {
+    let mut parser = Parser::new();
+    parser.set_language(&(spec.language)())?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| anyhow!("Failed to parse source"))?;
+
+    let mut symbols = Vec::new();
+    collect_symbols(spec, tree.root_node(), source, None, &mut symbols);
+    Ok(symbols)
+}
+
+fn collect_symbols(
+    spec: &LanguageSpec,
+    node: Node,
+    source: &str,
+    parent: Option<(&str, SymbolKind)>,
+    symbols: &mut Vec<Symbol>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match symbol_for_node(spec, child, source, parent) {
+            Some(symbol) => {
+                let name = symbol.name.clone();
+                let kind = symbol.kind;
+                symbols.push(symbol);
+                collect_symbols(spec, child, source, Some((&name, kind)), symbols);
+            }
+            None => collect_symbols(spec, child, source, parent, symbols),
+        }
+    }
+}
+
+fn symbol_for_node(
+    spec: &LanguageSpec,
+    node: Node,
+    source: &str,
+    parent: Option<(&str, SymbolKind)>,
+) -> Option<Symbol> {
+    let mut kind = spec
+        .symbols
+        .iter()
+        .find(|(k, _)| *k == node.kind())
+        .map(|(_, kind)| *kind)?;
+
+    // Declarations without a body, like `struct foo;` in C, are references rather than definitions
+    if node.kind().ends_with("_specifier") && node.child_by_field_name("body").is_none() {
+        return None;
+    }
+
+    let name = symbol_name(node, source)?;
+    if kind == SymbolKind::Function && parent.is_some_and(|(_, kind)| kind.has_methods()) {
+        kind = SymbolKind::Method;
+    }
+    if kind == SymbolKind::Type {
+        kind = match node.child_by_field_name("type").map(|n| n.kind()) {
+            Some("struct_type") => SymbolKind::Struct,
+            Some("interface_type") => SymbolKind::Interface,
+            _ => kind,
+        };
+    }
+    // Swift declares classes, structs, enums and extensions with the same node, and Kotlin
+    // does the same for classes and interfaces
+    if node.kind() == "class_declaration" {
+        let keyword = node
+            .child_by_field_name("declaration_kind")
+            .or_else(|| {
+                let mut cursor = node.walk();
+                let keyword = node
+                    .children(&mut cursor)
+                    .find(|child| child.kind() == "interface");
+                keyword
+            })
+            .map(|n| n.kind());
+        kind = match keyword {
+            Some("struct") => SymbolKind::Struct,
+            Some("enum") => SymbolKind::Enum,
+            Some("extension") => SymbolKind::Impl,
+            Some("interface") => SymbolKind::Interface,
+            _ => kind,
+        };
+    }
+
+    let text = node.utf8_text(source.as_bytes()).ok()?;
+    let first_line = text.lines().next().unwrap_or_default().trim();
+    let mut signature: String = first_line.chars().take(MAX_SIGNATURE_CHARS).collect();
+    if signature.len() < first_line.len() {
+        signature.push_str("...");
+    }
+    Some(Symbol {
+        name,
+        kind,
+        parent: parent.map(|(name, _)| name.to_string()),
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        doc_start_line: doc_start(node).start_position().row + 1,
+        signature,
+    })
+}
+
+/// The name a symbol is looked up by
+fn symbol_name(node: Node, source: &str) -> Option<String> {
+    let text = |n: Node| n.utf8_text(source.as_bytes()).ok().map(str::to_string);
+    match node.kind() {
+        // Impls are found by the type they are for
+        "impl_item" => node.child_by_field_name("type").and_then(text),
+        "type_alias" => node.child_by_field_name("type").and_then(text),
+        "init_declaration" => Some("init".to_string()),
+        "companion_object" => node
+            .child_by_field_name("name")
+            .and_then(text)
+            .or_else(|| Some("Companion".to_string())),
+        "function_definition" | "type_definition" if node.child_by_field_name("name").is_none() => {
+            let mut declarator = node.child_by_field_name("declarator")?;
+            while let Some(inner) = declarator.child_by_field_name("declarator") {
+                declarator = inner;
+            }
+            text(declarator)
+        }
+        _ => node.child_by_field_name("name").and_then(text),
+    }
+}
+
+/// The first of the comments, attributes and decorators directly above a symbol
+fn doc_start(node: Node) -> Node {
+    let mut start = match node.parent() {
+        Some(parent) if parent.kind() == "decorated_definition" => parent,
+        _ => node,
+    };
+    while let Some(previous) = start.prev_named_sibling() {
+        let attached = previous.kind().contains("comment")
+            || previous.kind() == "attribute_item"
+            || previous.kind() == "decorator";
+        if !attached || previous.end_position().row + 1 < start.start_position().row {
+            break;
+        }
+        start = previous;
+    }
+    start
+}
+
+/// Source files with supported languages under `dir`, honoring .gitignore and `is_ignored`
+pub fn source_files(
+    dir: &Path,
+    is_ignored: impl Fn(&Path) -> bool,
+    limit: usize,
+) -> (Vec<PathBuf>, bool) {
+    let mut files = Vec::new();
+    for entry in ignore::WalkBuilder::new(dir).build().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_some_and(|t| t.is_file())
+            || !is_supported(path)
+            || is_ignored(path)
+        {
+            continue;
+        }
+        if files.len() == limit {
+            return (files, true);
+        }
+        files.push(path.to_path_buf());
+    }
+    (files, false)
+}
+
+/// Source lines `start..=end` (1-indexed) with line numbers
+pub fn numbered_lines(source: &str, start: usize, end: usize) -> String {
+    source
+        .lines()
+        .enumerate()
+        .skip(start - 1)
+        .take(end + 1 - start)
+        .map(|(i, line)| format!("{}: {}", i + 1, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(file_name: &str, source: &str) -> Vec<Symbol> {
+        parse_source(&language_spec(Path::new(file_name)).unwrap(), source).unwrap()
+    }
+
+    fn summary(symbols: &[Symbol]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|s| format!("{} {}", s.kind, s.qualified_name()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_symbols() {
+        let source = r#"
+/// A point
+#[derive(Debug)]
+struct Point {
+    x: i32,
+}
+
+impl Point {
+    fn new(x: i32) -> Self {
+        Self { x }
+    }
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+fn main() {}
+"#;
+        let symbols = symbols("main.rs", source);
+        assert_eq!(
+            summary(&symbols),
+            vec![
+                "struct Point",
+                "impl Point",
+                "method Point::new",
+                "trait Shape",
+                "method Shape::area",
+                "fn main",
+            ]
+        );
+
+        let point = &symbols[0];
+        assert_eq!((point.doc_start_line, point.start_line), (2, 4));
+        assert_eq!(point.signature, "struct Point {");
+        assert!(symbols[2].matches("Point::new"));
+        assert!(symbols[2].matches("new"));
+        assert!(!symbols[2].matches("Shape::new"));
+    }
+
+    #[test]
+    fn test_python_and_c_symbols() {
+        let python = "class Greeter:\n    @staticmethod\n    def hello():\n        pass\n\ndef main():\n    pass\n";
+        let python_symbols = symbols("app.py", python);
+        assert_eq!(
+            summary(&python_symbols),
+            vec!["class Greeter", "method Greeter::hello", "fn main"]
+        );
+        assert_eq!(python_symbols[1].doc_start_line, 2);
+
+        let c = "struct point;\nstruct point { int x; };\nstatic int add(int a, int b) { return a + b; }\n";
+        assert_eq!(
+            summary(&symbols("math.c", c)),
+            vec!["struct point", "fn add"]
+        );
+    }
+
+    #[test]
+    fn test_php_swift_and_kotlin_symbols() {
+        let php = "<?php\nclass Greeter {\n    public function hello() {}\n}\nfunction main() {}\n";
+        assert_eq!(
+            summary(&symbols("app.php", php)),
+            vec!["class Greeter", "method Greeter::hello", "fn main"]
+        );
+
+        let swift = "struct Point {\n    func length() -> Int { 0 }\n}\nprotocol Shape {}\nextension Point: Shape {}\n";
+        assert_eq!(
+            summary(&symbols("Point.swift", swift)),
+            vec![
+                "struct Point",
+                "method Point::length",
+                "interface Shape",
+                "impl Point"
+            ]
+        );
+
+        let kotlin = "interface Shape\nclass Circle : Shape {\n    fun area(): Double = 0.0\n}\nfun main() {}\n";
+        assert_eq!(
+            summary(&symbols("Circle.kt", kotlin)),
+            vec![
+                "interface Shape",
+                "class Circle",
+                "method Circle::area",
+                "fn main"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        assert!(!is_supported(Path::new("notes.md")));
+        assert!(is_supported(Path::new("lib.go")));
+        assert!(is_supported(Path::new("build.gradle.kts")));
+    }
+
+    #[test]
+    fn test_numbered_lines() {
+        assert_eq!(numbered_lines("a\nb\nc\n", 2, 3), "2: b\n3: c");
+    }
+}
@@ -10,10 +10,11 @@ pub static APP_STRATEGY: Lazy<AppStrategyArgs> = Lazy::new(|| AppStrategyArgs {
 pub mod computercontroller;
 mod developer;
 pub mod google_drive;
-mod memory;
+pub mod memory;
 mod tutorial;
 
 pub use computercontroller::ComputerControllerRouter;
+pub use developer::checkpoint::{CheckpointJournal, CheckpointSummary, RestoredFile};
 pub use developer::DeveloperRouter;
 pub use google_drive::GoogleDriveRouter;
 pub use memory::MemoryRouter;
@@ -0,0 +1,159 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::store::Memory;
+
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Global and local memories exported to move them to another machine or share them
+///
+/// Embeddings are left out, since they are worked out again when the memories are loaded.
+/// Records of deleted memories are included, so importing the archive deletes them too.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub global: Vec<Memory>,
+    #[serde(default)]
+    pub local: Vec<Memory>,
+}
+
+impl MemoryArchive {
+    pub fn new(global: Vec<Memory>, local: Vec<Memory>) -> Self {
+        let strip = |memories: Vec<Memory>| {
+            memories
+                .into_iter()
+                .map(|memory| Memory {
+                    embedding: Vec::new(),
+                    embedding_model: String::new(),
+                    ..memory
+                })
+                .collect()
+        };
+        Self {
+            version: ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            global: strip(global),
+            local: strip(local),
+        }
+    }
+
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let archive: Self = serde_json::from_str(json).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Not a memory archive: {}", e),
+            )
+        })?;
+        if archive.version > ARCHIVE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Memory archive version {} was written by a newer version of goose",
+                    archive.version
+                ),
+            ));
+        }
+        Ok(archive)
+    }
+
+    pub fn to_json(&self) -> io::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_json()?)
+    }
+}
+
+/// Which memory to keep when an imported memory has the same id as an existing one,
+/// or repeats it in the same category
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Keep whichever was updated last
+    #[default]
+    Newer,
+    /// Keep the existing memory
+    Keep,
+    /// Replace the existing memory with the imported one
+    Replace,
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newer" => Ok(Self::Newer),
+            "keep" => Ok(Self::Keep),
+            "replace" => Ok(Self::Replace),
+            _ => Err(format!(
+                "Unknown conflict strategy '{}', expected newer, keep or replace",
+                s
+            )),
+        }
+    }
+}
+
+/// What importing memories changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    /// Existing memories replaced by an imported version
+    pub updated: usize,
+    /// Existing memories deleted because they were deleted where the archive came from
+    pub removed: usize,
+    /// Imported memories that were already known, or lost a conflict
+    pub unchanged: usize,
+}
+
+impl ImportSummary {
+    pub fn changed(&self) -> bool {
+        self.added + self.updated + self.removed > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(content: &str) -> Memory {
+        Memory {
+            id: "1".to_string(),
+            category: "development".to_string(),
+            content: content.to_string(),
+            tags: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            expires_at: None,
+            embedding: vec![0.5, 0.5],
+            embedding_model: "test".to_string(),
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_archive_round_trip_drops_embeddings() {
+        let archive = MemoryArchive::new(vec![memory("We use black")], vec![]);
+        assert!(archive.global[0].embedding.is_empty());
+
+        let read = MemoryArchive::from_json(&archive.to_json().unwrap()).unwrap();
+        assert_eq!(read, archive);
+
+        let newer = r#"{"version": 2, "exported_at": "2025-01-01T00:00:00Z"}"#;
+        let err = MemoryArchive::from_json(newer).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,177 @@
+use std::io;
+
+/// Turns text into vectors whose cosine similarity reflects how related the texts are
+pub trait Embedder: Send + Sync {
+    /// Names the model, so memories embedded by a different one are embedded again
+    fn model(&self) -> &str;
+
+    /// One vector for each text, in the same order
+    fn embed(&self, texts: &[String]) -> io::Result<Vec<Vec<f32>>>;
+}
+
+/// Embeds text with a function, for models that are run outside this crate
+///
+/// goose passes its configured embedding provider in this way, since this crate
+/// doesn't depend on goose.
+pub struct FnEmbedder<F> {
+    model: String,
+    embed: F,
+}
+
+impl<F> FnEmbedder<F>
+where
+    F: Fn(&[String]) -> io::Result<Vec<Vec<f32>>> + Send + Sync,
+{
+    pub fn new(model: impl Into<String>, embed: F) -> Self {
+        Self {
+            model: model.into(),
+            embed,
+        }
+    }
+}
+
+impl<F> Embedder for FnEmbedder<F>
+where
+    F: Fn(&[String]) -> io::Result<Vec<Vec<f32>>> + Send + Sync,
+{
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed(&self, texts: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        (self.embed)(texts)
+    }
+}
+
+/// Embeds text locally by hashing its words and word pairs into a fixed number of buckets
+///
+/// This needs no model or network access, so it is used when no embedding model is
+/// configured. It matches on shared vocabulary rather than meaning, which is enough to
+/// rank short facts against a query.
+pub struct HashingEmbedder {
+    dimensions: usize,
+    model: String,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions,
+            model: format!("hashing-{}", dimensions),
+        }
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+impl HashingEmbedder {
+    fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+        let words = tokenize(text);
+        let pairs = words
+            .windows(2)
+            .map(|pair| format!("{} {}", pair[0], pair[1]));
+
+        for (feature, weight) in words
+            .iter()
+            .cloned()
+            .map(|word| (word, 1.0))
+            .chain(pairs.map(|pair| (pair, 0.5)))
+        {
+            let hash = fnv1a(feature.as_bytes());
+            let bucket = (hash % self.dimensions as u64) as usize;
+            // The top bit of the hash picks the sign, so colliding features tend to cancel out
+            let sign = if hash & (1 << 63) == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign * weight;
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed(&self, texts: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+}
+
+/// Lowercase words with a trailing plural "s" removed, so "tests" matches "test"
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = word.to_lowercase();
+            match word.strip_suffix('s') {
+                Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+                _ => word,
+            }
+        })
+        .collect()
+}
+
+/// FNV-1a, which unlike the std hasher gives the same result across Rust versions
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// Cosine similarity of two vectors, or 0 if they can't be compared
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_related_text_scores_higher() {
+        let embedder = HashingEmbedder::default();
+        let query = embedder.embed_text("How do we format python code?");
+        let formatting = embedder.embed_text("We use black to format Python code in this project");
+        let deploys = embedder.embed_text("Deploys go through the staging cluster first");
+
+        assert!(
+            cosine_similarity(&query, &formatting) > cosine_similarity(&query, &deploys),
+            "formatting memory should rank above the deploy memory"
+        );
+    }
+
+    #[test]
+    fn test_embedding_is_stable_and_normalized() {
+        let embedder = HashingEmbedder::default();
+        let first = embedder.embed_text("Run tests with cargo nextest");
+        let second = embedder.embed_text("run TESTS with cargo nextest!");
+
+        assert_eq!(first.len(), embedder.dimensions);
+        assert!((cosine_similarity(&first, &second) - 1.0).abs() < 1e-6);
+        assert!((first.iter().map(|v| v * v).sum::<f32>() - 1.0).abs() < 1e-5);
+        assert_eq!(cosine_similarity(&first, &embedder.embed_text("")), 0.0);
+    }
+}
@@ -1,6 +1,11 @@
+mod archive;
+mod embedding;
+mod store;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use etcetera::{choose_app_strategy, AppStrategy};
-use indoc::formatdoc;
+use indoc::{formatdoc, indoc};
 use mcp_core::{
     handler::{PromptError, ResourceError, ToolError},
     protocol::ServerCapabilities,
@@ -8,26 +13,70 @@ use mcp_core::{
 };
 use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
-use rmcp::model::{Content, JsonRpcMessage, Prompt, Resource, Tool, ToolAnnotations};
+use rmcp::model::{
+    AnnotateAble, Content, JsonRpcMessage, Prompt, RawResource, Resource, Tool, ToolAnnotations,
+};
 use rmcp::object;
 use serde_json::Value;
 use std::{
     collections::HashMap,
-    fs,
     future::Future,
-    io::{self, Read, Write},
-    path::PathBuf,
+    io,
+    path::{Path, PathBuf},
     pin::Pin,
+    sync::Arc,
 };
 use tokio::sync::mpsc;
+use url::Url;
+
+pub use archive::{ConflictStrategy, ImportSummary, MemoryArchive};
+use embedding::{cosine_similarity, HashingEmbedder};
+pub use embedding::{Embedder, FnEmbedder};
+pub use store::{FileMemoryStore, Memory, MemoryStore};
+
+/// Memories in the same category at least this similar are merged on write
+const DUPLICATE_SIMILARITY: f32 = 0.95;
+
+const DEFAULT_SEARCH_RESULTS: usize = 5;
+
+/// Resource with the memories most relevant to a session, which goose reads when a session starts
+///
+/// Takes the text to rank memories against as `query` and a `max_tokens` budget, e.g.
+/// `memory://digest?query=fix+the+login+test&max_tokens=500`.
+pub const DIGEST_URI: &str = "memory://digest";
+
+const DEFAULT_DIGEST_TOKENS: usize = 1000;
+
+/// Local memories belong to the current project, so they rank above equally relevant global ones
+const LOCAL_RELEVANCE_BONUS: f32 = 0.2;
+
+/// How long the records of deleted memories are kept, so syncing deletes them on other machines
+const DELETED_RETENTION_DAYS: i64 = 90;
+
+/// What `remember` did with a memory
+#[derive(Debug, Clone, PartialEq)]
+pub enum Remembered {
+    Stored(String),
+    /// The memory duplicated the one with this id, which was updated instead
+    Merged(String),
+}
+
+/// A memory returned by `search`, with how relevant it is to the query
+#[derive(Debug, Clone)]
+pub struct MemoryMatch {
+    pub memory: Memory,
+    pub is_global: bool,
+    pub score: f32,
+}
 
 // MemoryRouter implementation
 #[derive(Clone)]
 pub struct MemoryRouter {
     tools: Vec<Tool>,
     instructions: String,
-    global_memory_dir: PathBuf,
-    local_memory_dir: PathBuf,
+    global_store: Arc<dyn MemoryStore>,
+    local_store: Arc<dyn MemoryStore>,
+    embedder: Arc<dyn Embedder>,
 }
 
 impl Default for MemoryRouter {
@@ -36,18 +85,66 @@ impl Default for MemoryRouter {
     }
 }
 
+/// Where global memories are kept
+pub fn global_memory_dir() -> PathBuf {
+    // choose_app_strategy().config_dir()
+    // - macOS/Linux: ~/.config/goose/memory/
+    // - Windows:     ~\AppData\Roaming\Block\goose\config\memory
+    // if it fails, fall back to `.config/goose/memory` (relative to the current dir)
+    choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.in_config_dir("memory"))
+        .unwrap_or_else(|_| PathBuf::from(".config/goose/memory"))
+}
+
+/// Where the memories local to a project directory are kept
+pub fn local_memory_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(".goose").join("memory")
+}
+
 impl MemoryRouter {
     pub fn new() -> Self {
+        // Check for .goose/memory in current directory
+        let working_dir = std::env::var("GOOSE_WORKING_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| std::env::current_dir().unwrap());
+
+        Self::with_stores(
+            Arc::new(FileMemoryStore::new(global_memory_dir())),
+            Arc::new(FileMemoryStore::new(local_memory_dir(&working_dir))),
+        )
+    }
+
+    /// Embed memories with `embedder` instead of the built-in hashing embedder
+    ///
+    /// Memories embedded by a different model are embedded again when they are next read.
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Create a router that keeps its memories in the given stores
+    pub fn with_stores(
+        global_store: Arc<dyn MemoryStore>,
+        local_store: Arc<dyn MemoryStore>,
+    ) -> Self {
         let remember_memory = Tool::new(
             "remember_memory",
-            "Stores a memory with optional tags in a specified category",
+            indoc! {r#"
+                Stores a memory with optional tags in a specified category.
+                Storing a memory that repeats an existing one in the same category updates the existing one.
+                Set ttl_days for information that is only true for a while.
+            "#},
             object!({
                 "type": "object",
                 "properties": {
                     "category": {"type": "string"},
                     "data": {"type": "string"},
                     "tags": {"type": "array", "items": {"type": "string"}},
-                    "is_global": {"type": "boolean"}
+                    "is_global": {"type": "boolean"},
+                    "ttl_days": {
+                        "type": "number",
+                        "description": "Forget the memory after this many days"
+                    }
                 },
                 "required": ["category", "data", "is_global"]
             }),
@@ -80,6 +177,39 @@ impl MemoryRouter {
             open_world_hint: Some(false),
         });
 
+        let search_memories = Tool::new(
+            "search_memories",
+            indoc! {r#"
+                Returns the stored memories most relevant to a query, across all categories.
+                Searches both global and local memories unless is_global is given.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "k": {
+                        "type": "integer",
+                        "description": "How many memories to return, 5 by default"
+                    },
+                    "category": {"type": "string"},
+                    "tags": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Only return memories with at least one of these tags"
+                    },
+                    "is_global": {"type": "boolean"}
+                },
+                "required": ["query"]
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Search Memories".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
         let remove_memory_category = Tool::new(
             "remove_memory_category",
             "Removes all memories within a specified category",
@@ -102,15 +232,16 @@ impl MemoryRouter {
 
         let remove_specific_memory = Tool::new(
             "remove_specific_memory",
-            "Removes a specific memory within a specified category",
+            "Removes a specific memory within a specified category, by its id or by text it contains",
             object!({
                 "type": "object",
                 "properties": {
                     "category": {"type": "string"},
                     "memory_content": {"type": "string"},
+                    "id": {"type": "string"},
                     "is_global": {"type": "boolean"}
                 },
-                "required": ["category", "memory_content", "is_global"]
+                "required": ["category", "is_global"]
             }),
         )
         .annotate(ToolAnnotations {
@@ -194,15 +325,24 @@ impl MemoryRouter {
                - Use: `retrieve_memories(category="development", is_global=False)`
                - Note: If you want to retrieve all local memories, use `retrieve_memories(category="*", is_global=False)`
                - Note: If you want to retrieve all global memories, use `retrieve_memories(category="*", is_global=True)`
+             - **Search by Relevance**:
+               - Returns the memories most relevant to a question, across categories and both storage locations.
+               - Use: `search_memories(query="code formatting", k=5)`
+               - Prefer this over retrieving whole categories once there are more than a few memories.
              - **Filter by Tags**:
                - Enables targeted retrieval based on specific tags.
-               - Use: Provide tag filters to refine search.
+               - Use: `search_memories(query="code formatting", tags=["formatting"])`
+             Storing Temporary Information:
+             - For facts that are only true for a while, such as a release freeze, pass ttl_days when remembering them.
+             - Use: `remember_memory(category="releases", data="Code freeze until Friday", is_global=False, ttl_days=7)`
             To remove a memory, use the following protocol:
             - **Remove by Category**:
               - Removes all memories within the specified category.
               - Use: `remove_memory_category(category="development", is_global=False)`
               - Note: If you want to remove all local memories, use `remove_memory_category(category="*", is_global=False)`
               - Note: If you want to remove all global memories, use `remove_memory_category(category="*", is_global=True)`
+            - **Remove a Single Memory**:
+              - Use: `remove_specific_memory(category="development", id="<id from search_memories>", is_global=False)`
             The Protocol is:
              1. Confirm what kind of information the user seeks by category or keyword.
              2. Suggest categories or relevant tags based on the user's request.
@@ -224,71 +364,49 @@ impl MemoryRouter {
              - Acknowledge the user about what is stored and where, for transparency and ease of future retrieval.
             "#};
 
-        // Check for .goose/memory in current directory
-        let local_memory_dir = std::env::var("GOOSE_WORKING_DIR")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| std::env::current_dir().unwrap())
-            .join(".goose")
-            .join("memory");
-
-        // choose_app_strategy().config_dir()
-        // - macOS/Linux: ~/.config/goose/memory/
-        // - Windows:     ~\AppData\Roaming\Block\goose\config\memory
-        // if it fails, fall back to `.config/goose/memory` (relative to the current dir)
-        let global_memory_dir = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .map(|strategy| strategy.in_config_dir("memory"))
-            .unwrap_or_else(|_| PathBuf::from(".config/goose/memory"));
-
         let mut memory_router = Self {
             tools: vec![
                 remember_memory,
                 retrieve_memories,
+                search_memories,
                 remove_memory_category,
                 remove_specific_memory,
             ],
             instructions: instructions.clone(),
-            global_memory_dir,
-            local_memory_dir,
+            global_store,
+            local_store,
+            embedder: Arc::new(HashingEmbedder::default()),
         };
 
-        let retrieved_global_memories = memory_router.retrieve_all(true);
-        let retrieved_local_memories = memory_router.retrieve_all(false);
-
         let mut updated_instructions = instructions;
 
         let memories_follow_up_instructions = formatdoc! {r#"
-            **Here are the user's currently saved memories:**
-            Please keep this information in mind when answering future questions.
+            **The user's saved memories**
+            The memories most relevant to the current session are included in the system prompt when it starts.
+            Use search_memories to look up others when they could help with a question.
             Do not bring up memories unless relevant.
-            Note: if the user has not saved any memories, this section will be empty.
-            Note: if the user removes a memory that was previously loaded into the system, please remove it from the system instructions.
             "#};
 
         updated_instructions.push_str("\n\n");
         updated_instructions.push_str(&memories_follow_up_instructions);
 
-        if let Ok(global_memories) = retrieved_global_memories {
-            if !global_memories.is_empty() {
-                updated_instructions.push_str("\n\nGlobal Memories:\n");
-                for (category, memories) in global_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
-                }
-            }
-        }
-
-        if let Ok(local_memories) = retrieved_local_memories {
-            if !local_memories.is_empty() {
-                updated_instructions.push_str("\n\nLocal Memories:\n");
-                for (category, memories) in local_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
-                }
+        for (is_global, heading) in [(true, "Global"), (false, "Local")] {
+            let Ok(categories) = memory_router.retrieve_all(is_global) else {
+                continue;
+            };
+            if categories.is_empty() {
+                continue;
             }
+            let mut categories = categories
+                .into_iter()
+                .map(|(category, memories)| format!("{} ({})", category, memories.len()))
+                .collect::<Vec<_>>();
+            categories.sort();
+            updated_instructions.push_str(&format!(
+                "\n{} memory categories: {}\n",
+                heading,
+                categories.join(", ")
+            ));
         }
 
         memory_router.set_instructions(updated_instructions);
@@ -305,102 +423,487 @@ impl MemoryRouter {
         &self.instructions
     }
 
-    fn get_memory_file(&self, category: &str, is_global: bool) -> PathBuf {
-        // Defaults to local memory if no is_global flag is provided
-        let base_dir = if is_global {
-            &self.global_memory_dir
+    fn store(&self, is_global: bool) -> &dyn MemoryStore {
+        if is_global {
+            self.global_store.as_ref()
         } else {
-            &self.local_memory_dir
-        };
-        base_dir.join(format!("{}.txt", category))
+            self.local_store.as_ref()
+        }
     }
 
-    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
-        let base_dir = if is_global {
-            &self.global_memory_dir
-        } else {
-            &self.local_memory_dir
-        };
-        let mut memories = HashMap::new();
-        if base_dir.exists() {
-            for entry in fs::read_dir(base_dir)? {
-                let entry = entry?;
-                if entry.file_type()?.is_file() {
-                    let category = entry.file_name().to_string_lossy().replace(".txt", "");
-                    let category_memories = self.retrieve(&category, is_global)?;
-                    memories.insert(
-                        category,
-                        category_memories.into_iter().flat_map(|(_, v)| v).collect(),
-                    );
+    /// The unexpired memories of one scope, as stored
+    fn load(&self, is_global: bool) -> io::Result<Vec<Memory>> {
+        let now = Utc::now();
+        let mut memories = self.store(is_global).load()?;
+        memories.retain(|memory| !memory.is_expired(now) && !memory.is_deleted());
+        Ok(memories)
+    }
+
+    /// The unexpired memories of one scope, with their embeddings filled in
+    pub fn memories(&self, is_global: bool) -> io::Result<Vec<Memory>> {
+        let mut memories = self.load(is_global)?;
+        if !self.embed_memories(&mut memories)? {
+            return Ok(memories);
+        }
+
+        // Keep the new embeddings so they aren't computed again on every search
+        let embedded = &memories;
+        let saved = self.store(is_global).update(&mut |stored| {
+            let mut changed = false;
+            for memory in stored.iter_mut() {
+                let Some(fresh) = embedded
+                    .iter()
+                    .find(|fresh| fresh.id == memory.id && fresh.updated_at == memory.updated_at)
+                else {
+                    continue;
+                };
+                if memory.embedding_model != fresh.embedding_model {
+                    memory.embedding = fresh.embedding.clone();
+                    memory.embedding_model = fresh.embedding_model.clone();
+                    changed = true;
                 }
             }
+            Ok(changed)
+        });
+        if let Err(e) = saved {
+            tracing::warn!("Failed to save memory embeddings: {}", e);
+        }
+        Ok(memories)
+    }
+
+    fn embed(&self, texts: Vec<String>) -> io::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let embeddings = self.embedder.embed(&texts)?;
+        if embeddings.len() != texts.len() {
+            return Err(io::Error::other(format!(
+                "Embedding model {} returned {} embeddings for {} texts",
+                self.embedder.model(),
+                embeddings.len(),
+                texts.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+
+    /// Embed the memories without an embedding from the current model, returning whether there were any
+    ///
+    /// Category and tags are embedded with the content so searches can match on them too.
+    fn embed_memories(&self, memories: &mut [Memory]) -> io::Result<bool> {
+        let model = self.embedder.model();
+        let mut stale = memories
+            .iter_mut()
+            .filter(|memory| {
+                !memory.is_deleted()
+                    && (memory.embedding.is_empty() || memory.embedding_model != model)
+            })
+            .collect::<Vec<_>>();
+        if stale.is_empty() {
+            return Ok(false);
+        }
+
+        let texts = stale
+            .iter()
+            .map(|memory| {
+                format!(
+                    "{} {} {}",
+                    memory.category,
+                    memory.tags.join(" "),
+                    memory.content
+                )
+            })
+            .collect();
+        for (memory, embedding) in stale.iter_mut().zip(self.embed(texts)?) {
+            memory.embedding = embedding;
+            memory.embedding_model = model.to_string();
+        }
+        Ok(true)
+    }
+
+    /// The memory in `category` that `data` repeats, if any
+    fn find_duplicate(
+        &self,
+        memories: &[Memory],
+        category: &str,
+        data: &str,
+    ) -> io::Result<Option<usize>> {
+        let candidates = (0..memories.len())
+            .filter(|&i| memories[i].category == category && !memories[i].is_deleted())
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let mut texts = vec![data.to_string()];
+        texts.extend(candidates.iter().map(|&i| memories[i].content.clone()));
+        let embeddings = self.embed(texts)?;
+        let Some((data, contents)) = embeddings.split_first() else {
+            return Ok(None);
+        };
+        Ok(candidates
+            .into_iter()
+            .zip(contents)
+            .find(|(_, content)| cosine_similarity(data, content) >= DUPLICATE_SIMILARITY)
+            .map(|(i, _)| i))
+    }
+
+    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
+        let mut memories: HashMap<String, Vec<String>> = HashMap::new();
+        for memory in self.load(is_global)? {
+            memories
+                .entry(memory.category)
+                .or_default()
+                .push(memory.content);
         }
         Ok(memories)
     }
 
     pub fn remember(
+        &self,
+        context: &str,
+        category: &str,
+        data: &str,
+        tags: &[&str],
+        is_global: bool,
+    ) -> io::Result<Remembered> {
+        self.remember_with_ttl(context, category, data, tags, is_global, None)
+    }
+
+    /// Store a memory that is forgotten once `ttl` has passed
+    ///
+    /// A memory that repeats one already stored in the same category replaces its
+    /// content, adds its tags and takes over its expiry, rather than being stored twice.
+    pub fn remember_with_ttl(
         &self,
         _context: &str,
         category: &str,
         data: &str,
         tags: &[&str],
         is_global: bool,
-    ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
+        ttl: Option<Duration>,
+    ) -> io::Result<Remembered> {
+        let now = Utc::now();
+        let expires_at = ttl.and_then(|ttl| now.checked_add_signed(ttl));
+
+        let mut remembered = None;
+        self.store(is_global).update(&mut |memories| {
+            prune(memories, now);
+
+            remembered = Some(match self.find_duplicate(memories, category, data)? {
+                Some(i) => {
+                    let memory = &mut memories[i];
+                    memory.content = data.to_string();
+                    for tag in tags {
+                        if !memory.tags.iter().any(|existing| existing == tag) {
+                            memory.tags.push(tag.to_string());
+                        }
+                    }
+                    memory.updated_at = now;
+                    memory.expires_at = expires_at;
+                    memory.embedding.clear();
+                    Remembered::Merged(memory.id.clone())
+                }
+                None => {
+                    let id = uuid::Uuid::new_v4().to_string();
+                    memories.push(Memory {
+                        id: id.clone(),
+                        category: category.to_string(),
+                        content: data.to_string(),
+                        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                        created_at: now,
+                        updated_at: now,
+                        expires_at,
+                        embedding: Vec::new(),
+                        embedding_model: String::new(),
+                        deleted_at: None,
+                    });
+                    Remembered::Stored(id)
+                }
+            });
+            self.embed_memories(memories)?;
+            Ok(true)
+        })?;
+        remembered.ok_or_else(|| io::Error::other("The memory store didn't run the update"))
+    }
 
-        if let Some(parent) = memory_file_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
+    /// The unexpired memories of one scope, for an archive
+    ///
+    /// Includes the records of deleted memories, so importing the archive deletes them too.
+    pub fn export(&self, is_global: bool) -> io::Result<Vec<Memory>> {
+        let mut memories = self.store(is_global).load()?;
+        prune(&mut memories, Utc::now());
+        Ok(memories)
+    }
+
+    /// Merge memories from an archive into one scope
+    pub fn import(
+        &self,
+        imported: Vec<Memory>,
+        is_global: bool,
+        on_conflict: ConflictStrategy,
+    ) -> io::Result<ImportSummary> {
+        let now = Utc::now();
+        let mut imported = Some(imported);
+        let mut summary = ImportSummary::default();
+        self.store(is_global).update(&mut |memories| {
+            prune(memories, now);
+            summary = self.merge(memories, imported.take().unwrap_or_default(), on_conflict)?;
+            if summary.changed() {
+                self.embed_memories(memories)?;
+            }
+            Ok(summary.changed())
+        })?;
+        Ok(summary)
+    }
+
+    /// Merge `imported` into `memories`
+    ///
+    /// An imported memory conflicts with an existing one that has the same id, or that it
+    /// repeats in the same category. `on_conflict` decides which of the two is kept, and the
+    /// kept memory keeps the existing id. Expired memories aren't imported. An imported
+    /// record of a deleted memory deletes the existing one when it wins the conflict.
+    pub fn merge(
+        &self,
+        memories: &mut Vec<Memory>,
+        imported: Vec<Memory>,
+        on_conflict: ConflictStrategy,
+    ) -> io::Result<ImportSummary> {
+        let now = Utc::now();
+        let imported = imported
+            .into_iter()
+            .filter(|memory| !memory.is_expired(now))
+            .collect::<Vec<_>>();
+        let mut summary = ImportSummary::default();
+
+        // Repeats are found by content alone, in one batch for the existing and imported memories
+        let mut embedded = self
+            .embed(
+                memories
+                    .iter()
+                    .chain(&imported)
+                    .filter(|memory| !memory.is_deleted())
+                    .map(|memory| memory.content.clone())
+                    .collect(),
+            )?
+            .into_iter();
+        let mut contents = memories
+            .iter()
+            .chain(&imported)
+            .map(|memory| {
+                if memory.is_deleted() {
+                    None
+                } else {
+                    embedded.next()
+                }
+            })
+            .collect::<Vec<_>>();
+        let imported_contents = contents.split_off(memories.len());
+
+        for (mut memory, content) in imported.into_iter().zip(imported_contents) {
+            memory.embedding.clear();
+
+            let existing =
+                memories
+                    .iter()
+                    .zip(&contents)
+                    .position(|(existing, existing_content)| {
+                        existing.id == memory.id
+                            || (existing.category == memory.category
+                                && match (existing_content, &content) {
+                                    (Some(existing), Some(content)) => {
+                                        cosine_similarity(existing, content) >= DUPLICATE_SIMILARITY
+                                    }
+                                    _ => false,
+                                })
+                    });
+            let Some(i) = existing else {
+                // Deleting a memory that was never here changes nothing
+                if !memory.is_deleted() {
+                    memories.push(memory);
+                    contents.push(content);
+                    summary.added += 1;
+                }
+                continue;
+            };
 
-        let mut file = fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&memory_file_path)?;
-        if !tags.is_empty() {
-            writeln!(file, "# {}", tags.join(" "))?;
+            let existing = &mut memories[i];
+            let identical = existing.category == memory.category
+                && existing.content == memory.content
+                && existing.tags == memory.tags
+                && existing.expires_at == memory.expires_at
+                && existing.is_deleted() == memory.is_deleted();
+            let use_imported = !identical
+                && match on_conflict {
+                    ConflictStrategy::Newer => memory.updated_at > existing.updated_at,
+                    ConflictStrategy::Keep => false,
+                    ConflictStrategy::Replace => true,
+                };
+            if use_imported {
+                if memory.is_deleted() && !existing.is_deleted() {
+                    summary.removed += 1;
+                } else {
+                    summary.updated += 1;
+                }
+                *existing = Memory {
+                    id: existing.id.clone(),
+                    created_at: existing.created_at.min(memory.created_at),
+                    ..memory
+                };
+                contents[i] = content;
+            } else {
+                summary.unchanged += 1;
+            }
         }
-        writeln!(file, "{}\n", data)?;
 
-        Ok(())
+        Ok(summary)
     }
 
+    /// The memories of a category, grouped by their tags
     pub fn retrieve(
         &self,
         category: &str,
         is_global: bool,
     ) -> io::Result<HashMap<String, Vec<String>>> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if !memory_file_path.exists() {
-            return Ok(HashMap::new());
+        let mut memories: HashMap<String, Vec<String>> = HashMap::new();
+        for memory in self.load(is_global)? {
+            if memory.category != category {
+                continue;
+            }
+            let tags = if memory.tags.is_empty() {
+                "untagged".to_string()
+            } else {
+                memory.tags.join(" ")
+            };
+            memories.entry(tags).or_default().push(memory.content);
         }
+        Ok(memories)
+    }
 
-        let mut file = fs::File::open(memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
-
-        let mut memories = HashMap::new();
-        for entry in content.split("\n\n") {
-            let mut lines = entry.lines();
-            if let Some(first_line) = lines.next() {
-                if let Some(stripped) = first_line.strip_prefix('#') {
-                    let tags = stripped
-                        .split_whitespace()
-                        .map(String::from)
-                        .collect::<Vec<_>>();
-                    memories.insert(tags.join(" "), lines.map(String::from).collect());
+    /// The memories most relevant to `query` that fit in about `max_tokens`, as a markdown list
+    ///
+    /// Local memories are included even when they don't match the query, since they were
+    /// saved for the project being worked on. Without a query, the most recently updated
+    /// memories come first.
+    pub fn digest(&self, query: Option<&str>, max_tokens: usize) -> io::Result<String> {
+        let query_embedding = match query {
+            Some(query) => self.embed(vec![query.to_string()])?.pop(),
+            None => None,
+        };
+
+        let mut ranked = Vec::new();
+        for is_global in [false, true] {
+            for memory in self.memories(is_global)? {
+                let relevance = query_embedding
+                    .as_ref()
+                    .map(|query| cosine_similarity(query, &memory.embedding));
+                if is_global && relevance.is_some_and(|relevance| relevance <= 0.0) {
+                    continue;
+                }
+                let bonus = if is_global {
+                    0.0
                 } else {
-                    let entry_data: Vec<String> = std::iter::once(first_line.to_string())
-                        .chain(lines.map(String::from))
-                        .collect();
-                    memories
-                        .entry("untagged".to_string())
-                        .or_insert_with(Vec::new)
-                        .extend(entry_data);
+                    LOCAL_RELEVANCE_BONUS
+                };
+                ranked.push(MemoryMatch {
+                    memory,
+                    is_global,
+                    score: relevance.unwrap_or(0.0) + bonus,
+                });
+            }
+        }
+        ranked.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(b.memory.updated_at.cmp(&a.memory.updated_at))
+        });
+
+        let mut digest = String::new();
+        let mut remaining = max_tokens;
+        for found in ranked {
+            let line = format!(
+                "- [{}/{}] {}{}\n",
+                if found.is_global { "global" } else { "local" },
+                found.memory.category,
+                found.memory.content.trim().replace('\n', "\n  "),
+                if found.memory.tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (tags: {})", found.memory.tags.join(" "))
                 }
+            );
+            // About four characters per token, which is close enough for a budget
+            let tokens = line.len().div_ceil(4);
+            if tokens > remaining {
+                continue;
             }
+            remaining -= tokens;
+            digest.push_str(&line);
         }
+        Ok(digest)
+    }
 
-        Ok(memories)
+    /// The `k` memories most relevant to `query`, best first
+    ///
+    /// Searches one scope when `is_global` is given and both otherwise. Memories can be
+    /// limited to a category, and to those with at least one of `tags`.
+    pub fn search(
+        &self,
+        query: &str,
+        k: usize,
+        is_global: Option<bool>,
+        category: Option<&str>,
+        tags: &[&str],
+    ) -> io::Result<Vec<MemoryMatch>> {
+        let query_embedding = self.embed(vec![query.to_string()])?.remove(0);
+        let scopes = match is_global {
+            Some(is_global) => vec![is_global],
+            None => vec![false, true],
+        };
+
+        let mut matches = Vec::new();
+        for is_global in scopes {
+            for memory in self.memories(is_global)? {
+                if category.is_some_and(|category| memory.category != category) {
+                    continue;
+                }
+                if !tags.is_empty() && !memory.tags.iter().any(|tag| tags.contains(&tag.as_str())) {
+                    continue;
+                }
+                let score = cosine_similarity(&query_embedding, &memory.embedding);
+                if score > 0.0 {
+                    matches.push(MemoryMatch {
+                        memory,
+                        is_global,
+                        score,
+                    });
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(k);
+        Ok(matches)
+    }
+
+    /// Keep only the memories matching `keep`, returning how many were removed
+    ///
+    /// Deleted memories leave a record behind, so syncing deletes them on other machines too.
+    fn retain(&self, is_global: bool, keep: impl Fn(&Memory) -> bool) -> io::Result<usize> {
+        let now = Utc::now();
+        let mut removed = 0;
+        self.store(is_global).update(&mut |memories| {
+            prune(memories, now);
+            removed = 0;
+            for memory in memories.iter_mut() {
+                if !memory.is_deleted() && !keep(memory) {
+                    memory.delete(now);
+                    removed += 1;
+                }
+            }
+            Ok(removed > 0)
+        })?;
+        Ok(removed)
     }
 
     pub fn remove_specific_memory(
@@ -409,45 +912,24 @@ impl MemoryRouter {
         memory_content: &str,
         is_global: bool,
     ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if !memory_file_path.exists() {
-            return Ok(());
-        }
-
-        let mut file = fs::File::open(&memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
-
-        let memories: Vec<&str> = content.split("\n\n").collect();
-        let new_content: Vec<String> = memories
-            .into_iter()
-            .filter(|entry| !entry.contains(memory_content))
-            .map(|s| s.to_string())
-            .collect();
-
-        fs::write(memory_file_path, new_content.join("\n\n"))?;
-
+        self.retain(is_global, |memory| {
+            memory.category != category || !memory.content.contains(memory_content)
+        })?;
         Ok(())
     }
 
-    pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if memory_file_path.exists() {
-            fs::remove_file(memory_file_path)?;
-        }
+    /// Remove the memory with this id, returning whether there was one
+    pub fn remove_memory_by_id(&self, id: &str, is_global: bool) -> io::Result<bool> {
+        Ok(self.retain(is_global, |memory| memory.id != id)? > 0)
+    }
 
+    pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
+        self.retain(is_global, |memory| memory.category != category)?;
         Ok(())
     }
 
     pub fn clear_all_global_or_local_memories(&self, is_global: bool) -> io::Result<()> {
-        let base_dir = if is_global {
-            &self.global_memory_dir
-        } else {
-            &self.local_memory_dir
-        };
-        if base_dir.exists() {
-            fs::remove_dir_all(base_dir)?;
-        }
+        self.retain(is_global, |_| false)?;
         Ok(())
     }
 
@@ -461,8 +943,37 @@ impl MemoryRouter {
                         "Data must exist when remembering a memory",
                     )
                 })?;
-                self.remember("context", args.category, data, &args.tags, args.is_global)?;
-                Ok(format!("Stored memory in category: {}", args.category))
+                let ttl = match tool_call.arguments.get("ttl_days") {
+                    None | Some(Value::Null) => None,
+                    Some(days) => Some(
+                        days.as_f64()
+                            .filter(|days| *days > 0.0)
+                            .and_then(|days| Duration::try_seconds((days * 86_400.0) as i64))
+                            .ok_or_else(|| {
+                                io::Error::new(
+                                    io::ErrorKind::InvalidInput,
+                                    "ttl_days must be a positive number",
+                                )
+                            })?,
+                    ),
+                };
+                match self.remember_with_ttl(
+                    "context",
+                    args.category,
+                    data,
+                    &args.tags,
+                    args.is_global,
+                    ttl,
+                )? {
+                    Remembered::Stored(id) => Ok(format!(
+                        "Stored memory {} in category: {}",
+                        id, args.category
+                    )),
+                    Remembered::Merged(id) => Ok(format!(
+                        "Updated existing memory {} in category: {}",
+                        id, args.category
+                    )),
+                }
             }
             "retrieve_memories" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
@@ -473,6 +984,54 @@ impl MemoryRouter {
                 };
                 Ok(format!("Retrieved memories: {:?}", memories))
             }
+            "search_memories" => {
+                let args = &tool_call.arguments;
+                let query = args["query"]
+                    .as_str()
+                    .filter(|query| !query.is_empty())
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "Query must be a string")
+                    })?;
+                let k = args["k"]
+                    .as_u64()
+                    .map(|k| k as usize)
+                    .unwrap_or(DEFAULT_SEARCH_RESULTS);
+                let tags = match &args["tags"] {
+                    Value::Array(arr) => arr.iter().filter_map(|v| v.as_str()).collect(),
+                    Value::String(s) => vec![s.as_str()],
+                    _ => Vec::new(),
+                };
+                let is_global = match args.get("is_global") {
+                    Some(Value::Bool(b)) => Some(*b),
+                    Some(Value::String(s)) => Some(s.to_lowercase() == "true"),
+                    _ => None,
+                };
+
+                let matches = self.search(query, k, is_global, args["category"].as_str(), &tags)?;
+                if matches.is_empty() {
+                    return Ok(format!("No memories found for: {}", query));
+                }
+                let mut result = format!("Found {} memories for: {}\n", matches.len(), query);
+                for (i, found) in matches.iter().enumerate() {
+                    let memory = &found.memory;
+                    result.push_str(&format!(
+                        "\n{}. [{}/{}] id: {}, score: {:.2}",
+                        i + 1,
+                        if found.is_global { "global" } else { "local" },
+                        memory.category,
+                        memory.id,
+                        found.score
+                    ));
+                    if !memory.tags.is_empty() {
+                        result.push_str(&format!(", tags: {}", memory.tags.join(" ")));
+                    }
+                    if let Some(expires_at) = memory.expires_at {
+                        result.push_str(&format!(", expires: {}", expires_at.to_rfc3339()));
+                    }
+                    result.push_str(&format!("\n{}\n", memory.content));
+                }
+                Ok(result)
+            }
             "remove_memory_category" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
                 if args.category == "*" {
@@ -488,7 +1047,25 @@ impl MemoryRouter {
             }
             "remove_specific_memory" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                let memory_content = tool_call.arguments["memory_content"].as_str().unwrap();
+                if let Some(id) = tool_call.arguments["id"].as_str() {
+                    return if self.remove_memory_by_id(id, args.is_global)? {
+                        Ok(format!("Removed memory {}", id))
+                    } else {
+                        Err(io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("No memory with id {}", id),
+                        ))
+                    };
+                }
+                let memory_content =
+                    tool_call.arguments["memory_content"]
+                        .as_str()
+                        .ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                "Either id or memory_content must be given",
+                            )
+                        })?;
                 self.remove_specific_memory(args.category, memory_content, args.is_global)?;
                 Ok(format!(
                     "Removed specific memory from category: {}",
@@ -511,7 +1088,10 @@ impl Router for MemoryRouter {
     }
 
     fn capabilities(&self) -> ServerCapabilities {
-        CapabilitiesBuilder::new().with_tools(false).build()
+        CapabilitiesBuilder::new()
+            .with_tools(false)
+            .with_resources(false, false)
+            .build()
     }
 
     fn list_tools(&self) -> Vec<Tool> {
@@ -540,14 +1120,51 @@ impl Router for MemoryRouter {
     }
 
     fn list_resources(&self) -> Vec<Resource> {
-        Vec::new()
+        let mut digest = RawResource::new(DIGEST_URI, "Relevant memories".to_string());
+        digest.description = Some(
+            "Saved memories ranked by relevance to the `query` parameter, up to `max_tokens`"
+                .to_string(),
+        );
+        digest.mime_type = Some("text/markdown".to_string());
+        vec![digest.no_annotation()]
     }
 
     fn read_resource(
         &self,
-        _uri: &str,
+        uri: &str,
     ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
-        Box::pin(async move { Ok("".to_string()) })
+        let this = self.clone();
+        let uri = uri.to_string();
+
+        Box::pin(async move {
+            let url = Url::parse(&uri)
+                .map_err(|e| ResourceError::NotFound(format!("Invalid URI: {}", e)))?;
+            if url.scheme() != "memory" || url.host_str() != Some("digest") {
+                return Err(ResourceError::NotFound(format!(
+                    "Resource not found: {}",
+                    uri
+                )));
+            }
+
+            let mut query = None;
+            let mut max_tokens = DEFAULT_DIGEST_TOKENS;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "query" if !value.trim().is_empty() => query = Some(value.into_owned()),
+                    "max_tokens" => {
+                        max_tokens = value.parse().map_err(|_| {
+                            ResourceError::ExecutionError(
+                                "max_tokens must be a whole number".to_string(),
+                            )
+                        })?
+                    }
+                    _ => {}
+                }
+            }
+
+            this.digest(query.as_deref(), max_tokens)
+                .map_err(|e| ResourceError::ExecutionError(e.to_string()))
+        })
     }
     fn list_prompts(&self) -> Vec<Prompt> {
         vec![]
@@ -567,6 +1184,17 @@ impl Router for MemoryRouter {
     }
 }
 
+/// Drop expired memories, and the records of memories deleted long ago
+fn prune(memories: &mut Vec<Memory>, now: DateTime<Utc>) {
+    let forget_before = now - Duration::days(DELETED_RETENTION_DAYS);
+    memories.retain(|memory| {
+        !memory.is_expired(now)
+            && memory
+                .deleted_at
+                .is_none_or(|deleted_at| deleted_at > forget_before)
+    });
+}
+
 #[derive(Debug)]
 struct MemoryArgs<'a> {
     category: &'a str,
@@ -622,22 +1250,25 @@ impl<'a> MemoryArgs<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
     use tempfile::tempdir;
 
+    fn test_router(memory_base: &Path) -> MemoryRouter {
+        MemoryRouter::with_stores(
+            Arc::new(FileMemoryStore::new(memory_base.join("global"))),
+            Arc::new(FileMemoryStore::new(memory_base.join("local"))),
+        )
+    }
+
     #[test]
     fn test_lazy_directory_creation() {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("test_memory");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
-        assert!(!router.global_memory_dir.exists());
-        assert!(!router.local_memory_dir.exists());
+        assert!(!memory_base.join("global").exists());
+        assert!(!memory_base.join("local").exists());
 
         router
             .remember(
@@ -649,8 +1280,8 @@ mod tests {
             )
             .unwrap();
 
-        assert!(router.local_memory_dir.exists());
-        assert!(!router.global_memory_dir.exists());
+        assert!(memory_base.join("local").exists());
+        assert!(!memory_base.join("global").exists());
 
         router
             .remember(
@@ -662,7 +1293,7 @@ mod tests {
             )
             .unwrap();
 
-        assert!(router.global_memory_dir.exists());
+        assert!(memory_base.join("global").exists());
     }
 
     #[test]
@@ -670,12 +1301,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("nonexistent_memory");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         assert!(router.clear_all_global_or_local_memories(false).is_ok());
         assert!(router.clear_all_global_or_local_memories(true).is_ok());
@@ -686,12 +1312,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("workflow_test");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         router
             .remember(
@@ -723,21 +1344,16 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("write_test");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
-        assert!(!router.local_memory_dir.exists());
+        assert!(!memory_base.join("local").exists());
 
         router
             .remember("context", "category", "data", &[], false)
             .unwrap();
 
-        assert!(router.local_memory_dir.exists());
-        assert!(router.local_memory_dir.join("category.txt").exists());
+        assert!(memory_base.join("local").exists());
+        assert!(memory_base.join("local").join("memories.json").exists());
     }
 
     #[test]
@@ -745,12 +1361,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("remove_test");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         router
             .remember("context", "category", "keep_this", &[], false)
@@ -777,4 +1388,352 @@ mod tests {
             .any(|v| v.iter().any(|content| content.contains("keep_this")));
         assert!(has_kept);
     }
+
+    #[test]
+    fn test_remember_merges_duplicates() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(temp_dir.path());
+
+        let first = router
+            .remember(
+                "context",
+                "development",
+                "We use black for formatting",
+                &["python"],
+                false,
+            )
+            .unwrap();
+        let second = router
+            .remember(
+                "context",
+                "development",
+                "we use Black for formatting.",
+                &["tools"],
+                false,
+            )
+            .unwrap();
+        let Remembered::Stored(id) = first else {
+            panic!("first memory should be stored");
+        };
+        assert_eq!(second, Remembered::Merged(id));
+
+        let memories = router.memories(false).unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].content, "we use Black for formatting.");
+        assert_eq!(memories[0].tags, vec!["python", "tools"]);
+
+        // The same fact in another category is kept separately
+        let other = router
+            .remember(
+                "context",
+                "style",
+                "We use black for formatting",
+                &[],
+                false,
+            )
+            .unwrap();
+        assert!(matches!(other, Remembered::Stored(_)));
+    }
+
+    #[test]
+    fn test_expired_memories_are_forgotten() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(temp_dir.path());
+
+        router
+            .remember_with_ttl(
+                "context",
+                "releases",
+                "Code freeze until Friday",
+                &[],
+                false,
+                Some(Duration::seconds(-1)),
+            )
+            .unwrap();
+        router
+            .remember_with_ttl(
+                "context",
+                "releases",
+                "Release branches are cut on Mondays",
+                &[],
+                false,
+                Some(Duration::days(30)),
+            )
+            .unwrap();
+
+        let memories = router.retrieve("releases", false).unwrap();
+        assert_eq!(
+            memories.get("untagged"),
+            Some(&vec!["Release branches are cut on Mondays".to_string()])
+        );
+
+        // Writing prunes expired memories from the index
+        router
+            .remember("context", "other", "Unrelated", &[], false)
+            .unwrap();
+        let stored = FileMemoryStore::new(temp_dir.path().join("local"))
+            .load()
+            .unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(stored
+            .iter()
+            .all(|memory| !memory.content.contains("freeze")));
+    }
+
+    #[test]
+    fn test_search_ranks_across_categories_and_scopes() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(temp_dir.path());
+
+        router
+            .remember(
+                "context",
+                "development",
+                "Format python code with black",
+                &["formatting"],
+                false,
+            )
+            .unwrap();
+        router
+            .remember(
+                "context",
+                "deploys",
+                "Deploys go through staging first",
+                &[],
+                false,
+            )
+            .unwrap();
+        router
+            .remember(
+                "context",
+                "editor",
+                "Format on save is enabled in vim",
+                &["formatting"],
+                true,
+            )
+            .unwrap();
+
+        let matches = router
+            .search("how do I format python code", 2, None, None, &[])
+            .unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].memory.content, "Format python code with black");
+        assert!(!matches[0].is_global);
+        assert_eq!(matches[1].memory.category, "editor");
+        assert!(matches[1].is_global);
+        assert!(matches[0].score >= matches[1].score);
+
+        let global_only = router
+            .search("format python code", 5, Some(true), None, &[])
+            .unwrap();
+        assert_eq!(global_only.len(), 1);
+
+        let tagged = router
+            .search("staging deploys", 5, None, None, &["formatting"])
+            .unwrap();
+        assert!(tagged
+            .iter()
+            .all(|found| found.memory.tags.contains(&"formatting".to_string())));
+    }
+
+    #[test]
+    fn test_remove_memory_by_id() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(temp_dir.path());
+
+        let Remembered::Stored(id) = router
+            .remember("context", "category", "remove_this", &[], true)
+            .unwrap()
+        else {
+            panic!("memory should be stored");
+        };
+
+        assert!(!router.remove_memory_by_id(&id, false).unwrap());
+        assert!(router.remove_memory_by_id(&id, true).unwrap());
+        assert!(router.memories(true).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_digest_resource_ranks_memories_within_budget() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(temp_dir.path());
+
+        router
+            .remember(
+                "context",
+                "testing",
+                "Run the login tests with npm run test:e2e",
+                &[],
+                false,
+            )
+            .unwrap();
+        router
+            .remember(
+                "context",
+                "deploys",
+                "Deploys go through staging first",
+                &[],
+                false,
+            )
+            .unwrap();
+        router
+            .remember(
+                "context",
+                "editor",
+                "Format python code with black",
+                &[],
+                true,
+            )
+            .unwrap();
+        router
+            .remember(
+                "context",
+                "testing",
+                "Flaky login tests usually mean the seed data is stale",
+                &[],
+                true,
+            )
+            .unwrap();
+
+        let digest = router
+            .read_resource("memory://digest?query=fix+the+login+tests&max_tokens=1000")
+            .await
+            .unwrap();
+        let lines = digest.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "- [local/testing] Run the login tests with npm run test:e2e",
+                "- [global/testing] Flaky login tests usually mean the seed data is stale",
+                "- [local/deploys] Deploys go through staging first",
+            ]
+        );
+
+        let small = router
+            .read_resource("memory://digest?query=fix+the+login+tests&max_tokens=15")
+            .await
+            .unwrap();
+        assert_eq!(small.lines().collect::<Vec<_>>(), lines[..1]);
+
+        assert!(router.read_resource("memory://other").await.is_err());
+    }
+
+    #[test]
+    fn test_import_resolves_conflicts() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(temp_dir.path());
+
+        let Remembered::Stored(id) = router
+            .remember(
+                "context",
+                "development",
+                "We use black for formatting",
+                &[],
+                true,
+            )
+            .unwrap()
+        else {
+            panic!("memory should be stored");
+        };
+
+        // An older edit of the same memory from another machine
+        let mut older = router.export(true).unwrap().remove(0);
+        older.content = "We use ruff for formatting".to_string();
+        older.updated_at -= Duration::days(1);
+        let unrelated = Memory {
+            id: "from-another-machine".to_string(),
+            category: "deploys".to_string(),
+            content: "Deploys go through staging first".to_string(),
+            ..older.clone()
+        };
+
+        let summary = router
+            .import(
+                vec![older.clone(), unrelated],
+                true,
+                ConflictStrategy::Newer,
+            )
+            .unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                added: 1,
+                updated: 0,
+                removed: 0,
+                unchanged: 1
+            }
+        );
+
+        let summary = router
+            .import(vec![older.clone()], true, ConflictStrategy::Replace)
+            .unwrap();
+        assert_eq!(summary.updated, 1);
+        let memories = router.memories(true).unwrap();
+        let replaced = memories.iter().find(|memory| memory.id == id).unwrap();
+        assert_eq!(replaced.content, "We use ruff for formatting");
+
+        // A repeat of a memory under another id conflicts with it too
+        let repeat = Memory {
+            id: "another-id".to_string(),
+            content: "we use Ruff for formatting.".to_string(),
+            updated_at: Utc::now(),
+            ..older
+        };
+        let summary = router
+            .import(vec![repeat], true, ConflictStrategy::Keep)
+            .unwrap();
+        assert_eq!(summary.unchanged, 1);
+        assert_eq!(router.memories(true).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_deletions_are_imported() {
+        let laptop_dir = tempdir().unwrap();
+        let laptop = test_router(laptop_dir.path());
+        let Remembered::Stored(id) = laptop
+            .remember(
+                "context",
+                "deploys",
+                "Deploys go through staging first",
+                &[],
+                true,
+            )
+            .unwrap()
+        else {
+            panic!("memory should be stored");
+        };
+        laptop
+            .remember("context", "development", "We use black", &[], true)
+            .unwrap();
+
+        let devbox_dir = tempdir().unwrap();
+        let devbox = test_router(devbox_dir.path());
+        let before = laptop.export(true).unwrap();
+        devbox
+            .import(before.clone(), true, ConflictStrategy::Newer)
+            .unwrap();
+        assert_eq!(devbox.memories(true).unwrap().len(), 2);
+
+        // The deleted memory leaves a record in the export, without its content
+        assert!(laptop.remove_memory_by_id(&id, true).unwrap());
+        let exported = laptop.export(true).unwrap();
+        let deleted = exported.iter().find(|memory| memory.id == id).unwrap();
+        assert!(deleted.is_deleted());
+        assert!(deleted.content.is_empty());
+        assert_eq!(laptop.memories(true).unwrap().len(), 1);
+
+        let summary = devbox
+            .import(exported, true, ConflictStrategy::Newer)
+            .unwrap();
+        assert_eq!(summary.removed, 1);
+        let remaining = devbox.memories(true).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].content, "We use black");
+
+        // Importing the archive from before the deletion doesn't bring it back
+        let summary = devbox
+            .import(before, true, ConflictStrategy::Newer)
+            .unwrap();
+        assert!(!summary.changed());
+    }
 }
@@ -0,0 +1,389 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const INDEX_FILE: &str = "memories.json";
+/// Held while the index is read and written back, so concurrent sessions don't lose each other's changes
+const LOCK_FILE: &str = "memories.lock";
+const INDEX_VERSION: u32 = 1;
+
+/// One remembered fact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Memory {
+    pub id: String,
+    pub category: String,
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// When the memory stops being returned, if it was stored with a TTL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Empty until computed, e.g. for memories imported from the old text files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedding: Vec<f32>,
+    /// The model that computed `embedding`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub embedding_model: String,
+    /// When the memory was deleted, for the record kept so syncing deletes it elsewhere too
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Memory {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Turn the memory into a record that it was deleted, keeping only its id and category
+    pub fn delete(&mut self, now: DateTime<Utc>) {
+        self.content.clear();
+        self.tags.clear();
+        self.embedding.clear();
+        self.embedding_model.clear();
+        self.expires_at = None;
+        self.updated_at = now;
+        self.deleted_at = Some(now);
+    }
+}
+
+/// Keeps the memories of one scope, global or local
+///
+/// Implementations only persist memories. Expiry, deduplication and search are
+/// handled by the memory router, so every backend behaves the same.
+pub trait MemoryStore: Send + Sync {
+    /// Every stored memory, including expired and deleted ones
+    fn load(&self) -> io::Result<Vec<Memory>>;
+
+    /// Replace the stored memories
+    fn save(&self, memories: &[Memory]) -> io::Result<()>;
+
+    /// Load the memories and save them again after `change`, if it returns true
+    ///
+    /// No other update to the store can happen in between, even from another process,
+    /// so changes made by concurrent sessions are kept.
+    fn update(
+        &self,
+        change: &mut dyn FnMut(&mut Vec<Memory>) -> io::Result<bool>,
+    ) -> io::Result<()>;
+
+    /// Remove every memory, along with anything else the store keeps
+    fn clear(&self) -> io::Result<()>;
+
+    /// Where the memories live, for showing to the user
+    fn location(&self) -> String;
+}
+
+#[derive(Serialize, Deserialize)]
+struct MemoryIndex {
+    version: u32,
+    memories: Vec<Memory>,
+}
+
+/// Stores memories with their embeddings in a single JSON index inside a directory
+///
+/// Directories written by older versions hold one `{category}.txt` file per category.
+/// When there is no index yet, their memories are moved into one the first time they
+/// are loaded, so the ids they are given stay the same.
+pub struct FileMemoryStore {
+    dir: PathBuf,
+}
+
+impl FileMemoryStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join(INDEX_FILE)
+    }
+
+    /// The memories in the index, or None if there is no index yet
+    fn read_index(&self) -> io::Result<Option<Vec<Memory>>> {
+        let path = self.index_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        let index: MemoryIndex = serde_json::from_str(&content).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Corrupt memory index {}: {}", path.display(), e),
+            )
+        })?;
+        if index.version > INDEX_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Memory index {} was written by a newer version of goose",
+                    path.display()
+                ),
+            ));
+        }
+        Ok(Some(index.memories))
+    }
+
+    fn write_index(&self, memories: &[Memory]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let index = MemoryIndex {
+            version: INDEX_VERSION,
+            memories: memories.to_vec(),
+        };
+        let content = serde_json::to_string_pretty(&index)?;
+
+        // Write to a temporary file first so a crash never leaves a truncated index
+        let path = self.index_path();
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, &path)
+    }
+
+    /// Wait until no other process is changing the store, which lasts until the file is dropped
+    fn lock(&self) -> io::Result<fs::File> {
+        fs::create_dir_all(&self.dir)?;
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(LOCK_FILE))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    /// Write the memories of the old category files to the index, unless another process already has
+    fn migrate(&self, legacy: Vec<Memory>) -> io::Result<Vec<Memory>> {
+        let _lock = self.lock()?;
+        if let Some(memories) = self.read_index()? {
+            return Ok(memories);
+        }
+        self.write_index(&legacy)?;
+        Ok(legacy)
+    }
+
+    fn load_legacy(&self) -> io::Result<Vec<Memory>> {
+        let mut memories = Vec::new();
+        if !self.dir.exists() {
+            return Ok(memories);
+        }
+
+        let mut paths = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            let category = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map(DateTime::<Utc>::from)
+                .unwrap_or_else(|_| Utc::now());
+            let content = fs::read_to_string(&path)?;
+            memories.extend(parse_legacy_category(&category, &content, modified));
+        }
+        Ok(memories)
+    }
+}
+
+impl MemoryStore for FileMemoryStore {
+    fn load(&self) -> io::Result<Vec<Memory>> {
+        if let Some(memories) = self.read_index()? {
+            return Ok(memories);
+        }
+        let legacy = self.load_legacy()?;
+        if legacy.is_empty() {
+            return Ok(legacy);
+        }
+        match self.migrate(legacy.clone()) {
+            Ok(memories) => Ok(memories),
+            Err(e) => {
+                // The directory may be read-only, so the old files can still be read as they are
+                tracing::warn!(
+                    "Failed to move the memories in {} to an index: {}",
+                    self.dir.display(),
+                    e
+                );
+                Ok(legacy)
+            }
+        }
+    }
+
+    fn save(&self, memories: &[Memory]) -> io::Result<()> {
+        let _lock = self.lock()?;
+        self.write_index(memories)
+    }
+
+    fn update(
+        &self,
+        change: &mut dyn FnMut(&mut Vec<Memory>) -> io::Result<bool>,
+    ) -> io::Result<()> {
+        let _lock = self.lock()?;
+        let mut memories = match self.read_index()? {
+            Some(memories) => memories,
+            None => self.load_legacy()?,
+        };
+        if change(&mut memories)? {
+            self.write_index(&memories)?;
+        }
+        Ok(())
+    }
+
+    fn clear(&self) -> io::Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+        // The lock file stays, since another process may be waiting on it
+        let _lock = self.lock()?;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == LOCK_FILE) {
+                continue;
+            }
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn location(&self) -> String {
+        self.dir.display().to_string()
+    }
+}
+
+/// Read a category file in the old format, where memories are separated by blank lines
+/// and may start with a `# tag1 tag2` line
+fn parse_legacy_category(category: &str, content: &str, modified: DateTime<Utc>) -> Vec<Memory> {
+    content
+        .split("\n\n")
+        .filter_map(|entry| {
+            let mut lines = entry.lines().peekable();
+            let tags = match lines
+                .peek()
+                .copied()
+                .and_then(|line| line.strip_prefix('#'))
+            {
+                Some(tags) => {
+                    let tags = tags.split_whitespace().map(String::from).collect();
+                    lines.next();
+                    tags
+                }
+                None => Vec::new(),
+            };
+            let content = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+            if content.is_empty() {
+                return None;
+            }
+            Some(Memory {
+                id: uuid::Uuid::new_v4().to_string(),
+                category: category.to_string(),
+                content,
+                tags,
+                created_at: modified,
+                updated_at: modified,
+                expires_at: None,
+                embedding: Vec::new(),
+                embedding_model: String::new(),
+                deleted_at: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_moves_legacy_category_files_to_the_index() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("development.txt"),
+            "# formatting tools\nWe use black\n\nRun tests with pytest\n\n",
+        )
+        .unwrap();
+        let store = FileMemoryStore::new(dir.path());
+
+        let memories = store.load().unwrap();
+        assert_eq!(memories.len(), 2);
+        assert_eq!(memories[0].category, "development");
+        assert_eq!(memories[0].content, "We use black");
+        assert_eq!(memories[0].tags, vec!["formatting", "tools"]);
+        assert_eq!(memories[1].content, "Run tests with pytest");
+        assert!(memories[1].tags.is_empty());
+
+        // The ids the memories were given are kept, so they can be referred to later
+        assert!(dir.path().join(INDEX_FILE).exists());
+        assert_eq!(store.load().unwrap(), memories);
+
+        store.save(&memories[..1]).unwrap();
+        let reloaded = store.load().unwrap();
+        assert_eq!(reloaded, memories[..1].to_vec());
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_all_kept() {
+        let dir = tempdir().unwrap();
+        let handles = (0..8)
+            .map(|i| {
+                let store = FileMemoryStore::new(dir.path());
+                std::thread::spawn(move || {
+                    store
+                        .update(&mut |memories| {
+                            let now = Utc::now();
+                            memories.push(Memory {
+                                id: i.to_string(),
+                                category: "development".to_string(),
+                                content: format!("Fact {}", i),
+                                tags: vec![],
+                                created_at: now,
+                                updated_at: now,
+                                expires_at: None,
+                                embedding: vec![],
+                                embedding_model: String::new(),
+                                deleted_at: None,
+                            });
+                            Ok(true)
+                        })
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let memories = FileMemoryStore::new(dir.path()).load().unwrap();
+        assert_eq!(memories.len(), 8);
+    }
+
+    #[test]
+    fn test_rejects_index_from_newer_version() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(INDEX_FILE),
+            r#"{"version": 99, "memories": []}"#,
+        )
+        .unwrap();
+
+        let err = FileMemoryStore::new(dir.path()).load().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,11 +1,39 @@
 use anyhow::Result;
+use goose::providers::embedding::BlockingEmbedder;
+use goose_mcp::memory::FnEmbedder;
 use goose_mcp::{
     ComputerControllerRouter, DeveloperRouter, GoogleDriveRouter, MemoryRouter, TutorialRouter,
 };
 use mcp_server::router::RouterService;
 use mcp_server::{BoundedService, ByteTransport, Server};
+use std::io;
+use std::sync::Arc;
 use tokio::io::{stdin, stdout};
 
+/// A memory router that embeds memories with the configured embedding model, if there is one
+fn memory_router() -> MemoryRouter {
+    let router = MemoryRouter::new();
+    match BlockingEmbedder::from_config() {
+        Ok(Some(embedder)) => {
+            let model = embedder.model().to_string();
+            router.with_embedder(Arc::new(FnEmbedder::new(
+                model,
+                move |texts: &[String]| embedder.embed(texts.to_vec()).map_err(io::Error::other),
+            )))
+        }
+        Ok(None) => {
+            tracing::warn!(
+                "No embedding model is configured, memories are matched by shared words"
+            );
+            router
+        }
+        Err(e) => {
+            tracing::warn!("Failed to set up the embedding model for memories: {}", e);
+            router
+        }
+    }
+}
+
 pub async fn run(name: &str) -> Result<()> {
     // Initialize logging
     crate::logging::setup_logging(Some(&format!("mcp-{name}")))?;
@@ -18,7 +46,7 @@ pub async fn run(name: &str) -> Result<()> {
             let router = GoogleDriveRouter::new().await;
             Some(Box::new(RouterService(router)))
         }
-        "memory" => Some(Box::new(RouterService(MemoryRouter::new()))),
+        "memory" => Some(Box::new(RouterService(memory_router()))),
         "tutorial" => Some(Box::new(RouterService(TutorialRouter::new()))),
         _ => None,
     };
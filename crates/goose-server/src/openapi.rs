@@ -1,16 +1,18 @@
 use goose::agents::extension::Envs;
+use goose::agents::extension::SandboxConfig;
 use goose::agents::extension::ToolInfo;
 use goose::agents::ExtensionConfig;
 use goose::config::permission::PermissionLevel;
 use goose::config::ExtensionEntry;
 use goose::message::{
-    ContextLengthExceeded, FrontendToolRequest, Message, MessageContent, RedactedThinkingContent,
-    SummarizationRequested, ThinkingContent, ToolConfirmationRequest, ToolRequest, ToolResponse,
+    ConfirmationKind, ContextLengthExceeded, ElicitationRequest, FrontendToolRequest, Message,
+    MessageContent, RedactedThinkingContent, SummarizationRequested, ThinkingContent,
+    ToolConfirmationRequest, ToolRequest, ToolResponse,
 };
 use goose::permission::permission_confirmation::PrincipalType;
 use goose::providers::base::{ConfigKey, ModelInfo, ProviderMetadata};
 use goose::session::info::SessionInfo;
-use goose::session::SessionMetadata;
+use goose::session::{ServedBy, SessionMetadata};
 use rmcp::model::{
     Annotations, Content, EmbeddedResource, ImageContent, RawEmbeddedResource, RawImageContent,
     RawTextContent, ResourceContents, Role, TextContent, Tool, ToolAnnotations,
@@ -370,9 +372,12 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::agent::get_tools,
         super::routes::agent::add_sub_recipes,
         super::routes::reply::confirm_permission,
+        super::routes::reply::respond_to_elicitation,
         super::routes::context::manage_context,
         super::routes::session::list_sessions,
         super::routes::session::get_session_history,
+        super::routes::session::search_sessions,
+        super::routes::session::fork_session,
         super::routes::schedule::create_schedule,
         super::routes::schedule::list_schedules,
         super::routes::schedule::delete_schedule,
@@ -398,10 +403,16 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::config_management::ToolPermission,
         super::routes::config_management::UpsertPermissionsQuery,
         super::routes::reply::PermissionConfirmationRequest,
+        super::routes::reply::ElicitationResponseRequest,
         super::routes::context::ContextManageRequest,
         super::routes::context::ContextManageResponse,
         super::routes::session::SessionListResponse,
         super::routes::session::SessionHistoryResponse,
+        super::routes::session::SessionSearchQuery,
+        super::routes::session::ForkSessionRequest,
+        super::routes::session::ForkSessionResponse,
+        super::routes::session::SessionSearchResponse,
+        goose::session::SearchResult,
         Message,
         MessageContent,
         ContentSchema,
@@ -416,6 +427,8 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         ToolResponse,
         ToolRequest,
         ToolConfirmationRequest,
+        ConfirmationKind,
+        ElicitationRequest,
         ThinkingContent,
         RedactedThinkingContent,
         FrontendToolRequest,
@@ -428,6 +441,7 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         ExtensionConfig,
         ConfigKey,
         Envs,
+        SandboxConfig,
         ToolSchema,
         ToolAnnotationsSchema,
         ToolInfo,
@@ -436,6 +450,7 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         ModelInfo,
         SessionInfo,
         SessionMetadata,
+        ServedBy,
         super::routes::schedule::CreateScheduleRequest,
         super::routes::schedule::UpdateScheduleRequest,
         super::routes::schedule::KillJobResponse,
@@ -462,6 +477,9 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         goose::recipe::SubRecipe,
         goose::agents::types::RetryConfig,
         goose::agents::types::SuccessCheck,
+        goose::context_mgmt::structured::CompactionConfig,
+        goose::context_mgmt::structured::CompactionMode,
+        goose::agents::CostBudget,
         super::routes::agent::AddSubRecipesRequest,
         super::routes::agent::AddSubRecipesResponse,
     ))
@@ -68,7 +68,7 @@ async fn manage_context(
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     } else if request.manage_action == "summarize" {
         (processed_messages, token_counts) = agent
-            .summarize_context(&request.messages)
+            .compact_context(&request.messages)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     }
@@ -6,7 +6,10 @@ use std::sync::OnceLock;
 use super::utils::verify_secret_key;
 use crate::state::AppState;
 use axum::{extract::State, routing::post, Json, Router};
-use goose::agents::{extension::Envs, ExtensionConfig};
+use goose::agents::{
+    extension::{Envs, SandboxConfig},
+    ExtensionConfig,
+};
 use http::{HeaderMap, StatusCode};
 use rmcp::model::Tool;
 use serde::{Deserialize, Serialize};
@@ -56,6 +59,9 @@ enum ExtensionConfigRequest {
         name: String,
         display_name: Option<String>,
         timeout: Option<u64>,
+        /// Sandbox for the extension's shell commands.
+        #[serde(default)]
+        sandbox: Option<SandboxConfig>,
     },
     /// Streamable HTTP extension using MCP Streamable HTTP specification.
     #[serde(rename = "streamable_http")]
@@ -247,11 +253,13 @@ async fn add_extension(
             name,
             display_name,
             timeout,
+            sandbox,
         } => ExtensionConfig::Builtin {
             name,
             display_name,
             timeout,
             bundled: None,
+            sandbox,
             description: None,
         },
         ExtensionConfigRequest::Frontend {
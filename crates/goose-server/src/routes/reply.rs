@@ -18,6 +18,7 @@ use goose::{
     permission::{Permission, PermissionConfirmation},
     session,
 };
+use mcp_core::protocol::{ElicitAction, ElicitResult};
 use mcp_core::ToolResult;
 use rmcp::model::{Content, ServerNotification};
 use serde::{Deserialize, Serialize};
@@ -99,6 +100,13 @@ enum MessageEvent {
         request_id: String,
         message: ServerNotification,
     },
+    Progress {
+        request_id: String,
+        progress: f64,
+        total: Option<f64>,
+        percentage: Option<f64>,
+        message: Option<String>,
+    },
 }
 
 async fn stream_event(
@@ -239,6 +247,9 @@ async fn reply_handler(
                                             ).await;
                                         }
                                     }
+                                    Ok(Some(Ok(AgentEvent::MaxTurnsReached | AgentEvent::Error(_)))) => {
+                                        // The assistant message before this event already told the client
+                                    }
                                     Ok(Some(Ok(AgentEvent::McpNotification((request_id, n))))) => {
                                         if let Err(e) = stream_event(MessageEvent::Notification{
                                             request_id: request_id.clone(),
@@ -254,6 +265,25 @@ async fn reply_handler(
                                         }
                                     }
 
+                                    Ok(Some(Ok(AgentEvent::ToolProgress(progress)))) => {
+                                        let percentage = progress.percentage();
+                                        if let Err(e) = stream_event(MessageEvent::Progress {
+                                            request_id: progress.request_id,
+                                            progress: progress.progress,
+                                            total: progress.total,
+                                            percentage,
+                                            message: progress.message,
+                                        }, &tx).await {
+                                            tracing::error!("Error sending progress through channel: {}", e);
+                                            let _ = stream_event(
+                                                MessageEvent::Error {
+                                                    error: e.to_string(),
+                                                },
+                                                &tx,
+                                            ).await;
+                                        }
+                                    }
+
                                     Ok(Some(Err(e))) => {
                                         tracing::error!("Error processing message: {}", e);
                                         let _ = stream_event(
@@ -360,6 +390,63 @@ pub async fn confirm_permission(
     Ok(Json(Value::Object(serde_json::Map::new())))
 }
 
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ElicitationResponseRequest {
+    id: String,
+    /// "accept", "decline" or "cancel"
+    #[schema(value_type = String)]
+    action: ElicitAction,
+    /// The submitted form values, when accepting
+    #[schema(value_type = Object)]
+    content: Option<Value>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/elicitation_response",
+    request_body = ElicitationResponseRequest,
+    responses(
+        (status = 200, description = "Elicitation response was delivered to the extension", body = Value),
+        (status = 400, description = "Unknown action, or values that don't match the requested schema"),
+        (status = 401, description = "Unauthorized - invalid secret key"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn respond_to_elicitation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    raw: Json<Value>,
+) -> Result<Json<Value>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let request: ElicitationResponseRequest = serde_json::from_value(raw.0).map_err(|e| {
+        tracing::warn!("Invalid elicitation response: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    let result = ElicitResult {
+        action: request.action,
+        content: match request.action {
+            ElicitAction::Accept => request.content,
+            ElicitAction::Decline | ElicitAction::Cancel => None,
+        },
+    };
+
+    agent
+        .handle_elicitation_response(request.id, result)
+        .await
+        .map_err(|e| {
+            tracing::warn!("Rejected elicitation response: {}", e);
+            StatusCode::BAD_REQUEST
+        })?;
+    Ok(Json(Value::Object(serde_json::Map::new())))
+}
+
 #[derive(Debug, Deserialize)]
 struct ToolResultRequest {
     id: String,
@@ -405,6 +492,7 @@ pub fn routes(state: Arc<AppState>) -> Router {
             post(reply_handler).layer(DefaultBodyLimit::max(50 * 1024 * 1024)),
         )
         .route("/confirm", post(confirm_permission))
+        .route("/elicitation_response", post(respond_to_elicitation))
         .route(
             "/tool_result",
             post(submit_tool_result).layer(DefaultBodyLimit::max(10 * 1024 * 1024)),
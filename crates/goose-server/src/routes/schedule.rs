@@ -12,6 +12,7 @@ use chrono::NaiveDateTime;
 
 use crate::routes::utils::verify_secret_key;
 use crate::state::AppState;
+use goose::agents::CostBudget;
 use goose::scheduler::ScheduledJob;
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
@@ -21,6 +22,8 @@ pub struct CreateScheduleRequest {
     cron: String,
     #[serde(default)]
     execution_mode: Option<String>, // "foreground" or "background"
+    #[serde(default)]
+    budget: Option<CostBudget>,
 }
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
@@ -81,6 +84,7 @@ pub struct SessionDisplayInfo {
     accumulated_total_tokens: Option<i32>,
     accumulated_input_tokens: Option<i32>,
     accumulated_output_tokens: Option<i32>,
+    accumulated_cost: Option<f64>,
 }
 
 fn parse_session_name_to_iso(session_name: &str) -> String {
@@ -127,6 +131,7 @@ async fn create_schedule(
         current_session_id: None,
         process_start_time: None,
         execution_mode: req.execution_mode.or(Some("background".to_string())), // Default to background
+        budget: req.budget,
     };
     scheduler
         .add_scheduled_job(job.clone())
@@ -137,6 +142,9 @@ async fn create_schedule(
                 goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
                 goose::scheduler::SchedulerError::CronParseError(_) => StatusCode::BAD_REQUEST,
                 goose::scheduler::SchedulerError::RecipeLoadError(_) => StatusCode::BAD_REQUEST,
+                goose::scheduler::SchedulerError::UnsupportedJobSetting(_) => {
+                    StatusCode::BAD_REQUEST
+                }
                 goose::scheduler::SchedulerError::JobIdExists(_) => StatusCode::CONFLICT,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }
@@ -303,6 +311,7 @@ async fn sessions_handler(
                     accumulated_total_tokens: metadata.accumulated_total_tokens,
                     accumulated_input_tokens: metadata.accumulated_input_tokens,
                     accumulated_output_tokens: metadata.accumulated_output_tokens,
+                    accumulated_cost: metadata.accumulated_cost,
                 })
                 .collect();
             Ok(Json(display_infos))
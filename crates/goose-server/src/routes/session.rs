@@ -5,16 +5,17 @@ use std::sync::Arc;
 
 use crate::state::AppState;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use goose::message::Message;
 use goose::session;
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
-use goose::session::SessionMetadata;
-use serde::Serialize;
+use goose::session::search::{parse_since, DEFAULT_SEARCH_LIMIT};
+use goose::session::{SearchQuery, SearchResult, SessionMetadata};
+use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 use utoipa::ToSchema;
 
@@ -36,6 +37,46 @@ pub struct SessionHistoryResponse {
     messages: Vec<Message>,
 }
 
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkSessionRequest {
+    /// Index of the first message to leave out of the fork
+    message_index: usize,
+    /// Id for the new session; generated when omitted
+    new_session_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkSessionResponse {
+    /// Id of the newly created session
+    session_id: String,
+    /// Metadata of the new session, including its parent and fork point
+    metadata: SessionMetadata,
+}
+
+#[derive(Deserialize, ToSchema, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchQuery {
+    /// Text to search for in messages, tool calls and tool output
+    q: String,
+    /// Only search sessions started in this directory or below it
+    working_dir: Option<String>,
+    /// Only match messages since this time (RFC 3339, YYYY-MM-DD, or an age like 7d)
+    since: Option<String>,
+    /// Only search sessions that used a tool whose name contains this text
+    tool: Option<String>,
+    /// Maximum number of results
+    limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchResponse {
+    /// Matching messages, best match first
+    results: Vec<SearchResult>,
+}
+
 #[derive(Serialize, ToSchema, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionInsights {
@@ -47,6 +88,8 @@ pub struct SessionInsights {
     avg_session_duration: f64,
     /// Total tokens used across all sessions
     total_tokens: i64,
+    /// Estimated cost in US dollars across all sessions
+    total_cost: f64,
     /// Activity trend for the last 7 days
     recent_activity: Vec<(String, usize)>,
 }
@@ -132,6 +175,105 @@ async fn get_session_history(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/fork",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session to fork")
+    ),
+    request_body = ForkSessionRequest,
+    responses(
+        (status = 200, description = "Session forked successfully", body = ForkSessionResponse),
+        (status = 400, description = "Invalid fork point or session id"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 409, description = "A session with the requested id already exists"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Fork a session at a message so the conversation can be retried from there
+async fn fork_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<ForkSessionRequest>,
+) -> Result<Json<ForkSessionResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let parent_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let new_session_id = request
+        .new_session_id
+        .unwrap_or_else(session::generate_fork_id);
+    let fork_path = session::get_path(session::Identifier::Name(new_session_id.clone()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let metadata =
+        session::fork_session(&parent_path, request.message_index, &fork_path).map_err(|e| {
+            match e.downcast_ref::<session::ForkError>() {
+                Some(session::ForkError::ParentNotFound) => StatusCode::NOT_FOUND,
+                Some(session::ForkError::ForkExists) => StatusCode::CONFLICT,
+                Some(session::ForkError::PastEnd { .. }) => StatusCode::BAD_REQUEST,
+                None => {
+                    error!("Failed to fork session: {:?}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            }
+        })?;
+
+    Ok(Json(ForkSessionResponse {
+        session_id: new_session_id,
+        metadata,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/search",
+    params(SessionSearchQuery),
+    responses(
+        (status = 200, description = "Matching messages retrieved successfully", body = SessionSearchResponse),
+        (status = 400, description = "Invalid search parameters"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Full-text search across all sessions
+async fn search_sessions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<SessionSearchQuery>,
+) -> Result<Json<SessionSearchResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let mut query = SearchQuery::new(params.q);
+    query.working_dir = params.working_dir.map(Into::into);
+    query.since = params
+        .since
+        .as_deref()
+        .map(parse_since)
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    query.tool = params.tool;
+    query.limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let results = session::search_sessions(&query).map_err(|e| {
+        error!("Failed to search sessions: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(SessionSearchResponse { results }))
+}
+
 #[utoipa::path(
     get,
     path = "/sessions/insights",
@@ -178,6 +320,7 @@ async fn get_session_insights(
     let mut dir_counts: HashMap<String, usize> = HashMap::new();
     let mut total_duration = 0.0;
     let mut total_tokens = 0;
+    let mut total_cost = 0.0;
     let mut activity_by_date: HashMap<String, usize> = HashMap::new();
 
     for session in &sessions {
@@ -204,6 +347,8 @@ async fn get_session_insights(
             }
         }
 
+        total_cost += session.metadata.accumulated_cost.unwrap_or(0.0);
+
         // Track activity by date
         if let Ok(date) = DateTime::parse_from_str(&session.modified, "%Y-%m-%d %H:%M:%S UTC") {
             let date_str = date.format("%Y-%m-%d").to_string();
@@ -213,11 +358,9 @@ async fn get_session_insights(
         // Calculate session duration from messages
         let session_path = session::get_path(session::Identifier::Name(session.id.clone()));
         if let Ok(session_path) = session_path {
-            if let Ok(messages) = session::read_messages(&session_path) {
-                if let (Some(first), Some(last)) = (messages.first(), messages.last()) {
-                    let duration = (last.created - first.created) as f64 / 60.0; // Convert to minutes
-                    total_duration += duration;
-                }
+            if let Ok(Some((first, last))) = session::read_message_time_range(&session_path) {
+                let duration = (last - first) as f64 / 60.0; // Convert to minutes
+                total_duration += duration;
             }
         }
     }
@@ -244,6 +387,7 @@ async fn get_session_insights(
         most_active_dirs,
         avg_session_duration,
         total_tokens,
+        total_cost,
         recent_activity,
     };
 
@@ -305,6 +449,8 @@ pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/sessions", get(list_sessions))
         .route("/sessions/{session_id}", get(get_session_history))
+        .route("/sessions/{session_id}/fork", post(fork_session))
+        .route("/sessions/search", get(search_sessions))
         .route("/sessions/insights", get(get_session_insights))
         .route("/sessions/activity-heatmap", get(get_activity_heatmap))
         .with_state(state)
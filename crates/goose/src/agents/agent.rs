@@ -4,10 +4,14 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
+use futures::future::Either;
 use futures::stream::BoxStream;
 use futures::{stream, FutureExt, Stream, StreamExt, TryStreamExt};
 use uuid::Uuid;
 
+use crate::agents::budget::CostBudget;
+use crate::agents::client_handler::ClientContext;
+use crate::agents::elicitation::AwaitingAnswer;
 use crate::agents::extension::{ExtensionConfig, ExtensionError, ExtensionResult, ToolInfo};
 use crate::agents::extension_manager::{get_parameter_names, ExtensionManager};
 use crate::agents::final_output_tool::{FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_OUTPUT_TOOL_NAME};
@@ -20,6 +24,7 @@ use crate::agents::prompt_manager::PromptManager;
 use crate::agents::recipe_tools::dynamic_task_tools::{
     create_dynamic_task, create_dynamic_task_tool, DYNAMIC_TASK_TOOL_NAME_PREFIX,
 };
+use crate::agents::reply_parts::{turn_cost, MAX_MEMORY_CONTEXTS};
 use crate::agents::retry::{RetryManager, RetryResult};
 use crate::agents::router_tool_selector::RouterToolSelectionStrategy;
 use crate::agents::router_tools::{ROUTER_LLM_SEARCH_TOOL_NAME, ROUTER_VECTOR_SEARCH_TOOL_NAME};
@@ -31,21 +36,29 @@ use crate::agents::subagent_execution_tool::tasks_manager::TasksManager;
 use crate::agents::tool_route_manager::ToolRouteManager;
 use crate::agents::tool_router_index_manager::ToolRouterIndexManager;
 use crate::agents::types::SessionConfig;
-use crate::agents::types::{FrontendTool, ToolResultReceiver};
+use crate::agents::types::{FrontendTool, ToolProgress, ToolResultReceiver};
 use crate::config::{Config, ExtensionConfigManager, PermissionManager};
 use crate::context_mgmt::auto_compact;
+use crate::context_mgmt::structured::CompactionConfig;
 use crate::message::{push_message, Message, ToolRequest};
 use crate::permission::permission_judge::{check_tool_permissions, PermissionCheckResult};
 use crate::permission::PermissionConfirmation;
+use crate::permission::PolicyCache;
 use crate::providers::base::Provider;
 use crate::providers::errors::ProviderError;
 use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::tool_monitor::{ToolCall, ToolMonitor};
-use crate::utils::is_token_cancelled;
+use crate::utils::{is_token_cancelled, safe_truncate, wait_for_cancellation};
+use lru::LruCache;
+use mcp_core::checkpoint::CHECKPOINT_ARG;
 use mcp_core::{ToolError, ToolResult};
 use regex::Regex;
-use rmcp::model::{Content, GetPromptResult, Prompt, ServerNotification, Tool};
+use rmcp::model::{
+    Content, GetPromptResult, LoggingLevel, LoggingMessageNotification,
+    LoggingMessageNotificationMethod, LoggingMessageNotificationParam, Prompt, ServerNotification,
+    Tool,
+};
 use serde_json::Value;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
@@ -96,14 +109,31 @@ pub struct Agent {
     pub(super) tool_route_manager: ToolRouteManager,
     pub(super) scheduler_service: Mutex<Option<Arc<dyn SchedulerTrait>>>,
     pub(super) retry_manager: RetryManager,
+    pub(super) checkpoint: Mutex<Option<Value>>,
+    pub(super) compaction_config: Mutex<CompactionConfig>,
+    pub(super) cost_budget: Mutex<CostBudget>,
+    pub(super) tool_policy: Mutex<PolicyCache>,
+    pub(super) pending_elicitations: Mutex<HashMap<String, AwaitingAnswer>>,
+    /// The saved memories added to the system prompt of the sessions used most recently
+    pub(super) memory_contexts: Mutex<LruCache<Option<crate::session::Identifier>, String>>,
+    pub(super) client_context: ClientContext,
 }
 
 #[derive(Clone, Debug)]
 pub enum AgentEvent {
     Message(Message),
     McpNotification((String, ServerNotification)),
-    ModelChange { model: String, mode: String },
+    ToolProgress(ToolProgress),
+    ModelChange {
+        model: String,
+        mode: String,
+    },
     HistoryReplaced(Vec<Message>),
+    /// The reply stopped because it took the most turns allowed without user input
+    MaxTurnsReached,
+    /// The reply stopped because the provider failed; the error is also shown to the
+    /// user in an assistant message
+    Error(String),
 }
 
 impl Default for Agent {
@@ -146,6 +176,26 @@ where
     })
 }
 
+/// The checkpoint that groups the edits made while answering the latest user message
+///
+/// Checkpoints are named after the time the user message was created, so they stay unique
+/// when the history is compacted or truncated.
+fn checkpoint_for_turn(session: &SessionConfig, messages: &[Message]) -> Option<Value> {
+    let message = messages
+        .iter()
+        .rev()
+        .find(|m| m.role == rmcp::model::Role::User && !m.as_concat_text().is_empty())?;
+    let dir = crate::session::get_path(session.id.clone())
+        .and_then(|path| crate::session::checkpoint_dir(&path))
+        .map_err(|e| tracing::warn!("Edit checkpoints are unavailable: {}", e))
+        .ok()?;
+    Some(serde_json::json!({
+        "dir": dir,
+        "name": message.created.to_string(),
+        "label": safe_truncate(&message.as_concat_text(), 60),
+    }))
+}
+
 impl Agent {
     pub fn new() -> Self {
         // Create channels with buffer size 32 (adjust if needed)
@@ -154,10 +204,13 @@ impl Agent {
 
         let tool_monitor = Arc::new(Mutex::new(None));
         let retry_manager = RetryManager::with_tool_monitor(tool_monitor.clone());
+        let client_context = ClientContext::default();
 
         Self {
             provider: Mutex::new(None),
-            extension_manager: Arc::new(RwLock::new(ExtensionManager::new())),
+            extension_manager: Arc::new(RwLock::new(ExtensionManager::with_client_context(
+                client_context.clone(),
+            ))),
             sub_recipe_manager: Mutex::new(SubRecipeManager::new()),
             tasks_manager: TasksManager::new(),
             final_output_tool: Arc::new(Mutex::new(None)),
@@ -172,6 +225,13 @@ impl Agent {
             tool_route_manager: ToolRouteManager::new(),
             scheduler_service: Mutex::new(None),
             retry_manager,
+            checkpoint: Mutex::new(None),
+            compaction_config: Mutex::new(CompactionConfig::default()),
+            cost_budget: Mutex::new(CostBudget::default()),
+            tool_policy: Mutex::new(PolicyCache::default()),
+            pending_elicitations: Mutex::new(HashMap::new()),
+            memory_contexts: Mutex::new(LruCache::new(MAX_MEMORY_CONTEXTS)),
+            client_context,
         }
     }
 
@@ -298,10 +358,17 @@ impl Agent {
 
         // Handle denied tools
         for request in &permission_check_result.denied {
+            let text = match permission_check_result.reasons.get(&request.id) {
+                Some(reason) => format!(
+                    "Denied by the tool policy: {}\n\n{}",
+                    reason, DECLINED_RESPONSE
+                ),
+                None => DECLINED_RESPONSE.to_string(),
+            };
             let mut response = message_tool_response.lock().await;
             *response = response.clone().with_tool_response(
                 request.id.clone(),
-                Ok(vec![rmcp::model::Content::text(DECLINED_RESPONSE)]),
+                Ok(vec![rmcp::model::Content::text(text)]),
             );
         }
 
@@ -344,6 +411,16 @@ impl Agent {
         self.extend_system_prompt(final_output_system_prompt).await;
     }
 
+    /// Override how auto-compaction condenses this agent's conversations
+    pub async fn set_compaction_config(&self, config: CompactionConfig) {
+        *self.compaction_config.lock().await = config;
+    }
+
+    /// Set the spending limits for this agent's sessions
+    pub async fn set_cost_budget(&self, budget: CostBudget) {
+        *self.cost_budget.lock().await = budget;
+    }
+
     pub async fn add_sub_recipes(&self, sub_recipes: Vec<SubRecipe>) {
         let mut sub_recipe_manager = self.sub_recipe_manager.lock().await;
         sub_recipe_manager.add_sub_recipe_tools(sub_recipes);
@@ -467,10 +544,19 @@ impl Agent {
                 Err(e) => return (request_id, Err(e)),
             }
         } else {
+            let mut tool_call = tool_call.clone();
+            if let Some(arguments) = tool_call.arguments.as_object_mut() {
+                // Only goose sets the checkpoint, never the model
+                arguments.remove(CHECKPOINT_ARG);
+                if extension_manager.is_builtin_tool(&tool_call.name) {
+                    if let Some(checkpoint) = self.checkpoint.lock().await.clone() {
+                        arguments.insert(CHECKPOINT_ARG.to_string(), checkpoint);
+                    }
+                }
+            }
+
             // Clone the result to ensure no references to extension_manager are returned
-            let result = extension_manager
-                .dispatch_tool_call(tool_call.clone())
-                .await;
+            let result = extension_manager.dispatch_tool_call(tool_call).await;
             result.unwrap_or_else(|e| {
                 ToolCallResult::from(Err(ToolError::ExecutionError(e.to_string())))
             })
@@ -626,6 +712,8 @@ impl Agent {
                 }
             }
             _ => {
+                // Extensions may list roots as soon as they start, before any reply sets them
+                self.ensure_roots().await;
                 let mut extension_manager = self.extension_manager.write().await;
                 extension_manager.add_extension(extension.clone()).await?;
             }
@@ -753,7 +841,9 @@ impl Agent {
         &self,
         messages: &[Message],
     ) -> Result<Option<(Vec<Message>, String)>> {
-        let compact_result = auto_compact::check_and_compact_messages(self, messages, None).await?;
+        let threshold = self.compaction_config.lock().await.threshold;
+        let compact_result =
+            auto_compact::check_and_compact_messages(self, messages, threshold).await?;
 
         if compact_result.compacted {
             let compacted_messages = compact_result.messages;
@@ -822,6 +912,7 @@ impl Agent {
         session: Option<SessionConfig>,
         cancel_token: Option<CancellationToken>,
     ) -> Result<BoxStream<'_, Result<AgentEvent>>> {
+        let memory_context = self.memory_context(messages, &session).await;
         let context = self.prepare_reply_context(messages, &session).await?;
         let ReplyContext {
             mut messages,
@@ -834,6 +925,24 @@ impl Agent {
         } = context;
         let reply_span = tracing::Span::current();
         self.reset_retry_attempts().await;
+        *self.checkpoint.lock().await = session
+            .as_ref()
+            .and_then(|session| checkpoint_for_turn(session, &messages));
+        if let Some(session) = &session {
+            self.update_roots(session).await;
+        }
+        let budget = self.cost_budget.lock().await.resolve();
+        let spent_at_start = session.as_ref().and_then(Self::session_cost).unwrap_or(0.0);
+        let working_dir = match &session {
+            Some(session) => session.working_dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        // Extensions' sampling and elicitation requests for this session come to this reply
+        let (extension_request_tx, mut extension_requests) = mpsc::channel(32);
+        let request_route = self.client_context.route_requests(
+            session.as_ref().map(|session| session.id.clone()),
+            extension_request_tx,
+        );
 
         if let Some(content) = messages
             .last()
@@ -846,6 +955,7 @@ impl Agent {
         Ok(Box::pin(async_stream::try_stream! {
             let _ = reply_span.enter();
             let mut turns_taken = 0u32;
+            let mut spent = spent_at_start;
             let max_turns = session
                 .as_ref()
                 .and_then(|s| s.max_turns)
@@ -873,12 +983,50 @@ impl Agent {
                     yield AgentEvent::Message(Message::assistant().with_text(
                         "I've reached the maximum number of actions I can do without user input. Would you like me to continue?"
                     ));
+                    yield AgentEvent::MaxTurnsReached;
+                    break;
+                }
+
+                if let Some(text) = budget.check(spent_at_start, spent).message() {
+                    yield AgentEvent::Message(Message::assistant().with_text(text));
                     break;
                 }
 
+                // Requests that came in while the reply wasn't waiting on the model or a tool
+                while let Ok(request) = extension_requests.try_recv() {
+                    let mut request_stream = self.handle_extension_request(request, &goose_mode, &session, &cancel_token);
+                    while let Some(msg) = request_stream.try_next().await? {
+                        yield AgentEvent::Message(msg);
+                    }
+                }
+
+                // Extensions whose servers stopped, e.g. killed after a cancelled tool call,
+                // are started again before the model picks its next tool
+                let notices = self.extension_manager.write().await.restart_closed_extensions().await;
+                if !notices.is_empty() {
+                    (tools, toolshim_tools, system_prompt) = self.prepare_tools_and_prompt().await?;
+                }
+                for (extension, notice) in notices {
+                    yield AgentEvent::McpNotification((
+                        extension,
+                        ServerNotification::LoggingMessageNotification(LoggingMessageNotification {
+                            method: LoggingMessageNotificationMethod,
+                            params: LoggingMessageNotificationParam {
+                                level: LoggingLevel::Warning,
+                                logger: None,
+                                data: Value::String(notice),
+                            },
+                            extensions: Default::default(),
+                        }),
+                    ));
+                }
+
+                // Extensions' active resources may have changed since the last turn. They
+                // aren't saved with the session, so each reply starts with all of them
+                self.add_resource_updates(&mut messages, turns_taken > 1).await;
                 let mut stream = Self::stream_response_from_provider(
                     self.provider().await?,
-                    &system_prompt,
+                    &PromptManager::with_memory_context(&system_prompt, &memory_context),
                     &messages,
                     &tools,
                     &toolshim_tools,
@@ -888,7 +1036,23 @@ impl Agent {
                 let mut messages_to_add = Vec::new();
                 let mut tools_updated = false;
 
-                while let Some(next) = stream.next().await {
+                loop {
+                    // Extensions can also ask for sampling or elicitation while the model answers
+                    let next = tokio::select! {
+                        next = stream.next() => Either::Left(next),
+                        Some(request) = extension_requests.recv() => Either::Right(request),
+                    };
+                    let next = match next {
+                        Either::Left(Some(next)) => next,
+                        Either::Left(None) => break,
+                        Either::Right(request) => {
+                            let mut request_stream = self.handle_extension_request(request, &goose_mode, &session, &cancel_token);
+                            while let Some(msg) = request_stream.try_next().await? {
+                                yield AgentEvent::Message(msg);
+                            }
+                            continue;
+                        }
+                    };
                     if is_token_cancelled(&cancel_token) {
                         break;
                     }
@@ -917,10 +1081,15 @@ impl Agent {
                             }
 
                             // Record usage for the session
-                            if let Some(ref session_config) = &session {
-                                if let Some(ref usage) = usage {
-                                    Self::update_session_metrics(session_config, usage, messages.len())
-                                        .await?;
+                            if let Some(ref usage) = usage {
+                                match &session {
+                                    Some(session_config) => {
+                                        let cost = Self::update_session_metrics(session_config, usage, messages.len())
+                                            .await?;
+                                        spent = cost.unwrap_or(spent);
+                                    }
+                                    // Without a session only this reply's spend counts towards the budget
+                                    None => spent += turn_cost(usage).await.unwrap_or(0.0),
                                 }
                             }
 
@@ -969,7 +1138,15 @@ impl Agent {
                                         );
                                     }
                                 } else {
+                                    request_route.set_tool_calls(
+                                        remaining_requests
+                                            .iter()
+                                            .filter_map(|request| request.tool_call.as_ref().ok())
+                                            .map(|tool_call| tool_call.name.clone())
+                                            .collect(),
+                                    );
                                     let mut permission_manager = PermissionManager::default();
+                                    let tool_policy = self.tool_policy.lock().await.get(&working_dir);
                                     let (permission_check_result, enable_extension_request_ids) =
                                         check_tool_permissions(
                                             &remaining_requests,
@@ -977,6 +1154,7 @@ impl Agent {
                                             readonly_tools.clone(),
                                             regular_tools.clone(),
                                             &mut permission_manager,
+                                            tool_policy.as_deref(),
                                             self.provider().await?,
                                         ).await;
 
@@ -991,6 +1169,7 @@ impl Agent {
                                     // Process tools requiring approval
                                     let mut tool_approval_stream = self.handle_approval_tool_requests(
                                         &permission_check_result.needs_approval,
+                                        &permission_check_result.reasons,
                                         tool_futures_arc.clone(),
                                         &mut permission_manager,
                                         message_tool_response.clone(),
@@ -1015,11 +1194,28 @@ impl Agent {
 
                                     let mut combined = stream::select_all(with_id);
                                     let mut all_install_successful = true;
-
-                                    while let Some((request_id, item)) = combined.next().await {
+                                    // Extensions can ask for sampling or elicitation while their tools run
+                                    loop {
                                         if is_token_cancelled(&cancel_token) {
                                             break;
                                         }
+                                        let next = tokio::select! {
+                                            next = combined.next() => next.map(Either::Left),
+                                            Some(request) = extension_requests.recv() => Some(Either::Right(request)),
+                                            // Stop waiting on running tools, which cancels their requests
+                                            _ = wait_for_cancellation(&cancel_token) => None,
+                                        };
+                                        let (request_id, item) = match next {
+                                            Some(Either::Left(next)) => next,
+                                            Some(Either::Right(request)) => {
+                                                let mut request_stream = self.handle_extension_request(request, &mode, &session, &cancel_token);
+                                                while let Some(msg) = request_stream.try_next().await? {
+                                                    yield AgentEvent::Message(msg);
+                                                }
+                                                continue;
+                                            }
+                                            None => break,
+                                        };
                                         match item {
                                             ToolStreamItem::Result(output) => {
                                                 if enable_extension_request_ids.contains(&request_id)
@@ -1032,13 +1228,18 @@ impl Agent {
                                                     response.clone().with_tool_response(request_id, output);
                                             }
                                             ToolStreamItem::Message(msg) => {
-                                                yield AgentEvent::McpNotification((
-                                                    request_id, msg,
-                                                ));
+                                                match ToolProgress::from_notification(&request_id, &msg) {
+                                                    Some(progress) => yield AgentEvent::ToolProgress(progress),
+                                                    None => yield AgentEvent::McpNotification((request_id, msg)),
+                                                }
                                             }
                                         }
                                     }
 
+                                    // Tool calls still running at this point were cancelled, so tell their servers
+                                    drop(combined);
+                                    request_route.set_tool_calls(Vec::new());
+
                                     if all_install_successful {
                                         tools_updated = true;
                                     }
@@ -1063,6 +1264,7 @@ impl Agent {
                             yield AgentEvent::Message(Message::assistant().with_text(
                                     format!("Ran into this error: {e}.\n\nPlease retry if you think this is a transient or recoverable error.")
                                 ));
+                            yield AgentEvent::Error(e.to_string());
                             break;
                         }
                     }
@@ -1343,6 +1545,7 @@ impl Agent {
             goose_provider: Some(provider_name.clone()),
             goose_model: Some(model_name.clone()),
             temperature: Some(model_config.temperature.unwrap_or(0.0)),
+            budget: None,
         };
 
         let recipe = Recipe::builder()
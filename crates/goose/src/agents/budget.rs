@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::Config;
+
+/// Spending limits for a session, in US dollars, from a recipe or a scheduled job
+///
+/// Anything left unset falls back to the global config. The cost of a turn is estimated
+/// from the pricing of the model that served it, so turns of models without known
+/// pricing don't count towards the limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CostBudget {
+    /// Spend at which the agent pauses and asks whether to go on (default: GOOSE_SESSION_SOFT_BUDGET)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soft_limit: Option<f64>,
+    /// Spend at which the agent stops (default: GOOSE_SESSION_HARD_BUDGET)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hard_limit: Option<f64>,
+}
+
+/// What the agent should do given how much a session has spent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetCheck {
+    WithinBudget,
+    /// The soft limit was crossed during this reply
+    SoftLimitReached {
+        limit: f64,
+        spent: f64,
+    },
+    HardLimitReached {
+        limit: f64,
+        spent: f64,
+    },
+}
+
+impl CostBudget {
+    /// The budget with unset limits filled in from the global config
+    pub fn resolve(&self) -> Self {
+        let config = Config::global();
+        Self {
+            soft_limit: self
+                .soft_limit
+                .or_else(|| config.get_param("GOOSE_SESSION_SOFT_BUDGET").ok()),
+            hard_limit: self
+                .hard_limit
+                .or_else(|| config.get_param("GOOSE_SESSION_HARD_BUDGET").ok()),
+        }
+    }
+
+    /// Layer `other` over this budget, keeping this budget's limits where `other` has none
+    pub fn merge(self, other: Option<CostBudget>) -> Self {
+        match other {
+            Some(other) => Self {
+                soft_limit: other.soft_limit.or(self.soft_limit),
+                hard_limit: other.hard_limit.or(self.hard_limit),
+            },
+            None => self,
+        }
+    }
+
+    /// Check the session's spend before the agent calls the model again
+    ///
+    /// The soft limit only pauses a reply that crossed it, so once the user chooses to go
+    /// on the session runs until the hard limit.
+    pub fn check(&self, spent_at_start: f64, spent: f64) -> BudgetCheck {
+        if let Some(limit) = self.hard_limit {
+            if spent >= limit {
+                return BudgetCheck::HardLimitReached { limit, spent };
+            }
+        }
+        if let Some(limit) = self.soft_limit {
+            if spent_at_start < limit && spent >= limit {
+                return BudgetCheck::SoftLimitReached { limit, spent };
+            }
+        }
+        BudgetCheck::WithinBudget
+    }
+}
+
+impl BudgetCheck {
+    /// The message the agent ends its reply with, if the budget stops it
+    pub fn message(&self) -> Option<String> {
+        match self {
+            BudgetCheck::WithinBudget => None,
+            BudgetCheck::SoftLimitReached { limit, spent } => Some(format!(
+                "This session has spent about ${:.2}, passing its soft budget of ${:.2}. Would you like me to continue?",
+                spent, limit
+            )),
+            BudgetCheck::HardLimitReached { limit, spent } => Some(format!(
+                "This session has spent about ${:.2}, reaching its budget of ${:.2}, so I've stopped. Start a new session or raise the budget to continue.",
+                spent, limit
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_budget() {
+        let budget = CostBudget {
+            soft_limit: Some(1.0),
+            hard_limit: Some(5.0),
+        };
+
+        assert_eq!(budget.check(0.0, 0.5), BudgetCheck::WithinBudget);
+        assert_eq!(
+            budget.check(0.5, 1.2),
+            BudgetCheck::SoftLimitReached {
+                limit: 1.0,
+                spent: 1.2
+            }
+        );
+        // The user chose to go on after the soft limit paused the last reply
+        assert_eq!(budget.check(1.2, 2.0), BudgetCheck::WithinBudget);
+        assert_eq!(
+            budget.check(1.2, 5.0),
+            BudgetCheck::HardLimitReached {
+                limit: 5.0,
+                spent: 5.0
+            }
+        );
+        assert_eq!(
+            CostBudget::default().check(0.0, 100.0),
+            BudgetCheck::WithinBudget
+        );
+    }
+
+    #[test]
+    fn test_merge_budget() {
+        let recipe = CostBudget {
+            soft_limit: Some(1.0),
+            hard_limit: Some(5.0),
+        };
+        let job = CostBudget {
+            soft_limit: None,
+            hard_limit: Some(2.0),
+        };
+
+        assert_eq!(
+            recipe.merge(Some(job)),
+            CostBudget {
+                soft_limit: Some(1.0),
+                hard_limit: Some(2.0),
+            }
+        );
+        assert_eq!(recipe.merge(None), recipe);
+    }
+}
@@ -0,0 +1,372 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use mcp_client::ClientHandler;
+use mcp_core::protocol::{
+    CreateMessageParams, CreateMessageResult, ElicitRequestParams, ElicitResult, ListRootsResult,
+    Root,
+};
+use rmcp::model::{ErrorCode, ErrorData};
+use tokio::sync::{mpsc, RwLock};
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::agents::elicitation::{request_elicitation, PendingElicitation};
+use crate::agents::sampling::{request_sampling, SamplingRequest};
+use crate::agents::types::SessionConfig;
+use crate::agents::Agent;
+use crate::message::Message;
+use crate::project;
+use crate::session;
+
+/// A request from an extension's server that the agent answers during its reply
+pub enum ExtensionRequest {
+    Sampling(SamplingRequest),
+    Elicitation(PendingElicitation),
+}
+
+/// The session a reply belongs to, `None` for replies without one
+pub type SessionKey = Option<session::Identifier>;
+
+struct ReplyRequests {
+    session: SessionKey,
+    requests: mpsc::Sender<ExtensionRequest>,
+    /// Tools the reply is calling
+    tool_calls: Vec<String>,
+}
+
+/// The replies in progress, which extension requests are routed to
+#[derive(Default)]
+pub struct RequestRoutes {
+    next_id: u64,
+    replies: HashMap<u64, ReplyRequests>,
+}
+
+/// What the agent shares with the MCP clients of its extensions
+#[derive(Clone, Default)]
+pub struct ClientContext {
+    pub requests: Arc<std::sync::Mutex<RequestRoutes>>,
+    pub roots: Arc<RwLock<Vec<Root>>>,
+}
+
+impl ClientContext {
+    /// Send extension requests meant for `session` to `requests` until the route is dropped
+    pub fn route_requests(
+        &self,
+        session: SessionKey,
+        requests: mpsc::Sender<ExtensionRequest>,
+    ) -> RequestRoute {
+        let mut routes = self.requests.lock().unwrap();
+        let id = routes.next_id;
+        routes.next_id += 1;
+        routes.replies.insert(
+            id,
+            ReplyRequests {
+                session,
+                requests,
+                tool_calls: Vec::new(),
+            },
+        );
+        RequestRoute {
+            id,
+            routes: self.requests.clone(),
+        }
+    }
+
+    /// Where to send a request from `extension_name`
+    ///
+    /// Requests go to the reply that is calling the extension's tools, or, when no reply is,
+    /// to the only reply in progress. When that doesn't single out one session, `None` is
+    /// returned so a session never answers, or pays for, another session's request.
+    pub fn requests_for(&self, extension_name: &str) -> Option<mpsc::Sender<ExtensionRequest>> {
+        let routes = self.requests.lock().unwrap();
+        let calling = |reply: &&ReplyRequests| {
+            reply.tool_calls.iter().any(|tool| {
+                tool.strip_prefix(extension_name)
+                    .is_some_and(|name| name.starts_with("__"))
+            })
+        };
+        let mut candidates: Vec<&ReplyRequests> = routes.replies.values().filter(calling).collect();
+        if candidates.is_empty() {
+            candidates = routes.replies.values().collect();
+        }
+        match candidates.as_slice() {
+            [reply] => Some(reply.requests.clone()),
+            [first, rest @ ..] if rest.iter().all(|reply| reply.session == first.session) => {
+                Some(first.requests.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A reply's route for extension requests, removed when dropped
+pub struct RequestRoute {
+    id: u64,
+    routes: Arc<std::sync::Mutex<RequestRoutes>>,
+}
+
+impl RequestRoute {
+    /// Record the tools the reply is calling, so requests from their extensions come to it
+    pub fn set_tool_calls(&self, tool_calls: Vec<String>) {
+        if let Some(reply) = self.routes.lock().unwrap().replies.get_mut(&self.id) {
+            reply.tool_calls = tool_calls;
+        }
+    }
+}
+
+impl Drop for RequestRoute {
+    fn drop(&mut self) {
+        if let Ok(mut routes) = self.routes.lock() {
+            routes.replies.remove(&self.id);
+        }
+    }
+}
+
+fn unrouted(kind: &str) -> ErrorData {
+    ErrorData {
+        code: ErrorCode::INTERNAL_ERROR,
+        message: Cow::from(format!("goose is not accepting {} requests", kind)),
+        data: None,
+    }
+}
+
+/// Answers requests from an extension's server on behalf of the agent
+pub struct AgentClientHandler {
+    extension_name: String,
+    context: ClientContext,
+}
+
+impl AgentClientHandler {
+    pub fn new(extension_name: String, context: ClientContext) -> Self {
+        Self {
+            extension_name,
+            context,
+        }
+    }
+}
+
+#[async_trait]
+impl ClientHandler for AgentClientHandler {
+    async fn create_message(
+        &self,
+        params: CreateMessageParams,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        let requests = self
+            .context
+            .requests_for(&self.extension_name)
+            .ok_or_else(|| unrouted("sampling"))?;
+        request_sampling(&requests, &self.extension_name, params).await
+    }
+
+    async fn create_elicitation(
+        &self,
+        params: ElicitRequestParams,
+    ) -> Result<ElicitResult, ErrorData> {
+        let requests = self
+            .context
+            .requests_for(&self.extension_name)
+            .ok_or_else(|| unrouted("elicitation"))?;
+        request_elicitation(&requests, &self.extension_name, params).await
+    }
+
+    async fn list_roots(&self) -> Result<ListRootsResult, ErrorData> {
+        Ok(ListRootsResult {
+            roots: self.context.roots.read().await.clone(),
+        })
+    }
+}
+
+fn directory_root(dir: &Path, name: Option<String>) -> Option<Root> {
+    let uri = Url::from_file_path(dir).ok()?.to_string();
+    let name = name.or_else(|| {
+        dir.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    });
+    Some(Root { uri, name })
+}
+
+/// The session's working directory, followed by the directories of projects containing the session
+pub fn workspace_roots(working_dir: &Path, session_id: Option<&str>) -> Vec<Root> {
+    let mut roots: Vec<Root> = directory_root(working_dir, None).into_iter().collect();
+
+    let projects = session_id
+        .map(|id| {
+            project::list_projects()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|metadata| project::get_project(&metadata.id).ok())
+                .filter(|project| project.session_ids.iter().any(|s| s == id))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    for project in projects {
+        if let Some(root) = directory_root(&project.default_directory, Some(project.name)) {
+            if !roots.iter().any(|r| r.uri == root.uri) {
+                roots.push(root);
+            }
+        }
+    }
+    roots
+}
+
+impl Agent {
+    /// Answer a request from an extension, yielding any messages the user needs to see
+    pub(crate) fn handle_extension_request<'a>(
+        &'a self,
+        request: ExtensionRequest,
+        goose_mode: &'a str,
+        session: &'a Option<SessionConfig>,
+        cancel_token: &'a Option<CancellationToken>,
+    ) -> BoxStream<'a, anyhow::Result<Message>> {
+        match request {
+            ExtensionRequest::Sampling(request) => {
+                self.handle_sampling_request(request, goose_mode, session)
+            }
+            ExtensionRequest::Elicitation(request) => {
+                self.handle_elicitation_request(request, cancel_token)
+            }
+        }
+    }
+
+    /// Point extensions' roots at the session's workspace, notifying them if it changed
+    pub(crate) async fn update_roots(&self, session: &SessionConfig) {
+        let session_id = match &session.id {
+            session::Identifier::Name(name) => Some(name.clone()),
+            session::Identifier::Path(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+        };
+        let working_dir = session.working_dir.clone();
+        // Finding the session's projects reads every project file, so keep it off the runtime
+        let roots = match tokio::task::spawn_blocking(move || {
+            workspace_roots(&working_dir, session_id.as_deref())
+        })
+        .await
+        {
+            Ok(roots) => roots,
+            Err(e) => {
+                tracing::warn!("Failed to list workspace roots: {}", e);
+                return;
+            }
+        };
+
+        {
+            let mut current = self.client_context.roots.write().await;
+            if *current == roots {
+                return;
+            }
+            *current = roots;
+        }
+
+        self.extension_manager
+            .read()
+            .await
+            .notify_roots_list_changed()
+            .await;
+    }
+
+    /// Give extensions the current directory as their root until a session sets the workspace
+    pub(crate) async fn ensure_roots(&self) {
+        let mut current = self.client_context.roots.write().await;
+        if !current.is_empty() {
+            return;
+        }
+        if let Ok(dir) = std::env::current_dir() {
+            *current = workspace_roots(&dir, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_roots_start_with_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots = workspace_roots(dir.path(), None);
+
+        assert_eq!(roots.len(), 1);
+        assert!(roots[0].uri.starts_with("file://"));
+        assert_eq!(
+            Url::parse(&roots[0].uri).unwrap().to_file_path().unwrap(),
+            dir.path()
+        );
+        assert_eq!(
+            roots[0].name.as_deref(),
+            dir.path().file_name().and_then(|n| n.to_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_roots_reads_shared_roots() {
+        let context = ClientContext::default();
+        let handler = AgentClientHandler::new("developer".to_string(), context.clone());
+        assert!(handler.list_roots().await.unwrap().roots.is_empty());
+
+        let root = Root {
+            uri: "file:///workspace".to_string(),
+            name: Some("workspace".to_string()),
+        };
+        *context.roots.write().await = vec![root.clone()];
+        assert_eq!(handler.list_roots().await.unwrap().roots, vec![root]);
+    }
+
+    #[test]
+    fn test_requests_go_to_the_session_calling_the_extension() {
+        let context = ClientContext::default();
+        let session = |name: &str| Some(session::Identifier::Name(name.to_string()));
+        let (first_tx, _first_rx) = mpsc::channel(1);
+        let (second_tx, _second_rx) = mpsc::channel(1);
+
+        assert!(context.requests_for("memory").is_none());
+
+        let first = context.route_requests(session("first"), first_tx.clone());
+        assert!(context
+            .requests_for("memory")
+            .is_some_and(|tx| tx.same_channel(&first_tx)));
+
+        let second = context.route_requests(session("second"), second_tx.clone());
+        assert!(context.requests_for("memory").is_none());
+
+        second.set_tool_calls(vec!["memory__remember".to_string()]);
+        assert!(context
+            .requests_for("memory")
+            .is_some_and(|tx| tx.same_channel(&second_tx)));
+        assert!(context.requests_for("mem").is_none());
+
+        drop(second);
+        assert!(context
+            .requests_for("memory")
+            .is_some_and(|tx| tx.same_channel(&first_tx)));
+        drop(first);
+        assert!(context.requests_for("memory").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_added_extension_sees_roots_before_first_reply() {
+        let agent = Agent::new();
+        assert!(agent.client_context.roots.read().await.is_empty());
+
+        agent.ensure_roots().await;
+        let roots = agent.client_context.roots.read().await.clone();
+        assert_eq!(
+            roots,
+            workspace_roots(&std::env::current_dir().unwrap(), None)
+        );
+
+        let root = Root {
+            uri: "file:///workspace".to_string(),
+            name: None,
+        };
+        *agent.client_context.roots.write().await = vec![root.clone()];
+        agent.ensure_roots().await;
+        assert_eq!(*agent.client_context.roots.read().await, vec![root]);
+    }
+}
@@ -3,6 +3,7 @@ use anyhow::Ok;
 use crate::message::Message;
 use crate::token_counter::create_async_token_counter;
 
+use crate::context_mgmt::structured::{compact_messages_structured, CompactionMode};
 use crate::context_mgmt::summarize::summarize_messages_async;
 use crate::context_mgmt::truncate::{truncate_messages, OldestFirstTruncation};
 use crate::context_mgmt::{estimate_target_context_limit, get_messages_token_counts_async};
@@ -84,4 +85,30 @@ impl Agent {
 
         Ok((new_messages, new_token_counts))
     }
+
+    /// Compact the conversation in the mode set for this agent, falling back to the global config.
+    pub async fn compact_context(
+        &self,
+        messages: &[Message],
+    ) -> Result<(Vec<Message>, Vec<usize>), anyhow::Error> {
+        let config = self.compaction_config.lock().await.clone();
+        if config.mode() == CompactionMode::Summarize {
+            return self.summarize_context(messages).await;
+        }
+
+        let provider = self.provider().await?;
+        let token_counter = create_async_token_counter()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
+        let target_context_limit = estimate_target_context_limit(provider.clone());
+
+        compact_messages_structured(
+            provider,
+            messages,
+            &token_counter,
+            target_context_limit,
+            config.keep_recent_turns(),
+        )
+        .await
+    }
 }
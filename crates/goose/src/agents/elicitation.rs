@@ -0,0 +1,304 @@
+use std::borrow::Cow;
+
+use async_stream::try_stream;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use mcp_core::protocol::{ElicitAction, ElicitRequestParams, ElicitResult};
+use rmcp::model::{ErrorCode, ErrorData};
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::agents::client_handler::ExtensionRequest;
+use crate::agents::Agent;
+use crate::message::Message;
+use crate::utils::wait_for_cancellation;
+
+/// An `elicitation/create` request from an extension, waiting for the user to answer it
+pub struct PendingElicitation {
+    pub id: String,
+    pub extension_name: String,
+    pub params: ElicitRequestParams,
+    pub respond: oneshot::Sender<Result<ElicitResult, ErrorData>>,
+}
+
+/// An elicitation request shown to the user, waiting for `handle_elicitation_response`
+pub struct AwaitingAnswer {
+    pub schema: Value,
+    pub answer: oneshot::Sender<ElicitResult>,
+}
+
+/// Hand an elicitation request from `extension_name` to the agent and wait for the user's answer
+pub async fn request_elicitation(
+    requests: &mpsc::Sender<ExtensionRequest>,
+    extension_name: &str,
+    params: ElicitRequestParams,
+) -> Result<ElicitResult, ErrorData> {
+    let (respond, response) = oneshot::channel();
+    requests
+        .send(ExtensionRequest::Elicitation(PendingElicitation {
+            id: format!("elicitation_{}", Uuid::new_v4()),
+            extension_name: extension_name.to_string(),
+            params,
+            respond,
+        }))
+        .await
+        .map_err(|_| ErrorData {
+            code: ErrorCode::INTERNAL_ERROR,
+            message: Cow::from("goose is not accepting elicitation requests"),
+            data: None,
+        })?;
+    response.await.map_err(|_| ErrorData {
+        code: ErrorCode::INTERNAL_ERROR,
+        message: Cow::from("The elicitation request was not answered"),
+        data: None,
+    })?
+}
+
+/// Check the values a user submitted against the schema the extension requested
+pub fn validate_elicitation_content(schema: &Value, content: &Value) -> Result<(), String> {
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| format!("The requested schema is invalid: {}", e))?;
+    let errors: Vec<String> = validator
+        .iter_errors(content)
+        .map(|error| format!("{}: {}", error.instance_path, error))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The submitted values don't match the requested schema: {}",
+            errors.join("; ")
+        ))
+    }
+}
+
+impl Agent {
+    /// Ask the user to fill in an extension's form
+    ///
+    /// Yields an elicitation request for the interface to render, then waits for
+    /// `handle_elicitation_response` with a matching id. Waiting stops early if the
+    /// extension gives up on the request, and cancelling the reply dismisses the form.
+    pub(crate) fn handle_elicitation_request<'a>(
+        &'a self,
+        mut request: PendingElicitation,
+        cancel_token: &'a Option<CancellationToken>,
+    ) -> BoxStream<'a, anyhow::Result<Message>> {
+        // The extension stopped waiting, e.g. because its request timed out
+        if request.respond.is_closed() {
+            return futures::stream::empty().boxed();
+        }
+
+        try_stream! {
+            let (answer, mut answered) = oneshot::channel();
+            self.pending_elicitations.lock().await.insert(
+                request.id.clone(),
+                AwaitingAnswer {
+                    schema: request.params.requested_schema.clone(),
+                    answer,
+                },
+            );
+            yield Message::user().with_elicitation_request(
+                request.id.clone(),
+                request.extension_name.clone(),
+                request.params.message.clone(),
+                request.params.requested_schema.clone(),
+            );
+
+            let answer = tokio::select! {
+                result = &mut answered => result.ok(),
+                _ = request.respond.closed() => None,
+                _ = wait_for_cancellation(cancel_token) => Some(ElicitResult {
+                    action: ElicitAction::Cancel,
+                    content: None,
+                }),
+            };
+            self.pending_elicitations.lock().await.remove(&request.id);
+
+            // Dropping the sender without an answer tells the extension the request failed
+            if let Some(result) = answer {
+                let _ = request.respond.send(Ok(result));
+            }
+        }
+        .boxed()
+    }
+
+    /// Answer an elicitation request the user filled in, declined or dismissed
+    ///
+    /// Accepted values must match the schema the extension requested; otherwise the
+    /// request keeps waiting and the error says what to fix.
+    pub async fn handle_elicitation_response(
+        &self,
+        request_id: String,
+        result: ElicitResult,
+    ) -> Result<(), String> {
+        let mut pending = self.pending_elicitations.lock().await;
+        let Some(awaiting) = pending.get(&request_id) else {
+            tracing::warn!(
+                "No elicitation request {} is waiting for an answer",
+                request_id
+            );
+            return Ok(());
+        };
+        if result.action == ElicitAction::Accept {
+            let content = result
+                .content
+                .as_ref()
+                .ok_or("Accepted without any values")?;
+            validate_elicitation_content(&awaiting.schema, content)?;
+        }
+        if let Some(awaiting) = pending.remove(&request_id) {
+            let _ = awaiting.answer.send(result);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_request_elicitation_forwards_to_agent() {
+        let (tx, mut rx) = mpsc::channel(1);
+
+        let agent = tokio::spawn(async move {
+            let Some(ExtensionRequest::Elicitation(request)) = rx.recv().await else {
+                panic!("expected an elicitation request");
+            };
+            assert_eq!(request.extension_name, "github");
+            assert!(request.id.starts_with("elicitation_"));
+            let _ = request.respond.send(Ok(ElicitResult {
+                action: ElicitAction::Accept,
+                content: Some(json!({"repo": "goose"})),
+            }));
+        });
+
+        let result = request_elicitation(
+            &tx,
+            "github",
+            ElicitRequestParams {
+                message: "Which repository?".to_string(),
+                requested_schema: json!({
+                    "type": "object",
+                    "properties": {"repo": {"type": "string"}}
+                }),
+            },
+        )
+        .await
+        .unwrap();
+        agent.await.unwrap();
+
+        assert_eq!(result.action, ElicitAction::Accept);
+        assert_eq!(result.content, Some(json!({"repo": "goose"})));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_elicitation_is_an_error() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let agent = tokio::spawn(async move {
+            // Drop the request without answering it
+            rx.recv().await.unwrap();
+        });
+
+        let err = request_elicitation(
+            &tx,
+            "github",
+            ElicitRequestParams {
+                message: "Which repository?".to_string(),
+                requested_schema: json!({"type": "object"}),
+            },
+        )
+        .await
+        .unwrap_err();
+        agent.await.unwrap();
+
+        assert_eq!(err.code, ErrorCode::INTERNAL_ERROR);
+    }
+
+    #[test]
+    fn test_validate_elicitation_content() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"repo": {"type": "string"}, "stars": {"type": "integer"}},
+            "required": ["repo"]
+        });
+
+        assert!(validate_elicitation_content(&schema, &json!({"repo": "goose"})).is_ok());
+        assert!(validate_elicitation_content(&schema, &json!({"stars": 3})).is_err());
+        assert!(
+            validate_elicitation_content(&schema, &json!({"repo": "goose", "stars": "many"}))
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_answer_keeps_the_request_waiting() {
+        let agent = Agent::new();
+        let (respond, mut response) = oneshot::channel();
+        let request = PendingElicitation {
+            id: "elicitation_1".to_string(),
+            extension_name: "github".to_string(),
+            params: ElicitRequestParams {
+                message: "Which repository?".to_string(),
+                requested_schema: json!({
+                    "type": "object",
+                    "properties": {"repo": {"type": "string"}},
+                    "required": ["repo"]
+                }),
+            },
+            respond,
+        };
+        let cancel_token = None;
+        let mut stream = agent.handle_elicitation_request(request, &cancel_token);
+        assert!(stream.next().await.unwrap().is_ok());
+
+        let invalid = ElicitResult {
+            action: ElicitAction::Accept,
+            content: Some(json!({"repo": 1})),
+        };
+        assert!(agent
+            .handle_elicitation_response("elicitation_1".to_string(), invalid)
+            .await
+            .is_err());
+        assert!(response.try_recv().is_err());
+
+        let valid = ElicitResult {
+            action: ElicitAction::Accept,
+            content: Some(json!({"repo": "goose"})),
+        };
+        agent
+            .handle_elicitation_response("elicitation_1".to_string(), valid.clone())
+            .await
+            .unwrap();
+        assert!(stream.next().await.is_none());
+        assert_eq!(response.await.unwrap().unwrap(), valid);
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_the_reply_dismisses_the_form() {
+        let agent = Agent::new();
+        let (respond, response) = oneshot::channel();
+        let request = PendingElicitation {
+            id: "elicitation_2".to_string(),
+            extension_name: "github".to_string(),
+            params: ElicitRequestParams {
+                message: "Which repository?".to_string(),
+                requested_schema: json!({"type": "object"}),
+            },
+            respond,
+        };
+        let cancel_token = Some(CancellationToken::new());
+        let mut stream = agent.handle_elicitation_request(request, &cancel_token);
+        assert!(stream.next().await.unwrap().is_ok());
+
+        cancel_token.as_ref().unwrap().cancel();
+        assert!(stream.next().await.is_none());
+        assert_eq!(
+            response.await.unwrap().unwrap().action,
+            ElicitAction::Cancel
+        );
+    }
+}
@@ -7,6 +7,8 @@ use thiserror::Error;
 use tracing::warn;
 use utoipa::ToSchema;
 
+pub use mcp_core::sandbox::{SandboxConfig, SANDBOX_ENV};
+
 use crate::config;
 use crate::config::extensions::name_to_key;
 use crate::config::permission::PermissionLevel;
@@ -170,6 +172,9 @@ pub enum ExtensionConfig {
         /// Whether this extension is bundled with Goose
         #[serde(default)]
         bundled: Option<bool>,
+        /// Run the extension's shell commands in a sandbox (Linux only)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sandbox: Option<SandboxConfig>,
     },
     /// Streamable HTTP client with a URI endpoint using MCP Streamable HTTP specification
     #[serde(rename = "streamable_http")]
@@ -229,6 +234,7 @@ impl Default for ExtensionConfig {
             description: None,
             timeout: Some(config::DEFAULT_EXTENSION_TIMEOUT),
             bundled: Some(true),
+            sandbox: None,
         }
     }
 }
@@ -8,20 +8,27 @@ use std::sync::Arc;
 use std::sync::LazyLock;
 use std::time::Duration;
 use tempfile::tempdir;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
 use tokio::task;
 use tokio_stream::wrappers::ReceiverStream;
 use tracing::{error, warn};
 
-use super::extension::{ExtensionConfig, ExtensionError, ExtensionInfo, ExtensionResult, ToolInfo};
+use super::extension::{
+    ExtensionConfig, ExtensionError, ExtensionInfo, ExtensionResult, ToolInfo, SANDBOX_ENV,
+};
 use super::tool_execution::ToolCallResult;
+use crate::agents::client_handler::{AgentClientHandler, ClientContext};
 use crate::agents::extension::Envs;
 use crate::config::{Config, ExtensionConfigManager};
 use crate::prompt_template;
-use mcp_client::client::{ClientCapabilities, ClientInfo, McpClient, McpClientTrait};
+use mcp_client::client::{
+    ClientCapabilities, ClientInfo, ElicitationCapability, McpClient, McpClientTrait,
+    RootsCapability, SamplingCapability,
+};
 use mcp_client::transport::{SseTransport, StdioTransport, StreamableHttpTransport, Transport};
+use mcp_client::ClientHandler;
 use mcp_core::{ToolCall, ToolError};
-use rmcp::model::{Content, Prompt, Resource, ResourceContents, Tool};
+use rmcp::model::{Content, Prompt, Resource, ResourceContents, ServerNotification, Tool};
 use serde_json::Value;
 
 // By default, we set it to Jan 1, 2020 if the resource does not have a timestamp
@@ -36,7 +43,12 @@ pub struct ExtensionManager {
     clients: HashMap<String, McpClientBox>,
     instructions: HashMap<String, String>,
     resource_capable_extensions: HashSet<String>,
+    builtin_extensions: HashSet<String>,
     temp_dirs: HashMap<String, tempfile::TempDir>,
+    client_context: Option<ClientContext>,
+    resource_cache: Arc<Mutex<ResourceCache>>,
+    /// How each extension was added, to restart it if its server goes away
+    configs: HashMap<String, ExtensionConfig>,
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -72,6 +84,176 @@ impl ResourceItem {
     }
 }
 
+struct CachedResource {
+    resource: Resource,
+    items: Vec<ResourceItem>,
+}
+
+/// Active resources read from each extension, kept current with `resources/subscribe`
+///
+/// An extension's resources are listed, read and subscribed to once. After that only the
+/// resources it reports as updated are read again, and the list is refreshed when it
+/// reports that the list changed. Extensions that can't subscribe aren't cached, since
+/// there's no telling when their resources change.
+#[derive(Default)]
+struct ResourceCache {
+    /// Extension name -> resource URI -> what was last read from it
+    extensions: HashMap<String, HashMap<String, CachedResource>>,
+    /// Resources updated since they were read, by extension
+    stale: HashMap<String, HashSet<String>>,
+    /// Extensions whose resource list changed since it was read
+    relist: HashSet<String>,
+    /// Extensions whose servers support `resources/subscribe`
+    subscribable: HashSet<String>,
+}
+
+impl ResourceCache {
+    fn mark_updated(&mut self, extension: &str, uri: String) {
+        self.stale
+            .entry(extension.to_string())
+            .or_default()
+            .insert(uri);
+    }
+
+    fn mark_list_changed(&mut self, extension: &str) {
+        self.relist.insert(extension.to_string());
+    }
+
+    fn add_extension(&mut self, extension: &str, subscribable: bool) {
+        self.remove_extension(extension);
+        if subscribable {
+            self.subscribable.insert(extension.to_string());
+        }
+    }
+
+    fn remove_extension(&mut self, extension: &str) {
+        self.extensions.remove(extension);
+        self.stale.remove(extension);
+        self.relist.remove(extension);
+        self.subscribable.remove(extension);
+    }
+
+    /// The contents of an extension's subscribed resources, reading only what changed
+    ///
+    /// With `only_changed`, just the resources read by this call are returned: new ones
+    /// and ones the extension reported updated.
+    async fn refresh(
+        &mut self,
+        extension: &str,
+        client: &dyn McpClientTrait,
+        only_changed: bool,
+    ) -> ExtensionResult<Vec<ResourceItem>> {
+        if !self.subscribable.contains(extension) {
+            return Ok(Vec::new());
+        }
+
+        let mut changed = HashSet::new();
+        if self.relist.remove(extension) || !self.extensions.contains_key(extension) {
+            let mut previous = self.extensions.remove(extension).unwrap_or_default();
+
+            let mut active = HashMap::new();
+            for resource in client.list_resources(None).await?.resources {
+                // Skip reading the resource if it's not marked active
+                // This avoids blowing up the context with inactive resources
+                if !resource_is_active(&resource) {
+                    continue;
+                }
+                if let Some(cached) = previous.remove(&resource.uri) {
+                    active.insert(resource.uri.clone(), cached);
+                    continue;
+                }
+                if let Err(e) = client.subscribe_resource(&resource.uri).await {
+                    warn!("Failed to subscribe to {}: {}", resource.uri, e);
+                    for uri in active.keys().chain(previous.keys()) {
+                        let _ = client.unsubscribe_resource(uri).await;
+                    }
+                    self.remove_extension(extension);
+                    return Ok(Vec::new());
+                }
+                let items = read_resource_items(extension, &resource, client).await;
+                changed.insert(resource.uri.clone());
+                active.insert(resource.uri.clone(), CachedResource { resource, items });
+            }
+
+            for uri in previous.keys() {
+                let _ = client.unsubscribe_resource(uri).await;
+            }
+            self.extensions.insert(extension.to_string(), active);
+        }
+
+        let stale = self.stale.remove(extension).unwrap_or_default();
+        let cached = self.extensions.get_mut(extension).expect("listed above");
+        for uri in stale {
+            if let Some(entry) = cached.get_mut(&uri) {
+                entry.items = read_resource_items(extension, &entry.resource, client).await;
+                changed.insert(uri);
+            }
+        }
+        Ok(cached
+            .iter()
+            .filter(|(uri, _)| !only_changed || changed.contains(*uri))
+            .flat_map(|(_, entry)| entry.items.clone())
+            .collect())
+    }
+}
+
+/// Read a resource's text contents; binary contents can't go into the model's context
+async fn read_resource_items(
+    extension: &str,
+    resource: &Resource,
+    client: &dyn McpClientTrait,
+) -> Vec<ResourceItem> {
+    let contents = match client.read_resource(&resource.uri).await {
+        Ok(contents) => contents,
+        Err(e) => {
+            warn!("Failed to read {}: {}", resource.uri, e);
+            return Vec::new();
+        }
+    };
+    contents
+        .contents
+        .into_iter()
+        .filter_map(|content| match content {
+            ResourceContents::TextResourceContents { uri, text, .. } => Some((uri, text)),
+            ResourceContents::BlobResourceContents { .. } => None,
+        })
+        .map(|(uri, text)| {
+            ResourceItem::new(
+                extension.to_string(),
+                uri,
+                resource.name.clone(),
+                text,
+                resource.timestamp().unwrap_or(*DEFAULT_TIMESTAMP),
+                resource.priority().unwrap_or(0.0),
+            )
+        })
+        .collect()
+}
+
+/// Keep the cache in step with an extension's resource notifications while its client lives
+fn watch_resource_notifications(
+    extension: String,
+    mut notifications: mpsc::Receiver<ServerNotification>,
+    cache: Arc<Mutex<ResourceCache>>,
+) {
+    tokio::spawn(async move {
+        while let Some(notification) = notifications.recv().await {
+            match notification {
+                ServerNotification::ResourceUpdatedNotification(updated) => {
+                    cache
+                        .lock()
+                        .await
+                        .mark_updated(&extension, updated.params.uri);
+                }
+                ServerNotification::ResourceListChangedNotification(_) => {
+                    cache.lock().await.mark_list_changed(&extension);
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
 /// Sanitizes a string by replacing invalid characters with underscores.
 /// Valid characters match [a-zA-Z0-9_-]
 fn normalize(input: String) -> String {
@@ -86,6 +268,22 @@ fn normalize(input: String) -> String {
     result.to_lowercase()
 }
 
+/// Seconds an extension may stay unresponsive after a cancelled tool call before it's killed
+const DEFAULT_CANCEL_GRACE_PERIOD_SECS: u64 = 30;
+
+/// Start extension processes so they are killed when they keep running a cancelled
+/// tool call for longer than `GOOSE_MCP_CANCEL_GRACE_PERIOD` seconds, where 0 never kills them
+fn stdio_transport(cmd: &str, args: Vec<String>, envs: HashMap<String, String>) -> StdioTransport {
+    let transport = StdioTransport::new(cmd, args, envs);
+    match Config::global()
+        .get_param::<u64>("GOOSE_MCP_CANCEL_GRACE_PERIOD")
+        .unwrap_or(DEFAULT_CANCEL_GRACE_PERIOD_SECS)
+    {
+        0 => transport,
+        seconds => transport.with_cancel_grace_period(Duration::from_secs(seconds)),
+    }
+}
+
 pub fn get_parameter_names(tool: &Tool) -> Vec<String> {
     tool.input_schema
         .get("properties")
@@ -107,7 +305,20 @@ impl ExtensionManager {
             clients: HashMap::new(),
             instructions: HashMap::new(),
             resource_capable_extensions: HashSet::new(),
+            builtin_extensions: HashSet::new(),
             temp_dirs: HashMap::new(),
+            client_context: None,
+            resource_cache: Arc::new(Mutex::new(ResourceCache::default())),
+            configs: HashMap::new(),
+        }
+    }
+
+    /// Create an ExtensionManager whose extensions can call back into the agent,
+    /// for sampling and to list the session's roots
+    pub fn with_client_context(context: ClientContext) -> Self {
+        Self {
+            client_context: Some(context),
+            ..Self::new()
         }
     }
 
@@ -178,6 +389,11 @@ impl ExtensionManager {
             Ok(all_envs)
         }
 
+        let handler = self.client_context.clone().map(|context| {
+            Arc::new(AgentClientHandler::new(sanitized_name.clone(), context))
+                as Arc<dyn ClientHandler>
+        });
+
         let mut client: Box<dyn McpClientTrait> = match &config {
             ExtensionConfig::Sse {
                 uri,
@@ -190,11 +406,12 @@ impl ExtensionManager {
                 let transport = SseTransport::new(uri, all_envs);
                 let handle = transport.start().await?;
                 Box::new(
-                    McpClient::connect(
+                    McpClient::connect_with_handler(
                         handle,
                         Duration::from_secs(
                             timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                         ),
+                        handler.clone(),
                     )
                     .await?,
                 )
@@ -212,11 +429,12 @@ impl ExtensionManager {
                     StreamableHttpTransport::with_headers(uri, all_envs, headers.clone());
                 let handle = transport.start().await?;
                 Box::new(
-                    McpClient::connect(
+                    McpClient::connect_with_handler(
                         handle,
                         Duration::from_secs(
                             timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                         ),
+                        handler.clone(),
                     )
                     .await?,
                 )
@@ -230,14 +448,15 @@ impl ExtensionManager {
                 ..
             } => {
                 let all_envs = merge_environments(envs, env_keys, &sanitized_name).await?;
-                let transport = StdioTransport::new(cmd, args.to_vec(), all_envs);
+                let transport = stdio_transport(cmd, args.to_vec(), all_envs);
                 let handle = transport.start().await?;
                 Box::new(
-                    McpClient::connect(
+                    McpClient::connect_with_handler(
                         handle,
                         Duration::from_secs(
                             timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                         ),
+                        handler.clone(),
                     )
                     .await?,
                 )
@@ -248,24 +467,30 @@ impl ExtensionManager {
                 description: _,
                 timeout,
                 bundled: _,
+                sandbox,
             } => {
                 let cmd = std::env::current_exe()
                     .expect("should find the current executable")
                     .to_str()
                     .expect("should resolve executable to string path")
                     .to_string();
-                let transport = StdioTransport::new(
-                    &cmd,
-                    vec!["mcp".to_string(), name.clone()],
-                    HashMap::new(),
-                );
+                let mut envs = HashMap::new();
+                if let Some(sandbox) = sandbox {
+                    envs.insert(
+                        SANDBOX_ENV.to_string(),
+                        serde_json::to_string(sandbox)
+                            .map_err(|e| ExtensionError::SetupError(e.to_string()))?,
+                    );
+                }
+                let transport = stdio_transport(&cmd, vec!["mcp".to_string(), name.clone()], envs);
                 let handle = transport.start().await?;
                 Box::new(
-                    McpClient::connect(
+                    McpClient::connect_with_handler(
                         handle,
                         Duration::from_secs(
                             timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                         ),
+                        handler.clone(),
                     )
                     .await?,
                 )
@@ -297,14 +522,15 @@ impl ExtensionManager {
                 args.push("python".to_string());
                 args.push(file_path.to_str().unwrap().to_string());
 
-                let transport = StdioTransport::new("uvx", args, HashMap::new());
+                let transport = stdio_transport("uvx", args, HashMap::new());
                 let handle = transport.start().await?;
                 let client = Box::new(
-                    McpClient::connect(
+                    McpClient::connect_with_handler(
                         handle,
                         Duration::from_secs(
                             timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
                         ),
+                        handler.clone(),
                     )
                     .await?,
                 );
@@ -321,7 +547,13 @@ impl ExtensionManager {
             name: "goose".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
-        let capabilities = ClientCapabilities::default();
+        let capabilities = ClientCapabilities {
+            sampling: handler.is_some().then(SamplingCapability::default),
+            roots: handler.is_some().then_some(RootsCapability {
+                list_changed: Some(true),
+            }),
+            elicitation: handler.is_some().then(ElicitationCapability::default),
+        };
 
         let init_result = client
             .initialize(info, capabilities)
@@ -333,15 +565,72 @@ impl ExtensionManager {
                 .insert(sanitized_name.clone(), instructions);
         }
 
-        if init_result.capabilities.resources.is_some() {
+        if let Some(resources) = &init_result.capabilities.resources {
             self.resource_capable_extensions
                 .insert(sanitized_name.clone());
+            self.resource_cache
+                .lock()
+                .await
+                .add_extension(&sanitized_name, resources.subscribe.unwrap_or(false));
+            watch_resource_notifications(
+                sanitized_name.clone(),
+                client.subscribe().await,
+                self.resource_cache.clone(),
+            );
+        }
+
+        if matches!(config, ExtensionConfig::Builtin { .. }) {
+            self.builtin_extensions.insert(sanitized_name.clone());
         }
 
+        self.configs.insert(sanitized_name.clone(), config);
         self.add_client(sanitized_name, client);
         Ok(())
     }
 
+    /// Start extensions again whose servers went away, e.g. because they were killed for
+    /// not responding after a cancelled tool call
+    ///
+    /// Extensions that fail to start are removed. Returns a notice for the user about each
+    /// extension, keyed by its name. Extensions busy with a request are left alone.
+    pub async fn restart_closed_extensions(&mut self) -> Vec<(String, String)> {
+        let closed: Vec<String> = self
+            .clients
+            .iter()
+            .filter(|(_, client)| client.try_lock().is_ok_and(|client| client.is_closed()))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        let mut notices = Vec::new();
+        for name in closed {
+            let Some(config) = self.configs.get(&name).cloned() else {
+                continue;
+            };
+            let notice = match self.add_extension(config).await {
+                Ok(()) => format!("The {} extension stopped and was restarted", name),
+                Err(e) => {
+                    let _ = self.remove_extension(&name).await;
+                    format!(
+                        "The {} extension stopped and could not be restarted, so it is unavailable: {}",
+                        name, e
+                    )
+                }
+            };
+            warn!("{}", notice);
+            notices.push((name, notice));
+        }
+        notices
+    }
+
+    /// Tell every extension that the session's roots changed
+    pub async fn notify_roots_list_changed(&self) {
+        for (name, client) in &self.clients {
+            if let Err(e) = client.lock().await.notify_roots_list_changed().await {
+                warn!("Failed to notify {} that roots changed: {}", name, e);
+            }
+        }
+    }
+
     pub fn add_client(&mut self, client_name: String, client: Box<dyn McpClientTrait>) {
         let sanitized_name = normalize(client_name);
         self.clients
@@ -367,7 +656,13 @@ impl ExtensionManager {
         self.clients.remove(&sanitized_name);
         self.instructions.remove(&sanitized_name);
         self.resource_capable_extensions.remove(&sanitized_name);
+        self.builtin_extensions.remove(&sanitized_name);
+        self.resource_cache
+            .lock()
+            .await
+            .remove_extension(&sanitized_name);
         self.temp_dirs.remove(&sanitized_name);
+        self.configs.remove(&sanitized_name);
         Ok(())
     }
 
@@ -473,41 +768,26 @@ impl ExtensionManager {
         Ok(tools)
     }
 
-    /// Get client resources and their contents
-    pub async fn get_resources(&self) -> ExtensionResult<Vec<ResourceItem>> {
+    /// Get the contents of the active resources the agent is subscribed to
+    ///
+    /// Contents are cached between calls and re-read when the extension reports them updated.
+    /// With `only_changed`, only resources read by this call are returned. Extensions that
+    /// fail are logged and skipped.
+    pub async fn get_resources(&self, only_changed: bool) -> Vec<ResourceItem> {
         let mut result: Vec<ResourceItem> = Vec::new();
+        let mut cache = self.resource_cache.lock().await;
 
         for (name, client) in &self.clients {
+            if !self.resource_capable_extensions.contains(name) {
+                continue;
+            }
             let client_guard = client.lock().await;
-            let resources = client_guard.list_resources(None).await?;
-
-            for resource in resources.resources {
-                // Skip reading the resource if it's not marked active
-                // This avoids blowing up the context with inactive resources
-                if !resource_is_active(&resource) {
-                    continue;
-                }
-
-                if let Ok(contents) = client_guard.read_resource(&resource.uri).await {
-                    for content in contents.contents {
-                        let (uri, content_str) = match content {
-                            ResourceContents::TextResourceContents { uri, text, .. } => (uri, text),
-                            ResourceContents::BlobResourceContents { uri, blob, .. } => (uri, blob),
-                        };
-
-                        result.push(ResourceItem::new(
-                            name.clone(),
-                            uri,
-                            resource.name.clone(),
-                            content_str,
-                            resource.timestamp().unwrap_or(*DEFAULT_TIMESTAMP),
-                            resource.priority().unwrap_or(0.0),
-                        ));
-                    }
-                }
+            match cache.refresh(name, &**client_guard, only_changed).await {
+                Ok(items) => result.extend(items),
+                Err(e) => warn!("Failed to refresh resources from {}: {}", name, e),
             }
         }
-        Ok(result)
+        result
     }
 
     /// Get the extension prompt including client instructions
@@ -519,6 +799,12 @@ impl ExtensionManager {
     }
 
     /// Find and return a reference to the appropriate client for a tool call
+    /// Whether a tool belongs to one of goose's builtin extensions
+    pub fn is_builtin_tool(&self, prefixed_name: &str) -> bool {
+        self.get_client_for_tool(prefixed_name)
+            .is_some_and(|(name, _)| self.builtin_extensions.contains(name))
+    }
+
     fn get_client_for_tool(&self, prefixed_name: &str) -> Option<(&str, McpClientBox)> {
         self.clients
             .iter()
@@ -608,6 +894,35 @@ impl ExtensionManager {
         Ok(result)
     }
 
+    /// Read the text of a resource from one extension
+    ///
+    /// Returns `None` if the extension isn't loaded, doesn't support resources or
+    /// can't read the resource.
+    pub async fn read_text_resource(&self, extension_name: &str, uri: &str) -> Option<String> {
+        if !self.resource_capable_extensions.contains(extension_name) {
+            return None;
+        }
+        let client = self.clients.get(extension_name)?;
+        let client_guard = client.lock().await;
+        match client_guard.read_resource(uri).await {
+            Ok(result) => Some(
+                result
+                    .contents
+                    .into_iter()
+                    .filter_map(|content| match content {
+                        ResourceContents::TextResourceContents { text, .. } => Some(text),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            Err(e) => {
+                tracing::warn!("Failed to read {} from {}: {}", uri, extension_name, e);
+                None
+            }
+        }
+    }
+
     async fn list_resources_from_extension(
         &self,
         extension_name: &str,
@@ -916,6 +1231,14 @@ mod tests {
             Err(Error::NotInitialized)
         }
 
+        async fn subscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn unsubscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
         async fn list_tools(&self, _next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
             Err(Error::NotInitialized)
         }
@@ -948,6 +1271,10 @@ mod tests {
         async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
             mpsc::channel(1).1
         }
+
+        async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+            Ok(())
+        }
     }
 
     #[test]
@@ -1095,4 +1422,272 @@ mod tests {
             panic!("Expected ToolError::NotFound");
         }
     }
+
+    /// Serves one active and one inactive resource, counting reads, plus an active image
+    struct ResourceClient {
+        reads: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl McpClientTrait for ResourceClient {
+        async fn initialize(
+            &mut self,
+            _info: ClientInfo,
+            _capabilities: ClientCapabilities,
+        ) -> Result<InitializeResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListResourcesResult, Error> {
+            use rmcp::model::{AnnotateAble, RawResource};
+            Ok(ListResourcesResult {
+                resources: vec![
+                    RawResource::new("ticket://1", "ticket")
+                        .no_annotation()
+                        .with_priority(1.0),
+                    RawResource::new("ticket://2", "archived").no_annotation(),
+                    RawResource::new("ticket://3", "screenshot")
+                        .no_annotation()
+                        .with_priority(1.0),
+                ],
+                next_cursor: None,
+            })
+        }
+
+        async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, Error> {
+            if uri == "ticket://3" {
+                return Ok(ReadResourceResult {
+                    contents: vec![ResourceContents::BlobResourceContents {
+                        uri: uri.to_string(),
+                        mime_type: Some("image/png".to_string()),
+                        blob: "iVBORw0KGgo=".to_string(),
+                    }],
+                });
+            }
+            let reads = self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            Ok(ReadResourceResult {
+                contents: vec![ResourceContents::TextResourceContents {
+                    uri: uri.to_string(),
+                    mime_type: Some("text/plain".to_string()),
+                    text: format!("read {}", reads),
+                }],
+            })
+        }
+
+        async fn subscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn unsubscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn list_tools(&self, _next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn call_tool(&self, _name: &str, _arguments: Value) -> Result<CallToolResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListPromptsResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            _arguments: Value,
+        ) -> Result<GetPromptResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+            mpsc::channel(1).1
+        }
+
+        async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resources_are_reread_only_when_updated() {
+        let mut extension_manager = ExtensionManager::new();
+        extension_manager.add_client(
+            "tickets".to_string(),
+            Box::new(ResourceClient {
+                reads: Default::default(),
+            }),
+        );
+        // Fails to list its resources, which shouldn't hide the other extension's
+        extension_manager.add_client("broken".to_string(), Box::new(MockClient {}));
+        for name in ["tickets", "broken"] {
+            extension_manager
+                .resource_capable_extensions
+                .insert(name.to_string());
+            extension_manager
+                .resource_cache
+                .lock()
+                .await
+                .add_extension(name, true);
+        }
+
+        let contents = |items: Vec<ResourceItem>| {
+            items
+                .into_iter()
+                .map(|item| item.content)
+                .collect::<Vec<_>>()
+        };
+
+        // Only the active text resource is read, and it stays cached until it's updated
+        let resources = extension_manager.get_resources(true).await;
+        assert_eq!(resources[0].uri, "ticket://1");
+        assert_eq!(contents(resources), vec!["read 1"]);
+        assert!(extension_manager.get_resources(true).await.is_empty());
+        assert_eq!(
+            contents(extension_manager.get_resources(false).await),
+            vec!["read 1"]
+        );
+
+        extension_manager
+            .resource_cache
+            .lock()
+            .await
+            .mark_updated("tickets", "ticket://1".to_string());
+        assert_eq!(
+            contents(extension_manager.get_resources(true).await),
+            vec!["read 2"]
+        );
+
+        // Relisting keeps what's cached for resources that are still there
+        extension_manager
+            .resource_cache
+            .lock()
+            .await
+            .mark_list_changed("tickets");
+        assert!(extension_manager.get_resources(true).await.is_empty());
+        assert_eq!(
+            contents(extension_manager.get_resources(false).await),
+            vec!["read 2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resources_need_subscriptions() {
+        let mut extension_manager = ExtensionManager::new();
+        extension_manager.add_client(
+            "tickets".to_string(),
+            Box::new(ResourceClient {
+                reads: Default::default(),
+            }),
+        );
+        extension_manager
+            .resource_capable_extensions
+            .insert("tickets".to_string());
+        extension_manager
+            .resource_cache
+            .lock()
+            .await
+            .add_extension("tickets", false);
+
+        assert!(extension_manager.get_resources(false).await.is_empty());
+    }
+
+    /// A client whose server has gone away
+    struct ClosedClient;
+
+    #[async_trait::async_trait]
+    impl McpClientTrait for ClosedClient {
+        async fn initialize(
+            &mut self,
+            _info: ClientInfo,
+            _capabilities: ClientCapabilities,
+        ) -> Result<InitializeResult, Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListResourcesResult, Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn read_resource(&self, _uri: &str) -> Result<ReadResourceResult, Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn subscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn unsubscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn list_tools(&self, _next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn call_tool(&self, _name: &str, _arguments: Value) -> Result<CallToolResult, Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListPromptsResult, Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            _arguments: Value,
+        ) -> Result<GetPromptResult, Error> {
+            Err(Error::NotReady)
+        }
+
+        async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+            mpsc::channel(1).1
+        }
+
+        async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+            Err(Error::NotReady)
+        }
+
+        fn is_closed(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_closed_extension_that_fails_to_restart_is_removed() {
+        let mut extension_manager = ExtensionManager::new();
+        extension_manager.add_client("healthy".to_string(), Box::new(MockClient {}));
+        extension_manager.add_client("crashed".to_string(), Box::new(ClosedClient));
+        extension_manager.configs.insert(
+            "crashed".to_string(),
+            ExtensionConfig::stdio("crashed", "goose-test-no-such-command", "", 5u64),
+        );
+
+        let notices = extension_manager.restart_closed_extensions().await;
+
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].0, "crashed");
+        assert!(notices[0].1.contains("unavailable"));
+        assert!(!extension_manager.clients.contains_key("crashed"));
+        assert!(extension_manager.clients.contains_key("healthy"));
+        assert!(extension_manager
+            .restart_closed_extensions()
+            .await
+            .is_empty());
+    }
 }
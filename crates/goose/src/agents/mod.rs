@@ -1,5 +1,8 @@
 mod agent;
+pub mod budget;
+pub mod client_handler;
 mod context;
+pub mod elicitation;
 pub mod extension;
 pub mod extension_manager;
 pub mod final_output_tool;
@@ -11,6 +14,7 @@ mod reply_parts;
 pub mod retry;
 mod router_tool_selector;
 mod router_tools;
+pub mod sampling;
 mod schedule_tool;
 pub mod sub_recipe_manager;
 pub mod subagent;
@@ -24,9 +28,10 @@ pub(crate) mod tool_vectordb;
 pub mod types;
 
 pub use agent::{Agent, AgentEvent};
+pub use budget::CostBudget;
 pub use extension::ExtensionConfig;
 pub use extension_manager::ExtensionManager;
 pub use prompt_manager::PromptManager;
 pub use subagent::{SubAgent, SubAgentProgress, SubAgentStatus};
 pub use subagent_task_config::TaskConfig;
-pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck};
+pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck, ToolProgress};
@@ -35,6 +35,21 @@ impl PromptManager {
         self.system_prompt_extras.push(instruction);
     }
 
+    /// Add the saved memories relevant to a session after the rest of its system prompt
+    ///
+    /// The memories differ between sessions, so they aren't kept with the other extras.
+    pub fn with_memory_context(system_prompt: &str, memories: &str) -> String {
+        let memories = memories.trim();
+        if memories.is_empty() {
+            return system_prompt.to_string();
+        }
+        format!(
+            "{}\n\n# Memories\n\nThe user saved these memories in earlier sessions, and they look \
+             relevant to this one. Use them where they apply.\n\n{}",
+            system_prompt, memories
+        )
+    }
+
     /// Override the system prompt with custom text
     pub fn set_system_prompt_override(&mut self, template: String) {
         self.system_prompt_override = Some(template);
@@ -221,4 +236,25 @@ mod tests {
             "system.md"
         );
     }
+
+    #[test]
+    fn test_memory_context_follows_instructions() {
+        let mut manager = PromptManager::new();
+        manager.add_system_prompt_extra("Be brief.".to_string());
+        let system_prompt = manager.build_system_prompt(vec![], None, Value::Null, None, None);
+        assert!(!system_prompt.contains("# Memories"));
+        assert_eq!(
+            PromptManager::with_memory_context(&system_prompt, " \n"),
+            system_prompt
+        );
+
+        let prompt = PromptManager::with_memory_context(
+            &system_prompt,
+            "- [local/testing] Run tests with pytest\n",
+        );
+        let instructions = prompt.find("Be brief.").unwrap();
+        let memories = prompt.find("# Memories").unwrap();
+        assert!(instructions < memories);
+        assert!(prompt.ends_with("- [local/testing] Run tests with pytest"));
+    }
 }
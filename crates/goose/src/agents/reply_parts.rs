@@ -1,20 +1,26 @@
 use anyhow::Result;
 use std::collections::HashSet;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use async_stream::try_stream;
 use futures::stream::StreamExt;
 
+use crate::agents::extension_manager::ResourceItem;
 use crate::agents::router_tool_selector::RouterToolSelectionStrategy;
+use crate::agents::types::SessionConfig;
+use crate::config::Config;
 use crate::message::{Message, MessageContent, ToolRequest};
 use crate::providers::base::{stream_from_single_message, MessageStream, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
+use crate::providers::pricing;
 use crate::providers::toolshim::{
     augment_message_with_tool_calls, convert_tool_messages_to_text,
     modify_system_prompt_for_tool_json, OllamaInterpreter,
 };
 use crate::session;
-use rmcp::model::Tool;
+use crate::utils::safe_truncate;
+use rmcp::model::{Role, Tool};
 
 use super::super::agents::Agent;
 
@@ -31,7 +37,145 @@ async fn toolshim_postprocess(
         .map_err(|e| ProviderError::ExecutionError(format!("Failed to augment message: {}", e)))
 }
 
+/// Estimated cost of a request, priced with the provider and model that served it so
+/// lead/worker switches and fallbacks are priced correctly
+pub(crate) async fn turn_cost(usage: &ProviderUsage) -> Option<f64> {
+    let provider = usage.provider.as_deref()?;
+    pricing::estimate_cost(provider, &usage.model, &usage.usage).await
+}
+
+/// Upper bound on the resource contents added to the conversation each turn
+const MAX_RESOURCE_CONTEXT_CHARS: usize = 32_000;
+
+/// The builtin extension that serves a digest of the memories relevant to a session
+const MEMORY_EXTENSION: &str = "memory";
+const MEMORY_DIGEST_URI: &str = "memory://digest";
+const DEFAULT_MEMORY_CONTEXT_TOKENS: usize = 1000;
+/// Only the start of a long first message is used to rank memories
+const MAX_MEMORY_QUERY_CHARS: usize = 2000;
+/// Most sessions whose memories are kept, so a long-running goosed doesn't grow without bound
+pub(crate) const MAX_MEMORY_CONTEXTS: NonZeroUsize = NonZeroUsize::new(100).unwrap();
+
+/// Render resources for the model, highest priority and most recent first
+fn render_resource_context(mut resources: Vec<ResourceItem>) -> String {
+    resources.sort_by(|a, b| {
+        b.priority
+            .total_cmp(&a.priority)
+            .then(b.timestamp.cmp(&a.timestamp))
+    });
+
+    let mut context = String::from(
+        "# Active Resources\n\nThe current contents of resources your extensions marked as active. \
+         They are sent again whenever they change, so prefer the latest copy in the conversation.\n",
+    );
+    let mut remaining = MAX_RESOURCE_CONTEXT_CHARS;
+    for resource in resources {
+        if remaining == 0 {
+            context.push_str(&format!(
+                "\n## {} ({})\n\nOmitted to save context; read it with the read_resource tool.\n",
+                resource.name, resource.uri
+            ));
+            continue;
+        }
+        let content = safe_truncate(&resource.content, remaining);
+        remaining = remaining.saturating_sub(content.chars().count());
+        context.push_str(&format!(
+            "\n## {} ({}) from {}\n\n```\n{}\n```\n",
+            resource.name, resource.uri, resource.client_name, content
+        ));
+    }
+    context
+}
+
+/// Add resource contents to the end of the conversation
+///
+/// The contents join the last message when the user wrote it, since some providers such as
+/// Bedrock reject two user messages in a row. Tool results get a message of their own, as
+/// OpenAI-style APIs don't allow user text between tool calls and their results.
+fn add_resource_context(messages: &mut Vec<Message>, context: String) {
+    match messages.last_mut() {
+        Some(last) if last.role == Role::User && !last.is_tool_response() => {
+            last.content.push(MessageContent::text(context))
+        }
+        _ => messages.push(Message::user().with_text(context)),
+    }
+}
+
 impl Agent {
+    /// Add the contents of the extensions' subscribed resources to the conversation
+    ///
+    /// Called before every turn, with `only_changed` after the first one, so updates
+    /// extensions report during a long session reach the model on the next turn. The
+    /// contents go in the conversation rather than the system prompt to keep its cached
+    /// prefix intact.
+    pub(crate) async fn add_resource_updates(
+        &self,
+        messages: &mut Vec<Message>,
+        only_changed: bool,
+    ) {
+        let extension_manager = self.extension_manager.read().await;
+        if !extension_manager.supports_resources() {
+            return;
+        }
+        let resources = extension_manager.get_resources(only_changed).await;
+        if !resources.is_empty() {
+            add_resource_context(messages, render_resource_context(resources));
+        }
+    }
+
+    /// The saved memories most relevant to a session, for its system prompt
+    ///
+    /// Memories come from the memory extension if it's enabled. They are ranked against
+    /// the first user message, and local memories for the working directory come first.
+    /// `GOOSE_MEMORY_CONTEXT_TOKENS` limits how much of the prompt they take up, and 0
+    /// turns this off.
+    ///
+    /// Each session keeps the memories it was started with, since goosed shares one agent
+    /// between sessions; only the `MAX_MEMORY_CONTEXTS` sessions used most recently are
+    /// remembered. Nothing is kept when the lookup fails or finds no memories, so it is
+    /// tried again on the next reply.
+    pub(crate) async fn memory_context(
+        &self,
+        messages: &[Message],
+        session: &Option<SessionConfig>,
+    ) -> String {
+        let key = session.as_ref().map(|session| session.id.clone());
+        if let Some(memories) = self.memory_contexts.lock().await.get(&key) {
+            return memories.clone();
+        }
+
+        let max_tokens = Config::global()
+            .get_param::<usize>("GOOSE_MEMORY_CONTEXT_TOKENS")
+            .unwrap_or(DEFAULT_MEMORY_CONTEXT_TOKENS);
+        if max_tokens == 0 {
+            return String::new();
+        }
+
+        let query = messages
+            .iter()
+            .find(|message| message.role == Role::User)
+            .map(|message| message.as_concat_text())
+            .unwrap_or_default();
+        let uri = format!(
+            "{}?max_tokens={}&query={}",
+            MEMORY_DIGEST_URI,
+            max_tokens,
+            urlencoding::encode(&safe_truncate(&query, MAX_MEMORY_QUERY_CHARS))
+        );
+        let memories = self
+            .extension_manager
+            .read()
+            .await
+            .read_text_resource(MEMORY_EXTENSION, &uri)
+            .await
+            .unwrap_or_default();
+
+        if !memories.trim().is_empty() {
+            self.memory_contexts.lock().await.put(key, memories.clone());
+        }
+        memories
+    }
+
     /// Prepares tools and system prompt for a provider request
     pub async fn prepare_tools_and_prompt(&self) -> anyhow::Result<(Vec<Tool>, Vec<Tool>, String)> {
         // Get tool selection strategy from config
@@ -266,11 +410,13 @@ impl Agent {
         (frontend_requests, other_requests, filtered_message)
     }
 
+    /// Record a turn's usage and cost in the session metadata, returning the session's
+    /// accumulated cost
     pub(crate) async fn update_session_metrics(
         session_config: &crate::agents::types::SessionConfig,
         usage: &ProviderUsage,
         messages_length: usize,
-    ) -> Result<()> {
+    ) -> Result<Option<f64>> {
         let session_file_path = match session::storage::get_path(session_config.id.clone()) {
             Ok(path) => path,
             Err(e) => {
@@ -286,6 +432,9 @@ impl Agent {
         metadata.output_tokens = usage.usage.output_tokens;
 
         metadata.message_count = messages_length + 1;
+        if let Some(provider) = &usage.provider {
+            metadata.record_served_by(provider, &usage.model, messages_length);
+        }
 
         let accumulate = |a: Option<i32>, b: Option<i32>| -> Option<i32> {
             match (a, b) {
@@ -301,9 +450,106 @@ impl Agent {
             metadata.accumulated_output_tokens,
             usage.usage.output_tokens,
         );
+        metadata.add_cost(turn_cost(usage).await);
+
+        session::storage::update_metadata(&session_file_path, &metadata).await?;
+
+        Ok(metadata.accumulated_cost)
+    }
+
+    /// The session's accumulated cost so far, if any of its turns could be priced
+    pub(crate) fn session_cost(
+        session_config: &crate::agents::types::SessionConfig,
+    ) -> Option<f64> {
+        let session_file_path = session::storage::get_path(session_config.id.clone()).ok()?;
+        session::storage::read_metadata(&session_file_path)
+            .ok()?
+            .accumulated_cost
+    }
+
+    /// Add usage from model calls outside the conversation, such as sampling requests,
+    /// to the session's accumulated totals without touching its current context size
+    pub(crate) async fn record_additional_usage(
+        session_config: &crate::agents::types::SessionConfig,
+        usage: &ProviderUsage,
+    ) -> Result<()> {
+        let session_file_path = session::storage::get_path(session_config.id.clone())
+            .map_err(|e| anyhow::anyhow!("Failed to get session file path: {}", e))?;
+        let mut metadata = session::storage::read_metadata(&session_file_path)?;
+
+        let add = |a: Option<i32>, b: Option<i32>| match (a, b) {
+            (Some(x), Some(y)) => Some(x + y),
+            _ => a.or(b),
+        };
+        metadata.accumulated_total_tokens =
+            add(metadata.accumulated_total_tokens, usage.usage.total_tokens);
+        metadata.accumulated_input_tokens =
+            add(metadata.accumulated_input_tokens, usage.usage.input_tokens);
+        metadata.accumulated_output_tokens = add(
+            metadata.accumulated_output_tokens,
+            usage.usage.output_tokens,
+        );
+        metadata.add_cost(turn_cost(usage).await);
 
         session::storage::update_metadata(&session_file_path, &metadata).await?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn resource(name: &str, content: &str, priority: f32, day: u32) -> ResourceItem {
+        ResourceItem::new(
+            "tickets".to_string(),
+            format!("ticket://{}", name),
+            name.to_string(),
+            content.to_string(),
+            Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap(),
+            priority,
+        )
+    }
+
+    #[test]
+    fn test_render_resource_context_orders_and_budgets() {
+        let context = render_resource_context(vec![
+            resource("older", "old state", 1.0, 1),
+            resource("newer", "new state", 1.0, 2),
+            resource("huge", &"x".repeat(MAX_RESOURCE_CONTEXT_CHARS * 2), 0.5, 3),
+            resource("last", "never shown", 0.1, 4),
+        ]);
+
+        let newer = context.find("## newer").unwrap();
+        let older = context.find("## older").unwrap();
+        assert!(newer < older);
+        assert!(context.contains("new state"));
+        assert!(context.len() < MAX_RESOURCE_CONTEXT_CHARS + 1_000);
+        assert!(!context.contains("never shown"));
+        assert!(context.contains("## last (ticket://last)\n\nOmitted"));
+    }
+
+    #[test]
+    fn test_add_resource_context_keeps_roles_alternating() {
+        let mut messages = vec![Message::user().with_text("hello")];
+        add_resource_context(&mut messages, "resources".to_string());
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content.len(), 2);
+        assert_eq!(messages[0].content[1].as_text(), Some("resources"));
+
+        messages.push(Message::assistant().with_text("hi"));
+        add_resource_context(&mut messages, "changed".to_string());
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[2].role, Role::User);
+        assert_eq!(messages[2].content[0].as_text(), Some("changed"));
+
+        messages.push(Message::assistant().with_text("calling a tool"));
+        messages.push(Message::user().with_tool_response("tool1", Ok(vec![])));
+        add_resource_context(&mut messages, "after tool".to_string());
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[4].content.len(), 1);
+        assert_eq!(messages[5].content[0].as_text(), Some("after tool"));
+    }
+}
@@ -11,9 +11,12 @@ use std::env;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::agents::tool_vectordb::ToolVectorDB;
+use crate::agents::tool_vectordb::{ToolVectorDB, DEFAULT_EMBEDDING_DIMENSION};
 use crate::message::Message;
 use crate::model::ModelConfig;
+use crate::providers::local_embedding::{
+    LocalEmbeddingProvider, LOCAL_EMBEDDING_DEFAULT_MODEL, LOCAL_EMBEDDING_PROVIDER,
+};
 use crate::providers::{self, base::Provider};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,28 +43,43 @@ pub struct VectorToolSelector {
 
 impl VectorToolSelector {
     pub async fn new(provider: Arc<dyn Provider>, table_name: String) -> Result<Self> {
-        let vector_db = ToolVectorDB::new(Some(table_name)).await?;
-
+        let mut dimension = DEFAULT_EMBEDDING_DIMENSION;
         let embedding_provider = if env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").is_ok() {
             // If env var is set, create a new provider for embeddings
             // Get embedding model and provider from environment variables
-            let embedding_model = env::var("GOOSE_EMBEDDING_MODEL")
-                .unwrap_or_else(|_| "text-embedding-3-small".to_string());
             let embedding_provider_name =
                 env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").unwrap_or_else(|_| "openai".to_string());
 
-            // Create the provider using the factory
-            let model_config = ModelConfig::new(embedding_model.as_str())
-                .context("Failed to create model config for embedding provider")?;
-            providers::create(&embedding_provider_name, model_config).context(format!(
-                "Failed to create {} provider for embeddings. If using OpenAI, make sure OPENAI_API_KEY env var is set or that you have configured the OpenAI provider via Goose before.",
-                embedding_provider_name
-            ))?
+            if embedding_provider_name == LOCAL_EMBEDDING_PROVIDER {
+                // The local model is loaded directly so the table can match its vector length
+                let embedding_model = env::var("GOOSE_EMBEDDING_MODEL")
+                    .unwrap_or_else(|_| LOCAL_EMBEDDING_DEFAULT_MODEL.to_string());
+                let model_config = ModelConfig::new(embedding_model.as_str())
+                    .context("Failed to create model config for embedding provider")?;
+                let local = LocalEmbeddingProvider::from_env(model_config).context(
+                    "Failed to load the local embedding model. Set GOOSE_LOCAL_EMBEDDING_MODEL_PATH to a directory containing config.json, tokenizer.json and model.safetensors.",
+                )?;
+                dimension = local.dimension();
+                Arc::new(local)
+            } else {
+                let embedding_model = env::var("GOOSE_EMBEDDING_MODEL")
+                    .unwrap_or_else(|_| "text-embedding-3-small".to_string());
+
+                // Create the provider using the factory
+                let model_config = ModelConfig::new(embedding_model.as_str())
+                    .context("Failed to create model config for embedding provider")?;
+                providers::create(&embedding_provider_name, model_config).context(format!(
+                    "Failed to create {} provider for embeddings. If using OpenAI, make sure OPENAI_API_KEY env var is set or that you have configured the OpenAI provider via Goose before.",
+                    embedding_provider_name
+                ))?
+            }
         } else {
             // Otherwise fall back to using the same provider instance as used for base goose model
             provider.clone()
         };
 
+        let vector_db = ToolVectorDB::with_dimension(Some(table_name), dimension).await?;
+
         Ok(Self {
             vector_db: Arc::new(RwLock::new(vector_db)),
             embedding_provider,
@@ -0,0 +1,297 @@
+use std::borrow::Cow;
+
+use async_stream::try_stream;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use mcp_core::protocol::{CreateMessageParams, CreateMessageResult};
+use rmcp::model::{Content, ErrorCode, ErrorData, Role};
+use serde_json::json;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+use crate::agents::client_handler::ExtensionRequest;
+use crate::agents::types::SessionConfig;
+use crate::agents::Agent;
+use crate::config::permission::PermissionLevel;
+use crate::config::PermissionManager;
+use crate::message::{Message, MessageContent};
+use crate::permission::Permission;
+
+/// Suffix of the pseudo tool name under which sampling permissions are remembered
+const SAMPLING_TOOL_SUFFIX: &str = "__sampling";
+
+/// Error code MCP uses for a sampling request the user rejected
+const USER_REJECTED: i32 = -1;
+
+/// A `sampling/createMessage` request from an extension, waiting for the agent to answer it
+pub struct SamplingRequest {
+    pub id: String,
+    pub extension_name: String,
+    pub params: CreateMessageParams,
+    pub respond: oneshot::Sender<Result<CreateMessageResult, ErrorData>>,
+}
+
+/// The name sampling requests from `extension_name` are confirmed under, e.g. `memory__sampling`
+pub fn sampling_principal(extension_name: &str) -> String {
+    format!("{}{}", extension_name, SAMPLING_TOOL_SUFFIX)
+}
+
+fn sampling_error(code: ErrorCode, message: impl Into<String>) -> ErrorData {
+    ErrorData {
+        code,
+        message: Cow::from(message.into()),
+        data: None,
+    }
+}
+
+/// Hand a sampling request from `extension_name` to the agent and wait for its answer
+pub async fn request_sampling(
+    requests: &mpsc::Sender<ExtensionRequest>,
+    extension_name: &str,
+    params: CreateMessageParams,
+) -> Result<CreateMessageResult, ErrorData> {
+    let (respond, response) = oneshot::channel();
+    requests
+        .send(ExtensionRequest::Sampling(SamplingRequest {
+            id: format!("sampling_{}", Uuid::new_v4()),
+            extension_name: extension_name.to_string(),
+            params,
+            respond,
+        }))
+        .await
+        .map_err(|_| {
+            sampling_error(
+                ErrorCode::INTERNAL_ERROR,
+                "goose is not accepting sampling requests",
+            )
+        })?;
+    response.await.map_err(|_| {
+        sampling_error(
+            ErrorCode(USER_REJECTED),
+            "The sampling request was cancelled",
+        )
+    })?
+}
+
+impl Agent {
+    /// Answer a sampling request with the agent's provider
+    ///
+    /// The request is confirmed like a tool call: it runs without asking in auto mode or
+    /// when the user always allows the extension to sample, and otherwise yields a
+    /// confirmation request and waits for the answer. Token usage counts toward the session.
+    pub(crate) fn handle_sampling_request<'a>(
+        &'a self,
+        request: SamplingRequest,
+        goose_mode: &'a str,
+        session: &'a Option<SessionConfig>,
+    ) -> BoxStream<'a, anyhow::Result<Message>> {
+        // The extension stopped waiting, e.g. because its request timed out
+        if request.respond.is_closed() {
+            return futures::stream::empty().boxed();
+        }
+
+        try_stream! {
+            let principal = sampling_principal(&request.extension_name);
+            let mut permission_manager = PermissionManager::default();
+            let permission = match (goose_mode, permission_manager.get_user_permission(&principal)) {
+                (_, Some(PermissionLevel::NeverAllow)) | ("chat", _) => Permission::DenyOnce,
+                (_, Some(PermissionLevel::AlwaysAllow)) | ("auto", _) => Permission::AllowOnce,
+                _ => {
+                    let arguments = json!({
+                        "system_prompt": request.params.system_prompt,
+                        "messages": request
+                            .params
+                            .messages
+                            .iter()
+                            .map(|m| {
+                                MessageContent::from(m.content.clone())
+                                    .as_text()
+                                    .unwrap_or("[non-text content]")
+                                    .to_string()
+                            })
+                            .collect::<Vec<_>>(),
+                        "max_tokens": request.params.max_tokens,
+                    });
+                    yield Message::user().with_sampling_confirmation_request(
+                        request.id.clone(),
+                        principal.clone(),
+                        arguments,
+                        Some(format!(
+                            "The {} extension would like to use the model. Allow? (y/n):",
+                            request.extension_name
+                        )),
+                    );
+
+                    let mut rx = self.confirmation_rx.lock().await;
+                    let mut permission = Permission::DenyOnce;
+                    while let Some((req_id, confirmation)) = rx.recv().await {
+                        if req_id == request.id {
+                            permission = confirmation.permission;
+                            break;
+                        }
+                    }
+                    permission
+                }
+            };
+
+            if permission == Permission::AlwaysAllow {
+                permission_manager.update_user_permission(&principal, PermissionLevel::AlwaysAllow);
+            }
+
+            let result = if permission == Permission::AllowOnce || permission == Permission::AlwaysAllow {
+                self.complete_sampling_request(request.params, session).await
+            } else {
+                Err(sampling_error(
+                    ErrorCode(USER_REJECTED),
+                    "The user declined the sampling request",
+                ))
+            };
+            let _ = request.respond.send(result);
+        }
+        .boxed()
+    }
+
+    async fn complete_sampling_request(
+        &self,
+        params: CreateMessageParams,
+        session: &Option<SessionConfig>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        let provider = self
+            .provider()
+            .await
+            .map_err(|e| sampling_error(ErrorCode::INTERNAL_ERROR, e.to_string()))?;
+
+        let messages: Vec<Message> = params
+            .messages
+            .into_iter()
+            .map(|m| {
+                Message::new(
+                    m.role,
+                    chrono::Utc::now().timestamp(),
+                    vec![MessageContent::from(m.content)],
+                )
+            })
+            .collect();
+        let system_prompt = params.system_prompt.unwrap_or_default();
+        let max_tokens = i32::try_from(params.max_tokens).unwrap_or(i32::MAX);
+        let mut model_config = provider
+            .get_model_config()
+            .with_max_tokens(Some(max_tokens));
+        if params.temperature.is_some() {
+            model_config = model_config.with_temperature(params.temperature);
+        }
+
+        let (message, usage) = provider
+            .complete_with_model(&model_config, &system_prompt, &messages, &[])
+            .await
+            .map_err(|e| sampling_error(ErrorCode::INTERNAL_ERROR, e.to_string()))?;
+
+        if let Some(session) = session {
+            if let Err(e) = Self::record_additional_usage(session, &usage).await {
+                tracing::warn!("Failed to record sampling usage: {}", e);
+            }
+        }
+
+        let (text, stopped) = apply_stop_sequences(
+            message.as_concat_text(),
+            params.stop_sequences.as_deref().unwrap_or_default(),
+        );
+        let stop_reason = if stopped {
+            "stopSequence"
+        } else if usage
+            .usage
+            .output_tokens
+            .is_some_and(|tokens| tokens >= max_tokens)
+        {
+            "maxTokens"
+        } else {
+            "endTurn"
+        };
+
+        Ok(CreateMessageResult {
+            role: Role::Assistant,
+            content: Content::text(text),
+            model: usage.model,
+            stop_reason: Some(stop_reason.to_string()),
+        })
+    }
+}
+
+/// Cut `text` at the first of the stop sequences it contains, reporting whether it was cut
+///
+/// Providers don't all take stop sequences, so they're applied to the finished response.
+fn apply_stop_sequences(mut text: String, stop_sequences: &[String]) -> (String, bool) {
+    let first_stop = stop_sequences
+        .iter()
+        .filter(|stop| !stop.is_empty())
+        .filter_map(|stop| text.find(stop.as_str()))
+        .min();
+    match first_stop {
+        Some(index) => {
+            text.truncate(index);
+            (text, true)
+        }
+        None => (text, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_sampling_forwards_to_agent() {
+        let (tx, mut rx) = mpsc::channel(1);
+
+        let agent = tokio::spawn(async move {
+            let Some(ExtensionRequest::Sampling(request)) = rx.recv().await else {
+                panic!("expected a sampling request");
+            };
+            assert_eq!(request.extension_name, "memory");
+            assert!(request.id.starts_with("sampling_"));
+            let _ = request.respond.send(Ok(CreateMessageResult {
+                role: Role::Assistant,
+                content: Content::text("a summary"),
+                model: "test-model".to_string(),
+                stop_reason: None,
+            }));
+        });
+
+        let result = request_sampling(
+            &tx,
+            "memory",
+            CreateMessageParams {
+                messages: vec![],
+                system_prompt: None,
+                max_tokens: 100,
+                temperature: None,
+                stop_sequences: None,
+                model_preferences: None,
+                include_context: None,
+                metadata: None,
+            },
+        )
+        .await
+        .unwrap();
+        agent.await.unwrap();
+
+        assert_eq!(result.model, "test-model");
+    }
+
+    #[test]
+    fn test_apply_stop_sequences() {
+        let stops = vec!["\n\n".to_string(), "END".to_string()];
+        assert_eq!(
+            apply_stop_sequences("one END two\n\nthree".to_string(), &stops),
+            ("one ".to_string(), true)
+        );
+        assert_eq!(
+            apply_stop_sequences("no stops here".to_string(), &stops),
+            ("no stops here".to_string(), false)
+        );
+        assert_eq!(
+            apply_stop_sequences("text".to_string(), &[String::new()]),
+            ("text".to_string(), false)
+        );
+    }
+}
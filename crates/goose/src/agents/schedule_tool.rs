@@ -151,6 +151,7 @@ impl Agent {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some(execution_mode.to_string()),
+            budget: None,
         };
 
         match scheduler.add_scheduled_job(job).await {
@@ -386,7 +387,7 @@ impl Agent {
         };
 
         // Check if session file exists
-        if !session_path.exists() {
+        if !crate::session::storage::session_exists(&session_path) {
             return Err(ToolError::ExecutionError(format!(
                 "Session '{}' not found",
                 session_id
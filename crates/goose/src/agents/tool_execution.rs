@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::future::Future;
 use std::sync::Arc;
 
@@ -10,7 +11,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
-use crate::message::{Message, ToolRequest};
+use crate::message::{Message, ToolRequest, TOOL_CONFIRMATION_PROMPT};
 use crate::permission::Permission;
 use mcp_core::ToolResult;
 use rmcp::model::Content;
@@ -51,6 +52,7 @@ impl Agent {
     pub(crate) fn handle_approval_tool_requests<'a>(
         &'a self,
         tool_requests: &'a [ToolRequest],
+        reasons: &'a HashMap<String, String>,
         tool_futures: Arc<Mutex<Vec<(String, ToolStream)>>>,
         permission_manager: &'a mut PermissionManager,
         message_tool_response: Arc<Mutex<Message>>,
@@ -59,11 +61,15 @@ impl Agent {
         try_stream! {
             for request in tool_requests {
                 if let Ok(tool_call) = request.tool_call.clone() {
+                    let prompt = match reasons.get(&request.id) {
+                        Some(reason) => format!("{}\n{}", reason, TOOL_CONFIRMATION_PROMPT),
+                        None => TOOL_CONFIRMATION_PROMPT.to_string(),
+                    };
                     let confirmation = Message::user().with_tool_confirmation_request(
                         request.id.clone(),
                         tool_call.name.clone(),
                         tool_call.arguments.clone(),
-                        Some("Goose would like to call the above tool. Allow? (y/n):".to_string()),
+                        Some(prompt),
                     );
                     yield confirmation;
 
@@ -26,10 +26,15 @@ pub struct ToolRecord {
 pub struct ToolVectorDB {
     connection: Arc<RwLock<Connection>>,
     table_name: String,
+    dimension: usize,
 }
 
+/// Length of OpenAI's text-embedding-3-small vectors, used unless the embedding model says otherwise
+pub const DEFAULT_EMBEDDING_DIMENSION: usize = 1536;
+
 impl ToolVectorDB {
-    pub async fn new(table_name: Option<String>) -> Result<Self> {
+    /// Open the table for vectors of the given length, as produced by the embedding model
+    pub async fn with_dimension(table_name: Option<String>, dimension: usize) -> Result<Self> {
         let db_path = Self::get_db_path()?;
 
         // Ensure the directory exists
@@ -47,6 +52,7 @@ impl ToolVectorDB {
         let tool_db = Self {
             connection: Arc::new(RwLock::new(connection)),
             table_name: table_name.unwrap_or_else(|| "tools".to_string()),
+            dimension,
         };
 
         // Initialize the table if it doesn't exist
@@ -91,7 +97,18 @@ impl ToolVectorDB {
             .await
             .context("Failed to list tables")?;
 
-        if !table_names.contains(&self.table_name) {
+        let mut exists = table_names.contains(&self.table_name);
+        if exists && self.stored_dimension(&connection).await? != Some(self.dimension) {
+            // The embedding model changed since the table was created, and its vectors
+            // can't be searched with the new ones, so start over with an empty table
+            connection
+                .drop_table(&self.table_name)
+                .await
+                .context("Failed to drop tools table with a different vector size")?;
+            exists = false;
+        }
+
+        if !exists {
             // Create the table schema
             let schema = Arc::new(Schema::new(vec![
                 Field::new("tool_name", DataType::Utf8, false),
@@ -101,7 +118,7 @@ impl ToolVectorDB {
                     "vector",
                     DataType::FixedSizeList(
                         Arc::new(Field::new("item", DataType::Float32, true)),
-                        1536, // OpenAI embedding dimension
+                        self.dimension as i32,
                     ),
                     false,
                 ),
@@ -115,8 +132,10 @@ impl ToolVectorDB {
             let extension_names = StringArray::from(vec![] as Vec<&str>);
 
             // Create empty fixed size list array for vectors
-            let mut vectors_builder =
-                FixedSizeListBuilder::new(arrow::array::Float32Builder::new(), 1536);
+            let mut vectors_builder = FixedSizeListBuilder::new(
+                arrow::array::Float32Builder::new(),
+                self.dimension as i32,
+            );
             let vectors = vectors_builder.finish();
 
             let batch = arrow::record_batch::RecordBatch::try_new(
@@ -153,6 +172,26 @@ impl ToolVectorDB {
         Ok(())
     }
 
+    /// Length of the vectors in the existing table, if it has a vector column
+    async fn stored_dimension(&self, connection: &Connection) -> Result<Option<usize>> {
+        let table = connection
+            .open_table(&self.table_name)
+            .execute()
+            .await
+            .context("Failed to open tools table")?;
+        let schema = table
+            .schema()
+            .await
+            .context("Failed to read table schema")?;
+
+        let dimension = match schema.field_with_name("vector").map(|f| f.data_type()) {
+            Ok(DataType::FixedSizeList(_, size)) => Some(*size as usize),
+            _ => None,
+        };
+
+        Ok(dimension)
+    }
+
     #[cfg(test)]
     pub async fn clear_tools(&self) -> Result<()> {
         let connection = self.connection.write().await;
@@ -202,7 +241,7 @@ impl ToolVectorDB {
                 "vector",
                 DataType::FixedSizeList(
                     Arc::new(Field::new("item", DataType::Float32, true)),
-                    1536,
+                    self.dimension as i32,
                 ),
                 false,
             ),
@@ -215,7 +254,7 @@ impl ToolVectorDB {
         let extension_names_array = StringArray::from(extension_names);
         // Build vectors array
         let mut vectors_builder =
-            FixedSizeListBuilder::new(arrow::array::Float32Builder::new(), 1536);
+            FixedSizeListBuilder::new(arrow::array::Float32Builder::new(), self.dimension as i32);
         for vector_opt in vectors_data {
             if let Some(vector) = vector_opt {
                 let values = vectors_builder.values();
@@ -387,7 +426,7 @@ mod tests {
             base_name: &str,
         ) -> Result<(Self, impl std::future::Future<Output = ()>)> {
             let unique_name = format!("{}_{}", base_name, uuid::Uuid::new_v4().simple());
-            let db = Self::new(Some(unique_name)).await?;
+            let db = Self::with_dimension(Some(unique_name), DEFAULT_EMBEDDING_DIMENSION).await?;
 
             let table_name = db.table_name.clone();
             let connection = db.connection.clone();
@@ -542,6 +581,74 @@ mod tests {
         result
     }
 
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_custom_dimension() -> Result<()> {
+        let unique_name = format!("test_custom_dimension_{}", uuid::Uuid::new_v4().simple());
+        let db = ToolVectorDB::with_dimension(Some(unique_name), 384).await?;
+
+        let result = async {
+            db.index_tools(vec![ToolRecord {
+                tool_name: "local_tool".to_string(),
+                description: "A tool indexed with a small local embedding model".to_string(),
+                schema: "{}".to_string(),
+                vector: vec![0.1; 384],
+                extension_name: "test_extension".to_string(),
+            }])
+            .await?;
+
+            let results = db.search_tools(vec![0.1; 384], 1, None).await?;
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].tool_name, "local_tool");
+            Ok(())
+        }
+        .await;
+
+        let _ = db.connection.read().await.drop_table(&db.table_name).await;
+        result
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_dimension_change_recreates_table() -> Result<()> {
+        let unique_name = format!("test_dimension_change_{}", uuid::Uuid::new_v4().simple());
+        let db =
+            ToolVectorDB::with_dimension(Some(unique_name.clone()), DEFAULT_EMBEDDING_DIMENSION)
+                .await?;
+        db.index_tools(vec![ToolRecord {
+            tool_name: "remote_tool".to_string(),
+            description: "A tool indexed with the default embedding model".to_string(),
+            schema: "{}".to_string(),
+            vector: vec![0.1; DEFAULT_EMBEDDING_DIMENSION],
+            extension_name: "test_extension".to_string(),
+        }])
+        .await?;
+
+        let db = ToolVectorDB::with_dimension(Some(unique_name), 384).await?;
+
+        let result = async {
+            assert!(db.search_tools(vec![0.1; 384], 1, None).await?.is_empty());
+
+            db.index_tools(vec![ToolRecord {
+                tool_name: "local_tool".to_string(),
+                description: "A tool indexed with a small local embedding model".to_string(),
+                schema: "{}".to_string(),
+                vector: vec![0.1; 384],
+                extension_name: "test_extension".to_string(),
+            }])
+            .await?;
+
+            let results = db.search_tools(vec![0.1; 384], 1, None).await?;
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].tool_name, "local_tool");
+            Ok(())
+        }
+        .await;
+
+        let _ = db.connection.read().await.drop_table(&db.table_name).await;
+        result
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_custom_db_path_override() -> Result<()> {
@@ -1,6 +1,6 @@
 use crate::session;
 use mcp_core::ToolResult;
-use rmcp::model::{Content, Tool};
+use rmcp::model::{Content, ServerNotification, Tool};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -95,3 +95,58 @@ pub struct SessionConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_config: Option<RetryConfig>,
 }
+
+/// Progress reported by an extension through `notifications/progress` while a tool runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolProgress {
+    /// The id of the tool request the progress belongs to
+    pub request_id: String,
+    pub progress: f64,
+    /// The amount of work in total, if the extension knows it
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+impl ToolProgress {
+    /// Read the progress out of a notification, if it is a progress notification
+    pub fn from_notification(request_id: &str, notification: &ServerNotification) -> Option<Self> {
+        let ServerNotification::ProgressNotification(notification) = notification else {
+            return None;
+        };
+        Some(Self {
+            request_id: request_id.to_string(),
+            progress: f64::from(notification.params.progress),
+            total: notification.params.total.map(f64::from),
+            message: notification.params.message.clone(),
+        })
+    }
+
+    /// How much of the work is done, from 0 to 100, when the total is known
+    pub fn percentage(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0.0)
+            .map(|total| (self.progress / total * 100.0).clamp(0.0, 100.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(progress: f64, total: Option<f64>) -> ToolProgress {
+        ToolProgress {
+            request_id: "req_1".to_string(),
+            progress,
+            total,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_tool_progress_percentage() {
+        assert_eq!(progress(5.0, Some(20.0)).percentage(), Some(25.0));
+        assert_eq!(progress(30.0, Some(20.0)).percentage(), Some(100.0));
+        assert_eq!(progress(5.0, Some(0.0)).percentage(), None);
+        assert_eq!(progress(5.0, None).percentage(), None);
+    }
+}
@@ -116,8 +116,9 @@ pub async fn check_compaction_needed(
 
 /// Perform compaction on messages
 ///
-/// This function performs the actual compaction using the agent's summarization
-/// capabilities. It assumes compaction is needed and should be called after
+/// This function performs the actual compaction in the agent's compaction mode,
+/// either a prose summary or a structured ledger with the most recent turns kept.
+/// It assumes compaction is needed and should be called after
 /// `check_compaction_needed` confirms it's necessary.
 ///
 /// # Arguments
@@ -142,7 +143,7 @@ pub async fn perform_compaction(
     info!("Performing compaction on {} tokens", tokens_before);
 
     // Perform compaction
-    let (compacted_messages, compacted_token_counts) = agent.summarize_context(messages).await?;
+    let (compacted_messages, compacted_token_counts) = agent.compact_context(messages).await?;
     let tokens_after: usize = compacted_token_counts.iter().sum();
 
     info!(
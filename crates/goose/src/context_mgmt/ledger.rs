@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use mcp_core::tool::ToolCall;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::message::{Message, MessageContent};
+
+/// Heading of the compacted message that carries the ledger, used to find it again
+/// when a conversation is compacted more than once
+pub const LEDGER_HEADING: &str = "## Compaction ledger";
+
+/// Start of the id of the messages compaction writes, the only ones a ledger is read back from
+pub const COMPACTION_MESSAGE_ID_PREFIX: &str = "compaction-";
+
+const MAX_FILES: usize = 50;
+const MAX_ERRORS: usize = 10;
+const MAX_DECISIONS: usize = 20;
+const MAX_DETAIL_CHARS: usize = 300;
+
+/// Facts about a conversation that compaction must not lose, kept in a form the model
+/// can read back exactly
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompactionLedger {
+    /// Files the tools touched, most recently touched last
+    #[serde(default)]
+    pub files: Vec<FileState>,
+    #[serde(default)]
+    pub open_tasks: Vec<String>,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    /// Tool calls that failed and have not since succeeded
+    #[serde(default)]
+    pub recent_errors: Vec<ToolFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileState {
+    pub path: String,
+    /// The tool command that last touched the file, such as `write` or `str_replace`
+    pub last_action: String,
+    /// What that command left behind, such as `written, 12 lines` or the error it hit
+    pub state: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolFailure {
+    pub tool: String,
+    /// The command or arguments the tool was called with
+    pub call: String,
+    pub error: String,
+}
+
+impl CompactionLedger {
+    /// Build the ledger for a conversation from its tool calls and checklists
+    ///
+    /// A ledger left by an earlier compaction is picked up and carried forward, so
+    /// nothing is lost when a conversation is compacted again. Only messages written by
+    /// compaction are read for one, so a ledger pasted by the user is never taken for it.
+    pub fn from_messages(messages: &[Message]) -> Self {
+        let mut ledger = Self::default();
+        let mut requests: HashMap<&str, &ToolCall> = HashMap::new();
+        // Checklist items in the order first seen, with whether they were checked off
+        let mut tasks: Vec<(String, bool)> = Vec::new();
+
+        for message in messages {
+            for content in &message.content {
+                match content {
+                    MessageContent::Text(text) if message.role == Role::User => {
+                        let previous = if is_compaction_message(message) {
+                            Self::parse(&text.text)
+                        } else {
+                            None
+                        };
+                        if let Some(previous) = previous {
+                            tasks = previous
+                                .open_tasks
+                                .iter()
+                                .map(|task| (task.clone(), false))
+                                .collect();
+                            ledger = previous;
+                        }
+                    }
+                    MessageContent::Text(text) => {
+                        for (task, done) in checklist_items(&text.text) {
+                            match tasks.iter_mut().find(|(known, _)| *known == task) {
+                                Some(entry) => entry.1 = done,
+                                None => tasks.push((task, done)),
+                            }
+                        }
+                    }
+                    MessageContent::ToolRequest(request) => {
+                        if let Ok(call) = &request.tool_call {
+                            requests.insert(request.id.as_str(), call);
+                        }
+                    }
+                    MessageContent::ToolResponse(response) => {
+                        if let Some(call) = requests.get(response.id.as_str()) {
+                            let result = response
+                                .tool_result
+                                .as_ref()
+                                .map(|_| ())
+                                .map_err(|e| e.to_string());
+                            ledger.record(call, result);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        ledger.open_tasks = tasks
+            .into_iter()
+            .filter(|(_, done)| !done)
+            .map(|(task, _)| task)
+            .collect();
+        ledger
+    }
+
+    /// Read back a ledger rendered by [`CompactionLedger::render`]
+    pub fn parse(text: &str) -> Option<Self> {
+        let (_, rest) = text.split_once(LEDGER_HEADING)?;
+        let (_, rest) = rest.split_once("```json")?;
+        let (json, _) = rest.split_once("```")?;
+        serde_json::from_str(json.trim()).ok()
+    }
+
+    /// The ledger as a markdown section holding a JSON block
+    pub fn render(&self) -> String {
+        let json = serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string());
+        format!(
+            "{}\nFiles touched, open tasks, key decisions and unresolved errors so far:\n```json\n{}\n```",
+            LEDGER_HEADING, json
+        )
+    }
+
+    /// Add decisions and open tasks the summarizer found, skipping ones already known
+    pub fn extend(&mut self, decisions: Vec<String>, open_tasks: Vec<String>) {
+        for decision in decisions {
+            if !self.decisions.contains(&decision) {
+                self.decisions.push(decision);
+            }
+        }
+        for task in open_tasks {
+            if !self.open_tasks.contains(&task) {
+                self.open_tasks.push(task);
+            }
+        }
+        truncate_front(&mut self.decisions, MAX_DECISIONS);
+    }
+
+    fn record(&mut self, call: &ToolCall, result: Result<(), String>) {
+        let description = describe_call(call);
+
+        if let Some(path) = call.arguments.get("path").and_then(Value::as_str) {
+            let last_action = call
+                .arguments
+                .get("command")
+                .and_then(Value::as_str)
+                .unwrap_or(&call.name)
+                .to_string();
+            let state = match &result {
+                Ok(()) => file_state(&last_action, &call.arguments),
+                Err(error) => format!("failed: {}", truncate(error)),
+            };
+            self.files.retain(|file| file.path != path);
+            self.files.push(FileState {
+                path: path.to_string(),
+                last_action,
+                state,
+            });
+            truncate_front(&mut self.files, MAX_FILES);
+        }
+
+        // A call that works after failing fixed the error, so stop reporting it
+        self.recent_errors
+            .retain(|failure| failure.tool != call.name || failure.call != description);
+        if let Err(error) = result {
+            self.recent_errors.push(ToolFailure {
+                tool: call.name.clone(),
+                call: description,
+                error: truncate(&error),
+            });
+            truncate_front(&mut self.recent_errors, MAX_ERRORS);
+        }
+    }
+}
+
+/// The argument that identifies a call, such as a shell command, or else all of them
+/// Whether compaction wrote the message, as opposed to the user or a tool
+pub fn is_compaction_message(message: &Message) -> bool {
+    message
+        .id
+        .as_deref()
+        .is_some_and(|id| id.starts_with(COMPACTION_MESSAGE_ID_PREFIX))
+}
+
+fn describe_call(call: &ToolCall) -> String {
+    let description = match call.arguments.get("command").and_then(Value::as_str) {
+        Some(command) => match call.arguments.get("path").and_then(Value::as_str) {
+            Some(path) => format!("{} {}", command, path),
+            None => command.to_string(),
+        },
+        None => call.arguments.to_string(),
+    };
+    truncate(&description)
+}
+
+fn file_state(action: &str, arguments: &Value) -> String {
+    match action {
+        "view" => "viewed".to_string(),
+        "write" => match arguments.get("file_text").and_then(Value::as_str) {
+            Some(text) => format!("written, {} lines", text.lines().count()),
+            None => "written".to_string(),
+        },
+        "str_replace" | "insert" => "edited".to_string(),
+        "undo_edit" => "last edit undone".to_string(),
+        _ => "ok".to_string(),
+    }
+}
+
+/// Markdown checklist items (`- [ ] task`, `- [x] task`) with whether they are checked
+fn checklist_items(text: &str) -> Vec<(String, bool)> {
+    text.lines()
+        .filter_map(|line| {
+            let item = line
+                .trim_start()
+                .strip_prefix("- [")
+                .or_else(|| line.trim_start().strip_prefix("* ["))?;
+            let (mark, task) = item.split_once(']')?;
+            let done = match mark {
+                " " => false,
+                "x" | "X" => true,
+                _ => return None,
+            };
+            let task = task.trim();
+            (!task.is_empty()).then(|| (task.to_string(), done))
+        })
+        .collect()
+}
+
+fn truncate(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_DETAIL_CHARS) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+fn truncate_front<T>(items: &mut Vec<T>, max: usize) {
+    if items.len() > max {
+        items.drain(..items.len() - max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::handler::ToolError;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    fn tool_turn(
+        id: &str,
+        name: &str,
+        arguments: Value,
+        result: Result<&str, &str>,
+    ) -> Vec<Message> {
+        let result = result
+            .map(|text| vec![Content::text(text)])
+            .map_err(|error| ToolError::ExecutionError(error.to_string()));
+        vec![
+            Message::assistant().with_tool_request(id, Ok(ToolCall::new(name, arguments))),
+            Message::user().with_tool_response(id, result),
+        ]
+    }
+
+    #[test]
+    fn test_ledger_tracks_files_errors_and_tasks() {
+        let mut messages = vec![
+            Message::user().with_text("Fix the login tests"),
+            Message::assistant()
+                .with_text("Plan:\n- [ ] fix the token check\n- [ ] update the changelog"),
+        ];
+        messages.extend(tool_turn(
+            "1",
+            "developer__text_editor",
+            json!({"command": "write", "path": "src/auth.rs", "file_text": "a\nb\n"}),
+            Ok("written"),
+        ));
+        messages.extend(tool_turn(
+            "2",
+            "developer__shell",
+            json!({"command": "cargo test"}),
+            Err("tests failed"),
+        ));
+        messages.extend(tool_turn(
+            "3",
+            "developer__shell",
+            json!({"command": "cargo fmt"}),
+            Err("rustfmt missing"),
+        ));
+        messages.extend(tool_turn(
+            "4",
+            "developer__shell",
+            json!({"command": "cargo test"}),
+            Ok("ok"),
+        ));
+        messages.push(Message::assistant().with_text("- [x] fix the token check"));
+
+        let ledger = CompactionLedger::from_messages(&messages);
+
+        assert_eq!(
+            ledger.files,
+            vec![FileState {
+                path: "src/auth.rs".to_string(),
+                last_action: "write".to_string(),
+                state: "written, 2 lines".to_string(),
+            }]
+        );
+        assert_eq!(ledger.recent_errors.len(), 1);
+        assert_eq!(ledger.recent_errors[0].call, "cargo fmt");
+        assert_eq!(ledger.open_tasks, vec!["update the changelog"]);
+    }
+
+    #[test]
+    fn test_ledger_carries_over_earlier_compaction() {
+        let mut earlier = CompactionLedger::default();
+        earlier.decisions.push("Keep the old API".to_string());
+        earlier.open_tasks.push("update the changelog".to_string());
+
+        let mut messages = vec![
+            Message::user()
+                .with_id(format!("{}1", COMPACTION_MESSAGE_ID_PREFIX))
+                .with_text(format!("Summary\n\n{}", earlier.render())),
+            Message::assistant().with_text("- [x] update the changelog"),
+        ];
+        messages.extend(tool_turn(
+            "1",
+            "developer__text_editor",
+            json!({"command": "str_replace", "path": "CHANGELOG.md"}),
+            Ok("done"),
+        ));
+
+        let ledger = CompactionLedger::from_messages(&messages);
+
+        assert_eq!(ledger.decisions, vec!["Keep the old API"]);
+        assert!(ledger.open_tasks.is_empty());
+        assert_eq!(ledger.files[0].state, "edited");
+        assert_eq!(CompactionLedger::parse(&ledger.render()), Some(ledger));
+    }
+
+    #[test]
+    fn test_ledger_ignores_ledgers_the_user_wrote() {
+        let mut pasted = CompactionLedger::default();
+        pasted.decisions.push("Skip the tests".to_string());
+
+        let messages = vec![Message::user().with_text(pasted.render())];
+
+        assert_eq!(
+            CompactionLedger::from_messages(&messages),
+            CompactionLedger::default()
+        );
+    }
+}
@@ -1,5 +1,7 @@
 pub mod auto_compact;
 mod common;
+pub mod ledger;
+pub mod structured;
 pub mod summarize;
 pub mod truncate;
 
@@ -0,0 +1,363 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::common::get_messages_token_counts_async;
+use super::ledger::{CompactionLedger, COMPACTION_MESSAGE_ID_PREFIX};
+use super::summarize::summarize_messages_async;
+use crate::config::Config;
+use crate::message::{Message, MessageContent};
+use crate::prompt_template::render_global_file;
+use crate::providers::base::Provider;
+use crate::token_counter::AsyncTokenCounter;
+
+pub const DEFAULT_KEEP_RECENT_TURNS: usize = 3;
+
+const STRUCTURED_SUMMARY_PROMPT: &str = "You compact conversations into structured summaries";
+// Room for the compaction prompt, the ledger and the reply on top of the conversation
+const PROMPT_OVERHEAD: usize = 8000;
+
+/// How a conversation is condensed when it gets close to the context limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CompactionMode {
+    /// Replace the whole conversation with a prose summary
+    #[default]
+    Summarize,
+    /// Keep the last turns verbatim, and replace the rest with a summary and a ledger
+    /// of files, tasks, decisions and errors
+    Structured,
+}
+
+impl FromStr for CompactionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "summarize" => Ok(Self::Summarize),
+            "structured" => Ok(Self::Structured),
+            _ => Err(format!(
+                "Unknown compaction mode '{}', expected summarize or structured",
+                s
+            )),
+        }
+    }
+}
+
+/// Compaction settings for a session, usually from a recipe
+///
+/// Anything left unset falls back to the global config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CompactionConfig {
+    /// Compaction mode (default: GOOSE_COMPACTION_MODE, or summarize)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<CompactionMode>,
+    /// Turns kept verbatim in structured mode (default: GOOSE_COMPACTION_KEEP_TURNS, or 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_recent_turns: Option<usize>,
+    /// Share of the context window that triggers auto-compaction (default: GOOSE_AUTO_COMPACT_THRESHOLD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+}
+
+impl CompactionConfig {
+    pub fn mode(&self) -> CompactionMode {
+        self.mode.unwrap_or_else(|| {
+            Config::global()
+                .get_param::<String>("GOOSE_COMPACTION_MODE")
+                .ok()
+                .and_then(|mode| mode.parse().ok())
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn keep_recent_turns(&self) -> usize {
+        self.keep_recent_turns.unwrap_or_else(|| {
+            Config::global()
+                .get_param::<usize>("GOOSE_COMPACTION_KEEP_TURNS")
+                .unwrap_or(DEFAULT_KEEP_RECENT_TURNS)
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct StructuredSummary {
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    decisions: Vec<String>,
+    #[serde(default)]
+    open_tasks: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct StructuredSummaryContext {
+    messages: String,
+    ledger: String,
+}
+
+/// Compact a conversation into a summary and ledger of everything but its last turns
+///
+/// The ledger of files touched, open tasks and tool errors is taken from the tool calls
+/// themselves, so it stays exact. The provider only summarizes the older turns and lists
+/// the decisions made in them. The last `keep_recent_turns` turns are kept verbatim,
+/// starting from a user message so no tool request is separated from its response.
+pub async fn compact_messages_structured(
+    provider: Arc<dyn Provider>,
+    messages: &[Message],
+    token_counter: &AsyncTokenCounter,
+    context_limit: usize,
+    keep_recent_turns: usize,
+) -> Result<(Vec<Message>, Vec<usize>), anyhow::Error> {
+    if messages.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+
+    let token_counts = get_messages_token_counts_async(token_counter, messages);
+    let split = recent_turns_start(
+        messages,
+        &token_counts,
+        keep_recent_turns,
+        context_limit / 2,
+    );
+    let (older, recent) = messages.split_at(split);
+
+    let mut ledger = CompactionLedger::from_messages(messages);
+    let older_tokens: usize = token_counts[..split].iter().sum();
+    let summary = summarize_structured(
+        provider,
+        older,
+        older_tokens,
+        &ledger,
+        token_counter,
+        context_limit,
+    )
+    .await?;
+    ledger.extend(summary.decisions, summary.open_tasks);
+
+    let compacted = Message::user()
+        .with_id(format!(
+            "{}{}",
+            COMPACTION_MESSAGE_ID_PREFIX,
+            uuid::Uuid::new_v4()
+        ))
+        .with_text(format!(
+            "Our earlier conversation was compacted. Summary:\n\n{}\n\n{}",
+            summary.summary.trim(),
+            ledger.render()
+        ));
+    let acknowledgement = if recent.is_empty() {
+        "I compacted our earlier conversation into the summary and ledger above.".to_string()
+    } else {
+        format!(
+            "I compacted our earlier conversation into the summary and ledger above. The last {} turns follow as they were.",
+            count_turns(recent)
+        )
+    };
+
+    let mut new_messages = vec![compacted, Message::assistant().with_text(acknowledgement)];
+    new_messages.extend(recent.iter().cloned());
+    let new_token_counts = get_messages_token_counts_async(token_counter, &new_messages);
+
+    Ok((new_messages, new_token_counts))
+}
+
+async fn summarize_structured(
+    provider: Arc<dyn Provider>,
+    older: &[Message],
+    older_tokens: usize,
+    ledger: &CompactionLedger,
+    token_counter: &AsyncTokenCounter,
+    context_limit: usize,
+) -> Result<StructuredSummary, anyhow::Error> {
+    if older.is_empty() {
+        return Ok(StructuredSummary::default());
+    }
+
+    // Conversations too long to send at once are condensed in chunks first
+    let messages = if older_tokens + PROMPT_OVERHEAD <= context_limit {
+        older.to_vec()
+    } else {
+        summarize_messages_async(Arc::clone(&provider), older, token_counter, context_limit)
+            .await?
+            .0
+    };
+    let context = StructuredSummaryContext {
+        messages: messages
+            .iter()
+            .map(|msg| format!("{:?}", msg))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        ledger: serde_json::to_string_pretty(ledger)?,
+    };
+    let prompt = render_global_file("summarize_structured.md", &context)?;
+    let request = vec![Message::user().with_text(prompt)];
+
+    let (response, _) = provider
+        .complete(STRUCTURED_SUMMARY_PROMPT, &request, &[])
+        .await?;
+    Ok(parse_structured_summary(&response.as_concat_text()))
+}
+
+/// Read the summarizer's JSON reply, keeping the whole reply as the summary if it isn't JSON
+fn parse_structured_summary(text: &str) -> StructuredSummary {
+    let json = match (text.find('{'), text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => text,
+    };
+    serde_json::from_str(json).unwrap_or_else(|_| StructuredSummary {
+        summary: text.to_string(),
+        ..Default::default()
+    })
+}
+
+/// A turn starts with a user message the user typed, rather than one carrying tool results
+fn is_turn_start(message: &Message) -> bool {
+    message.role == Role::User
+        && message
+            .content
+            .iter()
+            .any(|content| matches!(content, MessageContent::Text(_)))
+        && !message.is_tool_response()
+}
+
+fn count_turns(messages: &[Message]) -> usize {
+    messages.iter().filter(|msg| is_turn_start(msg)).count()
+}
+
+/// Index where the kept turns start, or the end of the conversation if none can be kept
+///
+/// Fewer turns are kept when the requested ones would leave nothing to compact, or would
+/// take up more than `max_tokens` on their own.
+fn recent_turns_start(
+    messages: &[Message],
+    token_counts: &[usize],
+    keep_recent_turns: usize,
+    max_tokens: usize,
+) -> usize {
+    let starts: Vec<usize> = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, msg)| is_turn_start(msg))
+        .map(|(index, _)| index)
+        .collect();
+
+    (1..=keep_recent_turns.min(starts.len()))
+        .rev()
+        .map(|turns| starts[starts.len() - turns])
+        .find(|&start| start > 0 && token_counts[start..].iter().sum::<usize>() <= max_tokens)
+        .unwrap_or(messages.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_mgmt::ledger::is_compaction_message;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use crate::providers::errors::ProviderError;
+    use crate::token_counter::create_async_token_counter;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::{Content, Tool};
+    use serde_json::json;
+
+    struct MockProvider {
+        model_config: ModelConfig,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text(
+                    r#"```json
+{"summary": "Fixed the login tests.", "decisions": ["Use JWT"], "open_tasks": []}
+```"#,
+                ),
+                ProviderUsage::new("mock".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    fn turn(n: usize) -> Vec<Message> {
+        let id = n.to_string();
+        vec![
+            Message::user().with_text(format!("Request {}", n)),
+            Message::assistant().with_tool_request(
+                &id,
+                Ok(ToolCall::new(
+                    "developer__text_editor",
+                    json!({"command": "view", "path": format!("src/{}.rs", n)}),
+                )),
+            ),
+            Message::user().with_tool_response(&id, Ok(vec![Content::text("fn main() {}")])),
+            Message::assistant().with_text(format!("Done {}", n)),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_structured_compaction_keeps_recent_turns() {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider {
+            model_config: ModelConfig::new("test-model").unwrap(),
+        });
+        let token_counter = create_async_token_counter().await.unwrap();
+        let messages: Vec<Message> = (1..=4).flat_map(turn).collect();
+
+        let (compacted, token_counts) =
+            compact_messages_structured(provider, &messages, &token_counter, 100_000, 2)
+                .await
+                .unwrap();
+
+        assert_eq!(compacted.len(), 2 + 8);
+        assert_eq!(token_counts.len(), compacted.len());
+        assert_eq!(compacted[2..], messages[8..]);
+
+        assert!(is_compaction_message(&compacted[0]));
+        let text = compacted[0].as_concat_text();
+        assert!(text.contains("Fixed the login tests."));
+        let ledger = CompactionLedger::parse(&text).unwrap();
+        assert_eq!(ledger.files.len(), 4);
+        assert_eq!(ledger.files[3].path, "src/4.rs");
+        assert_eq!(ledger.decisions, vec!["Use JWT"]);
+    }
+
+    #[test]
+    fn test_recent_turns_start() {
+        let messages: Vec<Message> = (1..=3).flat_map(turn).collect();
+        let counts = vec![10; messages.len()];
+
+        assert_eq!(recent_turns_start(&messages, &counts, 2, 1000), 4);
+        // Keeping every turn would leave nothing to compact
+        assert_eq!(recent_turns_start(&messages, &counts, 5, 1000), 4);
+        // Two turns don't fit, but one does
+        assert_eq!(recent_turns_start(&messages, &counts, 2, 50), 8);
+        assert_eq!(
+            recent_turns_start(&messages, &counts, 0, 1000),
+            messages.len()
+        );
+    }
+
+    #[test]
+    fn test_parse_structured_summary_falls_back_to_prose() {
+        let summary = parse_structured_summary("We fixed the tests.");
+        assert_eq!(summary.summary, "We fixed the tests.");
+        assert!(summary.decisions.is_empty());
+    }
+}
@@ -500,9 +500,10 @@ mod tests {
 
             // Fail if this looks like a one-shot request
             if system.contains("reasoning in `<analysis>` tags") {
-                return Err(ProviderError::RateLimitExceeded(
-                    "Simulated one-shot failure".to_string(),
-                ));
+                return Err(ProviderError::RateLimitExceeded {
+                    details: "Simulated one-shot failure".to_string(),
+                    retry_delay: None,
+                });
             }
 
             // Succeed for chunked requests (uses the old SUMMARY_PROMPT)
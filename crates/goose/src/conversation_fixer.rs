@@ -67,6 +67,13 @@ impl ConversationFixer {
                                     req.id
                                 ));
                             }
+                            MessageContent::ElicitationRequest(req) => {
+                                content_to_remove.push(idx);
+                                issues.push(format!(
+                                    "Removed elicitation request '{}' from user message",
+                                    req.id
+                                ));
+                            }
                             MessageContent::Thinking(_) | MessageContent::RedactedThinking(_) => {
                                 content_to_remove.push(idx);
                                 issues
@@ -66,6 +66,47 @@ pub struct ToolConfirmationRequest {
     pub tool_name: String,
     pub arguments: Value,
     pub prompt: Option<String>,
+    #[serde(default)]
+    pub kind: ConfirmationKind,
+}
+
+/// What a confirmation request asks the user to allow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfirmationKind {
+    /// A call to the tool named in the request
+    #[default]
+    ToolCall,
+    /// An extension asking to use the model through MCP sampling
+    Sampling,
+}
+
+/// The question asked when a tool call needs the user's approval
+pub const TOOL_CONFIRMATION_PROMPT: &str = "Goose would like to call the above tool. Allow? (y/n):";
+
+impl ToolConfirmationRequest {
+    /// Why the tool call needs approval, when a tool policy rule gave a reason
+    ///
+    /// The agent puts the reason before the question in the prompt.
+    pub fn reason(&self) -> Option<&str> {
+        self.prompt
+            .as_deref()?
+            .strip_suffix(TOOL_CONFIRMATION_PROMPT)
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+    }
+}
+
+/// An extension asking the user to fill in a form while one of its tools runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitationRequest {
+    pub id: String,
+    pub extension_name: String,
+    pub message: String,
+    /// A flat JSON object schema of the fields to ask for
+    #[schema(value_type = Object)]
+    pub requested_schema: Value,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -107,6 +148,7 @@ pub enum MessageContent {
     ToolRequest(ToolRequest),
     ToolResponse(ToolResponse),
     ToolConfirmationRequest(ToolConfirmationRequest),
+    ElicitationRequest(ElicitationRequest),
     FrontendToolRequest(FrontendToolRequest),
     Thinking(ThinkingContent),
     RedactedThinking(RedactedThinkingContent),
@@ -133,6 +175,9 @@ impl fmt::Display for MessageContent {
             MessageContent::ToolConfirmationRequest(r) => {
                 write!(f, "[ToolConfirmationRequest: {}]", r.tool_name)
             }
+            MessageContent::ElicitationRequest(r) => {
+                write!(f, "[ElicitationRequest: {}]", r.extension_name)
+            }
             MessageContent::FrontendToolRequest(r) => match &r.tool_call {
                 Ok(tool_call) => write!(f, "[FrontendToolRequest: {}]", tool_call.name),
                 Err(e) => write!(f, "[FrontendToolRequest: Error: {}]", e),
@@ -189,6 +234,37 @@ impl MessageContent {
             tool_name,
             arguments,
             prompt,
+            kind: ConfirmationKind::ToolCall,
+        })
+    }
+
+    /// A request to allow an extension's sampling request, confirmed under `principal`
+    pub fn sampling_confirmation_request<S: Into<String>>(
+        id: S,
+        principal: String,
+        arguments: Value,
+        prompt: Option<String>,
+    ) -> Self {
+        MessageContent::ToolConfirmationRequest(ToolConfirmationRequest {
+            id: id.into(),
+            tool_name: principal,
+            arguments,
+            prompt,
+            kind: ConfirmationKind::Sampling,
+        })
+    }
+
+    pub fn elicitation_request<S: Into<String>>(
+        id: S,
+        extension_name: String,
+        message: String,
+        requested_schema: Value,
+    ) -> Self {
+        MessageContent::ElicitationRequest(ElicitationRequest {
+            id: id.into(),
+            extension_name,
+            message,
+            requested_schema,
         })
     }
 
@@ -251,6 +327,14 @@ impl MessageContent {
         }
     }
 
+    pub fn as_elicitation_request(&self) -> Option<&ElicitationRequest> {
+        if let MessageContent::ElicitationRequest(ref elicitation_request) = self {
+            Some(elicitation_request)
+        } else {
+            None
+        }
+    }
+
     pub fn as_tool_response_text(&self) -> Option<String> {
         if let Some(tool_response) = self.as_tool_response() {
             if let Ok(contents) = &tool_response.tool_result {
@@ -480,6 +564,35 @@ impl Message {
         ))
     }
 
+    /// Add a request to allow an extension's sampling request
+    pub fn with_sampling_confirmation_request<S: Into<String>>(
+        self,
+        id: S,
+        principal: String,
+        arguments: Value,
+        prompt: Option<String>,
+    ) -> Self {
+        self.with_content(MessageContent::sampling_confirmation_request(
+            id, principal, arguments, prompt,
+        ))
+    }
+
+    /// Add a request for the user to fill in an extension's form
+    pub fn with_elicitation_request<S: Into<String>>(
+        self,
+        id: S,
+        extension_name: String,
+        message: String,
+        requested_schema: Value,
+    ) -> Self {
+        self.with_content(MessageContent::elicitation_request(
+            id,
+            extension_name,
+            message,
+            requested_schema,
+        ))
+    }
+
     pub fn with_frontend_tool_request<S: Into<String>>(
         self,
         id: S,
@@ -1,7 +1,9 @@
 pub mod permission_confirmation;
 pub mod permission_judge;
 pub mod permission_store;
+pub mod policy;
 
 pub use permission_confirmation::{Permission, PermissionConfirmation};
 pub use permission_judge::detect_read_only_tools;
 pub use permission_store::ToolPermissionStore;
+pub use policy::{PolicyAction, PolicyCache, PolicyDecision, PolicyExplanation, ToolPolicy};
@@ -2,6 +2,7 @@ use crate::agents::platform_tools::PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME;
 use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
 use crate::message::{Message, MessageContent, ToolRequest};
+use crate::permission::policy::ToolPolicy;
 use crate::providers::base::Provider;
 use chrono::Utc;
 use indoc::indoc;
@@ -9,7 +10,7 @@ use rmcp::model::{Tool, ToolAnnotations};
 use rmcp::object;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Creates the tool definition for checking read-only permissions.
@@ -154,6 +155,9 @@ pub struct PermissionCheckResult {
     pub approved: Vec<ToolRequest>,
     pub needs_approval: Vec<ToolRequest>,
     pub denied: Vec<ToolRequest>,
+    /// Why a policy rule asked about or denied a request, by request id
+    #[serde(default)]
+    pub reasons: HashMap<String, String>,
 }
 
 pub async fn check_tool_permissions(
@@ -162,6 +166,7 @@ pub async fn check_tool_permissions(
     tools_with_readonly_annotation: HashSet<String>,
     tools_without_annotation: HashSet<String>,
     permission_manager: &mut PermissionManager,
+    policy: Option<&ToolPolicy>,
     provider: Arc<dyn Provider>,
 ) -> (PermissionCheckResult, Vec<String>) {
     let mut approved = vec![];
@@ -169,18 +174,42 @@ pub async fn check_tool_permissions(
     let mut denied = vec![];
     let mut llm_detect_candidates = vec![];
     let mut extension_request_ids = vec![];
+    let mut reasons = HashMap::new();
 
     for request in candidate_requests {
         if let Ok(tool_call) = request.tool_call.clone() {
             if mode == "chat" {
                 continue;
-            } else if mode == "auto" {
-                approved.push(request.clone());
-            } else {
-                if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
-                    extension_request_ids.push(request.id.clone());
+            }
+
+            if mode != "auto" && tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
+                extension_request_ids.push(request.id.clone());
+            }
+
+            // 0. Policy rules match on arguments, so they take precedence in every mode
+            if let Some(decision) =
+                policy.and_then(|policy| policy.evaluate(&tool_call.name, &tool_call.arguments))
+            {
+                tracing::info!(
+                    "Tool policy rule '{}' decided {} for {}",
+                    decision.rule,
+                    decision.action,
+                    tool_call.name
+                );
+                if let Some(reason) = decision.reason {
+                    reasons.insert(request.id.clone(), reason);
                 }
+                match PermissionLevel::from(decision.action) {
+                    PermissionLevel::AlwaysAllow => approved.push(request.clone()),
+                    PermissionLevel::AskBefore => needs_approval.push(request.clone()),
+                    PermissionLevel::NeverAllow => denied.push(request.clone()),
+                }
+                continue;
+            }
 
+            if mode == "auto" {
+                approved.push(request.clone());
+            } else {
                 // 1. Check user-defined permission
                 if let Some(level) = permission_manager.get_user_permission(&tool_call.name) {
                     match level {
@@ -252,6 +281,7 @@ pub async fn check_tool_permissions(
             approved,
             needs_approval,
             denied,
+            reasons,
         },
         extension_request_ids,
     )
@@ -446,6 +476,7 @@ mod tests {
             tools_with_readonly_annotation,
             tools_without_annotation,
             &mut permission_manager,
+            None,
             provider,
         )
         .await;
@@ -505,6 +536,7 @@ mod tests {
             tools_with_readonly_annotation,
             tools_without_annotation,
             &mut permission_manager,
+            None,
             provider,
         )
         .await;
@@ -514,4 +546,51 @@ mod tests {
         assert_eq!(result.needs_approval.len(), 0); // data_fetcher should need approval
         assert_eq!(result.denied.len(), 0); // No tool should be denied in this test
     }
+
+    #[tokio::test]
+    async fn test_check_tool_permissions_policy_takes_precedence() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut permission_manager = PermissionManager::new(temp_file.path());
+        let provider = create_mock_provider();
+
+        // The user always allows the shell, but the policy still stops rm -rf
+        permission_manager.update_user_permission("developer__shell", PermissionLevel::AlwaysAllow);
+        let policy = ToolPolicy::parse(
+            "version: 1\nrules:\n  - tool: shell\n    args:\n      command: 'rm\\s+-rf'\n    action: deny\n    reason: Too destructive\n",
+            std::path::Path::new("policy.yaml"),
+            std::path::Path::new("/"),
+        )
+        .unwrap();
+
+        let shell = |id: &str, command: &str| ToolRequest {
+            id: id.to_string(),
+            tool_call: ToolResult::Ok(ToolCall {
+                name: "developer__shell".to_string(),
+                arguments: json!({ "command": command }),
+            }),
+        };
+        let candidate_requests = vec![shell("tool_1", "ls"), shell("tool_2", "rm -rf /")];
+
+        for mode in ["auto", "approve", "smart_approve"] {
+            let (result, _) = check_tool_permissions(
+                &candidate_requests,
+                mode,
+                HashSet::new(),
+                HashSet::new(),
+                &mut permission_manager,
+                Some(&policy),
+                provider.clone(),
+            )
+            .await;
+
+            assert_eq!(result.approved.len(), 1, "mode {}", mode);
+            assert_eq!(result.approved[0].id, "tool_1");
+            assert_eq!(result.denied.len(), 1, "mode {}", mode);
+            assert_eq!(result.denied[0].id, "tool_2");
+            assert_eq!(
+                result.reasons.get("tool_2").map(String::as_str),
+                Some("Too destructive")
+            );
+        }
+    }
 }
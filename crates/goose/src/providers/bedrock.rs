@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::time::Duration;
 
 use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage};
 use super::errors::ProviderError;
@@ -14,11 +13,11 @@ use aws_sdk_bedrockruntime::operation::converse::ConverseError;
 use aws_sdk_bedrockruntime::{types as bedrock, Client};
 use rmcp::model::Tool;
 use serde_json::Value;
-use tokio::time::sleep;
 
 // Import the migrated helper functions from providers/formats/bedrock.rs
 use super::formats::bedrock::{
-    from_bedrock_message, from_bedrock_usage, to_bedrock_message, to_bedrock_tool_config,
+    from_bedrock_message, from_bedrock_usage, to_bedrock_messages, to_bedrock_system,
+    to_bedrock_tool_config,
 };
 
 pub const BEDROCK_DOC_LINK: &str =
@@ -30,6 +29,15 @@ pub const BEDROCK_KNOWN_MODELS: &[&str] = &[
     "anthropic.claude-3-5-sonnet-20241022-v2:0",
 ];
 
+// Models that accept cache points, matched anywhere in the model id so cross-region
+// inference profiles such as us.anthropic.claude-sonnet-4-20250514-v1:0 match too
+const BEDROCK_CACHE_MODELS: &[&str] = &[
+    "claude-3-5-haiku",
+    "claude-3-7-sonnet",
+    "claude-sonnet-4",
+    "claude-opus-4",
+];
+
 #[derive(Debug, serde::Serialize)]
 pub struct BedrockProvider {
     #[serde(skip)]
@@ -90,144 +98,104 @@ impl Provider for BedrockProvider {
         self.model.clone()
     }
 
+    fn supports_cache_control(&self) -> bool {
+        BEDROCK_CACHE_MODELS
+            .iter()
+            .any(|model| self.model.model_name.contains(model))
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_with_model(&self.model, system, messages, tools)
+            .await
+    }
+
     #[tracing::instrument(
-        skip(self, system, messages, tools),
+        skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
     )]
-    async fn complete(
+    async fn complete_with_model(
         &self,
+        model_config: &ModelConfig,
         system: &str,
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let model_name = &self.model.model_name;
+        let model_name = &model_config.model_name;
+        let cache = self.supports_cache_control();
 
         let mut request = self
             .client
             .converse()
-            .system(bedrock::SystemContentBlock::Text(system.to_string()))
+            .set_system(Some(to_bedrock_system(system, cache)?))
             .model_id(model_name.to_string())
-            .set_messages(Some(
-                messages
-                    .iter()
-                    .map(to_bedrock_message)
-                    .collect::<Result<_>>()?,
-            ));
+            .set_messages(Some(to_bedrock_messages(messages, cache)?));
 
         if !tools.is_empty() {
-            request = request.tool_config(to_bedrock_tool_config(tools)?);
+            request = request.tool_config(to_bedrock_tool_config(tools, cache)?);
         }
 
-        // Retry configuration
-        const MAX_RETRIES: u32 = 10;
-        const INITIAL_BACKOFF_MS: u64 = 20_000; // 20 seconds
-        const MAX_BACKOFF_MS: u64 = 120_000; // 120 seconds (2 minutes)
-
-        let mut attempts = 0;
-        let mut backoff_ms = INITIAL_BACKOFF_MS;
-
-        loop {
-            attempts += 1;
-
-            match request.clone().send().await {
-                Ok(response) => {
-                    // Successful response, process it and return
-                    return match response.output {
-                        Some(bedrock::ConverseOutput::Message(message)) => {
-                            let usage = response
-                                .usage
-                                .as_ref()
-                                .map(from_bedrock_usage)
-                                .unwrap_or_default();
-
-                            let message = from_bedrock_message(&message)?;
-
-                            // Add debug trace with input context
-                            let debug_payload = serde_json::json!({
-                                "system": system,
-                                "messages": messages,
-                                "tools": tools
-                            });
-                            emit_debug_trace(
-                                &self.model,
-                                &debug_payload,
-                                &serde_json::to_value(&message).unwrap_or_default(),
-                                &usage,
-                            );
-
-                            let provider_usage = ProviderUsage::new(model_name.to_string(), usage);
-                            Ok((message, provider_usage))
-                        }
-                        _ => Err(ProviderError::RequestFailed(
-                            "No output from Bedrock".to_string(),
-                        )),
-                    };
+        let response = request
+            .send()
+            .await
+            .map_err(|err| match err.into_service_error() {
+                ConverseError::ThrottlingException(err) => ProviderError::RateLimitExceeded {
+                    details: format!("Failed to call Bedrock: {:?}", err),
+                    retry_delay: None,
+                },
+                ConverseError::AccessDeniedException(err) => {
+                    ProviderError::Authentication(format!("Failed to call Bedrock: {:?}", err))
+                }
+                ConverseError::ValidationException(err)
+                    if err
+                        .message()
+                        .unwrap_or_default()
+                        .contains("Input is too long for requested model.") =>
+                {
+                    ProviderError::ContextLengthExceeded(format!(
+                        "Failed to call Bedrock: {:?}",
+                        err
+                    ))
                 }
-                Err(err) => {
-                    match err.into_service_error() {
-                        ConverseError::ThrottlingException(throttle_err) => {
-                            if attempts > MAX_RETRIES {
-                                // We've exhausted our retries
-                                tracing::error!(
-                                    "Failed after {MAX_RETRIES} retries: {:?}",
-                                    throttle_err
-                                );
-                                return Err(ProviderError::RateLimitExceeded(format!(
-                                    "Failed to call Bedrock after {MAX_RETRIES} retries: {:?}",
-                                    throttle_err
-                                )));
-                            }
-
-                            // Log retry attempt
-                            tracing::warn!(
-                                "Bedrock throttling error (attempt {}/{}), retrying in {} ms: {:?}",
-                                attempts,
-                                MAX_RETRIES,
-                                backoff_ms,
-                                throttle_err
-                            );
-
-                            // Wait before retry with exponential backoff
-                            sleep(Duration::from_millis(backoff_ms)).await;
-
-                            // Calculate next backoff with exponential growth, capped at max
-                            backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
-
-                            // Continue to the next retry attempt
-                            continue;
-                        }
-                        ConverseError::AccessDeniedException(err) => {
-                            return Err(ProviderError::Authentication(format!(
-                                "Failed to call Bedrock: {:?}",
-                                err
-                            )));
-                        }
-                        ConverseError::ValidationException(err)
-                            if err
-                                .message()
-                                .unwrap_or_default()
-                                .contains("Input is too long for requested model.") =>
-                        {
-                            return Err(ProviderError::ContextLengthExceeded(format!(
-                                "Failed to call Bedrock: {:?}",
-                                err
-                            )));
-                        }
-                        ConverseError::ModelErrorException(err) => {
-                            return Err(ProviderError::ExecutionError(format!(
-                                "Failed to call Bedrock: {:?}",
-                                err
-                            )));
-                        }
-                        err => {
-                            return Err(ProviderError::ServerError(format!(
-                                "Failed to call Bedrock: {:?}",
-                                err
-                            )));
-                        }
-                    }
+                ConverseError::ModelErrorException(err) => {
+                    ProviderError::ExecutionError(format!("Failed to call Bedrock: {:?}", err))
                 }
+                err => ProviderError::ServerError(format!("Failed to call Bedrock: {:?}", err)),
+            })?;
+
+        match response.output {
+            Some(bedrock::ConverseOutput::Message(message)) => {
+                let usage = response
+                    .usage
+                    .as_ref()
+                    .map(from_bedrock_usage)
+                    .unwrap_or_default();
+
+                let message = from_bedrock_message(&message)?;
+
+                // Add debug trace with input context
+                let debug_payload = serde_json::json!({
+                    "system": system,
+                    "messages": messages,
+                    "tools": tools
+                });
+                emit_debug_trace(
+                    model_config,
+                    &debug_payload,
+                    &serde_json::to_value(&message).unwrap_or_default(),
+                    &usage,
+                );
+
+                let provider_usage = ProviderUsage::new(model_name.to_string(), usage);
+                Ok((message, provider_usage))
             }
+            _ => Err(ProviderError::RequestFailed(
+                "No output from Bedrock".to_string(),
+            )),
         }
     }
 }
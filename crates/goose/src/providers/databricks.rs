@@ -15,15 +15,16 @@ use super::embedding::EmbeddingCapable;
 use super::errors::ProviderError;
 use super::formats::databricks::{create_request, response_to_message};
 use super::oauth;
-use super::utils::{get_model, ImageFormat};
+use super::utils::{get_model, get_retry_after, ImageFormat};
 use crate::config::ConfigError;
 use crate::impl_provider_default;
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::formats::openai::{get_usage, response_to_streaming_message};
+use crate::providers::formats::openai::{
+    get_usage, response_to_streaming_message, update_request_for_cache_control,
+};
 use rmcp::model::Tool;
 use serde_json::json;
-use tokio::time::sleep;
 use tokio_stream::StreamExt;
 use tokio_util::codec::{FramedRead, LinesCodec};
 use url::Url;
@@ -36,14 +37,6 @@ const DEFAULT_SCOPES: &[&str] = &["all-apis", "offline_access"];
 
 /// Default timeout for API requests in seconds
 const DEFAULT_TIMEOUT_SECS: u64 = 600;
-/// Default initial interval for retry (in milliseconds)
-const DEFAULT_INITIAL_RETRY_INTERVAL_MS: u64 = 5000;
-/// Default maximum number of retries
-const DEFAULT_MAX_RETRIES: usize = 6;
-/// Default retry backoff multiplier
-const DEFAULT_BACKOFF_MULTIPLIER: f64 = 2.0;
-/// Default maximum interval for retry (in milliseconds)
-const DEFAULT_MAX_RETRY_INTERVAL_MS: u64 = 320_000;
 
 pub const DATABRICKS_DEFAULT_MODEL: &str = "databricks-claude-3-7-sonnet";
 // Databricks can passthrough to a wide range of models, we only provide the default
@@ -57,53 +50,6 @@ pub const DATABRICKS_KNOWN_MODELS: &[&str] = &[
 pub const DATABRICKS_DOC_URL: &str =
     "https://docs.databricks.com/en/generative-ai/external-models/index.html";
 
-/// Retry configuration for handling rate limit errors
-#[derive(Debug, Clone)]
-struct RetryConfig {
-    /// Maximum number of retry attempts
-    max_retries: usize,
-    /// Initial interval between retries in milliseconds
-    initial_interval_ms: u64,
-    /// Multiplier for backoff (exponential)
-    backoff_multiplier: f64,
-    /// Maximum interval between retries in milliseconds
-    max_interval_ms: u64,
-}
-
-impl Default for RetryConfig {
-    fn default() -> Self {
-        Self {
-            max_retries: DEFAULT_MAX_RETRIES,
-            initial_interval_ms: DEFAULT_INITIAL_RETRY_INTERVAL_MS,
-            backoff_multiplier: DEFAULT_BACKOFF_MULTIPLIER,
-            max_interval_ms: DEFAULT_MAX_RETRY_INTERVAL_MS,
-        }
-    }
-}
-
-impl RetryConfig {
-    /// Calculate the delay for a specific retry attempt (with jitter)
-    fn delay_for_attempt(&self, attempt: usize) -> Duration {
-        if attempt == 0 {
-            return Duration::from_millis(0);
-        }
-
-        // Calculate exponential backoff
-        let exponent = (attempt - 1) as u32;
-        let base_delay_ms = (self.initial_interval_ms as f64
-            * self.backoff_multiplier.powi(exponent as i32)) as u64;
-
-        // Apply max limit
-        let capped_delay_ms = std::cmp::min(base_delay_ms, self.max_interval_ms);
-
-        // Add jitter (+/-20% randomness) to avoid thundering herd problem
-        let jitter_factor = 0.8 + (rand::random::<f64>() * 0.4); // Between 0.8 and 1.2
-        let jittered_delay_ms = (capped_delay_ms as f64 * jitter_factor) as u64;
-
-        Duration::from_millis(jittered_delay_ms)
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DatabricksAuth {
     Token(String),
@@ -138,8 +84,6 @@ pub struct DatabricksProvider {
     auth: DatabricksAuth,
     model: ModelConfig,
     image_format: ImageFormat,
-    #[serde(skip)]
-    retry_config: RetryConfig,
 }
 
 impl_provider_default!(DatabricksProvider);
@@ -168,9 +112,6 @@ impl DatabricksProvider {
             .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()?;
 
-        // Load optional retry configuration from environment
-        let retry_config = Self::load_retry_config(config);
-
         // If we find a databricks token we prefer that
         if let Ok(api_key) = config.get_secret("DATABRICKS_TOKEN") {
             return Ok(Self {
@@ -179,7 +120,6 @@ impl DatabricksProvider {
                 auth: DatabricksAuth::token(api_key),
                 model,
                 image_format: ImageFormat::OpenAi,
-                retry_config,
             });
         }
 
@@ -190,44 +130,9 @@ impl DatabricksProvider {
             host,
             model,
             image_format: ImageFormat::OpenAi,
-            retry_config,
         })
     }
 
-    /// Loads retry configuration from environment variables or uses defaults.
-    fn load_retry_config(config: &crate::config::Config) -> RetryConfig {
-        let max_retries = config
-            .get_param("DATABRICKS_MAX_RETRIES")
-            .ok()
-            .and_then(|v: String| v.parse::<usize>().ok())
-            .unwrap_or(DEFAULT_MAX_RETRIES);
-
-        let initial_interval_ms = config
-            .get_param("DATABRICKS_INITIAL_RETRY_INTERVAL_MS")
-            .ok()
-            .and_then(|v: String| v.parse::<u64>().ok())
-            .unwrap_or(DEFAULT_INITIAL_RETRY_INTERVAL_MS);
-
-        let backoff_multiplier = config
-            .get_param("DATABRICKS_BACKOFF_MULTIPLIER")
-            .ok()
-            .and_then(|v: String| v.parse::<f64>().ok())
-            .unwrap_or(DEFAULT_BACKOFF_MULTIPLIER);
-
-        let max_interval_ms = config
-            .get_param("DATABRICKS_MAX_RETRY_INTERVAL_MS")
-            .ok()
-            .and_then(|v: String| v.parse::<u64>().ok())
-            .unwrap_or(DEFAULT_MAX_RETRY_INTERVAL_MS);
-
-        RetryConfig {
-            max_retries,
-            initial_interval_ms,
-            backoff_multiplier,
-            max_interval_ms,
-        }
-    }
-
     /// Create a new DatabricksProvider with the specified host and token
     ///
     /// # Arguments
@@ -249,7 +154,6 @@ impl DatabricksProvider {
             auth: DatabricksAuth::token(api_key),
             model,
             image_format: ImageFormat::OpenAi,
-            retry_config: RetryConfig::default(),
         })
     }
 
@@ -280,7 +184,7 @@ impl DatabricksProvider {
             format!("serving-endpoints/{}/invocations", self.model.model_name)
         };
 
-        match self.post_with_retry(path.as_str(), payload).await {
+        match self.post_request(path.as_str(), payload).await {
             Ok(res) => res.json().await.map_err(|_| {
                 ProviderError::RequestFailed("Response body is not valid JSON".to_string())
             }),
@@ -288,7 +192,7 @@ impl DatabricksProvider {
         }
     }
 
-    async fn post_with_retry(
+    async fn post_request(
         &self,
         path: &str,
         payload: &Value,
@@ -299,112 +203,91 @@ impl DatabricksProvider {
             ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
         })?;
 
-        let mut attempts = 0;
-        loop {
-            let auth_header = self.ensure_auth_header().await?;
-            let response = self
-                .client
-                .post(url.clone())
-                .header("Authorization", auth_header)
-                .json(payload)
-                .send()
-                .await?;
+        let auth_header = self.ensure_auth_header().await?;
+        let response = self
+            .client
+            .post(url)
+            .header("Authorization", auth_header)
+            .json(payload)
+            .send()
+            .await?;
 
-            let status = response.status();
+        let status = response.status();
 
-            break match status {
-                StatusCode::OK => Ok(response),
-                StatusCode::TOO_MANY_REQUESTS
-                | StatusCode::INTERNAL_SERVER_ERROR
-                | StatusCode::SERVICE_UNAVAILABLE => {
-                    if attempts < self.retry_config.max_retries {
-                        attempts += 1;
-                        tracing::warn!(
-                            "{}: retrying ({}/{})",
-                            status,
-                            attempts,
-                            self.retry_config.max_retries
-                        );
-
-                        let delay = self.retry_config.delay_for_attempt(attempts);
-                        tracing::info!("Backing off for {:?} before retry", delay);
-                        sleep(delay).await;
-
-                        continue;
-                    }
-
-                    Err(match status {
-                        StatusCode::TOO_MANY_REQUESTS => {
-                            ProviderError::RateLimitExceeded("Rate limit exceeded".to_string())
-                        }
-                        _ => ProviderError::ServerError("Server error".to_string()),
-                    })
-                }
-                StatusCode::BAD_REQUEST => {
-                    // Databricks provides a generic 'error' but also includes 'external_model_message' which is provider specific
-                    // We try to extract the error message from the payload and check for phrases that indicate context length exceeded
-                    let bytes = response.bytes().await?;
-                    let payload_str = String::from_utf8_lossy(&bytes).to_lowercase();
-                    let check_phrases = [
-                        "too long",
-                        "context length",
-                        "context_length_exceeded",
-                        "reduce the length",
-                        "token count",
-                        "exceeds",
-                        "exceed context limit",
-                        "input length",
-                        "max_tokens",
-                        "decrease input length",
-                        "context limit",
-                    ];
-                    if check_phrases.iter().any(|c| payload_str.contains(c)) {
-                        return Err(ProviderError::ContextLengthExceeded(payload_str));
-                    }
-
-                    let mut error_msg = "Unknown error".to_string();
-                    if let Ok(response_json) = serde_json::from_slice::<Value>(&bytes) {
-                        // try to convert message to string, if that fails use external_model_message
-                        error_msg = response_json
-                            .get("message")
-                            .and_then(|m| m.as_str())
-                            .or_else(|| {
-                                response_json
-                                    .get("external_model_message")
-                                    .and_then(|ext| ext.get("message"))
-                                    .and_then(|m| m.as_str())
-                            })
-                            .unwrap_or("Unknown error")
-                            .to_string();
-                    }
-
-                    tracing::debug!(
-                        "{}",
-                        format!(
-                            "Provider request failed with status: {}. Payload: {:?}",
-                            status, payload_str
-                        )
-                    );
-                    return Err(ProviderError::RequestFailed(format!(
-                        "Request failed with status: {}. Message: {}",
-                        status, error_msg
-                    )));
+        match status {
+            StatusCode::OK => Ok(response),
+            StatusCode::TOO_MANY_REQUESTS => Err(ProviderError::RateLimitExceeded {
+                details: "Rate limit exceeded".to_string(),
+                retry_delay: get_retry_after(response.headers()),
+            }),
+            status if status.is_server_error() => Err(ProviderError::ServerError(format!(
+                "Server error: {}",
+                status
+            ))),
+            StatusCode::BAD_REQUEST => {
+                // Databricks provides a generic 'error' but also includes 'external_model_message' which is provider specific
+                // We try to extract the error message from the payload and check for phrases that indicate context length exceeded
+                let bytes = response.bytes().await?;
+                let payload_str = String::from_utf8_lossy(&bytes).to_lowercase();
+                let check_phrases = [
+                    "too long",
+                    "context length",
+                    "context_length_exceeded",
+                    "reduce the length",
+                    "token count",
+                    "exceeds",
+                    "exceed context limit",
+                    "input length",
+                    "max_tokens",
+                    "decrease input length",
+                    "context limit",
+                ];
+                if check_phrases.iter().any(|c| payload_str.contains(c)) {
+                    return Err(ProviderError::ContextLengthExceeded(payload_str));
                 }
-                _ => {
-                    tracing::debug!(
-                        "{}",
-                        format!(
-                            "Provider request failed with status: {}. Payload: {:?}",
-                            status,
-                            response.text().await.ok().unwrap_or_default()
-                        )
-                    );
-                    return Err(ProviderError::RequestFailed(format!(
-                        "Request failed with status: {}",
-                        status
-                    )));
+
+                let mut error_msg = "Unknown error".to_string();
+                if let Ok(response_json) = serde_json::from_slice::<Value>(&bytes) {
+                    // try to convert message to string, if that fails use external_model_message
+                    error_msg = response_json
+                        .get("message")
+                        .and_then(|m| m.as_str())
+                        .or_else(|| {
+                            response_json
+                                .get("external_model_message")
+                                .and_then(|ext| ext.get("message"))
+                                .and_then(|m| m.as_str())
+                        })
+                        .unwrap_or("Unknown error")
+                        .to_string();
                 }
-            };
+
+                tracing::debug!(
+                    "{}",
+                    format!(
+                        "Provider request failed with status: {}. Payload: {:?}",
+                        status, payload_str
+                    )
+                );
+                Err(ProviderError::RequestFailed(format!(
+                    "Request failed with status: {}. Message: {}",
+                    status, error_msg
+                )))
+            }
+            _ => {
+                tracing::debug!(
+                    "{}",
+                    format!(
+                        "Provider request failed with status: {}. Payload: {:?}",
+                        status,
+                        response.text().await.ok().unwrap_or_default()
+                    )
+                );
+                Err(ProviderError::RequestFailed(format!(
+                    "Request failed with status: {}",
+                    status
+                )))
+            }
         }
     }
 }
@@ -430,22 +313,37 @@ impl Provider for DatabricksProvider {
         self.model.clone()
     }
 
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_with_model(&self.model, system, messages, tools)
+            .await
+    }
+
     #[tracing::instrument(
-        skip(self, system, messages, tools),
+        skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
     )]
-    async fn complete(
+    async fn complete_with_model(
         &self,
+        model_config: &ModelConfig,
         system: &str,
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let mut payload = create_request(&self.model, system, messages, tools, &self.image_format)?;
+        let mut payload =
+            create_request(model_config, system, messages, tools, &self.image_format)?;
         // Remove the model key which is part of the url with databricks
         payload
             .as_object_mut()
             .expect("payload should have model key")
             .remove("model");
+        if self.supports_cache_control() {
+            payload = update_request_for_cache_control(&payload);
+        }
 
         let response = self.post(&payload).await?;
 
@@ -456,7 +354,7 @@ impl Provider for DatabricksProvider {
             Usage::default()
         });
         let model = get_model(&response);
-        super::utils::emit_debug_trace(&self.model, &payload, &response, &usage);
+        super::utils::emit_debug_trace(model_config, &payload, &response, &usage);
 
         Ok((message, ProviderUsage::new(model, usage)))
     }
@@ -473,6 +371,9 @@ impl Provider for DatabricksProvider {
             .as_object_mut()
             .expect("payload should have model key")
             .remove("model");
+        if self.supports_cache_control() {
+            payload = update_request_for_cache_control(&payload);
+        }
 
         payload
             .as_object_mut()
@@ -480,7 +381,7 @@ impl Provider for DatabricksProvider {
             .insert("stream".to_string(), Value::Bool(true));
 
         let response = self
-            .post_with_retry(
+            .post_request(
                 format!("serving-endpoints/{}/invocations", self.model.model_name).as_str(),
                 &payload,
             )
@@ -509,6 +410,11 @@ impl Provider for DatabricksProvider {
         true
     }
 
+    fn supports_cache_control(&self) -> bool {
+        // Claude models served by Databricks accept Anthropic cache breakpoints
+        self.model.model_name.contains("claude")
+    }
+
     fn supports_embeddings(&self) -> bool {
         true
     }
@@ -1,6 +1,18 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::{mpsc, Arc};
+
+use super::base::Provider;
+use super::local_embedding::{
+    LocalEmbeddingProvider, LOCAL_EMBEDDING_DEFAULT_MODEL, LOCAL_EMBEDDING_PROVIDER,
+};
+use crate::config::Config;
+use crate::model::ModelConfig;
+
+/// The model the embedding providers use unless GOOSE_EMBEDDING_MODEL says otherwise
+const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingRequest {
@@ -22,3 +34,129 @@ pub struct EmbeddingData {
 pub trait EmbeddingCapable {
     async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>>;
 }
+
+/// The provider goose is configured to create embeddings with, and a name for its model
+///
+/// Uses GOOSE_EMBEDDING_MODEL_PROVIDER when it is set, then the main provider if it can
+/// create embeddings, then the local model if it has been downloaded.
+fn configured_embedding_provider() -> Result<Option<(String, Arc<dyn Provider>)>> {
+    let embedding_model = env::var("GOOSE_EMBEDDING_MODEL").ok();
+
+    if let Ok(name) = env::var("GOOSE_EMBEDDING_MODEL_PROVIDER") {
+        if name == LOCAL_EMBEDDING_PROVIDER {
+            let model =
+                embedding_model.unwrap_or_else(|| LOCAL_EMBEDDING_DEFAULT_MODEL.to_string());
+            let provider = LocalEmbeddingProvider::from_env(ModelConfig::new(&model)?)
+                .context("Failed to load the local embedding model")?;
+            return Ok(Some((format!("{}/{}", name, model), Arc::new(provider))));
+        }
+        let model = embedding_model.unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+        let provider = super::create(&name, ModelConfig::new(&model)?)
+            .with_context(|| format!("Failed to create {} provider for embeddings", name))?;
+        return Ok(Some((format!("{}/{}", name, model), provider)));
+    }
+
+    let config = Config::global();
+    if let (Ok(name), Ok(model)) = (
+        config.get_param::<String>("GOOSE_PROVIDER"),
+        config.get_param::<String>("GOOSE_MODEL"),
+    ) {
+        match super::create(&name, ModelConfig::new(&model)?) {
+            Ok(provider) if provider.supports_embeddings() => {
+                let model = embedding_model.unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+                return Ok(Some((format!("{}/{}", name, model), provider)));
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to create {} provider for embeddings: {}", name, e),
+        }
+    }
+
+    let path = LocalEmbeddingProvider::default_model_path()?;
+    if path.exists() {
+        let provider =
+            LocalEmbeddingProvider::load(&path, ModelConfig::new(LOCAL_EMBEDDING_DEFAULT_MODEL)?)?;
+        return Ok(Some((
+            format!(
+                "{}/{}",
+                LOCAL_EMBEDDING_PROVIDER, LOCAL_EMBEDDING_DEFAULT_MODEL
+            ),
+            Arc::new(provider),
+        )));
+    }
+    Ok(None)
+}
+
+type EmbeddingJob = (Vec<String>, mpsc::Sender<Result<Vec<Vec<f32>>>>);
+
+/// Creates embeddings with the configured provider from synchronous code
+///
+/// The provider runs on a thread with its own runtime, so this can be called from
+/// async code that can't await, like the memory extension's tools.
+pub struct BlockingEmbedder {
+    model: String,
+    jobs: mpsc::Sender<EmbeddingJob>,
+}
+
+impl BlockingEmbedder {
+    /// An embedder for the configured embedding provider, or None if there isn't one
+    pub fn from_config() -> Result<Option<Self>> {
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (jobs, job_rx) = mpsc::channel::<EmbeddingJob>();
+
+        std::thread::Builder::new()
+            .name("embeddings".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e.into()));
+                        return;
+                    }
+                };
+                let provider = match runtime.block_on(async { configured_embedding_provider() }) {
+                    Ok(Some((model, provider))) => {
+                        let _ = ready_tx.send(Ok(Some(model)));
+                        provider
+                    }
+                    Ok(None) => {
+                        let _ = ready_tx.send(Ok(None));
+                        return;
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                // Runs until the embedder is dropped
+                while let Ok((texts, reply)) = job_rx.recv() {
+                    let embeddings = runtime
+                        .block_on(provider.create_embeddings(texts))
+                        .map_err(anyhow::Error::from);
+                    let _ = reply.send(embeddings);
+                }
+            })?;
+
+        let model = ready_rx
+            .recv()
+            .map_err(|_| anyhow!("The embedding thread stopped while starting"))??;
+        Ok(model.map(|model| Self { model, jobs }))
+    }
+
+    /// The provider and model the embeddings come from, e.g. `openai/text-embedding-3-small`
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let (reply, response) = mpsc::channel();
+        self.jobs
+            .send((texts, reply))
+            .map_err(|_| anyhow!("The embedding thread has stopped"))?;
+        response
+            .recv()
+            .map_err(|_| anyhow!("The embedding thread has stopped"))?
+    }
+}
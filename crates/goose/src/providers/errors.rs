@@ -1,4 +1,5 @@
 use reqwest::StatusCode;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,12 +10,20 @@ pub enum ProviderError {
     #[error("Context length exceeded: {0}")]
     ContextLengthExceeded(String),
 
-    #[error("Rate limit exceeded: {0}")]
-    RateLimitExceeded(String),
+    #[error("Rate limit exceeded: {details}")]
+    RateLimitExceeded {
+        details: String,
+        /// How long the provider asked us to wait, from a `Retry-After` header
+        retry_delay: Option<Duration>,
+    },
 
     #[error("Server error: {0}")]
     ServerError(String),
 
+    /// The request never got a response, e.g. it timed out or could not connect
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
     #[error("Request failed: {0}")]
     RequestFailed(String),
 
@@ -36,7 +45,19 @@ impl From<anyhow::Error> for ProviderError {
 
 impl From<reqwest::Error> for ProviderError {
     fn from(error: reqwest::Error) -> Self {
-        ProviderError::ExecutionError(error.to_string())
+        match error.status() {
+            Some(StatusCode::TOO_MANY_REQUESTS) => ProviderError::RateLimitExceeded {
+                details: error.to_string(),
+                retry_delay: None,
+            },
+            Some(status) if status.is_server_error() => {
+                ProviderError::ServerError(error.to_string())
+            }
+            _ if error.is_timeout() || error.is_connect() || error.is_request() => {
+                ProviderError::NetworkError(error.to_string())
+            }
+            _ => ProviderError::ExecutionError(error.to_string()),
+        }
     }
 }
 
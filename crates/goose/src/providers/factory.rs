@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::{
     anthropic::AnthropicProvider,
@@ -7,6 +8,7 @@ use super::{
     bedrock::BedrockProvider,
     claude_code::ClaudeCodeProvider,
     databricks::DatabricksProvider,
+    fallback::{FallbackProvider, FallbackTarget, DEFAULT_FALLBACK_COOLDOWN_SECS},
     gcpvertexai::GcpVertexAIProvider,
     gemini_cli::GeminiCliProvider,
     google::GoogleProvider,
@@ -16,6 +18,8 @@ use super::{
     ollama::OllamaProvider,
     openai::OpenAiProvider,
     openrouter::OpenRouterProvider,
+    replay::ReplayProvider,
+    retry::RetryProvider,
     sagemaker_tgi::SageMakerTgiProvider,
     snowflake::SnowflakeProvider,
     venice::VeniceProvider,
@@ -65,13 +69,82 @@ pub fn providers() -> Vec<ProviderMetadata> {
 pub fn create(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
     let config = crate::config::Config::global();
 
+    let fallbacks = match config.get_param::<Vec<FallbackTarget>>("GOOSE_FALLBACK_PROVIDERS") {
+        Ok(targets) => create_fallbacks(&targets),
+        Err(_) => Vec::new(),
+    };
+    // Providers in a chain fail over rather than retry, and the chain is retried as a whole
+    let create_link = if fallbacks.is_empty() {
+        create_provider
+    } else {
+        create_chain_link
+    };
+
     // Check for lead model environment variables
-    if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
+    let provider = if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
         tracing::info!("Creating lead/worker provider from environment variables");
 
-        return create_lead_worker_from_env(name, &model, &lead_model_name);
+        create_lead_worker_from_env(name, &model, &lead_model_name, create_link)?
+    } else {
+        create_link(name, model)?
+    };
+
+    if fallbacks.is_empty() {
+        return Ok(provider);
     }
-    create_provider(name, model)
+    Ok(create_fallback_chain(name, provider, fallbacks))
+}
+
+/// Create the fallback providers to put behind the configured provider
+///
+/// Fallbacks that can't be created, for example because their credentials are missing,
+/// are left out of the chain rather than stopping the session from starting.
+fn create_fallbacks(targets: &[FallbackTarget]) -> Vec<(String, Arc<dyn Provider>)> {
+    let mut fallbacks = Vec::new();
+    for target in targets {
+        let provider = ModelConfig::new(&target.model)
+            .map_err(anyhow::Error::from)
+            .and_then(|model| create_chain_link(&target.provider, model));
+        match provider {
+            Ok(provider) => fallbacks.push((target.provider.clone(), provider)),
+            Err(e) => tracing::warn!(
+                "Skipping fallback provider {} ({}): {}",
+                target.provider,
+                target.model,
+                e
+            ),
+        }
+    }
+    fallbacks
+}
+
+/// Put the configured provider at the head of a chain of fallback providers
+fn create_fallback_chain(
+    name: &str,
+    primary: Arc<dyn Provider>,
+    fallbacks: Vec<(String, Arc<dyn Provider>)>,
+) -> Arc<dyn Provider> {
+    let config = crate::config::Config::global();
+    let cooldown = config
+        .get_param::<u64>("GOOSE_FALLBACK_COOLDOWN_SECS")
+        .unwrap_or(DEFAULT_FALLBACK_COOLDOWN_SECS);
+
+    let mut chain = vec![(name.to_string(), primary)];
+    chain.extend(fallbacks);
+    tracing::info!(
+        "Creating fallback chain: {}",
+        chain
+            .iter()
+            .map(|(name, provider)| format!(
+                "{} ({})",
+                name,
+                provider.get_model_config().model_name
+            ))
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    );
+    let chain = Arc::new(FallbackProvider::new(chain, Duration::from_secs(cooldown)));
+    RetryProvider::wrap_chain(name, chain)
 }
 
 /// Create a lead/worker provider from environment variables
@@ -79,6 +152,7 @@ fn create_lead_worker_from_env(
     default_provider_name: &str,
     default_model: &ModelConfig,
     lead_model_name: &str,
+    create_link: fn(&str, ModelConfig) -> Result<Arc<dyn Provider>>,
 ) -> Result<Arc<dyn Provider>> {
     let config = crate::config::Config::global();
 
@@ -133,8 +207,8 @@ fn create_lead_worker_from_env(
     };
 
     // Create the providers
-    let lead_provider = create_provider(&lead_provider_name, lead_model_config)?;
-    let worker_provider = create_provider(default_provider_name, worker_model_config)?;
+    let lead_provider = create_link(&lead_provider_name, lead_model_config)?;
+    let worker_provider = create_link(default_provider_name, worker_model_config)?;
 
     // Create the lead/worker provider with configured settings
     Ok(Arc::new(LeadWorkerProvider::new_with_settings(
@@ -147,28 +221,46 @@ fn create_lead_worker_from_env(
 }
 
 fn create_provider(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
+    Ok(RetryProvider::wrap(
+        name,
+        create_base_provider(name, model)?,
+    ))
+}
+
+/// Create a provider for a fallback chain, which returns errors for the chain to handle
+fn create_chain_link(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
+    Ok(RetryProvider::wrap_link(
+        name,
+        create_base_provider(name, model)?,
+    ))
+}
+
+/// Create a provider without the retry wrapper, for providers that wrap it themselves
+pub(crate) fn create_base_provider(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
     // We use Arc instead of Box to be able to clone for multiple async tasks
-    match name {
-        "anthropic" => Ok(Arc::new(AnthropicProvider::from_env(model)?)),
-        "aws_bedrock" => Ok(Arc::new(BedrockProvider::from_env(model)?)),
-        "azure_openai" => Ok(Arc::new(AzureProvider::from_env(model)?)),
-        "claude-code" => Ok(Arc::new(ClaudeCodeProvider::from_env(model)?)),
-        "databricks" => Ok(Arc::new(DatabricksProvider::from_env(model)?)),
-        "gcp_vertex_ai" => Ok(Arc::new(GcpVertexAIProvider::from_env(model)?)),
-        "gemini-cli" => Ok(Arc::new(GeminiCliProvider::from_env(model)?)),
-        // "github_copilot" => Ok(Arc::new(GithubCopilotProvider::from_env(model)?)),
-        "google" => Ok(Arc::new(GoogleProvider::from_env(model)?)),
-        "groq" => Ok(Arc::new(GroqProvider::from_env(model)?)),
-        "litellm" => Ok(Arc::new(LiteLLMProvider::from_env(model)?)),
-        "ollama" => Ok(Arc::new(OllamaProvider::from_env(model)?)),
-        "openai" => Ok(Arc::new(OpenAiProvider::from_env(model)?)),
-        "openrouter" => Ok(Arc::new(OpenRouterProvider::from_env(model)?)),
-        "sagemaker_tgi" => Ok(Arc::new(SageMakerTgiProvider::from_env(model)?)),
-        "snowflake" => Ok(Arc::new(SnowflakeProvider::from_env(model)?)),
-        "venice" => Ok(Arc::new(VeniceProvider::from_env(model)?)),
-        "xai" => Ok(Arc::new(XaiProvider::from_env(model)?)),
-        _ => Err(anyhow::anyhow!("Unknown provider: {}", name)),
-    }
+    let provider: Arc<dyn Provider> = match name {
+        "anthropic" => Arc::new(AnthropicProvider::from_env(model)?),
+        "aws_bedrock" => Arc::new(BedrockProvider::from_env(model)?),
+        "azure_openai" => Arc::new(AzureProvider::from_env(model)?),
+        "claude-code" => Arc::new(ClaudeCodeProvider::from_env(model)?),
+        "databricks" => Arc::new(DatabricksProvider::from_env(model)?),
+        "gcp_vertex_ai" => Arc::new(GcpVertexAIProvider::from_env(model)?),
+        "gemini-cli" => Arc::new(GeminiCliProvider::from_env(model)?),
+        // "github_copilot" => Arc::new(GithubCopilotProvider::from_env(model)?),
+        "google" => Arc::new(GoogleProvider::from_env(model)?),
+        "groq" => Arc::new(GroqProvider::from_env(model)?),
+        "litellm" => Arc::new(LiteLLMProvider::from_env(model)?),
+        "ollama" => Arc::new(OllamaProvider::from_env(model)?),
+        "openai" => Arc::new(OpenAiProvider::from_env(model)?),
+        "openrouter" => Arc::new(OpenRouterProvider::from_env(model)?),
+        "replay" => Arc::new(ReplayProvider::from_env(model)?),
+        "sagemaker_tgi" => Arc::new(SageMakerTgiProvider::from_env(model)?),
+        "snowflake" => Arc::new(SnowflakeProvider::from_env(model)?),
+        "venice" => Arc::new(VeniceProvider::from_env(model)?),
+        "xai" => Arc::new(XaiProvider::from_env(model)?),
+        _ => return Err(anyhow::anyhow!("Unknown provider: {}", name)),
+    };
+    Ok(provider)
 }
 
 #[cfg(test)]
@@ -176,6 +268,7 @@ mod tests {
     use super::*;
     use crate::message::{Message, MessageContent};
     use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use crate::providers::local_embedding::LOCAL_EMBEDDING_PROVIDER;
     use chrono::Utc;
     use rmcp::model::{AnnotateAble, RawTextContent, Role};
     use std::env;
@@ -385,6 +478,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fallback_chain_skips_unavailable_providers() {
+        let targets = vec![FallbackTarget {
+            provider: "not_a_provider".to_string(),
+            model: "some-model".to_string(),
+        }];
+
+        // With no usable fallbacks, the configured provider is used on its own
+        assert!(create_fallbacks(&targets).is_empty());
+    }
+
+    #[test]
+    fn test_local_embedding_provider_is_not_a_chat_provider() {
+        // It only creates embeddings, so it is used for tool selection but never offered
+        // as the model for a session
+        assert!(!providers()
+            .iter()
+            .any(|metadata| metadata.name == LOCAL_EMBEDDING_PROVIDER));
+        assert!(create_base_provider(
+            LOCAL_EMBEDDING_PROVIDER,
+            ModelConfig::new_or_fail("all-MiniLM-L6-v2")
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_worker_model_preserves_original_context_limit() {
         use std::env;
@@ -412,16 +530,19 @@ mod tests {
             ModelConfig::new_or_fail("gpt-3.5-turbo").with_context_limit(Some(16_000));
 
         // Test case 1: No environment variables - should preserve original context_limit
-        let result = create_lead_worker_from_env("openai", &default_model, "gpt-4o");
+        let result =
+            create_lead_worker_from_env("openai", &default_model, "gpt-4o", create_provider);
 
         // Test case 2: With GOOSE_WORKER_CONTEXT_LIMIT - should override original
         env::set_var("GOOSE_WORKER_CONTEXT_LIMIT", "32000");
-        let _result = create_lead_worker_from_env("openai", &default_model, "gpt-4o");
+        let _result =
+            create_lead_worker_from_env("openai", &default_model, "gpt-4o", create_provider);
         env::remove_var("GOOSE_WORKER_CONTEXT_LIMIT");
 
         // Test case 3: With GOOSE_CONTEXT_LIMIT - should override original
         env::set_var("GOOSE_CONTEXT_LIMIT", "64000");
-        let _result = create_lead_worker_from_env("openai", &default_model, "gpt-4o");
+        let _result =
+            create_lead_worker_from_env("openai", &default_model, "gpt-4o", create_provider);
         env::remove_var("GOOSE_CONTEXT_LIMIT");
 
         // Restore env vars
@@ -0,0 +1,399 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use rmcp::model::Tool;
+use serde::{Deserialize, Serialize};
+
+use super::base::{
+    stream_from_single_message, LeadWorkerProviderTrait, MessageStream, Provider, ProviderMetadata,
+    ProviderUsage,
+};
+use super::errors::ProviderError;
+use crate::message::Message;
+use crate::model::ModelConfig;
+
+pub const DEFAULT_FALLBACK_COOLDOWN_SECS: u64 = 300;
+
+/// A provider and model to fall back to, as listed in `GOOSE_FALLBACK_PROVIDERS`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackTarget {
+    pub provider: String,
+    pub model: String,
+}
+
+struct Link {
+    name: String,
+    provider: Arc<dyn Provider>,
+}
+
+/// A provider that tries a chain of providers in order, moving down the chain when one
+/// is unavailable
+///
+/// A provider that fails with an auth, outage or rate limit error is skipped for a
+/// cooldown period, after which it is tried again, so the chain returns to the first
+/// provider once it recovers. Context length errors move on to the next provider without
+/// a cooldown, since they depend on the request rather than the provider.
+pub struct FallbackProvider {
+    links: Vec<Link>,
+    cooldown: Duration,
+    cooling_until: Mutex<Vec<Option<Instant>>>,
+}
+
+impl FallbackProvider {
+    /// Create a chain from provider names and providers, in the order they should be tried
+    pub fn new(providers: Vec<(String, Arc<dyn Provider>)>, cooldown: Duration) -> Self {
+        let cooling_until = Mutex::new(vec![None; providers.len()]);
+        Self {
+            links: providers
+                .into_iter()
+                .map(|(name, provider)| Link { name, provider })
+                .collect(),
+            cooldown,
+            cooling_until,
+        }
+    }
+
+    /// Indexes of the providers to try, in order
+    ///
+    /// Providers cooling down are left out, unless all of them are.
+    fn attempt_order(&self, now: Instant) -> Vec<usize> {
+        let cooling_until = self.cooling_until.lock().unwrap();
+        let available: Vec<usize> = (0..self.links.len())
+            .filter(|&index| cooling_until[index].is_none_or(|until| until <= now))
+            .collect();
+        if available.is_empty() {
+            (0..self.links.len()).collect()
+        } else {
+            available
+        }
+    }
+
+    /// The provider the next request will go to first
+    fn current(&self) -> &Arc<dyn Provider> {
+        let index = self
+            .attempt_order(Instant::now())
+            .first()
+            .copied()
+            .unwrap_or(0);
+        &self.links[index].provider
+    }
+
+    fn set_cooldown(&self, index: usize, until: Option<Instant>) {
+        self.cooling_until.lock().unwrap()[index] = until;
+    }
+
+    /// Run `call` with the index of each provider to try until one succeeds
+    async fn with_fallback<T, F, Fut>(&self, operation: &str, call: F) -> Result<T, ProviderError>
+    where
+        F: Fn(usize) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let order = self.attempt_order(Instant::now());
+        let mut last_error = None;
+
+        for (position, &index) in order.iter().enumerate() {
+            let link = &self.links[index];
+            let error = match call(index).await {
+                Ok(result) => {
+                    self.set_cooldown(index, None);
+                    if index > 0 {
+                        tracing::info!("{} served by fallback provider {}", operation, link.name);
+                    }
+                    return Ok(result);
+                }
+                Err(error) => error,
+            };
+
+            let is_last = position + 1 == order.len();
+            if is_last || !should_fail_over(&error) {
+                return Err(error);
+            }
+            if !matches!(error, ProviderError::ContextLengthExceeded(_)) {
+                self.set_cooldown(index, Some(Instant::now() + self.cooldown));
+            }
+            tracing::warn!(
+                "{} failed on provider {} ({}), falling back to {}",
+                operation,
+                link.name,
+                error,
+                self.links[order[position + 1]].name
+            );
+            last_error = Some(error);
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ProviderError::ExecutionError("No providers in the fallback chain".to_string())
+        }))
+    }
+}
+
+/// Whether an error means another provider might succeed where this one failed
+fn should_fail_over(error: &ProviderError) -> bool {
+    matches!(
+        error,
+        ProviderError::Authentication(_)
+            | ProviderError::ContextLengthExceeded(_)
+            | ProviderError::RateLimitExceeded { .. }
+            | ProviderError::ServerError(_)
+            | ProviderError::NetworkError(_)
+    )
+}
+
+#[async_trait]
+impl Provider for FallbackProvider {
+    fn metadata() -> ProviderMetadata {
+        // This is a wrapper provider, so we return minimal metadata
+        ProviderMetadata::new(
+            "fallback",
+            "Fallback Provider",
+            "A provider that moves down a chain of providers when one is unavailable",
+            "",
+            vec![],
+            "",
+            vec![],
+        )
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.current().get_model_config()
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.with_fallback("Completion", |index| {
+            self.links[index].provider.complete(system, messages, tools)
+        })
+        .await
+    }
+
+    async fn complete_with_model(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.with_fallback("Completion", |index| {
+            // Each link keeps its own model and takes only the request's limits
+            let provider = &self.links[index].provider;
+            let link_config = provider
+                .get_model_config()
+                .with_max_tokens(model_config.max_tokens)
+                .with_temperature(model_config.temperature);
+            async move {
+                provider
+                    .complete_with_model(&link_config, system, messages, tools)
+                    .await
+            }
+        })
+        .await
+    }
+
+    async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.current().fetch_supported_models_async().await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.links
+            .iter()
+            .any(|link| link.provider.supports_embeddings())
+    }
+
+    fn supports_cache_control(&self) -> bool {
+        self.current().supports_cache_control()
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let provider = self
+            .attempt_order(Instant::now())
+            .into_iter()
+            .map(|index| &self.links[index].provider)
+            .find(|provider| provider.supports_embeddings())
+            .ok_or_else(|| {
+                ProviderError::ExecutionError(
+                    "No provider in the fallback chain supports embeddings".to_string(),
+                )
+            })?;
+        provider.create_embeddings(texts).await
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        self.current().as_lead_worker()
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        self.with_fallback("Stream", |index| {
+            let provider = &self.links[index].provider;
+            async move {
+                if provider.supports_streaming() {
+                    provider.stream(system, messages, tools).await
+                } else {
+                    let (message, usage) = provider.complete(system, messages, tools).await?;
+                    Ok(stream_from_single_message(message, usage))
+                }
+            }
+        })
+        .await
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.current().supports_streaming()
+    }
+
+    fn get_active_model_name(&self) -> String {
+        self.current().get_active_model_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct ScriptedProvider {
+        model_config: ModelConfig,
+        calls: AtomicUsize,
+        errors: Mutex<Vec<ProviderError>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(model: &str, errors: Vec<ProviderError>) -> Arc<Self> {
+            Arc::new(Self {
+                model_config: ModelConfig::new_or_fail(model),
+                calls: AtomicUsize::new(0),
+                errors: Mutex::new(errors),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let mut errors = self.errors.lock().unwrap();
+            if errors.is_empty() {
+                Ok((
+                    Message::assistant().with_text(&self.model_config.model_name),
+                    ProviderUsage::new(self.model_config.model_name.clone(), Usage::default()),
+                ))
+            } else {
+                Err(errors.remove(0))
+            }
+        }
+    }
+
+    fn chain(
+        first: &Arc<ScriptedProvider>,
+        second: &Arc<ScriptedProvider>,
+        cooldown: Duration,
+    ) -> FallbackProvider {
+        FallbackProvider::new(
+            vec![
+                ("anthropic".to_string(), first.clone() as Arc<dyn Provider>),
+                ("openai".to_string(), second.clone() as Arc<dyn Provider>),
+            ],
+            cooldown,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_and_cools_down() {
+        let first = ScriptedProvider::new(
+            "claude",
+            vec![ProviderError::ServerError("overloaded".to_string())],
+        );
+        let second = ScriptedProvider::new("gpt-4o", vec![]);
+        let provider = chain(&first, &second, Duration::from_secs(60));
+
+        let (message, usage) = provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(message.as_concat_text(), "gpt-4o");
+        assert_eq!(usage.model, "gpt-4o");
+
+        // The failed provider is skipped while it cools down
+        assert_eq!(provider.get_model_config().model_name, "gpt-4o");
+        provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(first.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(second.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_recovers_after_cooldown() {
+        let first = ScriptedProvider::new(
+            "claude",
+            vec![ProviderError::RateLimitExceeded {
+                details: "slow down".to_string(),
+                retry_delay: None,
+            }],
+        );
+        let second = ScriptedProvider::new("gpt-4o", vec![]);
+        let provider = chain(&first, &second, Duration::ZERO);
+
+        let (message, _) = provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(message.as_concat_text(), "gpt-4o");
+
+        let (message, _) = provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(message.as_concat_text(), "claude");
+    }
+
+    #[tokio::test]
+    async fn test_does_not_fail_over_on_other_errors() {
+        let first = ScriptedProvider::new(
+            "claude",
+            vec![ProviderError::RequestFailed(
+                "invalid tool schema".to_string(),
+            )],
+        );
+        let second = ScriptedProvider::new("gpt-4o", vec![]);
+        let provider = chain(&first, &second, Duration::from_secs(60));
+
+        let result = provider.complete("system", &[], &[]).await;
+        assert!(matches!(result, Err(ProviderError::RequestFailed(_))));
+        assert_eq!(second.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_context_length_skips_without_cooldown() {
+        let first = ScriptedProvider::new(
+            "claude",
+            vec![ProviderError::ContextLengthExceeded("too long".to_string())],
+        );
+        let second = ScriptedProvider::new(
+            "gpt-4o",
+            vec![ProviderError::ContextLengthExceeded("too long".to_string())],
+        );
+        let provider = chain(&first, &second, Duration::from_secs(60));
+
+        // Every provider was too small, so the agent still sees the context error
+        let result = provider.complete("system", &[], &[]).await;
+        assert!(matches!(
+            result,
+            Err(ProviderError::ContextLengthExceeded(_))
+        ));
+        assert_eq!(provider.get_model_config().model_name, "claude");
+    }
+}
@@ -90,6 +90,9 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                 MessageContent::ToolConfirmationRequest(_tool_confirmation_request) => {
                     // Skip tool confirmation requests
                 }
+                MessageContent::ElicitationRequest(_) => {
+                    // Skip elicitation requests
+                }
                 MessageContent::ContextLengthExceeded(_) => {
                     // Skip
                 }
@@ -302,6 +305,10 @@ pub fn get_usage(data: &Value) -> Result<Usage> {
             Some(total_input_i32),
             Some(output_tokens_i32),
             Some(total_tokens_i32),
+        )
+        .with_cache_tokens(
+            Some(cache_read_tokens.min(i32::MAX as u64) as i32),
+            Some(cache_creation_tokens.min(i32::MAX as u64) as i32),
         ))
     } else if data.as_object().is_some() {
         // Check if the data itself is the usage object (for message_delta events that might have usage at top level)
@@ -345,6 +352,10 @@ pub fn get_usage(data: &Value) -> Result<Usage> {
                 Some(total_input_i32),
                 Some(output_tokens_i32),
                 Some(total_tokens_i32),
+            )
+            .with_cache_tokens(
+                Some(cache_read_tokens.min(i32::MAX as u64) as i32),
+                Some(cache_creation_tokens.min(i32::MAX as u64) as i32),
             ))
         } else {
             tracing::debug!("🔍 Anthropic no token data found in object");
@@ -620,7 +631,11 @@ where
                                 (None, None) => None,
                             };
 
-                            let merged_usage = crate::providers::base::Usage::new(merged_input, merged_output, merged_total);
+                            let merged_usage = crate::providers::base::Usage::new(merged_input, merged_output, merged_total)
+                                .with_cache_tokens(
+                                    existing_usage.usage.cache_read_input_tokens.or(delta_usage.cache_read_input_tokens),
+                                    existing_usage.usage.cache_write_input_tokens.or(delta_usage.cache_write_input_tokens),
+                                );
                             final_usage = Some(crate::providers::base::ProviderUsage::new(existing_usage.model.clone(), merged_usage));
                             tracing::debug!("🔍 Anthropic MERGED usage: input_tokens={:?}, output_tokens={:?}, total_tokens={:?}",
                                     merged_input, merged_output, merged_total);
@@ -977,6 +992,8 @@ mod tests {
         assert_eq!(usage.input_tokens, Some(15007));
         assert_eq!(usage.output_tokens, Some(50));
         assert_eq!(usage.total_tokens, Some(15057)); // 15007 + 50
+        assert_eq!(usage.cache_read_input_tokens, Some(5000));
+        assert_eq!(usage.cache_write_input_tokens, Some(10000));
 
         Ok(())
     }
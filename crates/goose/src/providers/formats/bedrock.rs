@@ -13,6 +13,36 @@ use serde_json::Value;
 use super::super::base::Usage;
 use crate::message::{Message, MessageContent};
 
+/// Convert the conversation, with cache points after the last two user messages when
+/// `cache` is set, placed like the Anthropic format's `cache_control` markers
+pub fn to_bedrock_messages(messages: &[Message], cache: bool) -> Result<Vec<bedrock::Message>> {
+    let mut bedrock_messages: Vec<bedrock::Message> = Vec::new();
+    for message in messages {
+        let message = to_bedrock_message(message)?;
+        match bedrock_messages.last_mut() {
+            // Bedrock requires roles to alternate, so text added after tool results, such as
+            // resource updates, joins the message with the results
+            Some(last) if last.role == message.role => last.content.extend(message.content),
+            _ => bedrock_messages.push(message),
+        }
+    }
+
+    if cache {
+        for message in bedrock_messages
+            .iter_mut()
+            .rev()
+            .filter(|message| message.role == bedrock::ConversationRole::User)
+            .take(2)
+        {
+            message
+                .content
+                .push(bedrock::ContentBlock::CachePoint(to_bedrock_cache_point()?));
+        }
+    }
+
+    Ok(bedrock_messages)
+}
+
 pub fn to_bedrock_message(message: &Message) -> Result<bedrock::Message> {
     bedrock::Message::builder()
         .role(to_bedrock_role(&message.role))
@@ -33,6 +63,7 @@ pub fn to_bedrock_message_content(content: &MessageContent) -> Result<bedrock::C
         MessageContent::ToolConfirmationRequest(_tool_confirmation_request) => {
             bedrock::ContentBlock::Text("".to_string())
         }
+        MessageContent::ElicitationRequest(_) => bedrock::ContentBlock::Text("".to_string()),
         MessageContent::Image(image) => {
             bedrock::ContentBlock::Image(to_bedrock_image(&image.data, &image.mime_type)?)
         }
@@ -172,11 +203,36 @@ pub fn to_bedrock_image(data: &String, mime_type: &String) -> Result<bedrock::Im
         .build()?)
 }
 
-pub fn to_bedrock_tool_config(tools: &[Tool]) -> Result<bedrock::ToolConfiguration> {
+/// Convert the system prompt, with a cache point after it when `cache` is set
+pub fn to_bedrock_system(system: &str, cache: bool) -> Result<Vec<bedrock::SystemContentBlock>> {
+    let mut blocks = vec![bedrock::SystemContentBlock::Text(system.to_string())];
+    if cache {
+        blocks.push(bedrock::SystemContentBlock::CachePoint(
+            to_bedrock_cache_point()?,
+        ));
+    }
+    Ok(blocks)
+}
+
+/// Convert the tool definitions, with a cache point after the last one when `cache` is set,
+/// so all of them are cached as a single prefix
+pub fn to_bedrock_tool_config(tools: &[Tool], cache: bool) -> Result<bedrock::ToolConfiguration> {
+    let mut bedrock_tools = tools
+        .iter()
+        .map(to_bedrock_tool)
+        .collect::<Result<Vec<_>>>()?;
+    if cache {
+        bedrock_tools.push(bedrock::Tool::CachePoint(to_bedrock_cache_point()?));
+    }
     Ok(bedrock::ToolConfiguration::builder()
-        .set_tools(Some(
-            tools.iter().map(to_bedrock_tool).collect::<Result<_>>()?,
-        ))
+        .set_tools(Some(bedrock_tools))
+        .build()?)
+}
+
+/// A cache point, which caches everything before it in the request
+pub fn to_bedrock_cache_point() -> Result<bedrock::CachePointBlock> {
+    Ok(bedrock::CachePointBlock::builder()
+        .r#type(bedrock::CachePointType::Default)
         .build()?)
 }
 
@@ -323,11 +379,19 @@ pub fn from_bedrock_role(role: &bedrock::ConversationRole) -> Result<Role> {
 }
 
 pub fn from_bedrock_usage(usage: &bedrock::TokenUsage) -> Usage {
-    Usage {
-        input_tokens: Some(usage.input_tokens),
-        output_tokens: Some(usage.output_tokens),
-        total_tokens: Some(usage.total_tokens),
-    }
+    // Bedrock leaves cached tokens out of the input tokens, while Usage counts them in
+    let cache_read = usage.cache_read_input_tokens.unwrap_or(0);
+    let cache_write = usage.cache_write_input_tokens.unwrap_or(0);
+    let input_tokens = usage.input_tokens + cache_read + cache_write;
+    Usage::new(
+        Some(input_tokens),
+        Some(usage.output_tokens),
+        Some(input_tokens + usage.output_tokens),
+    )
+    .with_cache_tokens(
+        usage.cache_read_input_tokens,
+        usage.cache_write_input_tokens,
+    )
 }
 
 pub fn from_bedrock_json(document: &Document) -> Result<Value> {
@@ -442,4 +506,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_cache_points() -> Result<()> {
+        let messages = vec![
+            Message::user().with_text("first"),
+            Message::assistant().with_text("reply"),
+            Message::user().with_text("second"),
+            Message::assistant().with_text("reply"),
+            Message::user().with_text("third"),
+        ];
+        let is_cache_point = |block: Option<&bedrock::ContentBlock>| {
+            matches!(block, Some(bedrock::ContentBlock::CachePoint(_)))
+        };
+
+        let cached = to_bedrock_messages(&messages, true)?;
+        assert!(!is_cache_point(cached[0].content.last()));
+        assert!(is_cache_point(cached[2].content.last()));
+        assert!(is_cache_point(cached[4].content.last()));
+
+        let uncached = to_bedrock_messages(&messages, false)?;
+        assert!(uncached
+            .iter()
+            .all(|message| !is_cache_point(message.content.last())));
+
+        let system = to_bedrock_system("system", true)?;
+        assert!(matches!(
+            system.as_slice(),
+            [
+                bedrock::SystemContentBlock::Text(_),
+                bedrock::SystemContentBlock::CachePoint(_)
+            ]
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_consecutive_messages_with_the_same_role_are_merged() -> Result<()> {
+        let messages = vec![
+            Message::assistant()
+                .with_tool_request("tool1", Ok(ToolCall::new("example", serde_json::json!({})))),
+            Message::user().with_tool_response("tool1", Ok(vec![Content::text("Result")])),
+            Message::user().with_text("resources"),
+        ];
+
+        let bedrock_messages = to_bedrock_messages(&messages, false)?;
+        assert_eq!(bedrock_messages.len(), 2);
+        assert_eq!(bedrock_messages[1].role, bedrock::ConversationRole::User);
+        assert!(matches!(
+            bedrock_messages[1].content.as_slice(),
+            [
+                bedrock::ContentBlock::ToolResult(_),
+                bedrock::ContentBlock::Text(_)
+            ]
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bedrock_usage_counts_cached_tokens() {
+        let usage = bedrock::TokenUsage::builder()
+            .input_tokens(10)
+            .output_tokens(20)
+            .total_tokens(30)
+            .cache_read_input_tokens(1000)
+            .cache_write_input_tokens(200)
+            .build()
+            .unwrap();
+
+        let usage = from_bedrock_usage(&usage);
+        assert_eq!(usage.input_tokens, Some(1210));
+        assert_eq!(usage.total_tokens, Some(1230));
+        assert_eq!(usage.cache_read_input_tokens, Some(1000));
+        assert_eq!(usage.cache_write_input_tokens, Some(200));
+    }
 }
@@ -187,6 +187,9 @@ pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<
                 MessageContent::ToolConfirmationRequest(_) => {
                     // Skip tool confirmation requests
                 }
+                MessageContent::ElicitationRequest(_) => {
+                    // Skip elicitation requests
+                }
                 MessageContent::Image(image) => {
                     // Handle direct image content
                     content_array.push(json!({
@@ -16,10 +16,13 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
     messages
         .iter()
         .filter(|message| {
-            message
-                .content
-                .iter()
-                .any(|content| !matches!(content, MessageContent::ToolConfirmationRequest(_)))
+            message.content.iter().any(|content| {
+                !matches!(
+                    content,
+                    MessageContent::ToolConfirmationRequest(_)
+                        | MessageContent::ElicitationRequest(_)
+                )
+            })
         })
         .map(|message| {
             let role = if message.role == Role::User {
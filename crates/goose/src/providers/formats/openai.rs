@@ -199,6 +199,9 @@ pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<
                 MessageContent::ToolConfirmationRequest(_) => {
                     // Skip tool confirmation requests
                 }
+                MessageContent::ElicitationRequest(_) => {
+                    // Skip elicitation requests
+                }
                 MessageContent::Image(image) => {
                     // Handle direct image content
                     converted["content"] = json!([convert_image(image, image_format)]);
@@ -352,7 +355,110 @@ pub fn get_usage(usage: &Value) -> Usage {
             _ => None,
         });
 
+    // OpenAI reports cache hits in the prompt token details, counted in prompt_tokens.
+    // Claude behind an OpenAI-compatible endpoint may instead report cache reads and writes
+    // the Anthropic way, on top of prompt_tokens, while Usage counts them as input.
+    let cached_tokens = usage
+        .get("prompt_tokens_details")
+        .and_then(|details| details.get("cached_tokens"))
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+    let cache_write_input_tokens = usage
+        .get("cache_creation_input_tokens")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+
+    let (input_tokens, total_tokens, cache_read_input_tokens) = match cached_tokens {
+        Some(cached_tokens) => (input_tokens, total_tokens, Some(cached_tokens)),
+        None => {
+            let cache_read_input_tokens = usage
+                .get("cache_read_input_tokens")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32);
+            let uncounted =
+                cache_read_input_tokens.unwrap_or(0) + cache_write_input_tokens.unwrap_or(0);
+            (
+                input_tokens.map(|tokens| tokens + uncounted),
+                total_tokens.map(|tokens| tokens + uncounted),
+                cache_read_input_tokens,
+            )
+        }
+    };
+
     Usage::new(input_tokens, output_tokens, total_tokens)
+        .with_cache_tokens(cache_read_input_tokens, cache_write_input_tokens)
+}
+
+/// Add Anthropic cache breakpoints to a request, for Claude models served through an
+/// OpenAI-compatible endpoint
+///
+/// The system prompt and the last tool definition are marked, so all tools are cached as
+/// a single prefix, and the last two user or tool messages are marked as in the Anthropic
+/// format.
+pub fn update_request_for_cache_control(original_payload: &Value) -> Value {
+    let mut payload = original_payload.clone();
+
+    if let Some(messages_spec) = payload
+        .as_object_mut()
+        .and_then(|obj| obj.get_mut("messages"))
+        .and_then(|messages| messages.as_array_mut())
+    {
+        let mut marked = 0;
+        for message in messages_spec.iter_mut().rev() {
+            let role = message.get("role").and_then(|r| r.as_str());
+            if role == Some("user") || role == Some("tool") {
+                if let Some(content) = message.get_mut("content") {
+                    add_cache_control(content);
+                }
+                marked += 1;
+                if marked >= 2 {
+                    break;
+                }
+            }
+        }
+
+        if let Some(system_message) = messages_spec
+            .iter_mut()
+            .find(|msg| msg.get("role") == Some(&json!("system")))
+        {
+            if let Some(content) = system_message.get_mut("content") {
+                add_cache_control(content);
+            }
+        }
+    }
+
+    if let Some(last_tool) = payload
+        .as_object_mut()
+        .and_then(|obj| obj.get_mut("tools"))
+        .and_then(|tools| tools.as_array_mut())
+        .and_then(|tools| tools.last_mut())
+    {
+        if let Some(function) = last_tool
+            .get_mut("function")
+            .and_then(|f| f.as_object_mut())
+        {
+            function.insert("cache_control".to_string(), json!({ "type": "ephemeral" }));
+        }
+    }
+
+    payload
+}
+
+/// Mark message content as a cache breakpoint, turning plain text into a content part
+fn add_cache_control(content: &mut Value) {
+    if let Some(text) = content.as_str() {
+        *content = json!([{
+            "type": "text",
+            "text": text,
+            "cache_control": { "type": "ephemeral" }
+        }]);
+    } else if let Some(last_part) = content
+        .as_array_mut()
+        .and_then(|parts| parts.last_mut())
+        .and_then(|part| part.as_object_mut())
+    {
+        last_part.insert("cache_control".to_string(), json!({ "type": "ephemeral" }));
+    }
 }
 
 /// Validates and fixes tool schemas to ensure they have proper parameter structure.
@@ -430,12 +536,7 @@ where
                 .map_err(|e| anyhow!("Failed to parse streaming chunk: {}: {:?}", e, &line))?;
             let model = chunk.model.clone();
 
-            let usage = chunk.usage.as_ref().map(|u| {
-                ProviderUsage {
-                    usage: get_usage(u),
-                    model,
-                }
-            });
+            let usage = chunk.usage.as_ref().map(|u| ProviderUsage::new(model, get_usage(u)));
 
             if chunk.choices.is_empty() {
                 yield (None, usage)
@@ -841,6 +942,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_format_messages_text_after_tool_response() -> anyhow::Result<()> {
+        let messages = vec![
+            Message::assistant().with_tool_request(
+                "tool1",
+                Ok(ToolCall::new("example", json!({"param1": "value1"}))),
+            ),
+            Message::user().with_tool_response("tool1", Ok(vec![Content::text("Result")])),
+            Message::user().with_text("# Active Resources"),
+        ];
+
+        let spec = format_messages(&messages, &ImageFormat::OpenAi);
+
+        assert_eq!(spec.len(), 3);
+        assert!(spec[0]["tool_calls"].is_array());
+        assert_eq!(spec[1]["role"], "tool");
+        assert_eq!(spec[1]["tool_call_id"], spec[0]["tool_calls"][0]["id"]);
+        assert_eq!(spec[2]["role"], "user");
+        assert_eq!(spec[2]["content"], "# Active Resources");
+
+        Ok(())
+    }
+
     #[test]
     fn test_format_tools_duplicate() -> anyhow::Result<()> {
         let tool1 = Tool::new(
@@ -1127,6 +1251,71 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_usage_cached_tokens() {
+        let usage = get_usage(&json!({
+            "prompt_tokens": 1200,
+            "completion_tokens": 30,
+            "total_tokens": 1230,
+            "prompt_tokens_details": {"cached_tokens": 1024}
+        }));
+        assert_eq!(usage.input_tokens, Some(1200));
+        assert_eq!(usage.cache_read_input_tokens, Some(1024));
+        assert_eq!(usage.cache_write_input_tokens, None);
+
+        // Anthropic-style cache counts come on top of prompt_tokens
+        let usage = get_usage(&json!({
+            "prompt_tokens": 100,
+            "completion_tokens": 30,
+            "cache_read_input_tokens": 900,
+            "cache_creation_input_tokens": 200
+        }));
+        assert_eq!(usage.input_tokens, Some(1200));
+        assert_eq!(usage.total_tokens, Some(1230));
+        assert_eq!(usage.cache_read_input_tokens, Some(900));
+        assert_eq!(usage.cache_write_input_tokens, Some(200));
+    }
+
+    #[test]
+    fn test_update_request_for_cache_control() {
+        let payload = json!({
+            "messages": [
+                {"role": "system", "content": "You are a helpful assistant"},
+                {"role": "user", "content": "List the files"},
+                {"role": "assistant", "content": null, "tool_calls": []},
+                {"role": "tool", "tool_call_id": "1", "content": "a.txt"},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "And this image"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAA"}}
+                ]}
+            ],
+            "tools": [
+                {"type": "function", "function": {"name": "first"}},
+                {"type": "function", "function": {"name": "second"}}
+            ]
+        });
+
+        let payload = update_request_for_cache_control(&payload);
+        let messages = payload["messages"].as_array().unwrap();
+        let ephemeral = json!({"type": "ephemeral"});
+
+        assert_eq!(messages[0]["content"][0]["cache_control"], ephemeral);
+        assert_eq!(
+            messages[0]["content"][0]["text"],
+            "You are a helpful assistant"
+        );
+        // Only the last two user or tool messages are marked
+        assert_eq!(messages[1]["content"], "List the files");
+        assert_eq!(messages[3]["content"][0]["cache_control"], ephemeral);
+        assert!(messages[4]["content"][0].get("cache_control").is_none());
+        assert_eq!(messages[4]["content"][1]["cache_control"], ephemeral);
+
+        assert!(payload["tools"][0]["function"]
+            .get("cache_control")
+            .is_none());
+        assert_eq!(payload["tools"][1]["function"]["cache_control"], ephemeral);
+    }
+
     #[tokio::test]
     async fn test_streamed_multi_tool_response_to_messages() -> anyhow::Result<()> {
         let response_lines = r#"
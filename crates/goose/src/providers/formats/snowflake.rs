@@ -53,6 +53,9 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                 MessageContent::ToolConfirmationRequest(_) => {
                     // Skip tool confirmation requests
                 }
+                MessageContent::ElicitationRequest(_) => {
+                    // Skip elicitation requests
+                }
                 MessageContent::ContextLengthExceeded(_) => {
                     // Skip
                 }
@@ -394,18 +394,29 @@ impl Provider for GithubCopilotProvider {
         self.model.clone()
     }
 
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_with_model(&self.model, system, messages, tools)
+            .await
+    }
+
     #[tracing::instrument(
-        skip(self, system, messages, tools),
+        skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
     )]
-    async fn complete(
+    async fn complete_with_model(
         &self,
+        model_config: &ModelConfig,
         system: &str,
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
         let mut payload =
-            create_request(&self.model, system, messages, tools, &ImageFormat::OpenAi)?;
+            create_request(model_config, system, messages, tools, &ImageFormat::OpenAi)?;
 
         // Make request
         let response = self.post(&mut payload).await?;
@@ -417,7 +428,7 @@ impl Provider for GithubCopilotProvider {
             Usage::default()
         });
         let model = get_model(&response);
-        emit_debug_trace(&self.model, &payload, &response, &usage);
+        emit_debug_trace(model_config, &payload, &response, &usage);
         Ok((message, ProviderUsage::new(model, usage)))
     }
 
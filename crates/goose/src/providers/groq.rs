@@ -4,7 +4,7 @@ use crate::message::Message;
 use crate::model::ModelConfig;
 use crate::providers::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
 use crate::providers::formats::openai::{create_request, get_usage, response_to_message};
-use crate::providers::utils::get_model;
+use crate::providers::utils::{get_model, get_retry_after};
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::{Client, StatusCode};
@@ -71,6 +71,7 @@ impl GroqProvider {
             .await?;
 
         let status = response.status();
+        let retry_delay = get_retry_after(response.headers());
         let response_payload: Option<Value> = response.json().await.ok();
         let formatted_payload = format!("{:?}", response_payload);
 
@@ -84,9 +85,12 @@ impl GroqProvider {
                 Err(ProviderError::ContextLengthExceeded(formatted_payload))
             }
             StatusCode::TOO_MANY_REQUESTS => {
-                Err(ProviderError::RateLimitExceeded(formatted_payload))
+                Err(ProviderError::RateLimitExceeded {
+                    details: formatted_payload,
+                    retry_delay,
+                })
             }
-            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+            status if status.is_server_error() => {
                 Err(ProviderError::ServerError(formatted_payload))
             }
             _ => {
@@ -119,18 +123,29 @@ impl Provider for GroqProvider {
         self.model.clone()
     }
 
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_with_model(&self.model, system, messages, tools)
+            .await
+    }
+
     #[tracing::instrument(
-        skip(self, system, messages, tools),
+        skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
     )]
-    async fn complete(
+    async fn complete_with_model(
         &self,
+        model_config: &ModelConfig,
         system: &str,
         messages: &[Message],
         tools: &[Tool],
     ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
         let payload = create_request(
-            &self.model,
+            model_config,
             system,
             messages,
             tools,
@@ -145,7 +160,7 @@ impl Provider for GroqProvider {
             Usage::default()
         });
         let model = get_model(&response);
-        super::utils::emit_debug_trace(&self.model, &payload, &response, &usage);
+        super::utils::emit_debug_trace(model_config, &payload, &response, &usage);
         Ok((message, ProviderUsage::new(model, usage)))
     }
 
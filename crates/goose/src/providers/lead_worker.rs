@@ -409,6 +409,25 @@ impl Provider for LeadWorkerProvider {
         final_result
     }
 
+    async fn complete_with_model(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        // Requests with their own limits, like sampling, aren't turns of the session, so they
+        // use the active model without moving the lead/worker switch
+        let provider = self.get_active_provider().await;
+        let active_config = provider
+            .get_model_config()
+            .with_max_tokens(model_config.max_tokens)
+            .with_temperature(model_config.temperature);
+        provider
+            .complete_with_model(&active_config, system, messages, tools)
+            .await
+    }
+
     async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
         // Combine models from both providers
         let lead_models = self.lead_provider.fetch_supported_models_async().await?;
@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig, DTYPE};
+use etcetera::{choose_app_strategy, AppStrategy};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage};
+use super::errors::ProviderError;
+use crate::message::Message;
+use crate::model::ModelConfig;
+use rmcp::model::Tool;
+
+/// Name of the embedding-only provider, for `GOOSE_EMBEDDING_MODEL_PROVIDER`
+pub const LOCAL_EMBEDDING_PROVIDER: &str = "local";
+/// Config key for the directory holding the sentence-embedding model
+pub const GOOSE_LOCAL_EMBEDDING_MODEL_PATH: &str = "GOOSE_LOCAL_EMBEDDING_MODEL_PATH";
+
+pub const LOCAL_EMBEDDING_DEFAULT_MODEL: &str = "all-MiniLM-L6-v2";
+/// Longer inputs are truncated; tool descriptions rarely come close
+const MAX_TOKENS: usize = 256;
+
+/// A BERT-style sentence-embedding model loaded from disk
+///
+/// The model directory must contain `config.json`, `tokenizer.json` and `model.safetensors`,
+/// as published for models like sentence-transformers/all-MiniLM-L6-v2. Embeddings are the
+/// mean of the token embeddings, normalized to unit length.
+struct LocalEmbeddingModel {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+    dimension: usize,
+}
+
+impl LocalEmbeddingModel {
+    fn load(dir: &Path) -> Result<Self> {
+        let read = |name: &str| {
+            std::fs::read_to_string(dir.join(name)).with_context(|| {
+                format!(
+                    "Failed to read {} from the local embedding model at {}",
+                    name,
+                    dir.display()
+                )
+            })
+        };
+
+        let config_json = read("config.json")?;
+        let config: BertConfig =
+            serde_json::from_str(&config_json).context("Invalid embedding model config.json")?;
+        let dimension = serde_json::from_str::<serde_json::Value>(&config_json)?
+            .get("hidden_size")
+            .and_then(|v| v.as_u64())
+            .context("The embedding model config.json has no hidden_size")?
+            as usize;
+
+        let mut tokenizer = Tokenizer::from_file(dir.join("tokenizer.json"))
+            .map_err(|e| anyhow::anyhow!("Failed to load the embedding tokenizer: {}", e))?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_TOKENS,
+                ..TruncationParams::default()
+            }))
+            .map_err(|e| anyhow::anyhow!("Failed to configure the embedding tokenizer: {}", e))?;
+
+        let device = Device::Cpu;
+        let weights = dir.join("model.safetensors");
+        // Safety: the weights file is only read, and is not expected to change while goose runs
+        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[weights], DTYPE, &device)? };
+        let model = BertModel::load(vb, &config).context("Failed to load the embedding model")?;
+
+        Ok(Self {
+            model,
+            tokenizer,
+            device,
+            dimension,
+        })
+    }
+
+    fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(|e| anyhow::anyhow!("Failed to tokenize: {}", e))?;
+
+        let ids = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_ids(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let masks = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_attention_mask(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let input_ids = Tensor::stack(&ids, 0)?;
+        let attention_mask = Tensor::stack(&masks, 0)?;
+        let token_type_ids = input_ids.zeros_like()?;
+
+        let hidden = self
+            .model
+            .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+
+        // Mean pooling over the real tokens, ignoring padding
+        let mask = attention_mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+        let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+        let pooled = summed.broadcast_div(&mask.sum(1)?)?;
+        let normalized = pooled.broadcast_div(&pooled.sqr()?.sum_keepdim(1)?.sqrt()?)?;
+
+        Ok(normalized.to_vec2::<f32>()?)
+    }
+}
+
+/// Embedding-only provider backed by a local model, so vector tool routing works offline
+pub struct LocalEmbeddingProvider {
+    model: Arc<LocalEmbeddingModel>,
+    model_config: ModelConfig,
+}
+
+impl LocalEmbeddingProvider {
+    pub fn from_env(model_config: ModelConfig) -> Result<Self> {
+        let config = crate::config::Config::global();
+        let path = match config.get_param::<String>(GOOSE_LOCAL_EMBEDDING_MODEL_PATH) {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => Self::default_model_path()?,
+        };
+        Self::load(&path, model_config)
+    }
+
+    pub fn load(path: &Path, model_config: ModelConfig) -> Result<Self> {
+        let model = LocalEmbeddingModel::load(path)?;
+        tracing::info!(
+            "Loaded local embedding model from {} ({} dimensions)",
+            path.display(),
+            model.dimension
+        );
+        Ok(Self {
+            model: Arc::new(model),
+            model_config,
+        })
+    }
+
+    /// Where the model is looked for when no path is configured
+    pub fn default_model_path() -> Result<PathBuf> {
+        Ok(choose_app_strategy(crate::config::APP_STRATEGY.clone())?
+            .data_dir()
+            .join("models")
+            .join(LOCAL_EMBEDDING_DEFAULT_MODEL))
+    }
+
+    /// Length of the vectors this model produces
+    pub fn dimension(&self) -> usize {
+        self.model.dimension
+    }
+}
+
+#[async_trait]
+impl Provider for LocalEmbeddingProvider {
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata::new(
+            LOCAL_EMBEDDING_PROVIDER,
+            "Local Embeddings",
+            "Creates embeddings with a sentence-embedding model on this machine, without network access",
+            LOCAL_EMBEDDING_DEFAULT_MODEL,
+            vec![LOCAL_EMBEDDING_DEFAULT_MODEL],
+            "",
+            vec![ConfigKey::new(
+                GOOSE_LOCAL_EMBEDDING_MODEL_PATH,
+                false,
+                false,
+                None,
+            )],
+        )
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.model_config.clone()
+    }
+
+    async fn complete(
+        &self,
+        _system: &str,
+        _messages: &[Message],
+        _tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        Err(ProviderError::ExecutionError(
+            "The local provider only creates embeddings and can't be used as a chat model"
+                .to_string(),
+        ))
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        true
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let model = self.model.clone();
+        // Inference is CPU bound, so keep it off the async runtime's worker threads
+        tokio::task::spawn_blocking(move || model.embed(texts))
+            .await
+            .map_err(|e| ProviderError::ExecutionError(e.to_string()))?
+            .map_err(|e| ProviderError::ExecutionError(format!("Local embedding failed: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_model_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let model_config = ModelConfig::new(LOCAL_EMBEDDING_DEFAULT_MODEL).unwrap();
+        let err = LocalEmbeddingProvider::load(dir.path(), model_config)
+            .err()
+            .expect("loading from an empty directory should fail");
+        assert!(err.to_string().contains("config.json"));
+    }
+}
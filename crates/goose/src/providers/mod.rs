@@ -8,6 +8,7 @@ pub mod databricks;
 pub mod embedding;
 pub mod errors;
 mod factory;
+pub mod fallback;
 pub mod formats;
 mod gcpauth;
 pub mod gcpvertexai;
@@ -17,11 +18,14 @@ pub mod google;
 pub mod groq;
 pub mod lead_worker;
 pub mod litellm;
+pub mod local_embedding;
 pub mod oauth;
 pub mod ollama;
 pub mod openai;
 pub mod openrouter;
 pub mod pricing;
+pub mod replay;
+pub mod retry;
 pub mod sagemaker_tgi;
 pub mod snowflake;
 pub mod testprovider;
@@ -15,7 +15,9 @@ use tokio_util::io::StreamReader;
 use super::base::{ConfigKey, ModelInfo, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::embedding::{EmbeddingCapable, EmbeddingRequest, EmbeddingResponse};
 use super::errors::ProviderError;
-use super::formats::openai::{create_request, get_usage, response_to_message};
+use super::formats::openai::{
+    create_request, get_usage, response_to_message, update_request_for_cache_control,
+};
 use super::utils::{emit_debug_trace, get_model, handle_response_openai_compat, ImageFormat};
 use crate::impl_provider_default;
 use crate::message::Message;
@@ -161,17 +163,32 @@ impl Provider for OpenAiProvider {
         self.model.clone()
     }
 
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_with_model(&self.model, system, messages, tools)
+            .await
+    }
+
     #[tracing::instrument(
-        skip(self, system, messages, tools),
+        skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
     )]
-    async fn complete(
+    async fn complete_with_model(
         &self,
+        model_config: &ModelConfig,
         system: &str,
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let payload = create_request(&self.model, system, messages, tools, &ImageFormat::OpenAi)?;
+        let mut payload =
+            create_request(model_config, system, messages, tools, &ImageFormat::OpenAi)?;
+        if self.supports_cache_control() {
+            payload = update_request_for_cache_control(&payload);
+        }
 
         // Make request
         let response = handle_response_openai_compat(self.post(&payload).await?).await?;
@@ -183,7 +200,7 @@ impl Provider for OpenAiProvider {
             Usage::default()
         });
         let model = get_model(&response);
-        emit_debug_trace(&self.model, &payload, &response, &usage);
+        emit_debug_trace(model_config, &payload, &response, &usage);
         Ok((message, ProviderUsage::new(model, usage)))
     }
 
@@ -241,6 +258,11 @@ impl Provider for OpenAiProvider {
         true
     }
 
+    fn supports_cache_control(&self) -> bool {
+        // Claude models behind an OpenAI-compatible endpoint accept Anthropic cache breakpoints
+        self.model.model_name.contains("claude")
+    }
+
     async fn stream(
         &self,
         system: &str,
@@ -249,6 +271,9 @@ impl Provider for OpenAiProvider {
     ) -> Result<MessageStream, ProviderError> {
         let mut payload =
             create_request(&self.model, system, messages, tools, &ImageFormat::OpenAi)?;
+        if self.supports_cache_control() {
+            payload = update_request_for_cache_control(&payload);
+        }
         payload["stream"] = serde_json::Value::Bool(true);
         payload["stream_options"] = json!({
             "include_usage": true,
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -7,6 +8,8 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 
+use super::base::Usage;
+
 /// Disk cache configuration
 const CACHE_FILE_NAME: &str = "pricing_cache.json";
 const CACHE_TTL_DAYS: u64 = 7; // Cache for 7 days
@@ -38,6 +41,30 @@ pub struct PricingInfo {
     pub input_cost: f64,  // Cost per token
     pub output_cost: f64, // Cost per token
     pub context_length: Option<u32>,
+    /// Cost per token read from the prompt cache, when it is discounted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_cost: Option<f64>,
+    /// Cost per token written to the prompt cache, when it differs from the input cost
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_cost: Option<f64>,
+}
+
+impl PricingInfo {
+    /// Cost in USD of the tokens in `usage`
+    ///
+    /// Cached tokens are counted in the input tokens. They are charged at the cache read and
+    /// write prices where the model has them, and at the input price otherwise.
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let tokens = |count: Option<i32>| count.unwrap_or(0).max(0) as f64;
+        let cache_read = tokens(usage.cache_read_input_tokens);
+        let cache_write = tokens(usage.cache_write_input_tokens);
+        let uncached_input = (tokens(usage.input_tokens) - cache_read - cache_write).max(0.0);
+
+        uncached_input * self.input_cost
+            + cache_read * self.cache_read_cost.unwrap_or(self.input_cost)
+            + cache_write * self.cache_write_cost.unwrap_or(self.input_cost)
+            + tokens(usage.output_tokens) * self.output_cost
+    }
 }
 
 /// Cache for OpenRouter pricing data with disk persistence
@@ -160,6 +187,16 @@ impl PricingCache {
                             input_cost,
                             output_cost,
                             context_length: model.context_length,
+                            cache_read_cost: model
+                                .pricing
+                                .input_cache_read
+                                .as_deref()
+                                .and_then(convert_pricing),
+                            cache_write_cost: model
+                                .pricing
+                                .input_cache_write
+                                .as_deref()
+                                .and_then(convert_pricing),
                         },
                     );
                 }
@@ -257,6 +294,10 @@ pub struct OpenRouterModel {
 pub struct OpenRouterPricing {
     pub prompt: String,     // Cost per token for input (in USD)
     pub completion: String, // Cost per token for output (in USD)
+    #[serde(default)]
+    pub input_cache_read: Option<String>, // Cost per token read from the prompt cache
+    #[serde(default)]
+    pub input_cache_write: Option<String>, // Cost per token written to the prompt cache
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -308,6 +349,26 @@ pub async fn get_model_pricing(provider: &str, model: &str) -> Option<PricingInf
     PRICING_CACHE.get_model_pricing(provider, model).await
 }
 
+/// Estimate the cost in USD of a request's usage, if pricing for the model is known
+pub async fn estimate_cost(provider: &str, model: &str, usage: &Usage) -> Option<f64> {
+    // For OpenRouter, parse the model name to extract real provider/model
+    let openrouter_data = if provider == "openrouter" {
+        parse_model_id(model)
+    } else {
+        None
+    };
+
+    let (provider_to_use, model_to_use) = match &openrouter_data {
+        Some((real_provider, real_model)) => (real_provider.as_str(), real_model.as_str()),
+        None => (provider, model),
+    };
+
+    let cleaned_model = normalize_model_name(model_to_use);
+    get_model_pricing(provider_to_use, &cleaned_model)
+        .await
+        .map(|pricing| pricing.cost(usage))
+}
+
 /// Force refresh pricing data
 pub async fn refresh_pricing() -> Result<()> {
     PRICING_CACHE.refresh().await
@@ -360,6 +421,31 @@ pub fn parse_model_id(model_id: &str) -> Option<(String, String)> {
     }
 }
 
+/// Map a provider's model name to the name OpenRouter prices it under
+/// e.g., "claude-3-5-haiku-20241022" -> "claude-3.5-haiku"
+pub fn normalize_model_name(model: &str) -> String {
+    let mut result = model.to_string();
+
+    // Remove "-latest" suffix
+    if result.ends_with("-latest") {
+        result = result.strip_suffix("-latest").unwrap().to_string();
+    }
+
+    // Remove date-like suffixes: -YYYYMMDD
+    let re_date = Regex::new(r"-\d{8}$").unwrap();
+    if re_date.is_match(&result) {
+        result = re_date.replace(&result, "").to_string();
+    }
+
+    // Convert version numbers like -3-5- to -3.5- (e.g., claude-3-5-haiku -> claude-3.5-haiku)
+    let re_version = Regex::new(r"-(\d+)-(\d+)-").unwrap();
+    if re_version.is_match(&result) {
+        result = re_version.replace(&result, "-$1.$2-").to_string();
+    }
+
+    result
+}
+
 /// Convert OpenRouter pricing to cost per token (already in that format)
 pub fn convert_pricing(price_str: &str) -> Option<f64> {
     // OpenRouter prices are already in USD per token
@@ -396,6 +482,42 @@ mod tests {
         assert_eq!(convert_pricing("invalid"), None);
     }
 
+    #[test]
+    fn test_normalize_model_name() {
+        assert_eq!(
+            normalize_model_name("claude-3-5-haiku-20241022"),
+            "claude-3.5-haiku"
+        );
+        assert_eq!(normalize_model_name("gpt-4o-latest"), "gpt-4o");
+    }
+
+    #[test]
+    fn test_cost_discounts_cached_tokens() {
+        let pricing = PricingInfo {
+            input_cost: 0.000003,
+            output_cost: 0.000015,
+            context_length: None,
+            cache_read_cost: Some(0.0000003),
+            cache_write_cost: Some(0.00000375),
+        };
+        let usage = Usage::new(Some(11_000), Some(1_000), Some(12_000))
+            .with_cache_tokens(Some(8_000), Some(2_000));
+
+        // 1,000 uncached, 8,000 read and 2,000 written input tokens, and 1,000 output tokens
+        let expected =
+            1_000.0 * 0.000003 + 8_000.0 * 0.0000003 + 2_000.0 * 0.00000375 + 1_000.0 * 0.000015;
+        assert!((pricing.cost(&usage) - expected).abs() < 1e-12);
+
+        // Without cache prices, cached tokens cost the same as other input tokens
+        let pricing = PricingInfo {
+            cache_read_cost: None,
+            cache_write_cost: None,
+            ..pricing
+        };
+        let expected = 11_000.0 * 0.000003 + 1_000.0 * 0.000015;
+        assert!((pricing.cost(&usage) - expected).abs() < 1e-12);
+    }
+
     #[tokio::test]
     async fn test_claude_sonnet_4_pricing_lookup() {
         // Initialize the cache to load from disk
@@ -0,0 +1,519 @@
+use anyhow::Result;
+use async_stream::try_stream;
+use async_trait::async_trait;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage};
+use super::errors::ProviderError;
+use crate::message::Message;
+use crate::model::ModelConfig;
+use rmcp::model::Tool;
+
+/// Config key for the recording file read in replay mode and written in record mode
+pub const GOOSE_REPLAY_FILE: &str = "GOOSE_REPLAY_FILE";
+/// Config key naming the real provider to wrap; when set the replay provider records
+pub const GOOSE_REPLAY_RECORD_PROVIDER: &str = "GOOSE_REPLAY_RECORD_PROVIDER";
+
+const RECORDING_VERSION: u32 = 1;
+
+/// Timestamps such as the current date in the system prompt change on every run
+static TIMESTAMP_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExchangeKind {
+    Complete,
+    Stream,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    system: String,
+    messages: Vec<Message>,
+    tools: Vec<Tool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedChunk {
+    message: Option<Message>,
+    usage: Option<ProviderUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum RecordedResponse {
+    Complete {
+        message: Message,
+        usage: ProviderUsage,
+    },
+    Stream {
+        chunks: Vec<RecordedChunk>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Exchange {
+    /// Kept for humans diffing recordings; only the key is used for lookups
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Recording {
+    version: u32,
+    model: String,
+    streaming: bool,
+    exchanges: BTreeMap<String, Exchange>,
+}
+
+/// Records provider exchanges to a file and serves them back without a network
+///
+/// In record mode every `complete` and `stream` call is forwarded to the wrapped provider
+/// and the exchange is written to the recording file straight away, so an interrupted run
+/// keeps what it recorded. In replay mode the recording is the only source of responses
+/// and a request that was never recorded is an error.
+///
+/// Exchanges are keyed by a hash of the system prompt, messages and tools with the parts
+/// that vary between runs (message ids and creation times, timestamps in the system
+/// prompt, tool order) normalized away.
+pub struct ReplayProvider {
+    inner: Option<Arc<dyn Provider>>,
+    model: ModelConfig,
+    path: PathBuf,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl ReplayProvider {
+    pub fn from_env(model: ModelConfig) -> Result<Self> {
+        let config = crate::config::Config::global();
+        let path: String = config.get_param(GOOSE_REPLAY_FILE).map_err(|_| {
+            anyhow::anyhow!(
+                "{} must be set to use the replay provider",
+                GOOSE_REPLAY_FILE
+            )
+        })?;
+
+        match config.get_param::<String>(GOOSE_REPLAY_RECORD_PROVIDER) {
+            Ok(provider_name) => {
+                if provider_name == "replay" {
+                    return Err(anyhow::anyhow!(
+                        "{} must name a real provider",
+                        GOOSE_REPLAY_RECORD_PROVIDER
+                    ));
+                }
+                // The replay provider gets the retry wrapper, so record the bare provider
+                let inner = super::factory::create_base_provider(&provider_name, model)?;
+                Self::new_recording(inner, path)
+            }
+            Err(_) => Self::new_replaying(path, model),
+        }
+    }
+
+    /// Wrap `inner` and record its exchanges to `path`, keeping exchanges already there
+    pub fn new_recording(inner: Arc<dyn Provider>, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let model = inner.get_model_config();
+        let mut recording = if path.exists() {
+            load_recording(&path)?
+        } else {
+            Recording {
+                version: RECORDING_VERSION,
+                model: model.model_name.clone(),
+                streaming: false,
+                exchanges: BTreeMap::new(),
+            }
+        };
+        recording.streaming = inner.supports_streaming();
+
+        Ok(Self {
+            inner: Some(inner),
+            model,
+            path,
+            recording: Arc::new(Mutex::new(recording)),
+        })
+    }
+
+    /// Serve the exchanges recorded in `path`
+    pub fn new_replaying(path: impl Into<PathBuf>, model: ModelConfig) -> Result<Self> {
+        let path = path.into();
+        let recording = load_recording(&path)?;
+
+        Ok(Self {
+            inner: None,
+            model,
+            path,
+            recording: Arc::new(Mutex::new(recording)),
+        })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    pub fn exchange_count(&self) -> usize {
+        self.recording.lock().unwrap().exchanges.len()
+    }
+
+    fn lookup(&self, key: &str) -> Result<RecordedResponse, ProviderError> {
+        let recording = self.recording.lock().unwrap();
+        recording
+            .exchanges
+            .get(key)
+            .map(|exchange| exchange.response.clone())
+            .ok_or_else(|| {
+                let message = format!(
+                    "No recorded exchange {} in {}. The conversation diverged from the recording; re-record it with {} set",
+                    key,
+                    self.path.display(),
+                    GOOSE_REPLAY_RECORD_PROVIDER
+                );
+                tracing::error!("{}", message);
+                ProviderError::ExecutionError(message)
+            })
+    }
+}
+
+fn load_recording(path: &Path) -> Result<Recording> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read recording {}: {}", path.display(), e))?;
+    let recording: Recording = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid recording {}: {}", path.display(), e))?;
+    if recording.version != RECORDING_VERSION {
+        return Err(anyhow::anyhow!(
+            "Recording {} has version {}, expected {}",
+            path.display(),
+            recording.version,
+            RECORDING_VERSION
+        ));
+    }
+    Ok(recording)
+}
+
+/// Add an exchange and write the whole recording, replacing the file atomically
+fn record_exchange(
+    recording: &Mutex<Recording>,
+    path: &Path,
+    key: String,
+    exchange: Exchange,
+) -> Result<(), ProviderError> {
+    let content = {
+        let mut recording = recording.lock().unwrap();
+        recording.exchanges.insert(key, exchange);
+        serde_json::to_string_pretty(&*recording)
+            .map_err(|e| ProviderError::ExecutionError(e.to_string()))?
+    };
+
+    let write = || -> std::io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)
+    };
+    write().map_err(|e| {
+        ProviderError::ExecutionError(format!(
+            "Failed to write recording {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Hash the parts of a request that determine the response
+fn exchange_key(kind: ExchangeKind, system: &str, messages: &[Message], tools: &[Tool]) -> String {
+    let messages: Vec<Value> = messages
+        .iter()
+        .map(|message| json!({ "role": message.role, "content": message.content }))
+        .collect();
+
+    let mut tools: Vec<Value> = tools
+        .iter()
+        .map(|tool| {
+            json!({
+                "name": tool.name,
+                "description": tool.description,
+                "input_schema": tool.input_schema,
+            })
+        })
+        .collect();
+    tools.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+    let normalized = json!({
+        "kind": kind,
+        "system": TIMESTAMP_RE.replace_all(system, "<timestamp>"),
+        "messages": messages,
+        "tools": tools,
+    });
+
+    let mut hasher = Sha256::new();
+    hasher.update(normalized.to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata::new(
+            "replay",
+            "Replay",
+            "Records exchanges with another provider and replays them offline",
+            "replay",
+            vec![],
+            "",
+            vec![
+                ConfigKey::new(GOOSE_REPLAY_FILE, true, false, None),
+                ConfigKey::new(GOOSE_REPLAY_RECORD_PROVIDER, false, false, None),
+            ],
+        )
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let key = exchange_key(ExchangeKind::Complete, system, messages, tools);
+
+        let Some(inner) = &self.inner else {
+            return match self.lookup(&key)? {
+                RecordedResponse::Complete { message, usage } => Ok((message, usage)),
+                RecordedResponse::Stream { .. } => Err(ProviderError::ExecutionError(format!(
+                    "Recorded exchange {} is a stream, not a completion",
+                    key
+                ))),
+            };
+        };
+
+        let (message, usage) = inner.complete(system, messages, tools).await?;
+        let exchange = Exchange {
+            request: RecordedRequest {
+                system: system.to_string(),
+                messages: messages.to_vec(),
+                tools: tools.to_vec(),
+            },
+            response: RecordedResponse::Complete {
+                message: message.clone(),
+                usage: usage.clone(),
+            },
+        };
+        record_exchange(&self.recording, &self.path, key, exchange)?;
+
+        Ok((message, usage))
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.model.clone()
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let key = exchange_key(ExchangeKind::Stream, system, messages, tools);
+
+        let Some(inner) = &self.inner else {
+            return match self.lookup(&key)? {
+                RecordedResponse::Stream { chunks } => Ok(Box::pin(futures::stream::iter(
+                    chunks
+                        .into_iter()
+                        .map(|chunk| Ok((chunk.message, chunk.usage))),
+                ))),
+                RecordedResponse::Complete { .. } => Err(ProviderError::ExecutionError(format!(
+                    "Recorded exchange {} is a completion, not a stream",
+                    key
+                ))),
+            };
+        };
+
+        let mut inner_stream = inner.stream(system, messages, tools).await?;
+        let request = RecordedRequest {
+            system: system.to_string(),
+            messages: messages.to_vec(),
+            tools: tools.to_vec(),
+        };
+        let recording = self.recording.clone();
+        let path = self.path.clone();
+
+        // Pass chunks through as they arrive and only record streams that finish cleanly
+        Ok(Box::pin(try_stream! {
+            let mut chunks = Vec::new();
+            while let Some(item) = inner_stream.next().await {
+                let (message, usage) = item?;
+                chunks.push(RecordedChunk {
+                    message: message.clone(),
+                    usage: usage.clone(),
+                });
+                yield (message, usage);
+            }
+            let exchange = Exchange {
+                request,
+                response: RecordedResponse::Stream { chunks },
+            };
+            record_exchange(&recording, &path, key, exchange)?;
+        }))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        match &self.inner {
+            Some(inner) => inner.supports_streaming(),
+            None => self.recording.lock().unwrap().streaming,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+    use rmcp::object;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::tempdir;
+
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok((
+                Message::assistant().with_text(format!("reply {} to {}", call, messages.len())),
+                ProviderUsage::new("counting".to_string(), Usage::default()),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("counting")
+        }
+
+        async fn stream(
+            &self,
+            system: &str,
+            messages: &[Message],
+            tools: &[Tool],
+        ) -> Result<MessageStream, ProviderError> {
+            let (message, usage) = self.complete(system, messages, tools).await?;
+            Ok(Box::pin(futures::stream::iter(vec![
+                Ok((Some(Message::assistant().with_text("partial ")), None)),
+                Ok((Some(message), Some(usage))),
+            ])))
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+    }
+
+    fn tool(name: &str) -> Tool {
+        Tool::new(name.to_string(), "a tool".to_string(), object!({}))
+    }
+
+    async fn collect(stream: MessageStream) -> Vec<String> {
+        stream
+            .map(|item| item.unwrap().0.unwrap().as_concat_text())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("recordings").join("session.json");
+        let messages = vec![Message::user().with_text("hello")];
+        let tools = vec![tool("a"), tool("b")];
+
+        let recorder = ReplayProvider::new_recording(
+            Arc::new(CountingProvider {
+                calls: AtomicUsize::new(0),
+            }),
+            &path,
+        )?;
+        let (recorded, _) = recorder
+            .complete("It is 2025-01-01 10:00:00", &messages, &tools)
+            .await?;
+        let recorded_stream = collect(recorder.stream("system", &messages, &tools).await?).await;
+        assert_eq!(recorder.exchange_count(), 2);
+
+        let replayer = ReplayProvider::new_replaying(&path, ModelConfig::new_or_fail("counting"))?;
+        assert!(replayer.supports_streaming());
+
+        // Timestamps, message ids and creation times, and tool order don't affect the key
+        let mut later = Message::user().with_text("hello");
+        later.created += 3600;
+        later.id = Some("msg_1".to_string());
+        let (replayed, _) = replayer
+            .complete(
+                "It is 2026-06-30 23:59:59",
+                &[later],
+                &[tool("b"), tool("a")],
+            )
+            .await?;
+        assert_eq!(replayed.as_concat_text(), recorded.as_concat_text());
+
+        let replayed_stream = collect(replayer.stream("system", &messages, &tools).await?).await;
+        assert_eq!(replayed_stream, recorded_stream);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_miss_fails() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("session.json");
+
+        let recorder = ReplayProvider::new_recording(
+            Arc::new(CountingProvider {
+                calls: AtomicUsize::new(0),
+            }),
+            &path,
+        )?;
+        recorder
+            .complete("system", &[Message::user().with_text("hello")], &[])
+            .await?;
+
+        let replayer = ReplayProvider::new_replaying(&path, ModelConfig::new_or_fail("counting"))?;
+        let err = replayer
+            .complete("system", &[Message::user().with_text("goodbye")], &[])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No recorded exchange"));
+
+        // A recorded completion is not served as a stream
+        assert!(replayer
+            .stream("system", &[Message::user().with_text("hello")], &[])
+            .await
+            .is_err());
+
+        assert!(ReplayProvider::new_replaying(
+            dir.path().join("missing.json"),
+            ModelConfig::new_or_fail("counting")
+        )
+        .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,691 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use rmcp::model::Tool;
+use serde::de::DeserializeOwned;
+
+use super::base::{
+    LeadWorkerProviderTrait, MessageStream, Provider, ProviderMetadata, ProviderUsage,
+};
+use super::errors::ProviderError;
+use crate::config::Config;
+use crate::message::Message;
+use crate::model::ModelConfig;
+
+pub const DEFAULT_MAX_RETRIES: usize = 5;
+pub const DEFAULT_INITIAL_RETRY_INTERVAL_MS: u64 = 1000;
+pub const DEFAULT_BACKOFF_MULTIPLIER: f64 = 2.0;
+pub const DEFAULT_MAX_RETRY_INTERVAL_MS: u64 = 30_000;
+
+/// Rate limiters shared by every provider instance created with the same name
+static LIMITERS: Lazy<Mutex<HashMap<String, Arc<RateLimiter>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// How failed provider calls are retried and how fast calls may be made
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries for transient failures such as server errors and timeouts
+    pub max_retries: usize,
+    /// Retries for rate limit errors
+    pub max_rate_limit_retries: usize,
+    pub initial_interval_ms: u64,
+    pub backoff_multiplier: f64,
+    pub max_interval_ms: u64,
+    /// Requests allowed per minute across all sessions using the provider, unlimited if unset
+    pub requests_per_minute: Option<u32>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            max_rate_limit_retries: DEFAULT_MAX_RETRIES,
+            initial_interval_ms: DEFAULT_INITIAL_RETRY_INTERVAL_MS,
+            backoff_multiplier: DEFAULT_BACKOFF_MULTIPLIER,
+            max_interval_ms: DEFAULT_MAX_RETRY_INTERVAL_MS,
+            requests_per_minute: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Load the policy for a provider from the `GOOSE_PROVIDER_*` settings
+    ///
+    /// Databricks and GCP Vertex AI had their own retry settings before these existed,
+    /// and those still take precedence for their provider.
+    pub fn from_config(provider_name: &str) -> Self {
+        let mut policy = Self::default();
+
+        if let Some(retries) = param("GOOSE_PROVIDER_MAX_RETRIES") {
+            policy.max_retries = retries;
+            policy.max_rate_limit_retries = retries;
+        }
+        if let Some(interval) = param("GOOSE_PROVIDER_INITIAL_RETRY_INTERVAL_MS") {
+            policy.initial_interval_ms = interval;
+        }
+        if let Some(multiplier) = param("GOOSE_PROVIDER_BACKOFF_MULTIPLIER") {
+            policy.backoff_multiplier = multiplier;
+        }
+        if let Some(interval) = param("GOOSE_PROVIDER_MAX_RETRY_INTERVAL_MS") {
+            policy.max_interval_ms = interval;
+        }
+        policy.requests_per_minute = param::<u32>("GOOSE_PROVIDER_REQUESTS_PER_MINUTE");
+
+        let legacy_prefix = match provider_name {
+            "databricks" => Some("DATABRICKS"),
+            "gcp_vertex_ai" => Some("GCP"),
+            _ => None,
+        };
+        if let Some(prefix) = legacy_prefix {
+            if let Some(retries) = param(&format!("{}_MAX_RETRIES", prefix)) {
+                policy.max_retries = retries;
+                policy.max_rate_limit_retries = retries;
+            }
+            if let Some(retries) = param(&format!("{}_MAX_RATE_LIMIT_RETRIES", prefix)) {
+                policy.max_rate_limit_retries = retries;
+            }
+            if let Some(retries) = param(&format!("{}_MAX_OVERLOADED_RETRIES", prefix)) {
+                policy.max_retries = retries;
+            }
+            if let Some(interval) = param(&format!("{}_INITIAL_RETRY_INTERVAL_MS", prefix)) {
+                policy.initial_interval_ms = interval;
+            }
+            if let Some(multiplier) = param(&format!("{}_BACKOFF_MULTIPLIER", prefix)) {
+                policy.backoff_multiplier = multiplier;
+            }
+            if let Some(interval) = param(&format!("{}_MAX_RETRY_INTERVAL_MS", prefix)) {
+                policy.max_interval_ms = interval;
+            }
+        }
+
+        policy.backoff_multiplier = policy.backoff_multiplier.max(1.0);
+        policy.requests_per_minute = policy.requests_per_minute.filter(|rpm| *rpm > 0);
+        policy
+    }
+
+    /// Delay before the given retry, counting from 1, with +/-20% jitter so clients
+    /// that failed together don't retry together
+    pub fn delay_for_attempt(&self, attempt: usize) -> Duration {
+        if attempt == 0 {
+            return Duration::ZERO;
+        }
+
+        let exponent = (attempt - 1).min(i32::MAX as usize) as i32;
+        let base_delay_ms =
+            self.initial_interval_ms as f64 * self.backoff_multiplier.powi(exponent);
+        let capped_delay_ms = base_delay_ms.min(self.max_interval_ms as f64);
+
+        let jitter_factor = 0.8 + (rand::random::<f64>() * 0.4);
+        Duration::from_millis((capped_delay_ms * jitter_factor) as u64)
+    }
+}
+
+/// Read a setting that may be stored as a number or as a string
+fn param<T: DeserializeOwned + FromStr>(key: &str) -> Option<T> {
+    let config = Config::global();
+    config.get_param::<T>(key).ok().or_else(|| {
+        config
+            .get_param::<String>(key)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+    })
+}
+
+/// Whether a failed call is worth repeating
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryClass {
+    /// The provider is rate limiting us, and may have said for how long
+    RateLimited(Option<Duration>),
+    /// A failure that is likely to go away on its own, such as a 503 or a timeout
+    Transient,
+    /// Repeating the call would fail the same way
+    Permanent,
+}
+
+pub fn classify(error: &ProviderError) -> RetryClass {
+    match error {
+        ProviderError::RateLimitExceeded { retry_delay, .. } => {
+            RetryClass::RateLimited(*retry_delay)
+        }
+        ProviderError::ServerError(_) | ProviderError::NetworkError(_) => RetryClass::Transient,
+        _ => RetryClass::Permanent,
+    }
+}
+
+/// Token bucket limiting how often a provider is called
+///
+/// The bucket holds up to one second's worth of requests, so short bursts go through
+/// but the rate over a minute stays under the limit. A rate limit response pauses
+/// every caller, not just the one that hit it.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_minute: Option<u32>,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: Option<u32>) -> Self {
+        Self {
+            requests_per_minute,
+            state: Mutex::new(LimiterState {
+                tokens: bucket_capacity(requests_per_minute),
+                refilled_at: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// The limiter shared by every instance of the named provider
+    pub fn for_provider(name: &str, requests_per_minute: Option<u32>) -> Arc<Self> {
+        let mut limiters = LIMITERS.lock().unwrap();
+        match limiters.get(name) {
+            Some(limiter) if limiter.requests_per_minute == requests_per_minute => {
+                Arc::clone(limiter)
+            }
+            _ => {
+                let limiter = Arc::new(Self::new(requests_per_minute));
+                limiters.insert(name.to_string(), Arc::clone(&limiter));
+                limiter
+            }
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Hold back every caller for `delay`, such as after a rate limit response
+    pub fn pause(&self, delay: Duration) {
+        self.pause_at(Instant::now(), delay);
+    }
+
+    fn pause_at(&self, now: Instant, delay: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let until = now + delay;
+        if state.paused_until.is_none_or(|paused| paused < until) {
+            state.paused_until = Some(until);
+        }
+    }
+
+    /// Take a token if one is available, or return how long to wait for one
+    fn try_acquire(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(until) = state.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            state.paused_until = None;
+        }
+
+        let rpm = self.requests_per_minute?;
+        let per_second = rpm as f64 / 60.0;
+        let elapsed = now.saturating_duration_since(state.refilled_at);
+        state.tokens = (state.tokens + elapsed.as_secs_f64() * per_second)
+            .min(bucket_capacity(self.requests_per_minute));
+        state.refilled_at = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - state.tokens) / per_second))
+        }
+    }
+}
+
+fn bucket_capacity(requests_per_minute: Option<u32>) -> f64 {
+    requests_per_minute.map_or(0.0, |rpm| (rpm as f64 / 60.0).max(1.0))
+}
+
+/// Wraps a provider so every call is rate limited and retried the same way
+///
+/// Rate limit errors and transient failures are retried with exponential backoff,
+/// waiting as long as the provider asked if it sent a `Retry-After` header. Other
+/// errors are returned straight away. Streams are only retried while opening them.
+pub struct RetryProvider {
+    name: String,
+    inner: Arc<dyn Provider>,
+    policy: RetryPolicy,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RetryProvider {
+    pub fn new(name: &str, inner: Arc<dyn Provider>, policy: RetryPolicy) -> Self {
+        let limiter = RateLimiter::for_provider(name, policy.requests_per_minute);
+        Self {
+            name: name.to_string(),
+            inner,
+            policy,
+            limiter,
+        }
+    }
+
+    /// Wrap a provider using the retry settings from the config
+    pub fn wrap(name: &str, inner: Arc<dyn Provider>) -> Arc<dyn Provider> {
+        Arc::new(Self::new(name, inner, RetryPolicy::from_config(name)))
+    }
+
+    /// Wrap a provider that is a link in a fallback chain
+    ///
+    /// The link is rate limited like any other provider, but its errors are returned
+    /// straight away so the chain can move on to the next provider.
+    pub fn wrap_link(name: &str, inner: Arc<dyn Provider>) -> Arc<dyn Provider> {
+        let policy = RetryPolicy {
+            max_retries: 0,
+            max_rate_limit_retries: 0,
+            ..RetryPolicy::from_config(name)
+        };
+        Arc::new(Self::new(name, inner, policy))
+    }
+
+    /// Wrap a fallback chain, retrying it once every provider in it has failed
+    pub fn wrap_chain(name: &str, chain: Arc<dyn Provider>) -> Arc<dyn Provider> {
+        // Each link has its own rate limiter, so the chain doesn't need one
+        let policy = RetryPolicy {
+            requests_per_minute: None,
+            ..RetryPolicy::from_config(name)
+        };
+        Arc::new(Self::new(
+            &format!("{} fallback chain", name),
+            chain,
+            policy,
+        ))
+    }
+
+    async fn with_retries<T, F, Fut>(
+        &self,
+        operation: &str,
+        mut call: F,
+    ) -> Result<T, ProviderError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            self.limiter.acquire().await;
+            let error = match call().await {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+
+            attempt += 1;
+            let (max_retries, requested_delay) = match classify(&error) {
+                RetryClass::Permanent => return Err(error),
+                RetryClass::RateLimited(delay) => (self.policy.max_rate_limit_retries, delay),
+                RetryClass::Transient => (self.policy.max_retries, None),
+            };
+            if attempt > max_retries {
+                tracing::error!(
+                    "{} {} failed after {} retries: {}",
+                    self.name,
+                    operation,
+                    max_retries,
+                    error
+                );
+                return Err(error);
+            }
+
+            // A provider asking for a longer wait than we allow gets the longest we allow
+            let delay = requested_delay
+                .map(|delay| delay.min(Duration::from_millis(self.policy.max_interval_ms)))
+                .unwrap_or_else(|| self.policy.delay_for_attempt(attempt));
+            tracing::warn!(
+                "{} {} failed ({}), retrying in {:?} ({}/{})",
+                self.name,
+                operation,
+                error,
+                delay,
+                attempt,
+                max_retries
+            );
+            if matches!(error, ProviderError::RateLimitExceeded { .. }) {
+                self.limiter.pause(delay);
+            } else {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Record which provider served a request, unless a wrapped provider already has
+fn tag_usage(usage: ProviderUsage, provider: &str) -> ProviderUsage {
+    if usage.provider.is_some() {
+        usage
+    } else {
+        usage.with_provider(provider)
+    }
+}
+
+#[async_trait]
+impl Provider for RetryProvider {
+    fn metadata() -> ProviderMetadata {
+        // The wrapped provider's metadata is looked up by name, not through the wrapper
+        ProviderMetadata::empty()
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let (message, usage) = self
+            .with_retries("completion", || {
+                self.inner.complete(system, messages, tools)
+            })
+            .await?;
+        Ok((message, tag_usage(usage, &self.name)))
+    }
+
+    async fn complete_with_model(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let (message, usage) = self
+            .with_retries("completion", || {
+                self.inner
+                    .complete_with_model(model_config, system, messages, tools)
+            })
+            .await?;
+        Ok((message, tag_usage(usage, &self.name)))
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.inner.get_model_config()
+    }
+
+    async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.with_retries("model listing", || {
+            self.inner.fetch_supported_models_async()
+        })
+        .await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    fn supports_cache_control(&self) -> bool {
+        self.inner.supports_cache_control()
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        self.with_retries("embedding", || self.inner.create_embeddings(texts.clone()))
+            .await
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        self.inner.as_lead_worker()
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let stream = self
+            .with_retries("stream", || self.inner.stream(system, messages, tools))
+            .await?;
+        let name = self.name.clone();
+        Ok(Box::pin(stream.map(move |item| {
+            item.map(|(message, usage)| (message, usage.map(|usage| tag_usage(usage, &name))))
+        })))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn get_active_model_name(&self) -> String {
+        self.inner.get_active_model_name()
+    }
+
+    async fn generate_session_name(&self, messages: &[Message]) -> Result<String, ProviderError> {
+        self.inner.generate_session_name(messages).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FlakyProvider {
+        model_config: ModelConfig,
+        calls: AtomicUsize,
+        errors: Mutex<Vec<ProviderError>>,
+    }
+
+    impl FlakyProvider {
+        fn new(errors: Vec<ProviderError>) -> Self {
+            Self {
+                model_config: ModelConfig::new_or_fail("test-model"),
+                calls: AtomicUsize::new(0),
+                errors: Mutex::new(errors),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for FlakyProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let mut errors = self.errors.lock().unwrap();
+            if errors.is_empty() {
+                Ok((
+                    Message::assistant().with_text("ok"),
+                    ProviderUsage::new("test-model".to_string(), Usage::default()),
+                ))
+            } else {
+                Err(errors.remove(0))
+            }
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            max_rate_limit_retries: 2,
+            initial_interval_ms: 1,
+            backoff_multiplier: 2.0,
+            max_interval_ms: 5,
+            requests_per_minute: None,
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let delay = Some(Duration::from_secs(3));
+        assert_eq!(
+            classify(&ProviderError::RateLimitExceeded {
+                details: "slow down".to_string(),
+                retry_delay: delay,
+            }),
+            RetryClass::RateLimited(delay)
+        );
+        assert_eq!(
+            classify(&ProviderError::ServerError("500".to_string())),
+            RetryClass::Transient
+        );
+        assert_eq!(
+            classify(&ProviderError::NetworkError(
+                "operation timed out".to_string()
+            )),
+            RetryClass::Transient
+        );
+        assert_eq!(
+            classify(&ProviderError::RequestFailed(
+                "Request failed with status: 503 Service Unavailable".to_string()
+            )),
+            RetryClass::Permanent
+        );
+        assert_eq!(
+            classify(&ProviderError::ContextLengthExceeded(
+                "too long".to_string()
+            )),
+            RetryClass::Permanent
+        );
+    }
+
+    #[test]
+    fn test_delay_for_attempt() {
+        let policy = RetryPolicy {
+            initial_interval_ms: 1000,
+            max_interval_ms: 32000,
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(policy.delay_for_attempt(0), Duration::ZERO);
+        let delay1 = policy.delay_for_attempt(1).as_millis();
+        assert!((800..=1200).contains(&delay1));
+        let delay2 = policy.delay_for_attempt(2).as_millis();
+        assert!((1600..=2400).contains(&delay2));
+        // Capped at the max interval, plus jitter
+        assert!(policy.delay_for_attempt(10).as_millis() <= 38400);
+        assert!(policy.delay_for_attempt(usize::MAX).as_millis() <= 38400);
+    }
+
+    #[test]
+    fn test_rate_limiter_bucket() {
+        let limiter = RateLimiter::new(Some(120));
+        let start = Instant::now();
+        {
+            let mut state = limiter.state.lock().unwrap();
+            state.refilled_at = start;
+        }
+
+        // A burst of one second's worth goes through, then callers wait for a refill
+        assert_eq!(limiter.try_acquire(start), None);
+        assert_eq!(limiter.try_acquire(start), None);
+        assert_eq!(limiter.try_acquire(start), Some(Duration::from_millis(500)));
+        assert_eq!(
+            limiter.try_acquire(start + Duration::from_millis(500)),
+            None
+        );
+
+        let unlimited = RateLimiter::new(None);
+        assert_eq!(unlimited.try_acquire(start), None);
+    }
+
+    #[test]
+    fn test_rate_limiter_pause() {
+        let limiter = RateLimiter::new(None);
+        let start = Instant::now();
+
+        limiter.pause_at(start, Duration::from_secs(5));
+        // A shorter pause doesn't cut the longer one short
+        limiter.pause_at(start, Duration::from_secs(1));
+        assert_eq!(
+            limiter.try_acquire(start + Duration::from_secs(2)),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(limiter.try_acquire(start + Duration::from_secs(5)), None);
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let inner = Arc::new(FlakyProvider::new(vec![
+            ProviderError::ServerError("unavailable".to_string()),
+            ProviderError::RateLimitExceeded {
+                details: "slow down".to_string(),
+                retry_delay: Some(Duration::from_millis(1)),
+            },
+        ]));
+        let provider = RetryProvider::new("test_retries_transient", inner.clone(), fast_policy());
+
+        let (message, usage) = provider.complete("system", &[], &[]).await.unwrap();
+
+        assert_eq!(message.as_concat_text(), "ok");
+        assert_eq!(usage.provider.as_deref(), Some("test_retries_transient"));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_caps_the_delay_a_provider_asks_for() {
+        let inner = Arc::new(FlakyProvider::new(vec![ProviderError::RateLimitExceeded {
+            details: "come back tomorrow".to_string(),
+            retry_delay: Some(Duration::from_secs(24 * 60 * 60)),
+        }]));
+        let provider =
+            RetryProvider::new("test_caps_requested_delay", inner.clone(), fast_policy());
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            provider.complete("system", &[], &[]),
+        )
+        .await
+        .expect("the requested delay should be capped at max_interval_ms");
+
+        assert!(result.is_ok());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_chain_links_do_not_retry() {
+        let inner = Arc::new(FlakyProvider::new(vec![ProviderError::ServerError(
+            "unavailable".to_string(),
+        )]));
+        let provider = RetryProvider::wrap_link("test_chain_link", inner.clone());
+
+        let result = provider.complete("system", &[], &[]).await;
+
+        assert!(matches!(result, Err(ProviderError::ServerError(_))));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_on_permanent_and_exhausted_errors() {
+        let inner = Arc::new(FlakyProvider::new(vec![ProviderError::Authentication(
+            "bad key".to_string(),
+        )]));
+        let provider = RetryProvider::new("test_gives_up_permanent", inner.clone(), fast_policy());
+        let result = provider.complete("system", &[], &[]).await;
+        assert!(matches!(result, Err(ProviderError::Authentication(_))));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+        let errors = (0..5)
+            .map(|_| ProviderError::ServerError("unavailable".to_string()))
+            .collect();
+        let inner = Arc::new(FlakyProvider::new(errors));
+        let provider = RetryProvider::new("test_gives_up_exhausted", inner.clone(), fast_policy());
+        let result = provider.complete("system", &[], &[]).await;
+        assert!(matches!(result, Err(ProviderError::ServerError(_))));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+}
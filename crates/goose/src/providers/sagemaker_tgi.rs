@@ -5,10 +5,10 @@ use anyhow::Result;
 use async_trait::async_trait;
 use aws_config;
 use aws_sdk_bedrockruntime::config::ProvideCredentials;
+use aws_sdk_sagemakerruntime::operation::invoke_endpoint::InvokeEndpointError;
 use aws_sdk_sagemakerruntime::Client as SageMakerClient;
 use rmcp::model::Tool;
 use serde_json::{json, Value};
-use tokio::time::sleep;
 
 use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::errors::ProviderError;
@@ -165,7 +165,15 @@ impl SageMakerTgiProvider {
             .body(body.into_bytes().into())
             .send()
             .await
-            .map_err(|e| ProviderError::RequestFailed(format!("SageMaker invoke failed: {}", e)))?;
+            .map_err(|err| match err.into_service_error() {
+                err @ (InvokeEndpointError::ServiceUnavailable(_)
+                | InvokeEndpointError::InternalFailure(_)
+                | InvokeEndpointError::InternalDependencyException(_)
+                | InvokeEndpointError::ModelNotReadyException(_)) => {
+                    ProviderError::ServerError(format!("SageMaker invoke failed: {}", err))
+                }
+                err => ProviderError::RequestFailed(format!("SageMaker invoke failed: {}", err)),
+            })?;
 
         let response_body = response
             .body
@@ -279,79 +287,57 @@ impl Provider for SageMakerTgiProvider {
         self.model.clone()
     }
 
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_with_model(&self.model, system, messages, tools)
+            .await
+    }
+
     #[tracing::instrument(
-        skip(self, system, messages, tools),
+        skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
     )]
-    async fn complete(
+    async fn complete_with_model(
         &self,
+        model_config: &ModelConfig,
         system: &str,
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let model_name = &self.model.model_name;
+        let model_name = &model_config.model_name;
 
         let request_payload = self.create_tgi_request(system, messages).map_err(|e| {
             ProviderError::RequestFailed(format!("Failed to create request: {}", e))
         })?;
 
-        // Retry configuration
-        const MAX_RETRIES: u32 = 3;
-        const INITIAL_BACKOFF_MS: u64 = 1000; // 1 second
-        const MAX_BACKOFF_MS: u64 = 30000; // 30 seconds
-
-        let mut attempts = 0;
-        let mut backoff_ms = INITIAL_BACKOFF_MS;
-
-        loop {
-            attempts += 1;
-
-            match self.invoke_endpoint(request_payload.clone()).await {
-                Ok(response) => {
-                    let message = self.parse_tgi_response(response)?;
-
-                    // TGI doesn't provide usage statistics, so we estimate
-                    let usage = Usage {
-                        input_tokens: Some(0),  // Would need to tokenize input to get accurate count
-                        output_tokens: Some(0), // Would need to tokenize output to get accurate count
-                        total_tokens: Some(0),
-                    };
-
-                    // Add debug trace
-                    let debug_payload = serde_json::json!({
-                        "system": system,
-                        "messages": messages,
-                        "tools": tools
-                    });
-                    emit_debug_trace(
-                        &self.model,
-                        &debug_payload,
-                        &serde_json::to_value(&message).unwrap_or_default(),
-                        &usage,
-                    );
-
-                    let provider_usage = ProviderUsage::new(model_name.to_string(), usage);
-                    return Ok((message, provider_usage));
-                }
-                Err(err) => {
-                    if attempts > MAX_RETRIES {
-                        return Err(err);
-                    }
-
-                    // Log retry attempt
-                    tracing::warn!(
-                        "SageMaker TGI request failed (attempt {}/{}), retrying in {} ms: {:?}",
-                        attempts,
-                        MAX_RETRIES,
-                        backoff_ms,
-                        err
-                    );
-
-                    // Wait before retry
-                    sleep(Duration::from_millis(backoff_ms)).await;
-                    backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
-                }
-            }
-        }
+        let response = self.invoke_endpoint(request_payload).await?;
+        let message = self.parse_tgi_response(response)?;
+
+        // TGI doesn't provide usage statistics, so we estimate
+        let usage = Usage::new(
+            Some(0), // Would need to tokenize input to get accurate count
+            Some(0), // Would need to tokenize output to get accurate count
+            Some(0),
+        );
+
+        // Add debug trace
+        let debug_payload = serde_json::json!({
+            "system": system,
+            "messages": messages,
+            "tools": tools
+        });
+        emit_debug_trace(
+            model_config,
+            &debug_payload,
+            &serde_json::to_value(&message).unwrap_or_default(),
+            &usage,
+        );
+
+        let provider_usage = ProviderUsage::new(model_name.to_string(), usage);
+        Ok((message, provider_usage))
     }
 }
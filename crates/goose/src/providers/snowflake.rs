@@ -8,7 +8,7 @@ use std::time::Duration;
 use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage};
 use super::errors::ProviderError;
 use super::formats::snowflake::{create_request, get_usage, response_to_message};
-use super::utils::{get_model, ImageFormat};
+use super::utils::{get_model, get_retry_after, ImageFormat};
 use crate::config::ConfigError;
 use crate::impl_provider_default;
 use crate::message::Message;
@@ -129,6 +129,7 @@ impl SnowflakeProvider {
             .await?;
 
         let status = response.status();
+        let retry_delay = get_retry_after(response.headers());
 
         let payload_text: String = response.text().await.ok().unwrap_or_default();
 
@@ -365,15 +366,13 @@ impl SnowflakeProvider {
                     error_msg
                 )))
             }
-            StatusCode::TOO_MANY_REQUESTS => Err(ProviderError::RateLimitExceeded(
-                "Rate limit exceeded. Please try again later.".to_string(),
+            StatusCode::TOO_MANY_REQUESTS => Err(ProviderError::RateLimitExceeded {
+                details: "Rate limit exceeded. Please try again later.".to_string(),
+                retry_delay,
+            }),
+            status if status.is_server_error() => Err(ProviderError::ServerError(
+                "Snowflake service is temporarily unavailable. Please try again later.".to_string(),
             )),
-            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
-                Err(ProviderError::ServerError(
-                    "Snowflake service is temporarily unavailable. Please try again later."
-                        .to_string(),
-                ))
-            }
             _ => {
                 tracing::debug!(
                     "Provider request failed with status: {}. Response: {}",
@@ -410,17 +409,28 @@ impl Provider for SnowflakeProvider {
         self.model.clone()
     }
 
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        self.complete_with_model(&self.model, system, messages, tools)
+            .await
+    }
+
     #[tracing::instrument(
-        skip(self, system, messages, tools),
+        skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
     )]
-    async fn complete(
+    async fn complete_with_model(
         &self,
+        model_config: &ModelConfig,
         system: &str,
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let payload = create_request(&self.model, system, messages, tools)?;
+        let payload = create_request(model_config, system, messages, tools)?;
 
         let response = self.post(&payload).await?;
 
@@ -428,7 +438,7 @@ impl Provider for SnowflakeProvider {
         let message = response_to_message(&response)?;
         let usage = get_usage(&response)?;
         let model = get_model(&response);
-        super::utils::emit_debug_trace(&self.model, &payload, &response, &usage);
+        super::utils::emit_debug_trace(model_config, &payload, &response, &usage);
 
         Ok((message, ProviderUsage::new(model, usage)))
     }
@@ -4,12 +4,14 @@ use crate::model::ModelConfig;
 use anyhow::Result;
 use base64::Engine;
 use regex::Regex;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
 use reqwest::{Response, StatusCode};
 use rmcp::model::{AnnotateAble, ImageContent, RawImageContent};
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, json, Map, Value};
 use std::io::Read;
 use std::path::Path;
+use std::time::Duration;
 
 use crate::providers::errors::{OpenAIError, ProviderError};
 
@@ -44,11 +46,40 @@ pub fn convert_image(image: &ImageContent, image_format: &ImageFormat) -> Value
     }
 }
 
+/// How long a rate limited response asks the client to wait
+///
+/// Reads the `retry-after-ms` header that OpenAI and Azure send, then the standard
+/// `Retry-After` header in either seconds or HTTP date form.
+pub fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    // Negative, NaN or out of range values are ignored
+    let duration = |value: &str| {
+        value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(|number| Duration::try_from_secs_f64(number).ok())
+    };
+
+    if let Some(ms) = header("retry-after-ms").and_then(duration) {
+        return Some(ms / 1000);
+    }
+    let value = header(RETRY_AFTER.as_str())?;
+    if let Some(secs) = duration(value) {
+        return Some(secs);
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
 /// Handle response from OpenAI compatible endpoints
 /// Error codes: https://platform.openai.com/docs/guides/error-codes
 /// Context window exceeded: https://community.openai.com/t/help-needed-tackling-context-length-limits-in-openai-models/617543
 pub async fn handle_status_openai_compat(response: Response) -> Result<Response, ProviderError> {
     let status = response.status();
+    let retry_delay = get_retry_after(response.headers());
 
     match status {
         StatusCode::OK => Ok(response),
@@ -74,9 +105,12 @@ pub async fn handle_status_openai_compat(response: Response) -> Result<Response,
                     Err(ProviderError::RequestFailed(format!("Unknown error (status {})", status)))
                 }
                 (Ok(body), StatusCode::TOO_MANY_REQUESTS) => {
-                    Err(ProviderError::RateLimitExceeded(format!("{:?}", body)))
+                    Err(ProviderError::RateLimitExceeded {
+                        details: format!("{:?}", body),
+                        retry_delay,
+                    })
                 }
-                (Ok(body), StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE) => {
+                (Ok(body), status) if status.is_server_error() => {
                     Err(ProviderError::ServerError(format!("{:?}", body)))
                 }
                 (Ok(body), _) => {
@@ -149,6 +183,7 @@ fn get_google_final_status(status: StatusCode, payload: Option<&Value>) -> Statu
 /// - `Err(ProviderError)`: Describes the failure reason.
 pub async fn handle_response_google_compat(response: Response) -> Result<Value, ProviderError> {
     let status = response.status();
+    let retry_delay = get_retry_after(response.headers());
     let payload: Option<Value> = response.json().await.ok();
     let final_status = get_google_final_status(status, payload.as_ref());
 
@@ -175,9 +210,12 @@ pub async fn handle_response_google_compat(response: Response) -> Result<Value,
             Err(ProviderError::RequestFailed(format!("Request failed with status: {}. Message: {}", final_status, error_msg)))
         }
         StatusCode::TOO_MANY_REQUESTS => {
-            Err(ProviderError::RateLimitExceeded(format!("{:?}", payload)))
+            Err(ProviderError::RateLimitExceeded {
+                details: format!("{:?}", payload),
+                retry_delay,
+            })
         }
-        StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+        status if status.is_server_error() => {
             Err(ProviderError::ServerError(format!("{:?}", payload)))
         }
         _ => {
@@ -723,4 +761,34 @@ mod tests {
             "Hello\\u0001World"
         );
     }
+
+    #[test]
+    fn test_get_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(get_retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(get_retry_after(&headers), Some(Duration::from_secs(7)));
+
+        // The millisecond header is more precise, so it wins
+        headers.insert("retry-after-ms", "1500".parse().unwrap());
+        assert_eq!(get_retry_after(&headers), Some(Duration::from_millis(1500)));
+
+        let mut headers = HeaderMap::new();
+        let later = chrono::Utc::now() + chrono::Duration::seconds(120);
+        headers.insert(RETRY_AFTER, later.to_rfc2822().parse().unwrap());
+        let delay = get_retry_after(&headers).unwrap();
+        assert!(delay > Duration::from_secs(100) && delay <= Duration::from_secs(120));
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(get_retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "1e300".parse().unwrap());
+        assert_eq!(get_retry_after(&headers), None);
+
+        // An invalid millisecond header falls back to the standard one
+        headers.insert(RETRY_AFTER, "2".parse().unwrap());
+        headers.insert("retry-after-ms", "-5".parse().unwrap());
+        assert_eq!(get_retry_after(&headers), Some(Duration::from_secs(2)));
+    }
 }
@@ -3,8 +3,10 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
 
+use crate::agents::budget::CostBudget;
 use crate::agents::extension::ExtensionConfig;
 use crate::agents::types::RetryConfig;
+use crate::context_mgmt::structured::CompactionConfig;
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -39,6 +41,7 @@ fn default_version() -> String {
 /// * `parameters` - Additional parameters for the Recipe
 /// * `response` - Response configuration including JSON schema validation
 /// * `retry` - Retry configuration for automated validation and recovery
+/// * `compaction` - How auto-compaction condenses sessions run from the Recipe
 /// # Example
 ///
 ///
@@ -68,6 +71,7 @@ fn default_version() -> String {
 ///     response: None,
 ///     sub_recipes: None,
 ///     retry: None,
+///     compaction: None,
 /// };
 ///
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -114,6 +118,9 @@ pub struct Recipe {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compaction: Option<CompactionConfig>, // how auto-compaction condenses the session
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -135,6 +142,10 @@ pub struct Settings {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+
+    /// Spending limits for sessions run from the recipe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<CostBudget>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -250,6 +261,7 @@ pub struct RecipeBuilder {
     response: Option<Response>,
     sub_recipes: Option<Vec<SubRecipe>>,
     retry: Option<RetryConfig>,
+    compaction: Option<CompactionConfig>,
 }
 
 impl Recipe {
@@ -283,6 +295,7 @@ impl Recipe {
             response: None,
             sub_recipes: None,
             retry: None,
+            compaction: None,
         }
     }
     pub fn from_content(content: &str) -> Result<Self> {
@@ -399,6 +412,12 @@ impl RecipeBuilder {
         self
     }
 
+    /// Sets how auto-compaction condenses sessions run from the Recipe
+    pub fn compaction(mut self, compaction: CompactionConfig) -> Self {
+        self.compaction = Some(compaction);
+        self
+    }
+
     /// Builds the Recipe instance
     ///
     /// Returns an error if any required fields are missing
@@ -425,6 +444,7 @@ impl RecipeBuilder {
             response: self.response,
             sub_recipes: self.sub_recipes,
             retry: self.retry,
+            compaction: self.compaction,
         })
     }
 }
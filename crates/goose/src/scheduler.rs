@@ -13,7 +13,7 @@ use tokio::sync::Mutex;
 use tokio_cron_scheduler::{job::JobId, Job, JobScheduler as TokioJobScheduler};
 
 use crate::agents::AgentEvent;
-use crate::agents::{Agent, SessionConfig};
+use crate::agents::{Agent, CostBudget, SessionConfig};
 use crate::config::{self, Config};
 use crate::message::Message;
 use crate::providers::base::Provider as GooseProvider; // Alias to avoid conflict in test section
@@ -93,6 +93,7 @@ pub enum SchedulerError {
     AgentSetupError(String),
     PersistError(String),
     CronParseError(String),
+    UnsupportedJobSetting(String),
     SchedulerInternalError(String),
     AnyhowError(anyhow::Error),
 }
@@ -107,6 +108,9 @@ impl std::fmt::Display for SchedulerError {
             SchedulerError::AgentSetupError(e) => write!(f, "Agent setup error: {}", e),
             SchedulerError::PersistError(e) => write!(f, "Failed to persist schedules: {}", e),
             SchedulerError::CronParseError(e) => write!(f, "Invalid cron string: {}", e),
+            SchedulerError::UnsupportedJobSetting(e) => {
+                write!(f, "Unsupported job setting: {}", e)
+            }
             SchedulerError::SchedulerInternalError(e) => {
                 write!(f, "Scheduler internal error: {}", e)
             }
@@ -159,6 +163,9 @@ pub struct ScheduledJob {
     pub process_start_time: Option<DateTime<Utc>>,
     #[serde(default)]
     pub execution_mode: Option<String>, // "foreground" or "background"
+    /// Spending limits for each run, over those of the recipe's settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<CostBudget>,
 }
 
 async fn persist_jobs_from_arc(
@@ -1114,6 +1121,13 @@ async fn run_scheduled_job_internal(
     }?;
 
     let agent: Agent = Agent::new();
+    if let Some(compaction) = recipe.compaction.clone() {
+        agent.set_compaction_config(compaction).await;
+    }
+    let recipe_budget = recipe.settings.as_ref().and_then(|s| s.budget);
+    agent
+        .set_cost_budget(recipe_budget.unwrap_or_default().merge(job.budget))
+        .await;
 
     let agent_provider: Arc<dyn GooseProvider>; // Use the aliased GooseProvider
 
@@ -1233,12 +1247,24 @@ async fn run_scheduled_job_internal(
                         Ok(AgentEvent::McpNotification(_)) => {
                             // Handle notifications if needed
                         }
+                        Ok(AgentEvent::ToolProgress(_)) => {
+                            // Nobody is watching a scheduled job's progress
+                        }
                         Ok(AgentEvent::ModelChange { .. }) => {
                             // Model change events are informational, just continue
                         }
                         Ok(AgentEvent::HistoryReplaced(_)) => {
                             // Handle history replacement events if needed
                         }
+                        Ok(AgentEvent::MaxTurnsReached) => {
+                            tracing::warn!(
+                                "[Job {}] Stopped at the maximum number of turns",
+                                job.id
+                            );
+                        }
+                        Ok(AgentEvent::Error(e)) => {
+                            tracing::error!("[Job {}] Provider error: {}", job.id, e);
+                        }
                         Err(e) => {
                             tracing::error!(
                                 "[Job {}] Error receiving message from agent: {}",
@@ -1283,6 +1309,10 @@ async fn run_scheduled_job_internal(
                             accumulated_total_tokens: None,
                             accumulated_input_tokens: None,
                             accumulated_output_tokens: None,
+                            accumulated_cost: None,
+                            parent_session_id: None,
+                            fork_message_index: None,
+                            served_by: Vec::new(),
                         };
                         if let Err(e_fb) = crate::session::storage::save_messages_with_metadata(
                             &session_file_path,
@@ -1433,6 +1463,7 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            compaction: None,
         };
         let mut recipe_file = File::create(&recipe_filename)?;
         writeln!(
@@ -1453,6 +1484,7 @@ mod tests {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some("background".to_string()), // Default for test
+            budget: None,
         };
 
         let mock_model_config = ModelConfig::new_or_fail("test_model");
@@ -1464,12 +1496,13 @@ mod tests {
                 .await
                 .expect("run_scheduled_job_internal failed");
 
-        let session_dir = session::storage::ensure_session_dir()?;
-        let expected_session_path = session_dir.join(format!("{}.jsonl", created_session_id));
+        // The path only names the session; with the SQLite store there is no file behind it
+        let expected_session_path =
+            session::storage::get_path(session::Identifier::Name(created_session_id.clone()))?;
 
         assert!(
-            expected_session_path.exists(),
-            "Expected session file {} was not created",
+            session::session_exists(&expected_session_path),
+            "Expected session {} was not created",
             expected_session_path.display()
         );
 
@@ -0,0 +1,207 @@
+use crate::session::info::SessionInfo;
+use crate::session::storage::{
+    generate_session_id, read_messages, read_metadata, save_messages_with_metadata, session_exists,
+    SessionMetadata,
+};
+use crate::session::store::session_id_from_path;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Reasons a fork is refused that are down to the request rather than the session store
+#[derive(Error, Debug)]
+pub enum ForkError {
+    #[error("Session to fork does not exist")]
+    ParentNotFound,
+    #[error("A session with the fork's name already exists")]
+    ForkExists,
+    #[error("Fork point {at} is past the end of the session ({len} messages)")]
+    PastEnd { at: usize, len: usize },
+}
+
+/// Generate an id for a fork
+///
+/// Session ids only resolve to the second, so a random suffix keeps two forks made in the
+/// same second apart.
+pub fn generate_fork_id() -> String {
+    let suffix = Uuid::new_v4().simple().to_string();
+    format!("{}_{}", generate_session_id(), &suffix[..8])
+}
+
+/// Fork a session into a new session containing the messages before `at`
+///
+/// The fork keeps the parent's description and working directory and records the parent
+/// session id and fork point. Token counts start fresh so usage isn't counted twice.
+pub fn fork_session(parent_file: &Path, at: usize, fork_file: &Path) -> Result<SessionMetadata> {
+    if !session_exists(parent_file) {
+        return Err(ForkError::ParentNotFound.into());
+    }
+    if session_exists(fork_file) {
+        return Err(ForkError::ForkExists.into());
+    }
+
+    let messages = read_messages(parent_file)?;
+    if at > messages.len() {
+        return Err(ForkError::PastEnd {
+            at,
+            len: messages.len(),
+        }
+        .into());
+    }
+
+    let parent = read_metadata(parent_file)?;
+    let metadata = SessionMetadata {
+        working_dir: parent.working_dir,
+        description: parent.description,
+        project_id: parent.project_id,
+        message_count: at,
+        parent_session_id: Some(session_id_from_path(parent_file)?),
+        fork_message_index: Some(at),
+        served_by: parent
+            .served_by
+            .into_iter()
+            .filter(|run| run.first_message < at)
+            .collect(),
+        ..SessionMetadata::default()
+    };
+
+    save_messages_with_metadata(fork_file, &metadata, &messages[..at])?;
+    Ok(metadata)
+}
+
+/// Order sessions so that forks follow their parent, paired with their depth in the fork tree
+///
+/// Top-level sessions keep their relative order, as do siblings. Sessions whose parent
+/// is not in the list are treated as top-level.
+pub fn fork_tree(sessions: &[SessionInfo]) -> Vec<(usize, &SessionInfo)> {
+    let ids: HashSet<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<&SessionInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for session in sessions {
+        match session.metadata.parent_session_id.as_deref() {
+            Some(parent) if parent != session.id && ids.contains(parent) => {
+                children.entry(parent).or_default().push(session);
+            }
+            _ => roots.push(session),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(sessions.len());
+    let mut visited = HashSet::new();
+    for root in roots {
+        let mut stack = vec![(0, root)];
+        while let Some((depth, session)) = stack.pop() {
+            if !visited.insert(session.id.as_str()) {
+                continue;
+            }
+            ordered.push((depth, session));
+            if let Some(forks) = children.get(session.id.as_str()) {
+                stack.extend(forks.iter().rev().map(|fork| (depth + 1, *fork)));
+            }
+        }
+    }
+
+    // Sessions caught in a parent cycle are never reached from a root
+    ordered.extend(
+        sessions
+            .iter()
+            .filter(|session| !visited.contains(session.id.as_str()))
+            .map(|session| (0, session)),
+    );
+
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use tempfile::tempdir;
+
+    fn info(id: &str, parent: Option<&str>) -> SessionInfo {
+        SessionInfo {
+            id: id.to_string(),
+            path: format!("{}.jsonl", id),
+            modified: String::new(),
+            metadata: SessionMetadata {
+                parent_session_id: parent.map(str::to_string),
+                ..SessionMetadata::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_generate_fork_id() {
+        let first = generate_fork_id();
+        let second = generate_fork_id();
+        assert_ne!(first, second);
+        // A session id followed by the suffix
+        assert_eq!(first.len(), 24);
+        assert_eq!(&first[15..16], "_");
+    }
+
+    #[test]
+    fn test_fork_session() -> Result<()> {
+        let dir = tempdir()?;
+        let parent = dir.path().join("parent.jsonl");
+        let fork = dir.path().join("fork.jsonl");
+
+        let messages = vec![
+            Message::user().with_text("first"),
+            Message::assistant().with_text("second"),
+            Message::user().with_text("third"),
+        ];
+        let parent_metadata = SessionMetadata {
+            description: "Parent session".to_string(),
+            accumulated_total_tokens: Some(1000),
+            ..SessionMetadata::default()
+        };
+        save_messages_with_metadata(&parent, &parent_metadata, &messages)?;
+
+        let metadata = fork_session(&parent, 2, &fork)?;
+        assert_eq!(metadata.parent_session_id.as_deref(), Some("parent"));
+        assert_eq!(metadata.fork_message_index, Some(2));
+        assert_eq!(metadata.accumulated_total_tokens, None);
+
+        let forked = read_messages(&fork)?;
+        assert_eq!(forked.len(), 2);
+        assert_eq!(forked[1].as_concat_text(), "second");
+        assert_eq!(read_metadata(&fork)?.description, "Parent session");
+
+        // The parent is untouched and forks can't overwrite existing sessions
+        assert_eq!(read_messages(&parent)?.len(), 3);
+        let exists = fork_session(&parent, 1, &fork).unwrap_err();
+        assert!(matches!(
+            exists.downcast_ref::<ForkError>(),
+            Some(ForkError::ForkExists)
+        ));
+        let past_end = fork_session(&parent, 4, &dir.path().join("other.jsonl")).unwrap_err();
+        assert!(matches!(
+            past_end.downcast_ref::<ForkError>(),
+            Some(ForkError::PastEnd { at: 4, len: 3 })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fork_tree_ordering() {
+        let sessions = vec![
+            info("c", Some("a")),
+            info("a", None),
+            info("b", None),
+            info("d", Some("c")),
+            info("e", Some("missing")),
+        ];
+
+        let tree: Vec<(usize, &str)> = fork_tree(&sessions)
+            .into_iter()
+            .map(|(depth, s)| (depth, s.id.as_str()))
+            .collect();
+
+        assert_eq!(tree, vec![(0, "a"), (1, "c"), (2, "d"), (0, "b"), (0, "e")]);
+    }
+}
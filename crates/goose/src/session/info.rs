@@ -1,7 +1,8 @@
-use crate::session::{self, SessionMetadata};
+use crate::session::{self, JsonlSessionStore, SessionMetadata, SessionStore};
 use anyhow::Result;
 use serde::Serialize;
 use std::cmp::Ordering;
+use std::sync::Arc;
 use utoipa::ToSchema;
 
 #[derive(Clone, Serialize, ToSchema)]
@@ -19,7 +20,12 @@ pub enum SortOrder {
 }
 
 pub fn get_valid_sorted_sessions(sort_order: SortOrder) -> Result<Vec<SessionInfo>> {
-    let sessions = match session::list_sessions() {
+    let store: Arc<dyn SessionStore> = match session::configured_store() {
+        Some(store) => store,
+        None => Arc::new(JsonlSessionStore::new(session::ensure_session_dir()?)),
+    };
+
+    let mut session_infos = match store.list() {
         Ok(sessions) => sessions,
         Err(e) => {
             tracing::error!("Failed to list sessions: {:?}", e);
@@ -27,56 +33,6 @@ pub fn get_valid_sorted_sessions(sort_order: SortOrder) -> Result<Vec<SessionInf
         }
     };
 
-    let mut session_infos: Vec<SessionInfo> = Vec::new();
-    let mut corrupted_count = 0;
-
-    for (id, path) in sessions {
-        // Get file modification time with fallback
-        let modified = path
-            .metadata()
-            .and_then(|m| m.modified())
-            .map(|time| {
-                chrono::DateTime::<chrono::Utc>::from(time)
-                    .format("%Y-%m-%d %H:%M:%S UTC")
-                    .to_string()
-            })
-            .unwrap_or_else(|_| {
-                tracing::warn!("Failed to get modification time for session: {}", id);
-                "Unknown".to_string()
-            });
-
-        // Try to read metadata with error handling
-        match session::read_metadata(&path) {
-            Ok(metadata) => {
-                session_infos.push(SessionInfo {
-                    id,
-                    path: path.to_string_lossy().to_string(),
-                    modified,
-                    metadata,
-                });
-            }
-            Err(e) => {
-                corrupted_count += 1;
-                tracing::warn!(
-                    "Failed to read metadata for session '{}': {}. Skipping corrupted session.",
-                    id,
-                    e
-                );
-
-                // Optionally, we could create a placeholder entry for corrupted sessions
-                // to show them in the UI with an error indicator, but for now we skip them
-                continue;
-            }
-        }
-    }
-
-    if corrupted_count > 0 {
-        tracing::warn!(
-            "Skipped {} corrupted sessions during listing",
-            corrupted_count
-        );
-    }
-
     // Sort sessions by modified date
     // Since all dates are in ISO format (YYYY-MM-DD HH:MM:SS UTC), we can just use string comparison
     // This works because the ISO format ensures lexicographical ordering matches chronological ordering
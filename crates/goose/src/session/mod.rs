@@ -1,12 +1,20 @@
+pub mod fork;
 pub mod info;
+pub mod search;
+pub mod sqlite;
 pub mod storage;
+pub mod store;
 
 // Re-export common session types and functions
 pub use storage::{
-    ensure_session_dir, generate_description, generate_description_with_schedule_id,
-    generate_session_id, get_most_recent_session, get_path, list_sessions, persist_messages,
-    persist_messages_with_schedule_id, read_messages, read_metadata, update_metadata, Identifier,
+    checkpoint_dir, clear_session, delete_session, ensure_session_dir, generate_description,
+    generate_description_with_schedule_id, generate_session_id, get_most_recent_session, get_path,
+    list_sessions, persist_messages, persist_messages_with_schedule_id, read_message_time_range,
+    read_messages, read_metadata, session_exists, update_metadata, Identifier, ServedBy,
     SessionMetadata,
 };
+pub use store::{configured_store, JsonlSessionStore, SessionStore};
 
+pub use fork::{fork_session, fork_tree, generate_fork_id, ForkError};
 pub use info::{get_valid_sorted_sessions, SessionInfo};
+pub use search::{search_sessions, SearchQuery, SearchResult};
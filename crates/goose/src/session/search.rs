@@ -0,0 +1,486 @@
+use crate::message::{Message, MessageContent};
+use crate::session::storage::ensure_session_dir;
+use crate::session::store::{configured_store, JsonlSessionStore, SessionStore};
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+
+/// Default number of results returned by a search
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+// Number of words kept on either side of the first match in a snippet
+const SNIPPET_WORDS_BEFORE: usize = 8;
+const SNIPPET_WORDS_AFTER: usize = 16;
+
+// BM25 tuning parameters
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// A full-text query over session history
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    /// Free text to search for; terms are matched independently
+    pub text: String,
+    /// Only search sessions whose working directory is within this directory
+    pub working_dir: Option<PathBuf>,
+    /// Only match messages created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only search sessions that called a tool whose name contains this string
+    pub tool: Option<String>,
+    /// Maximum number of results
+    pub limit: usize,
+}
+
+impl SearchQuery {
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            working_dir: None,
+            since: None,
+            tool: None,
+            limit: DEFAULT_SEARCH_LIMIT,
+        }
+    }
+
+    /// Lowercased search terms extracted from the query text
+    pub fn terms(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        tokenize(&self.text)
+            .into_iter()
+            .filter(|term| seen.insert(term.clone()))
+            .collect()
+    }
+}
+
+/// A single message matching a search query
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// Id of the session containing the match
+    pub session_id: String,
+    /// Index of the matching message within the session
+    pub message_index: usize,
+    /// Relevance score, higher is better
+    pub score: f64,
+    /// Excerpt of the matching text with matched terms wrapped in `**`
+    pub snippet: String,
+    /// Description of the session
+    pub description: String,
+    /// Creation timestamp of the matching message
+    pub created: i64,
+}
+
+/// The searchable text of one message
+#[derive(Debug, Clone)]
+pub struct IndexedMessage {
+    pub index: usize,
+    pub created: i64,
+    /// Message text, tool call names and arguments, and tool response text
+    pub text: String,
+    /// Names of the tools requested or answered in this message
+    pub tools: Vec<String>,
+}
+
+/// Extract the searchable text of every message in a session
+///
+/// Tool responses don't carry the tool name, so it is looked up from the matching request.
+pub fn index_messages(messages: &[Message]) -> Vec<IndexedMessage> {
+    let mut tool_names: HashMap<&str, &str> = HashMap::new();
+    let mut indexed = Vec::with_capacity(messages.len());
+
+    for (index, message) in messages.iter().enumerate() {
+        let mut parts = Vec::new();
+        let mut tools = Vec::new();
+
+        for content in &message.content {
+            match content {
+                MessageContent::Text(text) => parts.push(text.text.clone()),
+                MessageContent::ToolRequest(request) => {
+                    if let Ok(tool_call) = &request.tool_call {
+                        tool_names.insert(request.id.as_str(), tool_call.name.as_str());
+                        tools.push(tool_call.name.clone());
+                        parts.push(tool_call.name.clone());
+                        parts.push(tool_call.arguments.to_string());
+                    }
+                }
+                MessageContent::ToolResponse(response) => {
+                    if let Some(name) = tool_names.get(response.id.as_str()) {
+                        tools.push(name.to_string());
+                    }
+                    if let Some(text) = content.as_tool_response_text() {
+                        parts.push(text);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if parts.is_empty() {
+            continue;
+        }
+
+        indexed.push(IndexedMessage {
+            index,
+            created: message.created,
+            text: parts.join("\n"),
+            tools,
+        });
+    }
+
+    indexed
+}
+
+/// Split text into lowercase alphanumeric terms
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// Build a short excerpt around the first matching term, highlighting matches
+pub fn make_snippet(text: &str, terms: &[String]) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let is_match = |word: &str| tokenize(word).iter().any(|token| terms.contains(token));
+
+    let first = words.iter().position(|word| is_match(word)).unwrap_or(0);
+    let start = first.saturating_sub(SNIPPET_WORDS_BEFORE);
+    let end = (first + SNIPPET_WORDS_AFTER).min(words.len());
+
+    let mut snippet = words[start..end]
+        .iter()
+        .map(|word| {
+            if is_match(word) {
+                format!("**{}**", word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if start > 0 {
+        snippet.insert_str(0, "… ");
+    }
+    if end < words.len() {
+        snippet.push_str(" …");
+    }
+    snippet
+}
+
+/// Parse a `--since` style value: an RFC 3339 timestamp, a `YYYY-MM-DD` date,
+/// or a relative age such as `30m`, `12h`, `7d` or `2w`
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        if let Some(midnight) = date.and_hms_opt(0, 0, 0) {
+            return Ok(midnight.and_utc());
+        }
+    }
+
+    if let Some(unit) = value.chars().last() {
+        let amount = &value[..value.len() - unit.len_utf8()];
+        if let Ok(amount) = amount.parse::<i64>() {
+            let age = match unit {
+                'm' => Some(Duration::try_minutes(amount)),
+                'h' => Some(Duration::try_hours(amount)),
+                'd' => Some(Duration::try_days(amount)),
+                'w' => Some(Duration::try_weeks(amount)),
+                _ => None,
+            };
+            if let Some(age) = age {
+                if amount < 0 {
+                    return Err(anyhow::anyhow!(
+                        "Invalid time '{}': age can't be negative",
+                        value
+                    ));
+                }
+                return age
+                    .and_then(|age| Utc::now().checked_sub_signed(age))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Invalid time '{}': age is out of range", value)
+                    });
+            }
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "Invalid time '{}': expected RFC 3339, YYYY-MM-DD, or an age like 7d",
+        value
+    ))
+}
+
+struct Candidate {
+    session_id: String,
+    description: String,
+    message: IndexedMessage,
+    term_counts: HashMap<String, usize>,
+    length: usize,
+}
+
+/// Search a store by reading every session and ranking messages with BM25
+///
+/// This is the fallback for stores without a native full-text index.
+pub fn scan_search<S: SessionStore + ?Sized>(
+    store: &S,
+    query: &SearchQuery,
+) -> Result<Vec<SearchResult>> {
+    let terms = query.terms();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let tool_filter = query.tool.as_ref().map(|tool| tool.to_lowercase());
+    let since = query.since.map(|since| since.timestamp());
+
+    let mut candidates = Vec::new();
+    let mut document_count = 0usize;
+    let mut total_length = 0usize;
+
+    for session in store.list()? {
+        if let Some(dir) = &query.working_dir {
+            if !session.metadata.working_dir.starts_with(dir) {
+                continue;
+            }
+        }
+
+        let messages = match store.read_messages(&session.id) {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!("Skipping session '{}' during search: {}", session.id, e);
+                continue;
+            }
+        };
+        let indexed = index_messages(&messages);
+
+        if let Some(tool) = &tool_filter {
+            let used_tool = indexed
+                .iter()
+                .flat_map(|message| message.tools.iter())
+                .any(|name| name.to_lowercase().contains(tool.as_str()));
+            if !used_tool {
+                continue;
+            }
+        }
+
+        for message in indexed {
+            if since.is_some_and(|since| message.created < since) {
+                continue;
+            }
+
+            let tokens = tokenize(&message.text);
+            document_count += 1;
+            total_length += tokens.len();
+
+            let mut term_counts = HashMap::new();
+            for token in &tokens {
+                if terms.contains(token) {
+                    *term_counts.entry(token.clone()).or_insert(0) += 1;
+                }
+            }
+            if term_counts.is_empty() {
+                continue;
+            }
+
+            candidates.push(Candidate {
+                session_id: session.id.clone(),
+                description: session.metadata.description.clone(),
+                message,
+                term_counts,
+                length: tokens.len(),
+            });
+        }
+    }
+
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let average_length = total_length as f64 / document_count.max(1) as f64;
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for candidate in &candidates {
+        for term in candidate.term_counts.keys() {
+            *document_frequency.entry(term.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let mut results: Vec<SearchResult> = candidates
+        .iter()
+        .map(|candidate| {
+            let score = candidate
+                .term_counts
+                .iter()
+                .map(|(term, &count)| {
+                    let df = document_frequency[term.as_str()] as f64;
+                    let idf = (1.0 + (document_count as f64 - df + 0.5) / (df + 0.5)).ln();
+                    let tf = count as f64;
+                    let norm = 1.0 - BM25_B + BM25_B * candidate.length as f64 / average_length;
+                    idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm)
+                })
+                .sum();
+
+            SearchResult {
+                session_id: candidate.session_id.clone(),
+                message_index: candidate.message.index,
+                score,
+                snippet: make_snippet(&candidate.message.text, &terms),
+                description: candidate.description.clone(),
+                created: candidate.message.created,
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.created.cmp(&a.created))
+    });
+    results.truncate(query.limit);
+
+    Ok(results)
+}
+
+/// Search the history of every stored session
+pub fn search_sessions(query: &SearchQuery) -> Result<Vec<SearchResult>> {
+    match configured_store() {
+        Some(store) => store.search(query),
+        None => JsonlSessionStore::new(ensure_session_dir()?).search(query),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::sqlite::SqliteSessionStore;
+    use crate::session::storage::SessionMetadata;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    fn sample_session() -> Vec<Message> {
+        vec![
+            Message::user().with_text("The migration test is flaky again, can you look?"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(ToolCall::new(
+                    "developer__shell",
+                    json!({"command": "cargo test migration"}),
+                )),
+            ),
+            Message::user().with_tool_response(
+                "call_1",
+                Ok(vec![Content::text(
+                    "test result: FAILED. 1 failed; timeout",
+                )]),
+            ),
+            Message::assistant().with_text("I fixed the race in the migration runner."),
+        ]
+    }
+
+    #[test]
+    fn test_index_messages_includes_tools() {
+        let indexed = index_messages(&sample_session());
+        assert_eq!(indexed.len(), 4);
+        assert!(indexed[1].text.contains("cargo test migration"));
+        assert_eq!(indexed[1].tools, vec!["developer__shell".to_string()]);
+        assert_eq!(indexed[2].tools, vec!["developer__shell".to_string()]);
+        assert!(indexed[2].text.contains("FAILED"));
+    }
+
+    #[test]
+    fn test_make_snippet_highlights_terms() {
+        let terms = vec!["flaky".to_string()];
+        let snippet = make_snippet("The migration test is flaky again", &terms);
+        assert_eq!(snippet, "The migration test is **flaky** again");
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert!(parse_since("2025-01-31").is_ok());
+        assert!(parse_since("2025-01-31T10:00:00Z").is_ok());
+        let week_ago = parse_since("7d").unwrap();
+        assert!(week_ago < Utc::now() - Duration::days(6));
+        assert!(parse_since("yesterday").is_err());
+        assert!(parse_since("99999999999999w").is_err());
+        assert!(parse_since("-7d").is_err());
+    }
+
+    #[test]
+    fn test_scan_and_sqlite_search_agree() -> Result<()> {
+        let store = SqliteSessionStore::open_in_memory()?;
+        store.save("fixes", &SessionMetadata::default(), &sample_session())?;
+        store.save(
+            "other",
+            &SessionMetadata::default(),
+            &[Message::user().with_text("Write a haiku about autumn")],
+        )?;
+
+        let query = SearchQuery::new("flaky migration");
+        let scanned = scan_search(&store, &query)?;
+        let indexed = store.search(&query)?;
+
+        for results in [&scanned, &indexed] {
+            assert!(!results.is_empty());
+            assert!(results.iter().all(|r| r.session_id == "fixes"));
+            assert_eq!(results[0].message_index, 0);
+        }
+
+        let mut tool_query = SearchQuery::new("autumn");
+        tool_query.tool = Some("shell".to_string());
+        assert!(scan_search(&store, &tool_query)?.is_empty());
+        assert!(store.search(&tool_query)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_and_sqlite_working_dir_filters_agree() -> Result<()> {
+        let store = SqliteSessionStore::open_in_memory()?;
+        // Session metadata swaps missing working directories for another one, so these exist
+        let work = tempfile::tempdir()?;
+        for (id, dir) in [
+            ("app", "app"),
+            ("nested", "app/crates/core"),
+            ("sibling", "app-old"),
+            ("underscore", "a_p"),
+            ("wildcard", "axp"),
+        ] {
+            let working_dir = work.path().join(dir);
+            std::fs::create_dir_all(&working_dir)?;
+            store.save(
+                id,
+                &SessionMetadata::new(working_dir),
+                &[Message::user().with_text("deploy the release")],
+            )?;
+        }
+
+        let root = work.path().to_string_lossy();
+        for (dir, expected) in [
+            (format!("{}/app", root), vec!["app", "nested"]),
+            (format!("{}/app/", root), vec!["app", "nested"]),
+            (format!("{}/a_p", root), vec!["underscore"]),
+        ] {
+            let mut query = SearchQuery::new("deploy");
+            query.working_dir = Some(PathBuf::from(&dir));
+
+            for results in [scan_search(&store, &query)?, store.search(&query)?] {
+                let mut ids = results
+                    .iter()
+                    .map(|r| r.session_id.as_str())
+                    .collect::<Vec<_>>();
+                ids.sort();
+                assert_eq!(ids, expected, "working dir {}", dir);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,699 @@
+use crate::message::Message;
+use crate::session::info::SessionInfo;
+use crate::session::search::{index_messages, SearchQuery, SearchResult};
+use crate::session::storage::SessionMetadata;
+use crate::session::store::{format_modified, virtual_path, SessionStore};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rmcp::model::Role;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+const SCHEMA_VERSION: i64 = 2;
+const JSONL_MIGRATED_KEY: &str = "jsonl_migrated";
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS store_meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    working_dir TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    schedule_id TEXT,
+    project_id TEXT,
+    message_count INTEGER NOT NULL DEFAULT 0,
+    total_tokens INTEGER,
+    input_tokens INTEGER,
+    output_tokens INTEGER,
+    accumulated_total_tokens INTEGER,
+    accumulated_input_tokens INTEGER,
+    accumulated_output_tokens INTEGER,
+    metadata TEXT NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_updated_at ON sessions(updated_at);
+CREATE INDEX IF NOT EXISTS idx_sessions_working_dir ON sessions(working_dir);
+CREATE INDEX IF NOT EXISTS idx_sessions_schedule_id ON sessions(schedule_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_project_id ON sessions(project_id);
+
+CREATE TABLE IF NOT EXISTS messages (
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    idx INTEGER NOT NULL,
+    role TEXT NOT NULL,
+    created INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    PRIMARY KEY (session_id, idx)
+);
+
+CREATE INDEX IF NOT EXISTS idx_messages_created ON messages(session_id, created);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS message_fts USING fts5(
+    content,
+    session_id UNINDEXED,
+    idx UNINDEXED,
+    tokenize = 'porter unicode61'
+);
+
+CREATE TABLE IF NOT EXISTS message_tools (
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    idx INTEGER NOT NULL,
+    tool_name TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_tools_session ON message_tools(session_id);
+CREATE INDEX IF NOT EXISTS idx_message_tools_name ON message_tools(tool_name);
+"#;
+
+/// Session store keeping metadata, token counts and messages in a SQLite database
+///
+/// Unlike the JSONL files, listing sessions only touches the `sessions` table and there
+/// is no cap on the number of messages per session.
+pub struct SqliteSessionStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteSessionStore {
+    /// Open (or create) the database at `path` and bring its schema up to date
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create session database directory")?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open session database {}", path.display()))?;
+        Self::from_connection(conn)
+    }
+
+    /// Open a private in-memory database
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;",
+        )?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to initialize session database schema")?;
+
+        let mut store = Self {
+            conn: Mutex::new(conn),
+        };
+        store.upgrade_schema()?;
+        Ok(store)
+    }
+
+    /// Bring databases created by older versions up to the current schema
+    fn upgrade_schema(&mut self) -> Result<()> {
+        let conn = self
+            .conn
+            .get_mut()
+            .map_err(|_| anyhow::anyhow!("Session database lock poisoned"))?;
+
+        let version = conn
+            .query_row(
+                "SELECT value FROM store_meta WHERE key = 'schema_version'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .and_then(|value| value.parse::<i64>().ok());
+
+        let tx = conn.transaction()?;
+        if let Some(version) = version {
+            if version < 2 {
+                // Version 2 added the full-text search index
+                rebuild_search_index(&tx)?;
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO store_meta (key, value) VALUES ('schema_version', ?1)",
+            params![SCHEMA_VERSION.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Session database lock poisoned"))
+    }
+
+    /// Whether the one-time import of JSONL session files has already run
+    pub fn jsonl_migrated(&self) -> Result<bool> {
+        let conn = self.lock()?;
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM store_meta WHERE key = ?1",
+                params![JSONL_MIGRATED_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.is_some())
+    }
+
+    /// Record that JSONL session files have been imported so the migration doesn't rerun
+    pub fn mark_jsonl_migrated(&self) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO store_meta (key, value) VALUES (?1, ?2)",
+            params![JSONL_MIGRATED_KEY, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
+
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+    }
+}
+
+fn parse_metadata(json: &str) -> Result<SessionMetadata> {
+    serde_json::from_str(json).context("Failed to parse stored session metadata")
+}
+
+/// Replace the search index entries of a session
+fn index_session(tx: &Transaction, id: &str, messages: &[Message], from: usize) -> Result<()> {
+    tx.execute(
+        "DELETE FROM message_fts WHERE session_id = ?1 AND idx >= ?2",
+        params![id, from as i64],
+    )?;
+    tx.execute(
+        "DELETE FROM message_tools WHERE session_id = ?1 AND idx >= ?2",
+        params![id, from as i64],
+    )?;
+
+    let mut insert_text =
+        tx.prepare("INSERT INTO message_fts (content, session_id, idx) VALUES (?1, ?2, ?3)")?;
+    let mut insert_tool =
+        tx.prepare("INSERT INTO message_tools (session_id, idx, tool_name) VALUES (?1, ?2, ?3)")?;
+
+    // Index the whole list so tool responses can find the name of an earlier request
+    for message in index_messages(messages)
+        .into_iter()
+        .filter(|message| message.index >= from)
+    {
+        insert_text.execute(params![message.text, id, message.index as i64])?;
+        for tool in &message.tools {
+            insert_tool.execute(params![id, message.index as i64, tool])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Index of the first message that differs from what is stored for the session
+///
+/// Sessions mostly grow by appending, so saving only has to write the messages from here on.
+fn first_changed_message(tx: &Transaction, id: &str, serialized: &[String]) -> Result<usize> {
+    let mut stmt = tx.prepare("SELECT content FROM messages WHERE session_id = ?1 ORDER BY idx")?;
+    let mut rows = stmt.query(params![id])?;
+    let mut index = 0;
+    while let Some(row) = rows.next()? {
+        match serialized.get(index) {
+            Some(content) if *content == row.get::<_, String>(0)? => index += 1,
+            _ => break,
+        }
+    }
+    Ok(index)
+}
+
+/// A `LIKE` pattern, escaped with `\`, matching paths strictly inside `dir`
+///
+/// Matches whole components like `Path::starts_with`, so `/work/app` doesn't match `/work/app-old`.
+fn like_subpath_pattern(dir: &str) -> String {
+    let mut pattern = String::with_capacity(dir.len() + 3);
+    for c in dir.chars().chain([std::path::MAIN_SEPARATOR]) {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Index every stored message, used when upgrading a database without a search index
+fn rebuild_search_index(tx: &Transaction) -> Result<()> {
+    let mut sessions: HashMap<String, Vec<Message>> = HashMap::new();
+    {
+        let mut stmt = tx.prepare("SELECT session_id, content FROM messages ORDER BY idx")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (session_id, content) = row?;
+            let messages = sessions.entry(session_id).or_default();
+            match serde_json::from_str::<Message>(&content) {
+                Ok(message) => messages.push(message),
+                // Keep indexes aligned with the messages table
+                Err(_) => messages.push(Message::user()),
+            }
+        }
+    }
+
+    for (id, messages) in &sessions {
+        index_session(tx, id, messages, 0)?;
+    }
+
+    Ok(())
+}
+
+impl SessionStore for SqliteSessionStore {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn exists(&self, id: &str) -> Result<bool> {
+        let conn = self.lock()?;
+        let found: Option<i64> = conn
+            .query_row("SELECT 1 FROM sessions WHERE id = ?1", params![id], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    fn list(&self) -> Result<Vec<SessionInfo>> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare("SELECT id, metadata, updated_at FROM sessions")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+        drop(conn);
+
+        let mut sessions = Vec::with_capacity(rows.len());
+        for (id, metadata_json, updated_at) in rows {
+            let metadata = match parse_metadata(&metadata_json) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::warn!("Skipping session '{}' with invalid metadata: {}", id, e);
+                    continue;
+                }
+            };
+            let modified = DateTime::<Utc>::from_timestamp(updated_at, 0)
+                .map(format_modified)
+                .unwrap_or_else(|| "Unknown".to_string());
+            let path = virtual_path(&id)?;
+
+            sessions.push(SessionInfo {
+                id,
+                path: path.to_string_lossy().to_string(),
+                modified,
+                metadata,
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    fn most_recent(&self) -> Result<Option<String>> {
+        let conn = self.lock()?;
+        Ok(conn
+            .query_row(
+                "SELECT id FROM sessions ORDER BY updated_at DESC, id DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn read_metadata(&self, id: &str) -> Result<SessionMetadata> {
+        let conn = self.lock()?;
+        let metadata_json: Option<String> = conn
+            .query_row(
+                "SELECT metadata FROM sessions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match metadata_json {
+            Some(json) => parse_metadata(&json),
+            None => Ok(SessionMetadata::default()),
+        }
+    }
+
+    fn read_messages(&self, id: &str) -> Result<Vec<Message>> {
+        let conn = self.lock()?;
+        let mut stmt =
+            conn.prepare("SELECT idx, content FROM messages WHERE session_id = ?1 ORDER BY idx")?;
+        let rows = stmt
+            .query_map(params![id], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        // Skipping a row would shift the index of every later message, which forks and
+        // incremental saves rely on
+        rows.into_iter()
+            .map(|(idx, content)| {
+                serde_json::from_str::<Message>(&content)
+                    .with_context(|| format!("Failed to parse message {} in session '{}'", idx, id))
+            })
+            .collect()
+    }
+
+    fn message_time_range(&self, id: &str) -> Result<Option<(i64, i64)>> {
+        let conn = self.lock()?;
+        let range: (Option<i64>, Option<i64>) = conn.query_row(
+            "SELECT MIN(created), MAX(created) FROM messages WHERE session_id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(range.0.zip(range.1))
+    }
+
+    fn save(&self, id: &str, metadata: &SessionMetadata, messages: &[Message]) -> Result<()> {
+        let metadata_json = serde_json::to_string(metadata)?;
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO sessions (
+                id, working_dir, description, schedule_id, project_id, message_count,
+                total_tokens, input_tokens, output_tokens,
+                accumulated_total_tokens, accumulated_input_tokens, accumulated_output_tokens,
+                metadata, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            ON CONFLICT(id) DO UPDATE SET
+                working_dir = excluded.working_dir,
+                description = excluded.description,
+                schedule_id = excluded.schedule_id,
+                project_id = excluded.project_id,
+                message_count = excluded.message_count,
+                total_tokens = excluded.total_tokens,
+                input_tokens = excluded.input_tokens,
+                output_tokens = excluded.output_tokens,
+                accumulated_total_tokens = excluded.accumulated_total_tokens,
+                accumulated_input_tokens = excluded.accumulated_input_tokens,
+                accumulated_output_tokens = excluded.accumulated_output_tokens,
+                metadata = excluded.metadata,
+                updated_at = excluded.updated_at",
+            params![
+                id,
+                metadata.working_dir.to_string_lossy().to_string(),
+                metadata.description,
+                metadata.schedule_id,
+                metadata.project_id,
+                metadata.message_count as i64,
+                metadata.total_tokens,
+                metadata.input_tokens,
+                metadata.output_tokens,
+                metadata.accumulated_total_tokens,
+                metadata.accumulated_input_tokens,
+                metadata.accumulated_output_tokens,
+                metadata_json,
+                Utc::now().timestamp(),
+            ],
+        )?;
+
+        let serialized = messages
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+        let first_changed = first_changed_message(&tx, id, &serialized)?;
+
+        tx.execute(
+            "DELETE FROM messages WHERE session_id = ?1 AND idx >= ?2",
+            params![id, first_changed as i64],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO messages (session_id, idx, role, created, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for (idx, (message, content)) in messages
+                .iter()
+                .zip(&serialized)
+                .enumerate()
+                .skip(first_changed)
+            {
+                insert.execute(params![
+                    id,
+                    idx as i64,
+                    role_name(&message.role),
+                    message.created,
+                    content,
+                ])?;
+            }
+        }
+
+        index_session(&tx, id, messages, first_changed)?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, id: &str) -> Result<()> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM message_fts WHERE session_id = ?1", params![id])?;
+        tx.execute("DELETE FROM sessions WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let terms = query.terms();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Quote every term so user input can't inject FTS5 query syntax
+        let match_expr = terms
+            .iter()
+            .map(|term| format!("\"{}\"", term))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+
+        let working_dir = query.working_dir.as_ref().map(|dir| {
+            dir.to_string_lossy()
+                .trim_end_matches(std::path::MAIN_SEPARATOR)
+                .to_string()
+        });
+        let within_working_dir = working_dir.as_deref().map(like_subpath_pattern);
+
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT message_fts.session_id, message_fts.idx, bm25(message_fts),
+                    snippet(message_fts, 0, '**', '**', '…', 24), s.description, m.created
+             FROM message_fts
+             JOIN sessions s ON s.id = message_fts.session_id
+             JOIN messages m ON m.session_id = message_fts.session_id AND m.idx = message_fts.idx
+             WHERE message_fts MATCH ?1
+               AND (?2 IS NULL OR s.working_dir = ?2 OR s.working_dir LIKE ?6 ESCAPE '\\')
+               AND (?3 IS NULL OR m.created >= ?3)
+               AND (?4 IS NULL OR message_fts.session_id IN (
+                    SELECT session_id FROM message_tools
+                    WHERE instr(lower(tool_name), lower(?4)) > 0))
+             ORDER BY bm25(message_fts), m.created DESC
+             LIMIT ?5",
+        )?;
+
+        let results = stmt
+            .query_map(
+                params![
+                    match_expr,
+                    working_dir,
+                    query.since.map(|since| since.timestamp()),
+                    query.tool,
+                    query.limit as i64,
+                    within_working_dir,
+                ],
+                |row| {
+                    Ok(SearchResult {
+                        session_id: row.get(0)?,
+                        message_index: row.get::<_, i64>(1)? as usize,
+                        // bm25() is lower for better matches
+                        score: -row.get::<_, f64>(2)?,
+                        snippet: row.get(3)?,
+                        description: row.get(4)?,
+                        created: row.get(5)?,
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::storage::save_messages_to_file;
+    use crate::session::store::{migrate_sessions, JsonlSessionStore};
+    use tempfile::tempdir;
+
+    fn sample_messages() -> Vec<Message> {
+        vec![
+            Message::user().with_text("Hello"),
+            Message::assistant().with_text("Hi there"),
+        ]
+    }
+
+    #[test]
+    fn test_save_and_read_roundtrip() -> Result<()> {
+        let store = SqliteSessionStore::open_in_memory()?;
+        let metadata = SessionMetadata {
+            description: "roundtrip".to_string(),
+            accumulated_total_tokens: Some(42),
+            ..Default::default()
+        };
+
+        store.save("20250101_000000", &metadata, &sample_messages())?;
+
+        assert!(store.exists("20250101_000000")?);
+        let read_back = store.read_metadata("20250101_000000")?;
+        assert_eq!(read_back.description, "roundtrip");
+        assert_eq!(read_back.accumulated_total_tokens, Some(42));
+
+        let messages = store.read_messages("20250101_000000")?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].as_concat_text(), "Hello");
+        assert_eq!(messages[1].role, Role::Assistant);
+
+        // Saving fewer messages replaces the previous ones
+        store.save("20250101_000000", &metadata, &sample_messages()[..1])?;
+        assert_eq!(store.read_messages("20250101_000000")?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_only_writes_changed_messages() -> Result<()> {
+        let store = SqliteSessionStore::open_in_memory()?;
+        store.lock()?.execute_batch(
+            "CREATE TEMP TABLE written (idx INTEGER NOT NULL);
+             CREATE TEMP TRIGGER record_written AFTER INSERT ON messages
+             BEGIN INSERT INTO written (idx) VALUES (new.idx); END;",
+        )?;
+        // The indexes of the messages inserted since the last call
+        let written = || -> Result<Vec<i64>> {
+            let conn = store.lock()?;
+            let indexes = conn
+                .prepare("SELECT idx FROM written ORDER BY idx")?
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<i64>, _>>()?;
+            conn.execute("DELETE FROM written", [])?;
+            Ok(indexes)
+        };
+
+        let mut messages = sample_messages();
+        store.save("grow", &SessionMetadata::default(), &messages)?;
+        assert_eq!(written()?, vec![0, 1]);
+
+        // Appending keeps the stored rows
+        messages.push(Message::user().with_text("Another question"));
+        store.save("grow", &SessionMetadata::default(), &messages)?;
+        assert_eq!(written()?, vec![2]);
+
+        // Changing a message rewrites it and everything after it
+        messages[1] = Message::assistant().with_text("A different answer");
+        store.save("grow", &SessionMetadata::default(), &messages)?;
+        assert_eq!(written()?, vec![1, 2]);
+
+        let read_back = store.read_messages("grow")?;
+        assert_eq!(read_back.len(), 3);
+        assert_eq!(read_back[1].as_concat_text(), "A different answer");
+        assert_eq!(read_back[2].as_concat_text(), "Another question");
+
+        let results = store.search(&SearchQuery::new("different"))?;
+        assert_eq!(results.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreadable_message_is_an_error() -> Result<()> {
+        let store = SqliteSessionStore::open_in_memory()?;
+        store.save("broken", &SessionMetadata::default(), &sample_messages())?;
+        store.lock()?.execute(
+            "UPDATE messages SET content = 'not json' WHERE session_id = 'broken' AND idx = 0",
+            [],
+        )?;
+
+        assert!(store.read_messages("broken").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_message_count_limit() -> Result<()> {
+        let store = SqliteSessionStore::open_in_memory()?;
+        let messages: Vec<Message> = (0..6000)
+            .map(|i| Message::user().with_text(format!("message {}", i)))
+            .collect();
+
+        store.save("big", &SessionMetadata::default(), &messages)?;
+        assert_eq!(store.read_messages("big")?.len(), 6000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_and_missing_sessions() -> Result<()> {
+        let store = SqliteSessionStore::open_in_memory()?;
+        assert!(store.read_messages("missing")?.is_empty());
+        assert_eq!(store.message_time_range("missing")?, None);
+
+        store.save("gone", &SessionMetadata::default(), &sample_messages())?;
+        store.delete("gone")?;
+        assert!(!store.exists("gone")?);
+        assert!(store.read_messages("gone")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_from_jsonl() -> Result<()> {
+        let dir = tempdir()?;
+        let session_dir = dir.path().join("sessions");
+        fs::create_dir_all(&session_dir)?;
+
+        let metadata = SessionMetadata {
+            description: "from jsonl".to_string(),
+            ..Default::default()
+        };
+        save_messages_to_file(
+            &session_dir.join("20240101_120000.jsonl"),
+            &metadata,
+            &sample_messages(),
+        )?;
+
+        let store = SqliteSessionStore::open(&dir.path().join("sessions.db"))?;
+        assert!(!store.jsonl_migrated()?);
+
+        let jsonl = JsonlSessionStore::new(session_dir);
+        assert_eq!(migrate_sessions(&jsonl, &store)?, 1);
+        store.mark_jsonl_migrated()?;
+
+        assert!(store.jsonl_migrated()?);
+        assert_eq!(
+            store.read_metadata("20240101_120000")?.description,
+            "from jsonl"
+        );
+        assert_eq!(store.read_messages("20240101_120000")?.len(), 2);
+
+        // Running again doesn't duplicate anything
+        assert_eq!(migrate_sessions(&jsonl, &store)?, 0);
+
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@
 
 use crate::message::Message;
 use crate::providers::base::Provider;
+use crate::session::store;
 use crate::utils::safe_truncate;
 use anyhow::Result;
 use chrono::Local;
@@ -64,6 +65,29 @@ pub struct SessionMetadata {
     pub accumulated_input_tokens: Option<i32>,
     /// The number of output tokens used in the session. Accumulated across all messages.
     pub accumulated_output_tokens: Option<i32>,
+    /// Estimated cost of the session in US dollars, accumulated across all turns using the pricing of the model that served each turn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accumulated_cost: Option<f64>,
+    /// ID of the session this session was forked from, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_session_id: Option<String>,
+    /// Number of messages copied from the parent session when this session was forked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_message_index: Option<usize>,
+    /// Providers and models that answered the session's turns, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub served_by: Vec<ServedBy>,
+}
+
+/// A run of consecutive turns answered by the same provider and model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ServedBy {
+    pub provider: String,
+    pub model: String,
+    /// Index of the message that starts the run
+    pub first_message: usize,
+    /// Number of turns in the run
+    pub turns: usize,
 }
 
 // Custom deserializer to handle old sessions without working_dir
@@ -84,7 +108,15 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_total_tokens: Option<i32>,
             accumulated_input_tokens: Option<i32>,
             accumulated_output_tokens: Option<i32>,
+            #[serde(default)]
+            accumulated_cost: Option<f64>,
             working_dir: Option<PathBuf>,
+            #[serde(default)]
+            parent_session_id: Option<String>,
+            #[serde(default)]
+            fork_message_index: Option<usize>,
+            #[serde(default)]
+            served_by: Vec<ServedBy>,
         }
 
         let helper = Helper::deserialize(deserializer)?;
@@ -106,6 +138,10 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_total_tokens: helper.accumulated_total_tokens,
             accumulated_input_tokens: helper.accumulated_input_tokens,
             accumulated_output_tokens: helper.accumulated_output_tokens,
+            accumulated_cost: helper.accumulated_cost,
+            parent_session_id: helper.parent_session_id,
+            fork_message_index: helper.fork_message_index,
+            served_by: helper.served_by,
             working_dir,
         })
     }
@@ -132,6 +168,30 @@ impl SessionMetadata {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            accumulated_cost: None,
+            parent_session_id: None,
+            fork_message_index: None,
+            served_by: Vec::new(),
+        }
+    }
+
+    /// Note which provider and model answered the turn whose reply is at `message_index`
+    pub fn record_served_by(&mut self, provider: &str, model: &str, message_index: usize) {
+        match self.served_by.last_mut() {
+            Some(last) if last.provider == provider && last.model == model => last.turns += 1,
+            _ => self.served_by.push(ServedBy {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                first_message: message_index,
+                turns: 1,
+            }),
+        }
+    }
+
+    /// Add the estimated cost of a request, if it could be priced, to the session's total
+    pub fn add_cost(&mut self, cost: Option<f64>) {
+        if let Some(cost) = cost {
+            self.accumulated_cost = Some(self.accumulated_cost.unwrap_or(0.0) + cost);
         }
     }
 }
@@ -145,7 +205,7 @@ impl Default for SessionMetadata {
 // The single app name used for all Goose applications
 const APP_NAME: &str = "goose";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Identifier {
     Name(String),
     Path(PathBuf),
@@ -337,8 +397,24 @@ pub fn ensure_session_dir() -> Result<PathBuf> {
     Ok(data_dir)
 }
 
+/// Directory holding the edit checkpoints journaled for a session
+///
+/// The developer extension creates it on the first edit, so it may not exist yet.
+pub fn checkpoint_dir(session_file: &Path) -> Result<PathBuf> {
+    Ok(ensure_session_dir()?
+        .join("checkpoints")
+        .join(store::session_id_from_path(session_file)?))
+}
+
 /// Get the path to the most recently modified session file
 pub fn get_most_recent_session() -> Result<PathBuf> {
+    if let Some(store) = store::configured_store() {
+        return match store.most_recent()? {
+            Some(id) => store::virtual_path(&id),
+            None => Err(anyhow::anyhow!("No session files found")),
+        };
+    }
+
     let session_dir = ensure_session_dir()?;
     let mut entries = fs::read_dir(&session_dir)?
         .filter_map(|entry| entry.ok())
@@ -366,6 +442,14 @@ pub fn get_most_recent_session() -> Result<PathBuf> {
 
 /// List all available session files
 pub fn list_sessions() -> Result<Vec<(String, PathBuf)>> {
+    if let Some(store) = store::configured_store() {
+        return store
+            .list()?
+            .into_iter()
+            .map(|session| Ok((session.id, PathBuf::from(session.path))))
+            .collect();
+    }
+
     let session_dir = ensure_session_dir()?;
     let entries = fs::read_dir(&session_dir)?
         .filter_map(|entry| {
@@ -384,6 +468,54 @@ pub fn list_sessions() -> Result<Vec<(String, PathBuf)>> {
     Ok(entries)
 }
 
+/// Check whether a session exists for the given session file path
+pub fn session_exists(session_file: &Path) -> bool {
+    match store::store_for(session_file) {
+        Some(store) => store::session_id_from_path(session_file)
+            .and_then(|id| store.exists(&id))
+            .unwrap_or(false),
+        None => session_file.exists(),
+    }
+}
+
+/// Delete a session and all of its messages
+pub fn delete_session(session_file: &Path) -> Result<()> {
+    if let Some(store) = store::store_for(session_file) {
+        return store.delete(&store::session_id_from_path(session_file)?);
+    }
+
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    fs::remove_file(&secure_path)?;
+    Ok(())
+}
+
+/// Remove all messages and metadata from a session while keeping it resumable
+pub fn clear_session(session_file: &Path) -> Result<()> {
+    if let Some(store) = store::store_for(session_file) {
+        let id = store::session_id_from_path(session_file)?;
+        let working_dir = store.read_metadata(&id)?.working_dir;
+        return store.save(&id, &SessionMetadata::new(working_dir), &[]);
+    }
+
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    fs::remove_file(&secure_path)?;
+    fs::File::create(&secure_path)?;
+    Ok(())
+}
+
+/// Creation timestamps of the first and last message of a session, if it has any
+pub fn read_message_time_range(session_file: &Path) -> Result<Option<(i64, i64)>> {
+    if let Some(store) = store::store_for(session_file) {
+        return store.message_time_range(&store::session_id_from_path(session_file)?);
+    }
+
+    let messages = read_messages(session_file)?;
+    Ok(messages
+        .first()
+        .zip(messages.last())
+        .map(|(first, last)| (first.created, last.created)))
+}
+
 /// Generate a session ID using timestamp format (yyyymmdd_hhmmss)
 pub fn generate_session_id() -> String {
     Local::now().format("%Y%m%d_%H%M%S").to_string()
@@ -400,6 +532,15 @@ pub fn generate_session_id() -> String {
 /// - Validates file paths to prevent directory traversal
 /// - Includes all security limits from read_messages_with_truncation
 pub fn read_messages(session_file: &Path) -> Result<Vec<Message>> {
+    if let Some(store) = store::store_for(session_file) {
+        return store.read_messages(&store::session_id_from_path(session_file)?);
+    }
+
+    read_messages_from_file(session_file)
+}
+
+/// Read messages from a JSONL session file, bypassing the configured session store
+pub(crate) fn read_messages_from_file(session_file: &Path) -> Result<Vec<Message>> {
     // Validate the path for security
     let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
 
@@ -998,6 +1139,15 @@ fn truncate_json_string(json_str: &str, max_content_size: usize) -> String {
 /// Returns default empty metadata if the file doesn't exist or has no metadata.
 /// Includes security checks for file access and content validation.
 pub fn read_metadata(session_file: &Path) -> Result<SessionMetadata> {
+    if let Some(store) = store::store_for(session_file) {
+        return store.read_metadata(&store::session_id_from_path(session_file)?);
+    }
+
+    read_metadata_from_file(session_file)
+}
+
+/// Read metadata from a JSONL session file, bypassing the configured session store
+pub(crate) fn read_metadata_from_file(session_file: &Path) -> Result<SessionMetadata> {
     // Validate the path for security
     let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
 
@@ -1078,7 +1228,7 @@ pub async fn persist_messages_with_schedule_id(
     let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
 
     // Security check: message count limit
-    if messages.len() > MAX_MESSAGE_COUNT {
+    if exceeds_message_limit(session_file, messages) {
         tracing::warn!("Message count exceeds limit: {}", messages.len());
         return Err(anyhow::anyhow!("Too many messages"));
     }
@@ -1104,7 +1254,7 @@ pub async fn persist_messages_with_schedule_id(
         }
         _ => {
             // Read existing metadata or create new with proper working_dir
-            let mut metadata = if secure_path.exists() {
+            let mut metadata = if session_exists(&secure_path) {
                 read_metadata(&secure_path)?
             } else {
                 // Create new metadata with the provided working_dir or fall back to home
@@ -1145,6 +1295,23 @@ pub fn save_messages_with_metadata(
     session_file: &Path,
     metadata: &SessionMetadata,
     messages: &[Message],
+) -> Result<()> {
+    if let Some(store) = store::store_for(session_file) {
+        return store.save(
+            &store::session_id_from_path(session_file)?,
+            metadata,
+            messages,
+        );
+    }
+
+    save_messages_to_file(session_file, metadata, messages)
+}
+
+/// Write a JSONL session file, bypassing the configured session store
+pub(crate) fn save_messages_to_file(
+    session_file: &Path,
+    metadata: &SessionMetadata,
+    messages: &[Message],
 ) -> Result<()> {
     use fs2::FileExt;
 
@@ -1251,6 +1418,13 @@ pub fn save_messages_with_metadata(
     Ok(())
 }
 
+/// Whether a message list is too long to persist
+///
+/// The limit protects JSONL reads from resource exhaustion; database backed stores don't need it.
+fn exceeds_message_limit(session_file: &Path, messages: &[Message]) -> bool {
+    store::store_for(session_file).is_none() && messages.len() > MAX_MESSAGE_COUNT
+}
+
 /// Generate a description for the session using the provider
 ///
 /// This function is called when appropriate to generate a short description
@@ -1284,7 +1458,7 @@ pub async fn generate_description_with_schedule_id(
     let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
 
     // Security check: message count limit
-    if messages.len() > MAX_MESSAGE_COUNT {
+    if exceeds_message_limit(session_file, messages) {
         tracing::warn!(
             "Message count exceeds limit during description generation: {}",
             messages.len()
@@ -1304,7 +1478,7 @@ pub async fn generate_description_with_schedule_id(
         })?;
 
     // Create metadata with proper working_dir or read existing and update
-    let mut metadata = if secure_path.exists() {
+    let mut metadata = if session_exists(&secure_path) {
         read_metadata(&secure_path)?
     } else {
         // Create new metadata with the provided working_dir or fall back to home
@@ -1963,4 +2137,59 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_record_served_by() -> Result<()> {
+        let mut metadata = SessionMetadata::default();
+        metadata.record_served_by("anthropic", "claude-sonnet-4", 1);
+        metadata.record_served_by("anthropic", "claude-sonnet-4", 3);
+        metadata.record_served_by("openai", "gpt-4o", 5);
+
+        assert_eq!(
+            metadata.served_by,
+            vec![
+                ServedBy {
+                    provider: "anthropic".to_string(),
+                    model: "claude-sonnet-4".to_string(),
+                    first_message: 1,
+                    turns: 2,
+                },
+                ServedBy {
+                    provider: "openai".to_string(),
+                    model: "gpt-4o".to_string(),
+                    first_message: 5,
+                    turns: 1,
+                },
+            ]
+        );
+
+        // Round trips through the session file, and older files without it still load
+        let json = serde_json::to_string(&metadata)?;
+        let read_back: SessionMetadata = serde_json::from_str(&json)?;
+        assert_eq!(read_back.served_by, metadata.served_by);
+        let old: SessionMetadata =
+            serde_json::from_str(r#"{"description": "old", "message_count": 2}"#)?;
+        assert!(old.served_by.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_cost() -> Result<()> {
+        let mut metadata = SessionMetadata::default();
+        // Requests to models without known pricing leave the cost unset
+        metadata.add_cost(None);
+        assert_eq!(metadata.accumulated_cost, None);
+
+        metadata.add_cost(Some(0.25));
+        metadata.add_cost(None);
+        metadata.add_cost(Some(0.5));
+        assert_eq!(metadata.accumulated_cost, Some(0.75));
+
+        let json = serde_json::to_string(&metadata)?;
+        let read_back: SessionMetadata = serde_json::from_str(&json)?;
+        assert_eq!(read_back.accumulated_cost, Some(0.75));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,308 @@
+use crate::config::Config;
+use crate::message::Message;
+use crate::session::info::SessionInfo;
+use crate::session::search::{self, SearchQuery, SearchResult};
+use crate::session::sqlite::SqliteSessionStore;
+use crate::session::storage::{self, Identifier, SessionMetadata};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Config key selecting the session backend ("jsonl" or "sqlite")
+pub const GOOSE_SESSION_BACKEND: &str = "GOOSE_SESSION_BACKEND";
+/// Config key overriding the location of the SQLite session database
+pub const GOOSE_SESSION_DB_PATH: &str = "GOOSE_SESSION_DB_PATH";
+
+const MODIFIED_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+/// A storage backend for sessions, addressed by session id
+///
+/// The path based functions in `session::storage` remain the public API; they route
+/// through the configured store so callers don't need to know which backend is in use.
+pub trait SessionStore: Send + Sync {
+    /// Short name of the backend, used for logging
+    fn name(&self) -> &'static str;
+
+    /// Check whether a session with this id has been stored
+    fn exists(&self, id: &str) -> Result<bool>;
+
+    /// List every stored session with its metadata
+    fn list(&self) -> Result<Vec<SessionInfo>>;
+
+    /// Id of the most recently updated session, if any
+    fn most_recent(&self) -> Result<Option<String>>;
+
+    /// Read the metadata of a session, or the default metadata if it doesn't exist
+    fn read_metadata(&self, id: &str) -> Result<SessionMetadata>;
+
+    /// Read all messages of a session, or an empty list if it doesn't exist
+    fn read_messages(&self, id: &str) -> Result<Vec<Message>>;
+
+    /// Creation timestamps of the first and last message in a session
+    fn message_time_range(&self, id: &str) -> Result<Option<(i64, i64)>> {
+        let messages = self.read_messages(id)?;
+        Ok(messages
+            .first()
+            .zip(messages.last())
+            .map(|(first, last)| (first.created, last.created)))
+    }
+
+    /// Replace the stored metadata and messages of a session
+    fn save(&self, id: &str, metadata: &SessionMetadata, messages: &[Message]) -> Result<()>;
+
+    /// Remove a session and all of its messages
+    fn delete(&self, id: &str) -> Result<()>;
+
+    /// Full-text search across the messages of every stored session
+    fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        search::scan_search(self, query)
+    }
+}
+
+/// The original backend: one JSONL file per session, metadata on the first line
+pub struct JsonlSessionStore {
+    session_dir: PathBuf,
+}
+
+impl JsonlSessionStore {
+    pub fn new(session_dir: PathBuf) -> Self {
+        Self { session_dir }
+    }
+
+    fn path_for(&self, id: &str) -> PathBuf {
+        self.session_dir.join(format!("{}.jsonl", id))
+    }
+}
+
+impl SessionStore for JsonlSessionStore {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn exists(&self, id: &str) -> Result<bool> {
+        Ok(self.path_for(id).exists())
+    }
+
+    fn list(&self) -> Result<Vec<SessionInfo>> {
+        let mut sessions = Vec::new();
+        let mut corrupted_count = 0;
+
+        for entry in fs::read_dir(&self.session_dir)?.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+            let Some(id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+
+            // Get file modification time with fallback
+            let modified = path
+                .metadata()
+                .and_then(|m| m.modified())
+                .map(|time| format_modified(DateTime::<Utc>::from(time)))
+                .unwrap_or_else(|_| {
+                    tracing::warn!("Failed to get modification time for session: {}", id);
+                    "Unknown".to_string()
+                });
+
+            match storage::read_metadata_from_file(&path) {
+                Ok(metadata) => sessions.push(SessionInfo {
+                    id,
+                    path: path.to_string_lossy().to_string(),
+                    modified,
+                    metadata,
+                }),
+                Err(e) => {
+                    corrupted_count += 1;
+                    tracing::warn!(
+                        "Failed to read metadata for session '{}': {}. Skipping corrupted session.",
+                        id,
+                        e
+                    );
+                }
+            }
+        }
+
+        if corrupted_count > 0 {
+            tracing::warn!(
+                "Skipped {} corrupted sessions during listing",
+                corrupted_count
+            );
+        }
+
+        Ok(sessions)
+    }
+
+    fn most_recent(&self) -> Result<Option<String>> {
+        let most_recent = fs::read_dir(&self.session_dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+            .max_by_key(|entry| {
+                entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
+            });
+
+        Ok(most_recent.and_then(|entry| {
+            entry
+                .path()
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+        }))
+    }
+
+    fn read_metadata(&self, id: &str) -> Result<SessionMetadata> {
+        storage::read_metadata_from_file(&self.path_for(id))
+    }
+
+    fn read_messages(&self, id: &str) -> Result<Vec<Message>> {
+        let path = self.path_for(id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        storage::read_messages_from_file(&path)
+    }
+
+    fn save(&self, id: &str, metadata: &SessionMetadata, messages: &[Message]) -> Result<()> {
+        storage::save_messages_to_file(&self.path_for(id), metadata, messages)
+    }
+
+    fn delete(&self, id: &str) -> Result<()> {
+        let path = self.path_for(id);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Copy every session from `source` into `target`, skipping sessions the target already has
+///
+/// Returns the number of sessions that were copied. Sessions that fail to load are logged
+/// and skipped so that a single corrupted file doesn't block the migration.
+pub fn migrate_sessions(source: &dyn SessionStore, target: &dyn SessionStore) -> Result<usize> {
+    let mut migrated = 0;
+
+    for session in source.list()? {
+        if target.exists(&session.id)? {
+            continue;
+        }
+
+        let messages = match source.read_messages(&session.id) {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping session '{}' during migration to {}: {}",
+                    session.id,
+                    target.name(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        target.save(&session.id, &session.metadata, &messages)?;
+        migrated += 1;
+    }
+
+    Ok(migrated)
+}
+
+/// Format a timestamp the same way session listings report file modification times
+pub(crate) fn format_modified(time: DateTime<Utc>) -> String {
+    time.format(MODIFIED_FORMAT).to_string()
+}
+
+/// Virtual session file path for a session id
+///
+/// Non-file backends still hand out paths so that existing callers can keep treating
+/// sessions as files; only the file stem is used to address the session.
+pub(crate) fn virtual_path(id: &str) -> Result<PathBuf> {
+    storage::get_path(Identifier::Name(id.to_string()))
+}
+
+/// Extract the session id addressed by a session file path
+pub(crate) fn session_id_from_path(session_file: &Path) -> Result<String> {
+    session_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Invalid session path"))
+}
+
+static CONFIGURED_STORE: OnceCell<Option<Arc<dyn SessionStore>>> = OnceCell::new();
+
+/// The non-file session store selected in config, if any
+///
+/// Returns `None` when sessions live in per-session JSONL files. The SQLite backend is
+/// the default: the database is opened on first use, and any existing JSONL sessions
+/// are imported once.
+pub fn configured_store() -> Option<Arc<dyn SessionStore>> {
+    CONFIGURED_STORE
+        .get_or_init(|| {
+            let backend = Config::global()
+                .get_param::<String>(GOOSE_SESSION_BACKEND)
+                .unwrap_or_else(|_| "sqlite".to_string());
+
+            match backend.to_lowercase().as_str() {
+                "sqlite" => match open_sqlite_store() {
+                    Ok(store) => Some(store as Arc<dyn SessionStore>),
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to open SQLite session store, falling back to JSONL files: {}",
+                            e
+                        );
+                        None
+                    }
+                },
+                "jsonl" => None,
+                other => {
+                    tracing::warn!(
+                        "Unknown {} '{}', using JSONL session files",
+                        GOOSE_SESSION_BACKEND,
+                        other
+                    );
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+/// The configured store for a session file path, if it addresses a stored session
+///
+/// Only paths in the session directory name stored sessions. Any other path, such as a
+/// temporary file in tests, is read and written as a JSONL file.
+pub(crate) fn store_for(session_file: &Path) -> Option<Arc<dyn SessionStore>> {
+    let session_dir = storage::ensure_session_dir().ok()?;
+    if session_file.parent() != Some(session_dir.as_path()) {
+        return None;
+    }
+    configured_store()
+}
+
+fn open_sqlite_store() -> Result<Arc<SqliteSessionStore>> {
+    let session_dir = storage::ensure_session_dir()?;
+    let db_path = match Config::global().get_param::<String>(GOOSE_SESSION_DB_PATH) {
+        Ok(custom_path) => PathBuf::from(custom_path),
+        Err(_) => session_dir.join("sessions.db"),
+    };
+
+    let store = SqliteSessionStore::open(&db_path)?;
+    if !store.jsonl_migrated()? {
+        let migrated = migrate_sessions(&JsonlSessionStore::new(session_dir), &store)?;
+        store.mark_jsonl_migrated()?;
+        tracing::info!(
+            "Migrated {} JSONL sessions into {}",
+            migrated,
+            db_path.display()
+        );
+    }
+
+    Ok(Arc::new(store))
+}
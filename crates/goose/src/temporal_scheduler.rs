@@ -545,6 +545,13 @@ impl TemporalScheduler {
             "TemporalScheduler: add_scheduled_job() called for job '{}'",
             job.id
         );
+        // The Temporal service only passes the recipe to each run, so a job budget would never apply
+        if job.budget.is_some() {
+            return Err(SchedulerError::UnsupportedJobSetting(format!(
+                "job '{}' has a budget, which the Temporal scheduler doesn't support; set the budget in the recipe's settings instead",
+                job.id
+            )));
+        }
 
         // Normalize the cron expression to ensure it's 6-field format
         let normalized_cron = normalize_cron_expression(&job.cron);
@@ -601,6 +608,7 @@ impl TemporalScheduler {
                         current_session_id: None, // Not provided by Temporal service
                         process_start_time: None, // Not provided by Temporal service
                         execution_mode: tj.execution_mode,
+                        budget: None,
                     }
                 })
                 .collect();
@@ -1239,6 +1247,41 @@ impl SchedulerTrait for TemporalScheduler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agents::CostBudget;
+
+    #[tokio::test]
+    async fn test_job_budget_is_rejected() {
+        let scheduler = TemporalScheduler {
+            http_client: Client::new(),
+            service_url: "http://localhost:1".to_string(),
+            port_config: PortConfig {
+                http_port: 1,
+                temporal_port: 2,
+                ui_port: 3,
+            },
+        };
+        let job = ScheduledJob {
+            id: "budgeted".to_string(),
+            source: "recipe.yaml".to_string(),
+            cron: "0 0 * * * *".to_string(),
+            last_run: None,
+            currently_running: false,
+            paused: false,
+            current_session_id: None,
+            process_start_time: None,
+            execution_mode: None,
+            budget: Some(CostBudget {
+                soft_limit: None,
+                hard_limit: Some(1.0),
+            }),
+        };
+
+        let result = scheduler.add_scheduled_job(job).await;
+        assert!(matches!(
+            result,
+            Err(SchedulerError::UnsupportedJobSetting(_))
+        ));
+    }
 
     #[tokio::test]
     async fn test_sessions_method_exists_and_compiles() {
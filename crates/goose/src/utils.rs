@@ -26,6 +26,14 @@ pub fn is_token_cancelled(cancellation_token: &Option<CancellationToken>) -> boo
         .is_some_and(|t| t.is_cancelled())
 }
 
+/// Wait until the token is cancelled, or forever if there is no token
+pub async fn wait_for_cancellation(cancellation_token: &Option<CancellationToken>) {
+    match cancellation_token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,12 +140,19 @@ async fn run_truncate_test(
             Ok(AgentEvent::McpNotification(n)) => {
                 println!("MCP Notification: {n:?}");
             }
+            Ok(AgentEvent::ToolProgress(progress)) => {
+                println!("Tool progress: {progress:?}");
+            }
             Ok(AgentEvent::ModelChange { .. }) => {
                 // Model change events are informational, just continue
             }
             Ok(AgentEvent::HistoryReplaced(_)) => {
                 // Handle history replacement events if needed
             }
+            Ok(AgentEvent::MaxTurnsReached) => {}
+            Ok(AgentEvent::Error(e)) => {
+                println!("Provider error: {e}");
+            }
             Err(e) => {
                 println!("Error: {:?}", e);
                 return Err(e);
@@ -1027,6 +1034,7 @@ mod max_turns_tests {
         tokio::pin!(reply_stream);
 
         let mut responses = Vec::new();
+        let mut reached_max_turns = false;
         while let Some(response_result) = reply_stream.next().await {
             match response_result {
                 Ok(AgentEvent::Message(response)) => {
@@ -1044,14 +1052,19 @@ mod max_turns_tests {
                     responses.push(response);
                 }
                 Ok(AgentEvent::McpNotification(_)) => {}
+                Ok(AgentEvent::ToolProgress(_)) => {}
                 Ok(AgentEvent::ModelChange { .. }) => {}
                 Ok(AgentEvent::HistoryReplaced(_)) => {}
+                Ok(AgentEvent::MaxTurnsReached) => reached_max_turns = true,
+                Ok(AgentEvent::Error(e)) => panic!("Unexpected provider error: {e}"),
                 Err(e) => {
                     return Err(e);
                 }
             }
         }
 
+        assert!(reached_max_turns, "Expected a MaxTurnsReached event");
+
         assert!(
             responses.len() >= 1,
             "Expected at least 1 response, got {}",
@@ -363,6 +363,7 @@ impl ScheduleToolTestBuilder {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some("background".to_string()),
+            budget: None,
         };
         {
             let mut jobs = self.scheduler.jobs.lock().await;
@@ -412,5 +413,9 @@ pub fn create_test_session_metadata(message_count: usize, working_dir: &str) ->
         accumulated_total_tokens: Some(100),
         accumulated_input_tokens: Some(50),
         accumulated_output_tokens: Some(50),
+        accumulated_cost: None,
+        parent_session_id: None,
+        fork_message_index: None,
+        served_by: Vec::new(),
     }
 }
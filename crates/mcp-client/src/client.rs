@@ -1,22 +1,22 @@
 use mcp_core::protocol::{
-    CallToolResult, Implementation, InitializeResult, ListPromptsResult, ListResourcesResult,
-    ListToolsResult, ReadResourceResult, ServerCapabilities, METHOD_NOT_FOUND,
+    CallToolResult, EmptyResult, Implementation, InitializeResult, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, ReadResourceResult, ServerCapabilities, METHOD_NOT_FOUND,
 };
 use rmcp::model::{
     GetPromptResult, JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest,
-    JsonRpcResponse, JsonRpcVersion2_0, Notification, NumberOrString, Request, RequestId,
-    ServerNotification,
+    JsonRpcResponse, JsonRpcVersion2_0, Notification, Request, RequestId, ServerNotification,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::{
-    atomic::{AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
 use thiserror::Error;
 use tokio::sync::{mpsc, Mutex};
 use tower::{timeout::TimeoutLayer, Layer, Service, ServiceExt};
 
+use crate::handler::{dispatch_request, ClientHandler};
 use crate::{McpService, TransportHandle};
 
 pub type BoxError = Box<dyn std::error::Error + Sync + Send>;
@@ -72,7 +72,29 @@ pub struct ClientInfo {
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct ClientCapabilities {
-    // Add fields as needed. For now, empty capabilities are fine.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roots: Option<RootsCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<ElicitationCapability>,
+}
+
+/// Advertises that the client answers `sampling/createMessage` requests
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct SamplingCapability {}
+
+/// Advertises that the client answers `elicitation/create` requests
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ElicitationCapability {}
+
+/// Advertises that the client answers `roots/list` requests
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RootsCapability {
+    /// Whether the client sends `notifications/roots/list_changed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_changed: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -99,6 +121,12 @@ pub trait McpClientTrait: Send + Sync {
 
     async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, Error>;
 
+    /// Ask the server to send `notifications/resources/updated` when the resource changes
+    async fn subscribe_resource(&self, uri: &str) -> Result<(), Error>;
+
+    /// Stop the updates requested with `subscribe_resource`
+    async fn unsubscribe_resource(&self, uri: &str) -> Result<(), Error>;
+
     async fn list_tools(&self, next_cursor: Option<String>) -> Result<ListToolsResult, Error>;
 
     async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, Error>;
@@ -108,6 +136,52 @@ pub trait McpClientTrait: Send + Sync {
     async fn get_prompt(&self, name: &str, arguments: Value) -> Result<GetPromptResult, Error>;
 
     async fn subscribe(&self) -> mpsc::Receiver<ServerNotification>;
+
+    /// Tell the server the roots changed, so it calls `roots/list` again
+    async fn notify_roots_list_changed(&self) -> Result<(), Error>;
+
+    /// Whether the connection to the server is gone, e.g. because its process was terminated
+    fn is_closed(&self) -> bool {
+        false
+    }
+}
+
+/// Cancels a request on the server when it is dropped before the response arrives,
+/// e.g. because the caller stopped waiting or the request timed out
+struct CancelOnDrop<T>
+where
+    T: TransportHandle + Send + Sync + 'static,
+{
+    service: Option<McpService<T>>,
+    id: RequestId,
+}
+
+impl<T> CancelOnDrop<T>
+where
+    T: TransportHandle + Send + Sync + 'static,
+{
+    fn disarm(mut self) {
+        self.service = None;
+    }
+}
+
+impl<T> Drop for CancelOnDrop<T>
+where
+    T: TransportHandle + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        let Some(service) = self.service.take() else {
+            return;
+        };
+        let id = self.id.clone();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                service
+                    .cancel(id, "The client is no longer waiting for a response")
+                    .await;
+            });
+        }
+    }
 }
 
 /// The MCP client is the interface for MCP operations.
@@ -120,6 +194,7 @@ where
     server_capabilities: Option<ServerCapabilities>,
     server_info: Option<Implementation>,
     notification_subscribers: Arc<Mutex<Vec<mpsc::Sender<ServerNotification>>>>,
+    closed: Arc<AtomicBool>,
 }
 
 impl<T> McpClient<T>
@@ -127,11 +202,24 @@ where
     T: TransportHandle + Send + Sync + 'static,
 {
     pub async fn connect(transport: T, timeout: std::time::Duration) -> Result<Self, Error> {
+        Self::connect_with_handler(transport, timeout, None).await
+    }
+
+    /// Connect, answering requests from the server with `handler`
+    ///
+    /// Without a handler, server requests are rejected as unsupported.
+    pub async fn connect_with_handler(
+        transport: T,
+        timeout: std::time::Duration,
+        handler: Option<Arc<dyn ClientHandler>>,
+    ) -> Result<Self, Error> {
         let service = McpService::new(transport.clone());
         let service_ptr = service.clone();
         let notification_subscribers =
             Arc::new(Mutex::new(Vec::<mpsc::Sender<ServerNotification>>::new()));
         let subscribers_ptr = notification_subscribers.clone();
+        let closed = Arc::new(AtomicBool::new(false));
+        let closed_ptr = closed.clone();
 
         tokio::spawn(async move {
             loop {
@@ -139,15 +227,42 @@ where
                     Ok(message) => {
                         tracing::info!("Received message: {:?}", message);
                         match message {
-                            JsonRpcMessage::Response(JsonRpcResponse {
-                                id: NumberOrString::Number(id),
-                                ..
-                            })
-                            | JsonRpcMessage::Error(JsonRpcError {
-                                id: NumberOrString::Number(id),
-                                ..
-                            }) => {
-                                service_ptr.respond(&id.to_string(), Ok(message)).await;
+                            JsonRpcMessage::Response(JsonRpcResponse { ref id, .. })
+                            | JsonRpcMessage::Error(JsonRpcError { ref id, .. }) => {
+                                let id = id.to_string();
+                                service_ptr.respond(&id, Ok(message)).await;
+                            }
+                            JsonRpcMessage::Request(JsonRpcRequest { id, request, .. }) => {
+                                // Requests can wait on the user, so answer them off the receive loop
+                                let transport = transport.clone();
+                                let handler = handler.clone();
+                                tokio::spawn(async move {
+                                    let response = match dispatch_request(
+                                        handler.as_deref(),
+                                        &request.method,
+                                        request.params,
+                                    )
+                                    .await
+                                    {
+                                        Ok(result) => JsonRpcMessage::Response(JsonRpcResponse {
+                                            jsonrpc: JsonRpcVersion2_0,
+                                            id,
+                                            result,
+                                        }),
+                                        Err(error) => JsonRpcMessage::Error(JsonRpcError {
+                                            jsonrpc: JsonRpcVersion2_0,
+                                            id,
+                                            error,
+                                        }),
+                                    };
+                                    if let Err(e) = transport.send(response).await {
+                                        tracing::error!(
+                                            "Failed to respond to {}: {}",
+                                            request.method,
+                                            e
+                                        );
+                                    }
+                                });
                             }
                             JsonRpcMessage::Notification(JsonRpcNotification {
                                 notification,
@@ -169,6 +284,7 @@ where
                         }
                     }
                     Err(e) => {
+                        closed_ptr.store(true, Ordering::SeqCst);
                         service_ptr.hangup(e).await;
                         subscribers_ptr.lock().await.clear();
                         break;
@@ -185,6 +301,7 @@ where
             server_capabilities: None,
             server_info: None,
             notification_subscribers,
+            closed,
         })
     }
 
@@ -213,19 +330,29 @@ where
             },
         });
 
-        let response_msg = service
-            .call(request)
-            .await
-            .map_err(|e| Error::McpServerError {
-                server: self
-                    .server_info
-                    .as_ref()
-                    .map(|s| s.name.clone())
-                    .unwrap_or("".to_string()),
-                method: method.to_string(),
-                // we don't need include params because it can be really large
-                source: Box::<Error>::new(e.into()),
-            })?;
+        // Servers must not be asked to cancel initialization
+        let cancel_on_drop = (method != "initialize").then(|| CancelOnDrop {
+            service: Some(service.get_ref().clone()),
+            id: RequestId::Number(id_num as u32),
+        });
+
+        let response_msg = service.call(request).await;
+        if let Some(guard) = cancel_on_drop {
+            if response_msg.is_ok() {
+                guard.disarm();
+            }
+        }
+
+        let response_msg = response_msg.map_err(|e| Error::McpServerError {
+            server: self
+                .server_info
+                .as_ref()
+                .map(|s| s.name.clone())
+                .unwrap_or("".to_string()),
+            method: method.to_string(),
+            // we don't need include params because it can be really large
+            source: Box::<Error>::new(e.into()),
+        })?;
 
         match response_msg {
             JsonRpcMessage::Response(JsonRpcResponse { id, result, .. }) => {
@@ -294,6 +421,31 @@ where
     fn completed_initialization(&self) -> bool {
         self.server_capabilities.is_some()
     }
+
+    /// Send `resources/subscribe` or `resources/unsubscribe` if the server supports subscriptions
+    async fn send_resource_subscription(&self, method: &str, uri: &str) -> Result<(), Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+        let supports_subscribe = self
+            .server_capabilities
+            .as_ref()
+            .unwrap()
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        if !supports_subscribe {
+            return Err(Error::RpcError {
+                code: METHOD_NOT_FOUND,
+                message: "Server does not support resource subscriptions".to_string(),
+            });
+        }
+
+        let params = serde_json::json!({ "uri": uri });
+        let _: EmptyResult = self.send_request(method, params).await?;
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -375,6 +527,16 @@ where
         self.send_request("resources/read", params).await
     }
 
+    async fn subscribe_resource(&self, uri: &str) -> Result<(), Error> {
+        self.send_resource_subscription("resources/subscribe", uri)
+            .await
+    }
+
+    async fn unsubscribe_resource(&self, uri: &str) -> Result<(), Error> {
+        self.send_resource_subscription("resources/unsubscribe", uri)
+            .await
+    }
+
     async fn list_tools(&self, next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
         if !self.completed_initialization() {
             return Err(Error::NotInitialized);
@@ -456,4 +618,16 @@ where
         self.notification_subscribers.lock().await.push(tx);
         rx
     }
+
+    async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+        self.send_notification("notifications/roots/list_changed", serde_json::json!({}))
+            .await
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
 }
@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use mcp_core::protocol::{
+    CreateMessageParams, CreateMessageResult, ElicitRequestParams, ElicitResult, ListRootsResult,
+};
+use rmcp::model::{ErrorCode, ErrorData, JsonObject};
+use std::borrow::Cow;
+
+/// Answers the requests a server sends to the client
+///
+/// Every method has a default that rejects the request, so implementors only handle
+/// the capabilities they advertise in `ClientCapabilities`.
+#[async_trait]
+pub trait ClientHandler: Send + Sync {
+    /// Handle `sampling/createMessage` by completing the conversation with an LLM
+    async fn create_message(
+        &self,
+        _params: CreateMessageParams,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        Err(method_not_found("sampling/createMessage"))
+    }
+
+    /// Handle `roots/list` with the directories the server may work in
+    async fn list_roots(&self) -> Result<ListRootsResult, ErrorData> {
+        Err(method_not_found("roots/list"))
+    }
+
+    /// Handle `elicitation/create` by asking the user for the requested information
+    async fn create_elicitation(
+        &self,
+        _params: ElicitRequestParams,
+    ) -> Result<ElicitResult, ErrorData> {
+        Err(method_not_found("elicitation/create"))
+    }
+}
+
+fn method_not_found(method: &str) -> ErrorData {
+    ErrorData {
+        code: ErrorCode::METHOD_NOT_FOUND,
+        message: Cow::from(format!("Client does not support '{}'", method)),
+        data: None,
+    }
+}
+
+fn invalid_params(error: serde_json::Error) -> ErrorData {
+    ErrorData {
+        code: ErrorCode::INVALID_PARAMS,
+        message: Cow::from(error.to_string()),
+        data: None,
+    }
+}
+
+fn internal_error(error: serde_json::Error) -> ErrorData {
+    ErrorData {
+        code: ErrorCode::INTERNAL_ERROR,
+        message: Cow::from(error.to_string()),
+        data: None,
+    }
+}
+
+fn to_object<R: serde::Serialize>(result: R) -> Result<JsonObject, ErrorData> {
+    match serde_json::to_value(result).map_err(internal_error)? {
+        serde_json::Value::Object(object) => Ok(object),
+        _ => Ok(JsonObject::new()),
+    }
+}
+
+/// Route a server request to the matching handler method
+pub(crate) async fn dispatch_request(
+    handler: Option<&dyn ClientHandler>,
+    method: &str,
+    params: JsonObject,
+) -> Result<JsonObject, ErrorData> {
+    let Some(handler) = handler else {
+        return Err(method_not_found(method));
+    };
+    let params = serde_json::Value::Object(params);
+    match method {
+        "sampling/createMessage" => {
+            let params = serde_json::from_value(params).map_err(invalid_params)?;
+            to_object(handler.create_message(params).await?)
+        }
+        "roots/list" => to_object(handler.list_roots().await?),
+        "elicitation/create" => {
+            let params = serde_json::from_value(params).map_err(invalid_params)?;
+            to_object(handler.create_elicitation(params).await?)
+        }
+        _ => Err(method_not_found(method)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{Content, Role};
+    use serde_json::json;
+
+    struct EchoHandler;
+
+    #[async_trait]
+    impl ClientHandler for EchoHandler {
+        async fn create_message(
+            &self,
+            params: CreateMessageParams,
+        ) -> Result<CreateMessageResult, ErrorData> {
+            Ok(CreateMessageResult {
+                role: Role::Assistant,
+                content: params.messages[0].content.clone(),
+                model: "echo".to_string(),
+                stop_reason: Some("endTurn".to_string()),
+            })
+        }
+    }
+
+    fn params(value: serde_json::Value) -> JsonObject {
+        value.as_object().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_sampling_request() {
+        let result = dispatch_request(
+            Some(&EchoHandler),
+            "sampling/createMessage",
+            params(json!({
+                "messages": [{"role": "user", "content": {"type": "text", "text": "hello"}}],
+                "maxTokens": 100
+            })),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["model"], "echo");
+        assert_eq!(result["stopReason"], "endTurn");
+        assert_eq!(
+            serde_json::from_value::<Content>(result["content"].clone()).unwrap(),
+            Content::text("hello")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_rejects_unsupported_requests() {
+        let err = dispatch_request(None, "sampling/createMessage", JsonObject::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::METHOD_NOT_FOUND);
+
+        let err = dispatch_request(
+            Some(&EchoHandler),
+            "sampling/createMessage",
+            JsonObject::new(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+        // EchoHandler doesn't override list_roots or create_elicitation
+        let err = dispatch_request(Some(&EchoHandler), "roots/list", JsonObject::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::METHOD_NOT_FOUND);
+
+        let err = dispatch_request(
+            Some(&EchoHandler),
+            "elicitation/create",
+            params(json!({"message": "Name?", "requestedSchema": {"type": "object"}})),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, ErrorCode::METHOD_NOT_FOUND);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod client;
+pub mod handler;
 pub mod oauth;
 pub mod service;
 pub mod transport;
@@ -6,7 +7,11 @@ pub mod transport;
 #[cfg(test)]
 mod oauth_tests;
 
-pub use client::{ClientCapabilities, ClientInfo, Error, McpClient, McpClientTrait};
+pub use client::{
+    ClientCapabilities, ClientInfo, ElicitationCapability, Error, McpClient, McpClientTrait,
+    RootsCapability, SamplingCapability,
+};
+pub use handler::ClientHandler;
 pub use oauth::{authenticate_service, ServiceConfig};
 pub use service::McpService;
 pub use transport::{
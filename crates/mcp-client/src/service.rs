@@ -1,5 +1,9 @@
 use futures::future::BoxFuture;
-use rmcp::model::{JsonRpcMessage, JsonRpcRequest};
+use rmcp::model::{
+    JsonObject, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, JsonRpcVersion2_0,
+    Notification, Request, RequestId,
+};
+use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -30,6 +34,56 @@ impl<T: TransportHandle> McpService<T> {
     pub async fn hangup(&self, error: Error) {
         self.pending_requests.broadcast_close(error).await
     }
+
+    /// Tell the server to stop working on a request nobody is waiting for anymore
+    ///
+    /// If the transport has a cancel grace period, the server is pinged afterwards and
+    /// terminated when it doesn't answer within that period, e.g. because it is still
+    /// busy with the cancelled request.
+    pub async fn cancel(&self, id: RequestId, reason: &str) {
+        self.pending_requests.remove(&id.to_string()).await;
+
+        let params = json!({ "requestId": id, "reason": reason });
+        let notification = JsonRpcMessage::Notification(JsonRpcNotification {
+            jsonrpc: JsonRpcVersion2_0,
+            notification: Notification {
+                method: "notifications/cancelled".to_string(),
+                params: params.as_object().unwrap().clone(),
+                extensions: Default::default(),
+            },
+        });
+        if let Err(e) = self.inner.send(notification).await {
+            tracing::debug!("Failed to cancel request {}: {}", id, e);
+            return;
+        }
+
+        let Some(grace_period) = self.inner.cancel_grace_period() else {
+            return;
+        };
+        let ping_id = RequestId::String(format!("ping-after-cancel-{}", id).into());
+        let ping = JsonRpcMessage::Request(JsonRpcRequest {
+            jsonrpc: JsonRpcVersion2_0,
+            id: ping_id.clone(),
+            request: Request {
+                method: "ping".to_string(),
+                params: JsonObject::new(),
+                extensions: Default::default(),
+            },
+        });
+        // Any answer, even an error, shows the server is free to handle requests again
+        if tokio::time::timeout(grace_period, self.clone().call(ping))
+            .await
+            .is_err()
+        {
+            self.pending_requests.remove(&ping_id.to_string()).await;
+            tracing::warn!(
+                "Server did not respond within {:?} after request {} was cancelled, terminating it",
+                grace_period,
+                id
+            );
+            self.inner.terminate().await;
+        }
+    }
 }
 
 impl<T> Service<JsonRpcMessage> for McpService<T>
@@ -121,6 +175,11 @@ impl PendingRequests {
         }
     }
 
+    /// Stop waiting for a response, dropping it if it still arrives
+    pub async fn remove(&self, id: &str) {
+        self.requests.write().await.remove(id);
+    }
+
     pub async fn broadcast_close(&self, error: Error) {
         for (_, tx) in self.requests.write().await.drain() {
             let err = match &error {
@@ -143,3 +202,94 @@ impl PendingRequests {
         self.len().await == 0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use tokio::sync::Mutex;
+
+    /// Records what the client sends and never answers, like a server stuck on a request
+    #[derive(Clone, Default)]
+    struct StuckTransport {
+        sent: Arc<Mutex<Vec<JsonRpcMessage>>>,
+        terminated: Arc<AtomicBool>,
+        grace_period: Option<Duration>,
+    }
+
+    #[async_trait]
+    impl TransportHandle for StuckTransport {
+        async fn send(&self, message: JsonRpcMessage) -> Result<(), Error> {
+            self.sent.lock().await.push(message);
+            Ok(())
+        }
+
+        async fn receive(&self) -> Result<TransportMessageRecv, Error> {
+            futures::future::pending().await
+        }
+
+        fn cancel_grace_period(&self) -> Option<Duration> {
+            self.grace_period
+        }
+
+        async fn terminate(&self) {
+            self.terminated.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn sent_methods(sent: &[JsonRpcMessage]) -> Vec<String> {
+        sent.iter()
+            .map(|message| match message {
+                JsonRpcMessage::Request(request) => request.request.method.clone(),
+                JsonRpcMessage::Notification(notification) => {
+                    notification.notification.method.clone()
+                }
+                _ => "other".to_string(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_notifies_server() {
+        let transport = StuckTransport::default();
+        let service = McpService::new(transport.clone());
+
+        service
+            .cancel(RequestId::Number(7), "user interrupted")
+            .await;
+
+        let sent = transport.sent.lock().await;
+        assert_eq!(sent_methods(&sent), vec!["notifications/cancelled"]);
+        let JsonRpcMessage::Notification(notification) = &sent[0] else {
+            panic!("expected a notification");
+        };
+        assert_eq!(notification.notification.params["requestId"], 7);
+        assert_eq!(
+            notification.notification.params["reason"],
+            "user interrupted"
+        );
+        assert!(!transport.terminated.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_terminates_unresponsive_server_after_grace_period() {
+        let transport = StuckTransport {
+            grace_period: Some(Duration::from_millis(10)),
+            ..Default::default()
+        };
+        let service = McpService::new(transport.clone());
+
+        service
+            .cancel(RequestId::Number(7), "user interrupted")
+            .await;
+
+        assert_eq!(
+            sent_methods(&transport.sent.lock().await),
+            vec!["notifications/cancelled", "ping"]
+        );
+        assert!(transport.terminated.load(Ordering::SeqCst));
+        assert!(service.pending_requests.is_empty().await);
+    }
+}
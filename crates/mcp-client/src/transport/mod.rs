@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use rmcp::model::{JsonObject, JsonRpcMessage, Request, ServerNotification};
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::mpsc;
 
@@ -57,6 +58,15 @@ pub type TransportMessageRecv = JsonRpcMessage<Request, JsonObject, ServerNotifi
 pub trait TransportHandle: Send + Sync + Clone + 'static {
     async fn send(&self, message: JsonRpcMessage) -> Result<(), Error>;
     async fn receive(&self) -> Result<TransportMessageRecv, Error>;
+
+    /// How long the server may stay unresponsive after a request is cancelled before
+    /// it is terminated. `None` leaves the server running.
+    fn cancel_grace_period(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Forcefully stop the server, for transports that own its process
+    async fn terminate(&self) {}
 }
 
 pub async fn serialize_and_send(
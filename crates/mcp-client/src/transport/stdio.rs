@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
 
 use async_trait::async_trait;
 use rmcp::model::JsonRpcMessage;
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, Notify};
 
 // Import nix crate components instead of libc
 #[cfg(unix)]
@@ -32,6 +33,7 @@ pub struct StdioActor {
     stdin: Option<ChildStdin>,
     stdout: Option<ChildStdout>,
     stderr: Option<ChildStderr>,
+    terminate: Arc<Notify>,
 }
 
 impl Drop for StdioActor {
@@ -67,25 +69,37 @@ impl StdioActor {
         pin!(incoming);
         pin!(outgoing);
 
-        // Use select! to wait for either I/O completion or process exit
-        tokio::select! {
+        // Use select! to wait for either I/O completion, process exit or termination
+        let terminated = tokio::select! {
             result = &mut incoming => {
                 tracing::debug!("Stdin handler completed: {:?}", result);
+                false
             }
             result = &mut outgoing => {
                 tracing::debug!("Stdout handler completed: {:?}", result);
+                false
             }
             // capture the status so we don't need to wait for a timeout
             status = self.process.wait() => {
                 tracing::debug!("Process exited with status: {:?}", status);
+                false
             }
+            _ = self.terminate.notified() => true,
+        };
+
+        if terminated {
+            self.kill_process_group();
+            let _ = self.process.wait().await;
         }
 
         // Then always try to read stderr before cleaning up
         let mut stderr_buffer = Vec::new();
         if let Some(mut stderr) = self.stderr.take() {
             if let Ok(bytes) = stderr.read_to_end(&mut stderr_buffer).await {
-                let err_msg = if bytes > 0 {
+                let err_msg = if terminated {
+                    "Process was stopped after it did not respond to a cancelled request"
+                        .to_string()
+                } else if bytes > 0 {
                     String::from_utf8_lossy(&stderr_buffer).to_string()
                 } else {
                     "Process ended unexpectedly".to_string()
@@ -100,6 +114,18 @@ impl StdioActor {
         }
     }
 
+    /// Kill the process along with anything it started, such as shell commands
+    fn kill_process_group(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.process.id() {
+            if let Ok(pgid) = getpgid(Some(Pid::from_raw(pid as i32))) {
+                let _ = kill(Pid::from_raw(-pgid.as_raw()), Signal::SIGKILL);
+                return;
+            }
+        }
+        let _ = self.process.start_kill();
+    }
+
     async fn handle_proc_output(stdout: ChildStdout, sender: mpsc::Sender<TransportMessageRecv>) {
         let mut reader = BufReader::new(stdout);
         let mut line = String::new();
@@ -154,6 +180,8 @@ pub struct StdioTransportHandle {
     sender: mpsc::Sender<String>,                               // to process
     receiver: Arc<Mutex<mpsc::Receiver<TransportMessageRecv>>>, // from process
     error_receiver: Arc<Mutex<mpsc::Receiver<Error>>>,
+    terminate: Arc<Notify>,
+    cancel_grace_period: Option<Duration>,
 }
 
 #[async_trait::async_trait]
@@ -175,6 +203,14 @@ impl TransportHandle for StdioTransportHandle {
             }
         }
     }
+
+    fn cancel_grace_period(&self) -> Option<Duration> {
+        self.cancel_grace_period
+    }
+
+    async fn terminate(&self) {
+        self.terminate.notify_one();
+    }
 }
 
 impl StdioTransportHandle {
@@ -194,6 +230,7 @@ pub struct StdioTransport {
     command: String,
     args: Vec<String>,
     env: HashMap<String, String>,
+    cancel_grace_period: Option<Duration>,
 }
 
 impl StdioTransport {
@@ -206,9 +243,16 @@ impl StdioTransport {
             command: command.into(),
             args,
             env,
+            cancel_grace_period: None,
         }
     }
 
+    /// Kill the process if it is still unresponsive this long after a request was cancelled
+    pub fn with_cancel_grace_period(mut self, grace_period: Duration) -> Self {
+        self.cancel_grace_period = Some(grace_period);
+        self
+    }
+
     async fn spawn_process(&self) -> Result<(Child, ChildStdin, ChildStdout, ChildStderr), Error> {
         let mut command = Command::new(&self.command);
         command
@@ -281,6 +325,7 @@ impl Transport for StdioTransport {
         let (outbox_tx, outbox_rx) = mpsc::channel(32);
         let (inbox_tx, inbox_rx) = mpsc::channel(32);
         let (error_tx, error_rx) = mpsc::channel(1);
+        let terminate = Arc::new(Notify::new());
 
         let actor = StdioActor {
             receiver: Some(outbox_rx), // client to process
@@ -290,6 +335,7 @@ impl Transport for StdioTransport {
             stdin: Some(stdin),
             stdout: Some(stdout),
             stderr: Some(stderr),
+            terminate: terminate.clone(),
         };
 
         tokio::spawn(actor.run());
@@ -298,6 +344,8 @@ impl Transport for StdioTransport {
             sender: outbox_tx,                        // client to process
             receiver: Arc::new(Mutex::new(inbox_rx)), // process to client
             error_receiver: Arc::new(Mutex::new(error_rx)),
+            terminate,
+            cancel_grace_period: self.cancel_grace_period,
         };
         Ok(handle)
     }
@@ -0,0 +1,4 @@
+/// Reserved tool argument through which goose passes builtin extensions the current edit checkpoint
+///
+/// goose removes it from the arguments the model sends, so only goose can set it.
+pub const CHECKPOINT_ARG: &str = "__goose_checkpoint";
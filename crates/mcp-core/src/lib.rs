@@ -1,5 +1,7 @@
+pub mod checkpoint;
 pub mod handler;
 pub mod tool;
 pub use tool::{Tool, ToolCall};
 pub mod protocol;
+pub mod sandbox;
 pub use handler::{ToolError, ToolResult};
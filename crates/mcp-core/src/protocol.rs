@@ -1,6 +1,6 @@
 /// The protocol messages exchanged between client and server
 use rmcp::model::Tool;
-use rmcp::model::{Content, ErrorData, Prompt, PromptMessage, Resource, ResourceContents};
+use rmcp::model::{Content, ErrorData, Prompt, PromptMessage, Resource, ResourceContents, Role};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -217,6 +217,86 @@ pub struct GetPromptResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EmptyResult {}
 
+/// A message in a sampling request or result
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SamplingMessage {
+    pub role: Role,
+    pub content: Content,
+}
+
+/// Parameters of a `sampling/createMessage` request sent by a server
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageParams {
+    pub messages: Vec<SamplingMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    pub role: Role,
+    pub content: Content,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+/// A directory or file the client exposes to servers as a root
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Root {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ListRootsResult {
+    pub roots: Vec<Root>,
+}
+
+/// Parameters of an `elicitation/create` request sent by a server
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitRequestParams {
+    /// The question to show the user
+    pub message: String,
+    /// A flat JSON object schema describing the answer the server expects
+    pub requested_schema: Value,
+}
+
+/// How the user responded to an elicitation request
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    /// The user submitted the form
+    Accept,
+    /// The user explicitly refused to answer
+    Decline,
+    /// The user dismissed the form without choosing
+    Cancel,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ElicitResult {
+    pub action: ElicitAction,
+    /// The submitted values, present when the action is `accept`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Value>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// Environment variable through which goose hands a builtin extension its sandbox settings
+pub const SANDBOX_ENV: &str = "GOOSE_SANDBOX";
+
+/// Sandbox for the shell commands a builtin extension runs
+///
+/// Commands run under bubblewrap in new Linux namespaces: the working directory and
+/// `writable_paths` are writable, everything else is read-only and the network is off
+/// unless `network` is set. The limits are unset by default, and `timeout_seconds`
+/// applies even when the sandbox itself is disabled.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(default)]
+pub struct SandboxConfig {
+    pub enabled: bool,
+    pub network: bool,
+    pub writable_paths: Vec<String>,
+    /// CPU time limit per command, in seconds
+    pub cpu_seconds: Option<u64>,
+    /// Virtual memory limit per command, in megabytes
+    pub memory_mb: Option<u64>,
+    /// Wall clock limit per command, in seconds
+    pub timeout_seconds: Option<u64>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            network: false,
+            writable_paths: Vec::new(),
+            cpu_seconds: None,
+            memory_mb: None,
+            timeout_seconds: None,
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// Wall clock limit for a single command
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_seconds.map(Duration::from_secs)
+    }
+}
@@ -26,6 +26,33 @@ export type AuthorRequest = {
     metadata?: string | null;
 };
 
+/**
+ * Compaction settings for a session, usually from a recipe
+ *
+ * Anything left unset falls back to the global config.
+ */
+export type CompactionConfig = {
+    /**
+     * Turns kept verbatim in structured mode (default: GOOSE_COMPACTION_KEEP_TURNS, or 3)
+     */
+    keep_recent_turns?: number | null;
+    mode?: CompactionMode | null;
+    /**
+     * Share of the context window that triggers auto-compaction (default: GOOSE_AUTO_COMPACT_THRESHOLD)
+     */
+    threshold?: number | null;
+};
+
+/**
+ * How a conversation is condensed when it gets close to the context limit
+ */
+export type CompactionMode = 'summarize' | 'structured';
+
+/**
+ * What a confirmation request asks the user to allow
+ */
+export type ConfirmationKind = 'toolCall' | 'sampling';
+
 export type ConfigKey = {
     default?: string | null;
     name: string;
@@ -76,6 +103,24 @@ export type ContextManageResponse = {
     tokenCounts: Array<number>;
 };
 
+/**
+ * Spending limits for a session, in US dollars, from a recipe or a scheduled job
+ *
+ * Anything left unset falls back to the global config. The cost of a turn is estimated
+ * from the pricing of the model that served it, so turns of models without known
+ * pricing don't count towards the limits.
+ */
+export type CostBudget = {
+    /**
+     * Spend at which the agent stops (default: GOOSE_SESSION_HARD_BUDGET)
+     */
+    hard_limit?: number | null;
+    /**
+     * Spend at which the agent pauses and asks whether to go on (default: GOOSE_SESSION_SOFT_BUDGET)
+     */
+    soft_limit?: number | null;
+};
+
 export type CreateRecipeRequest = {
     activities?: Array<string> | null;
     author?: AuthorRequest | null;
@@ -90,6 +135,7 @@ export type CreateRecipeResponse = {
 };
 
 export type CreateScheduleRequest = {
+    budget?: CostBudget | null;
     cron: string;
     execution_mode?: string | null;
     id: string;
@@ -104,6 +150,35 @@ export type DecodeRecipeResponse = {
     recipe: Recipe;
 };
 
+/**
+ * An extension asking the user to fill in a form while one of its tools runs
+ */
+export type ElicitationRequest = {
+    extensionName: string;
+    id: string;
+    message: string;
+    /**
+     * A flat JSON object schema of the fields to ask for
+     */
+    requestedSchema: {
+        [key: string]: unknown;
+    };
+};
+
+export type ElicitationResponseRequest = {
+    /**
+     * "accept", "decline" or "cancel"
+     */
+    action: string;
+    /**
+     * The submitted form values, when accepting
+     */
+    content?: {
+        [key: string]: unknown;
+    } | null;
+    id: string;
+};
+
 export type EmbeddedResource = {
     annotations?: Annotations | {
         [key: string]: unknown;
@@ -168,6 +243,10 @@ export type ExtensionConfig = {
      * The name used to identify this extension
      */
     name: string;
+    /**
+     * Run the extension's shell commands in a sandbox (Linux only)
+     */
+    sandbox?: SandboxConfig | null;
     timeout?: number | null;
     type: 'builtin';
 } | {
@@ -246,6 +325,25 @@ export type ExtensionResponse = {
     extensions: Array<ExtensionEntry>;
 };
 
+export type ForkSessionRequest = {
+    /**
+     * Index of the first message to leave out of the fork
+     */
+    messageIndex: number;
+    /**
+     * Id for the new session; generated when omitted
+     */
+    newSessionId?: string | null;
+};
+
+export type ForkSessionResponse = {
+    metadata: SessionMetadata;
+    /**
+     * Id of the newly created session
+     */
+    sessionId: string;
+};
+
 export type FrontendToolRequest = {
     id: string;
     toolCall: {
@@ -298,6 +396,8 @@ export type MessageContent = (TextContent & {
     type: 'toolResponse';
 }) | (ToolConfirmationRequest & {
     type: 'toolConfirmationRequest';
+}) | (ElicitationRequest & {
+    type: 'elicitationRequest';
 }) | (FrontendToolRequest & {
     type: 'frontendToolRequest';
 }) | (ThinkingContent & {
@@ -432,6 +532,7 @@ export type RawTextContent = {
  * * `parameters` - Additional parameters for the Recipe
  * * `response` - Response configuration including JSON schema validation
  * * `retry` - Retry configuration for automated validation and recovery
+ * * `compaction` - How auto-compaction condenses sessions run from the Recipe
  * # Example
  *
  *
@@ -461,12 +562,14 @@ export type RawTextContent = {
  * response: None,
  * sub_recipes: None,
  * retry: None,
+ * compaction: None,
  * };
  *
  */
 export type Recipe = {
     activities?: Array<string> | null;
     author?: Author | null;
+    compaction?: CompactionConfig | null;
     context?: Array<string> | null;
     description: string;
     extensions?: Array<ExtensionConfig> | null;
@@ -544,7 +647,37 @@ export type RunNowResponse = {
     session_id: string;
 };
 
+/**
+ * Sandbox for the shell commands a builtin extension runs
+ *
+ * Commands run under bubblewrap in new Linux namespaces: the working directory and
+ * `writable_paths` are writable, everything else is read-only and the network is off
+ * unless `network` is set. The limits are unset by default, and `timeout_seconds`
+ * applies even when the sandbox itself is disabled.
+ */
+export type SandboxConfig = {
+    /**
+     * CPU time limit per command, in seconds
+     */
+    cpu_seconds?: number | null;
+    enabled?: boolean;
+    /**
+     * Virtual memory limit per command, in megabytes
+     */
+    memory_mb?: number | null;
+    network?: boolean;
+    /**
+     * Wall clock limit per command, in seconds
+     */
+    timeout_seconds?: number | null;
+    writable_paths?: Array<string>;
+};
+
 export type ScheduledJob = {
+    /**
+     * Spending limits for each run, over those of the recipe's settings
+     */
+    budget?: CostBudget | null;
     cron: string;
     current_session_id?: string | null;
     currently_running?: boolean;
@@ -556,7 +689,54 @@ export type ScheduledJob = {
     source: string;
 };
 
+/**
+ * A single message matching a search query
+ */
+export type SearchResult = {
+    /**
+     * Creation timestamp of the matching message
+     */
+    created: number;
+    /**
+     * Description of the session
+     */
+    description: string;
+    /**
+     * Index of the matching message within the session
+     */
+    messageIndex: number;
+    /**
+     * Relevance score, higher is better
+     */
+    score: number;
+    /**
+     * Id of the session containing the match
+     */
+    sessionId: string;
+    /**
+     * Excerpt of the matching text with matched terms wrapped in `**`
+     */
+    snippet: string;
+};
+
+/**
+ * A run of consecutive turns answered by the same provider and model
+ */
+export type ServedBy = {
+    /**
+     * Index of the message that starts the run
+     */
+    first_message: number;
+    model: string;
+    provider: string;
+    /**
+     * Number of turns in the run
+     */
+    turns: number;
+};
+
 export type SessionDisplayInfo = {
+    accumulatedCost?: number | null;
     accumulatedInputTokens?: number | null;
     accumulatedOutputTokens?: number | null;
     accumulatedTotalTokens?: number | null;
@@ -601,6 +781,10 @@ export type SessionListResponse = {
  * Metadata for a session, stored as the first line in the session file
  */
 export type SessionMetadata = {
+    /**
+     * Estimated cost of the session in US dollars, accumulated across all turns using the pricing of the model that served each turn.
+     */
+    accumulated_cost?: number | null;
     /**
      * The number of input tokens used in the session. Accumulated across all messages.
      */
@@ -617,6 +801,10 @@ export type SessionMetadata = {
      * A short description of the session, typically 3 words or less
      */
     description: string;
+    /**
+     * Number of messages copied from the parent session when this session was forked
+     */
+    fork_message_index?: number | null;
     /**
      * The number of input tokens used in the session. Retrieved from the provider's last usage.
      */
@@ -629,6 +817,10 @@ export type SessionMetadata = {
      * The number of output tokens used in the session. Retrieved from the provider's last usage.
      */
     output_tokens?: number | null;
+    /**
+     * ID of the session this session was forked from, if any
+     */
+    parent_session_id?: string | null;
     /**
      * ID of the project this session belongs to, if any
      */
@@ -637,6 +829,10 @@ export type SessionMetadata = {
      * ID of the schedule that triggered this session, if any
      */
     schedule_id?: string | null;
+    /**
+     * Providers and models that answered the session's turns, in order
+     */
+    served_by?: Array<ServedBy>;
     /**
      * The total number of tokens used in the session. Retrieved from the provider's last usage.
      */
@@ -647,11 +843,45 @@ export type SessionMetadata = {
     working_dir: string;
 };
 
+export type SessionSearchQuery = {
+    /**
+     * Maximum number of results
+     */
+    limit?: number | null;
+    /**
+     * Text to search for in messages, tool calls and tool output
+     */
+    q: string;
+    /**
+     * Only match messages since this time (RFC 3339, YYYY-MM-DD, or an age like 7d)
+     */
+    since?: string | null;
+    /**
+     * Only search sessions that used a tool whose name contains this text
+     */
+    tool?: string | null;
+    /**
+     * Only search sessions started in this directory or below it
+     */
+    workingDir?: string | null;
+};
+
+export type SessionSearchResponse = {
+    /**
+     * Matching messages, best match first
+     */
+    results: Array<SearchResult>;
+};
+
 export type SessionsQuery = {
     limit?: number;
 };
 
 export type Settings = {
+    /**
+     * Spending limits for sessions run from the recipe
+     */
+    budget?: CostBudget | null;
     goose_model?: string | null;
     goose_provider?: string | null;
     temperature?: number | null;
@@ -716,6 +946,7 @@ export type ToolAnnotations = {
 export type ToolConfirmationRequest = {
     arguments: unknown;
     id: string;
+    kind?: ConfirmationKind;
     prompt?: string | null;
     toolName: string;
 };
@@ -1148,6 +1379,35 @@ export type ConfirmPermissionResponses = {
     200: unknown;
 };
 
+export type RespondToElicitationData = {
+    body: ElicitationResponseRequest;
+    path?: never;
+    query?: never;
+    url: '/elicitation_response';
+};
+
+export type RespondToElicitationErrors = {
+    /**
+     * Unknown action, or values that don't match the requested schema
+     */
+    400: unknown;
+    /**
+     * Unauthorized - invalid secret key
+     */
+    401: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type RespondToElicitationResponses = {
+    /**
+     * Elicitation response was delivered to the extension
+     */
+    200: unknown;
+};
+
 export type ManageContextData = {
     body: ContextManageRequest;
     path?: never;
@@ -1583,6 +1843,58 @@ export type ListSessionsResponses = {
 
 export type ListSessionsResponse = ListSessionsResponses[keyof ListSessionsResponses];
 
+export type SearchSessionsData = {
+    body?: never;
+    path?: never;
+    query: {
+        /**
+         * Text to search for in messages, tool calls and tool output
+         */
+        q: string;
+        /**
+         * Only search sessions started in this directory or below it
+         */
+        workingDir?: string | null;
+        /**
+         * Only match messages since this time (RFC 3339, YYYY-MM-DD, or an age like 7d)
+         */
+        since?: string | null;
+        /**
+         * Only search sessions that used a tool whose name contains this text
+         */
+        tool?: string | null;
+        /**
+         * Maximum number of results
+         */
+        limit?: number | null;
+    };
+    url: '/sessions/search';
+};
+
+export type SearchSessionsErrors = {
+    /**
+     * Invalid search parameters
+     */
+    400: unknown;
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type SearchSessionsResponses = {
+    /**
+     * Matching messages retrieved successfully
+     */
+    200: SessionSearchResponse;
+};
+
+export type SearchSessionsResponse = SearchSessionsResponses[keyof SearchSessionsResponses];
+
 export type GetSessionHistoryData = {
     body?: never;
     path: {
@@ -1619,6 +1931,50 @@ export type GetSessionHistoryResponses = {
 
 export type GetSessionHistoryResponse = GetSessionHistoryResponses[keyof GetSessionHistoryResponses];
 
+export type ForkSessionData = {
+    body: ForkSessionRequest;
+    path: {
+        /**
+         * Unique identifier for the session to fork
+         */
+        session_id: string;
+    };
+    query?: never;
+    url: '/sessions/{session_id}/fork';
+};
+
+export type ForkSessionErrors = {
+    /**
+     * Invalid fork point or session id
+     */
+    400: unknown;
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Session not found
+     */
+    404: unknown;
+    /**
+     * A session with the requested id already exists
+     */
+    409: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type ForkSessionResponses = {
+    /**
+     * Session forked successfully
+     */
+    200: ForkSessionResponse;
+};
+
+export type ForkSessionResponse2 = ForkSessionResponses[keyof ForkSessionResponses];
+
 export type ClientOptions = {
     baseUrl: `${string}://${string}` | (string & {});
 };
\ No newline at end of file
@@ -20,11 +20,15 @@ const toolConfirmationState = new Map<
   }
 >();
 
+// Tool policy rules put their reason before this question in the prompt
+const CONFIRMATION_PROMPT = 'Goose would like to call the above tool. Allow? (y/n):';
+
 interface ToolConfirmationProps {
   isCancelledMessage: boolean;
   isClicked: boolean;
   toolConfirmationId: string;
   toolName: string;
+  prompt?: string;
 }
 
 export default function ToolConfirmation({
@@ -32,7 +36,12 @@ export default function ToolConfirmation({
   isClicked,
   toolConfirmationId,
   toolName,
+  prompt,
 }: ToolConfirmationProps) {
+  const reason = prompt?.endsWith(CONFIRMATION_PROMPT)
+    ? prompt.slice(0, -CONFIRMATION_PROMPT.length).trim()
+    : '';
+
   // Check if we have a stored state for this tool confirmation
   const storedState = toolConfirmationState.get(toolConfirmationId);
 
@@ -121,6 +130,7 @@ export default function ToolConfirmation({
   ) : (
     <>
       <div className="goose-message-content bg-background-muted rounded-2xl px-4 py-2 rounded-b-none text-textStandard">
+        {reason && <p className="mb-1 text-textSubtle">{reason}</p>}
         Goose would like to call the above tool. Allow?
       </div>
       {clicked ? (
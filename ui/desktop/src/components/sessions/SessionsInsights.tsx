@@ -16,6 +16,7 @@ interface SessionInsightsType {
   mostActiveDirs: [string, number][];
   avgSessionDuration: number;
   totalTokens: number;
+  totalCost: number;
 }
 
 export function SessionInsights() {
@@ -58,6 +59,7 @@ export function SessionInsights() {
           mostActiveDirs: [],
           avgSessionDuration: 0,
           totalTokens: 0,
+          totalCost: 0,
         });
       } finally {
         setIsLoading(false);
@@ -97,6 +99,7 @@ export function SessionInsights() {
             mostActiveDirs: [],
             avgSessionDuration: 0,
             totalTokens: 0,
+            totalCost: 0,
           };
         }
         // If we already have insights, just make sure loading is false
@@ -176,7 +179,7 @@ export function SessionInsights() {
       {/* Stats containers - full bleed with 2px gaps */}
       <div className="flex flex-col flex-1 space-y-0.5">
         {/* Top row with three equal columns */}
-        <div className="grid grid-cols-2 gap-0.5">
+        <div className="grid grid-cols-3 gap-0.5">
           {/* Total Sessions Card Skeleton */}
           <Card className="w-full py-6 px-6 border-none rounded-2xl bg-background-default">
             <CardContent className="flex flex-col justify-end h-full p-0">
@@ -206,6 +209,16 @@ export function SessionInsights() {
               </div>
             </CardContent>
           </Card>
+
+          {/* Estimated Cost Card Skeleton */}
+          <Card className="w-full py-6 px-6 border-none rounded-2xl bg-background-default">
+            <CardContent className="flex flex-col justify-end h-full p-0">
+              <div className="flex flex-col justify-end">
+                <Skeleton className="h-10 w-20 mb-1" />
+                <span className="text-xs text-text-muted">Estimated cost</span>
+              </div>
+            </CardContent>
+          </Card>
         </div>
 
         {/* Recent Chats Card Skeleton */}
@@ -291,7 +304,7 @@ export function SessionInsights() {
         )}
 
         {/* Top row with three equal columns */}
-        <div className="grid grid-cols-2 gap-0.5">
+        <div className="grid grid-cols-3 gap-0.5">
           {/* Total Sessions Card */}
           <Card className="w-full py-6 px-6 border-none rounded-2xl bg-background-default">
             <CardContent className="page-transition flex flex-col justify-end h-full p-0">
@@ -331,6 +344,18 @@ export function SessionInsights() {
               </div>
             </CardContent>
           </Card>
+
+          {/* Estimated Cost Card */}
+          <Card className="w-full py-6 px-6 border-none rounded-2xl bg-background-default">
+            <CardContent className="page-transition flex flex-col justify-end h-full p-0">
+              <div className="flex flex-col justify-end">
+                <p className="text-4xl font-mono font-light flex items-end">
+                  ${Math.max(insights?.totalCost ?? 0, 0).toFixed(2)}
+                </p>
+                <span className="text-xs text-text-muted">Estimated cost</span>
+              </div>
+            </CardContent>
+          </Card>
         </div>
 
         {/* Recent Chats Card */}
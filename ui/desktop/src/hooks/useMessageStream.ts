@@ -39,13 +39,23 @@ export interface NotificationEvent {
   };
 }
 
+export interface ProgressEvent {
+  type: 'Progress';
+  request_id: string;
+  progress: number;
+  total: number | null;
+  percentage: number | null;
+  message: string | null;
+}
+
 // Event types for SSE stream
 type MessageEvent =
   | { type: 'Message'; message: Message }
   | { type: 'Error'; error: string }
   | { type: 'Finish'; reason: string }
   | { type: 'ModelChange'; model: string; mode: string }
-  | NotificationEvent;
+  | NotificationEvent
+  | ProgressEvent;
 
 export interface UseMessageStreamOptions {
   /**
@@ -336,6 +346,25 @@ export function useMessageStream({
                     break;
                   }
 
+                  case 'Progress': {
+                    // Keep progress with the tool call's other notifications so it renders alongside its logs
+                    const progressNotification: NotificationEvent = {
+                      type: 'Notification',
+                      request_id: parsedEvent.request_id,
+                      message: {
+                        method: 'notifications/progress',
+                        params: {
+                          progressToken: parsedEvent.request_id,
+                          progress: parsedEvent.progress,
+                          ...(parsedEvent.total !== null && { total: parsedEvent.total }),
+                          ...(parsedEvent.message !== null && { message: parsedEvent.message }),
+                        },
+                      },
+                    };
+                    setNotifications((prev) => [...prev, progressNotification]);
+                    break;
+                  }
+
                   case 'ModelChange': {
                     // Update the current model in the frontend
                     const modelInfo = {
@@ -61,6 +61,14 @@ export interface ToolConfirmationRequestMessageContent {
   prompt?: string;
 }
 
+export interface ElicitationRequestMessageContent {
+  type: 'elicitationRequest';
+  id: string;
+  extensionName: string;
+  message: string;
+  requestedSchema: Record<string, unknown>;
+}
+
 export interface ExtensionCall {
   name: string;
   arguments: Record<string, unknown>;
@@ -89,6 +97,7 @@ export type MessageContent =
   | ToolRequestMessageContent
   | ToolResponseMessageContent
   | ToolConfirmationRequestMessageContent
+  | ElicitationRequestMessageContent
   | ContextLengthExceededContent
   | SummarizationRequestedContent;
 
@@ -225,6 +234,14 @@ export function getToolConfirmationContent(
   );
 }
 
+export function getElicitationRequestContent(
+  message: Message
+): ElicitationRequestMessageContent | undefined {
+  return message.content.find(
+    (content): content is ElicitationRequestMessageContent => content.type === 'elicitationRequest'
+  );
+}
+
 export function hasCompletedToolCalls(message: Message): boolean {
   const toolRequests = getToolRequests(message);
   if (toolRequests.length === 0) return false;